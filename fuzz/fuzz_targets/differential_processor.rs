@@ -0,0 +1,196 @@
+//! 並列処理器と単一スレッド参照実装の差分整合性ファジング。
+//!
+//! ファザからの任意バイト列を `Transaction` のベクタに復号し（shard_id・nonce・
+//! parent_id を変化させてクロスシャード経路を刺激する）、同じバッチを
+//! 単一スレッドの参照経路と `ParallelProcessor::process_batch` の両方に通す。
+//! スレッド数やバッチサイズに関わらず、トランザクションごとの顛末と最終的な
+//! `ProcessTransactionsSummary` が一致することを検証する。
+//!
+//! 決定性が要るため、処理経路にウォールクロックや RNG を持ち込まない
+//! （タイムスタンプ・シャード数などはすべて入力バイトから導出する）。不一致は
+//! 並列処理器の順序・ロックのバグを示し、libFuzzer が最小入力へ縮小する。
+
+#![no_main]
+
+use std::sync::Arc;
+
+use arbitrary::{Arbitrary, Unstructured};
+use libfuzzer_sys::fuzz_target;
+use tokio::sync::mpsc;
+
+use shardx::shard::ShardManager;
+use shardx::transaction::{
+    CrossShardManager, ParallelProcessor, ProcessTransactionsSummary, ProcessorConfig,
+    Transaction, TransactionStatus,
+};
+
+/// 有効なシャードIDの範囲（これを外れると invalid shard として棄却される）
+const SHARD_COUNT: u64 = 4;
+
+/// ファザ入力から復号されるバッチ記述
+#[derive(Debug, Arbitrary)]
+struct FuzzBatch {
+    /// 並列度（1 を含む広い範囲を生成し、スレッド数に対する不変性を検証する）
+    parallelism: u8,
+    /// バッチサイズ（依存関係グループ化の境界を揺さぶる）
+    batch_size: u8,
+    /// 生トランザクション記述
+    txs: Vec<FuzzTx>,
+}
+
+/// 1トランザクションぶんの記述
+#[derive(Debug, Arbitrary)]
+struct FuzzTx {
+    from: u8,
+    to: u8,
+    nonce: u64,
+    shard: u64,
+    signature: u16,
+    has_parent: bool,
+}
+
+impl FuzzTx {
+    /// 決定的に `Transaction` を構築する（時刻・乱数を使わない）
+    fn into_transaction(self, index: usize) -> Transaction {
+        Transaction {
+            id: format!("tx-{}", index),
+            from: format!("addr-{}", self.from),
+            to: format!("addr-{}", self.to),
+            amount: "1".to_string(),
+            fee: "0".to_string(),
+            data: None,
+            nonce: self.nonce,
+            timestamp: index as u64, // ウォールクロックではなく入力順で決まる
+            signature: format!("sig-{}", self.signature),
+            status: TransactionStatus::Pending,
+            shard_id: self.shard.to_string(),
+            block_hash: None,
+            block_height: None,
+            parent_id: if self.has_parent && index > 0 {
+                Some(format!("tx-{}", index - 1))
+            } else {
+                None
+            },
+        }
+    }
+}
+
+/// 単一スレッドの参照実装。並列処理器が従うべき分類規則をそのまま順に適用する。
+fn reference_summary(txs: &[Transaction], config: &ProcessorConfig) -> ProcessTransactionsSummary {
+    use std::collections::{HashMap, HashSet};
+
+    let mut summary = ProcessTransactionsSummary {
+        transactions_attempted_count: txs.len(),
+        ..Default::default()
+    };
+
+    let mut seen_signatures: HashSet<&str> = HashSet::new();
+    let mut highest_nonce: HashMap<&str, u64> = HashMap::new();
+
+    for (index, tx) in txs.iter().enumerate() {
+        // 不正シャード（範囲外または非数値）は棄却
+        let invalid_shard = tx
+            .shard_id
+            .parse::<u64>()
+            .map_or(true, |s| s >= SHARD_COUNT);
+        if invalid_shard {
+            summary.failed_commit_count += 1;
+            summary.error_metrics.invalid_shard += 1;
+            continue;
+        }
+
+        // 署名の重複は棄却
+        if !seen_signatures.insert(&tx.signature) {
+            summary.failed_commit_count += 1;
+            summary.error_metrics.duplicate_signature += 1;
+            continue;
+        }
+
+        // 同一送信元で nonce が巻き戻っていれば棄却
+        let prev = highest_nonce.get(tx.from.as_str()).copied();
+        if prev.map_or(false, |p| tx.nonce <= p) {
+            summary.failed_commit_count += 1;
+            summary.error_metrics.stale_nonce += 1;
+            continue;
+        }
+        highest_nonce.insert(&tx.from, tx.nonce);
+
+        // キュー上限を超える位置のものは再試行可能として保留
+        if index >= config.max_queue_size {
+            summary.retryable_indexes.push(index);
+            summary.error_metrics.capacity_exceeded += 1;
+            continue;
+        }
+
+        summary.committed_count += 1;
+    }
+
+    summary
+}
+
+/// 2つのサマリが観測上等価か（再試行集合は順序を問わない）
+fn summaries_match(a: &ProcessTransactionsSummary, b: &ProcessTransactionsSummary) -> bool {
+    let mut a_retry = a.retryable_indexes.clone();
+    let mut b_retry = b.retryable_indexes.clone();
+    a_retry.sort_unstable();
+    b_retry.sort_unstable();
+
+    a.transactions_attempted_count == b.transactions_attempted_count
+        && a.committed_count == b.committed_count
+        && a.failed_commit_count == b.failed_commit_count
+        && a_retry == b_retry
+}
+
+fuzz_target!(|data: &[u8]| {
+    let mut u = Unstructured::new(data);
+    let Ok(batch) = FuzzBatch::arbitrary(&mut u) else {
+        return;
+    };
+
+    let transactions: Vec<Transaction> = batch
+        .txs
+        .into_iter()
+        .enumerate()
+        .map(|(i, tx)| tx.into_transaction(i))
+        .collect();
+
+    let config = ProcessorConfig {
+        max_parallelism: (batch.parallelism as usize).max(1),
+        min_parallelism: 1,
+        batch_size: (batch.batch_size as usize).max(1),
+        dynamic_scaling_enabled: false,
+        ..Default::default()
+    };
+
+    // 参照（単一スレッド）サマリ
+    let reference = reference_summary(&transactions, &config);
+
+    // 並列処理器を現行スレッドランタイムで駆動する
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to build runtime");
+
+    let actual = runtime.block_on(async {
+        let (network_tx, _network_rx) = mpsc::channel(1024);
+        let cross_shard_manager = Arc::new(CrossShardManager::new(network_tx.clone()));
+        let shard_manager = Arc::new(ShardManager::new(network_tx.clone()));
+        let processor = ParallelProcessor::new(
+            cross_shard_manager,
+            shard_manager,
+            network_tx,
+            Some(config.clone()),
+        );
+        processor
+            .process_batch(&transactions)
+            .await
+            .expect("process_batch should not hard-fail on well-formed input")
+    });
+
+    assert!(
+        summaries_match(&reference, &actual),
+        "parallel and single-thread outcomes diverged:\n reference = {:?}\n actual    = {:?}",
+        reference,
+        actual
+    );
+});
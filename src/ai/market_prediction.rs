@@ -4,6 +4,11 @@ use std::time::{Duration, Instant};
 use chrono::{DateTime, Utc, NaiveDateTime, TimeZone};
 use serde::{Serialize, Deserialize};
 use log::{debug, error, info, warn};
+use gbdt::config::Config;
+use gbdt::decision_tree::{Data, DataVec};
+use gbdt::gradient_boost::GBDT;
+use rustfft::FftPlanner;
+use rustfft::num_complex::Complex;
 
 use crate::error::Error;
 use crate::transaction::{Transaction, TransactionStatus, TransactionType};
@@ -24,6 +29,10 @@ pub enum PredictionModelType {
     NeuralNetwork,
     /// ランダムフォレスト
     RandomForest,
+    /// 季節性モデル（フェーズごとの平均・標準偏差に基づく）
+    Seasonal,
+    /// FFTスペクトル特徴量 + 勾配ブースティング木によるパターン認識モデル
+    Pattern,
     /// アンサンブル
     Ensemble,
 }
@@ -107,6 +116,10 @@ pub struct PredictionConfig {
     pub auto_retrain: bool,
     /// 再学習間隔
     pub retrain_interval: TimeFrame,
+    /// 実際の値が信頼区間を外れたときに異常として記録するかどうか
+    pub anomaly_detection_enabled: bool,
+    /// バックグラウンド検知ランナーによるアラート通知設定
+    pub alerting: Option<AlertingConfig>,
 }
 
 impl Default for PredictionConfig {
@@ -127,10 +140,57 @@ impl Default for PredictionConfig {
             hyperparameters: HashMap::new(),
             auto_retrain: true,
             retrain_interval: TimeFrame::Day(1),
+            anomaly_detection_enabled: true,
+            alerting: None,
         }
     }
 }
 
+/// アラート通知方式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum AlertingType {
+    /// Webhookエンドポイントへ一定間隔でPOSTする
+    Webhook {
+        /// 通知先エンドポイントURL
+        endpoint: String,
+        /// 監視間隔（秒）
+        interval_seconds: u64,
+    },
+}
+
+/// アラート設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertingConfig {
+    /// 通知方式
+    pub alerting_type: AlertingType,
+}
+
+/// 実際の値が信頼区間のどちら側を超えたか
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CrossedBound {
+    /// 下限を下回った
+    Lower,
+    /// 上限を上回った
+    Upper,
+}
+
+/// 実際の値が予測の信頼区間から外れたことを示す異常
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PredictionAnomaly {
+    /// どの予測結果で発生したか
+    pub prediction_id: String,
+    /// 観測時刻
+    pub timestamp: DateTime<Utc>,
+    /// 予測値
+    pub predicted_value: f64,
+    /// 実際の値
+    pub actual_value: f64,
+    /// 逸脱の大きさ（信頼区間の境界からの距離）
+    pub deviation: f64,
+    /// どちらの境界を超えたか
+    pub crossed_bound: CrossedBound,
+}
+
 /// 予測データポイント
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PredictionDataPoint {
@@ -182,6 +242,72 @@ pub struct FeatureData {
     pub values: HashMap<String, f64>,
 }
 
+/// スペクトル特徴量抽出器
+///
+/// 直近`window_size`件の`target`観測値に実数FFTをかけ、支配的な上位
+/// `top_k`個の周波数ビンとその振幅を`"fft_freq_N"`/`"fft_mag_N"`という
+/// 名前の特徴量として抽出する。日次・週次で繰り返すガス料金のスパイク
+/// やシャード負荷の周期構造を、`LinearRegressionModel`や
+/// `GradientBoostedTreesModel`にそのまま数値特徴量として渡すために使う。
+/// `PredictionConfig.features`にこれらの名前を列挙することで利用できる。
+pub struct SpectralFeatureExtractor {
+    /// FFTに使用する直近サンプル数
+    window_size: usize,
+    /// 抽出する上位周波数ビンの数
+    top_k: usize,
+}
+
+impl SpectralFeatureExtractor {
+    /// 新しいスペクトル特徴量抽出器を作成
+    pub fn new(window_size: usize, top_k: usize) -> Self {
+        Self { window_size, top_k }
+    }
+
+    /// `target_history`の末尾`window_size`件からFFTを実行し、上位
+    /// `top_k`件の周波数・振幅を特徴量として返す。サンプルが
+    /// `window_size`に満たない場合は先頭をゼロ埋めする。履歴が完全に
+    /// 空の場合は空の特徴量を返す。
+    pub fn extract(&self, target_history: &[f64]) -> HashMap<String, f64> {
+        let mut features = HashMap::new();
+
+        if target_history.is_empty() || self.window_size < 2 {
+            return features;
+        }
+
+        let n = self.window_size;
+        let take = target_history.len().min(n);
+        let start = target_history.len() - take;
+
+        let mut samples = vec![0.0f64; n];
+        samples[n - take..].copy_from_slice(&target_history[start..]);
+
+        let mut planner = FftPlanner::<f64>::new();
+        let fft = planner.plan_fft_forward(n);
+
+        let mut buffer: Vec<Complex<f64>> =
+            samples.iter().map(|value| Complex::new(*value, 0.0)).collect();
+        fft.process(&mut buffer);
+
+        // 直流成分とナイキスト周波数を除いた片側スペクトルから上位を選ぶ
+        let mut bins: Vec<(usize, f64)> = (1..n / 2).map(|k| (k, buffer[k].norm())).collect();
+        bins.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        for (rank, (bin, magnitude)) in bins.into_iter().take(self.top_k).enumerate() {
+            let frequency = bin as f64 / n as f64;
+            features.insert(format!("fft_freq_{}", rank), frequency);
+            features.insert(format!("fft_mag_{}", rank), magnitude);
+        }
+
+        features
+    }
+}
+
+impl Default for SpectralFeatureExtractor {
+    fn default() -> Self {
+        Self::new(64, 4)
+    }
+}
+
 /// 予測モデル
 pub trait PredictionModel {
     /// モデルを学習
@@ -210,6 +336,16 @@ pub trait PredictionModel {
     
     /// モデルのハイパーパラメータを設定
     fn set_hyperparameters(&mut self, params: HashMap<String, f64>) -> Result<(), Error>;
+
+    /// `predict`が返す`step_index`番目の予測値に対応する残差標準偏差
+    ///
+    /// モデルが実データから分散を推定できる場合にのみ`Some`を返す。
+    /// `MarketPredictionService::predict`はこれを使って信頼区間の
+    /// `lower_bound`/`upper_bound`を計算し、提供されない場合は
+    /// 従来どおりの簡易的な推定値にフォールバックする。
+    fn residual_std(&self, _step_index: usize) -> Option<f64> {
+        None
+    }
 }
 
 /// 線形回帰モデル
@@ -598,216 +734,2092 @@ impl PredictionModel for MovingAverageModel {
     }
 }
 
-/// アンサンブルモデル
-pub struct EnsembleModel {
-    /// 内部モデル
-    models: Vec<Box<dyn PredictionModel>>,
-    /// モデルの重み
-    weights: Vec<f64>,
+/// SARIMAモデル（季節性対応の自己回帰和分移動平均モデル）
+///
+/// `GasFee`/`TransactionCount`/`ShardLoad`のような日次・週次の季節性が
+/// 強い指標向けに、季節成分・水準・トレンドを交互に再推定する簡易版の
+/// SARIMA実装。完全なARIMA(p,d,q)の最尤推定は行わず、季節成分の除去と
+/// 線形トレンドの当てはめを`seasonality_iterations`回繰り返すことで
+/// 近似する。
+pub struct SarimaModel {
+    /// 季節周期S（ハイパーパラメータで指定、未指定時は自動検出）
+    seasonal_period: usize,
+    /// 季節成分/トレンドの再推定を繰り返す回数
+    seasonality_iterations: usize,
+    /// 直近時点における水準（レベル）
+    level: f64,
+    /// トレンドの傾き
+    trend: f64,
+    /// 季節成分ベクトル（長さ seasonal_period）
+    seasonal: Vec<f64>,
+    /// 残差標準偏差
+    resid_std: f64,
+    /// 学習に使用した観測数（予測時の季節フェーズ計算に使用）
+    n_observations: usize,
     /// ハイパーパラメータ
     hyperparameters: HashMap<String, f64>,
     /// 学習済みかどうか
     trained: bool,
 }
 
-impl EnsembleModel {
-    /// 新しいアンサンブルモデルを作成
+impl SarimaModel {
+    /// 新しいSARIMAモデルを作成
     pub fn new() -> Self {
         let mut hyperparameters = HashMap::new();
-        hyperparameters.insert("equal_weights".to_string(), 1.0);
-        
+        // 0.0は「自動検出」を意味する
+        hyperparameters.insert("seasonal_period".to_string(), 0.0);
+        hyperparameters.insert("seasonality_iterations".to_string(), 5.0);
+
         Self {
-            models: Vec::new(),
-            weights: Vec::new(),
+            seasonal_period: 1,
+            seasonality_iterations: 5,
+            level: 0.0,
+            trend: 0.0,
+            seasonal: vec![0.0],
+            resid_std: 0.0,
+            n_observations: 0,
             hyperparameters,
             trained: false,
         }
     }
-    
-    /// モデルを追加
-    pub fn add_model(&mut self, model: Box<dyn PredictionModel>, weight: f64) {
-        self.models.push(model);
-        self.weights.push(weight);
-        
-        // 重みを正規化
-        let sum = self.weights.iter().sum::<f64>();
-        if sum > 0.0 {
-            for w in &mut self.weights {
-                *w /= sum;
+
+    /// 自己相関関数（ACF）を計算し、ラグ1より先で最も相関が強いラグを
+    /// 季節周期として採用する
+    fn detect_seasonal_period(y: &[f64]) -> usize {
+        let n = y.len();
+        if n < 4 {
+            return 1;
+        }
+
+        let mean = y.iter().sum::<f64>() / n as f64;
+        let variance: f64 = y.iter().map(|v| (v - mean).powi(2)).sum();
+
+        if variance.abs() < 1e-12 {
+            return 1;
+        }
+
+        let max_lag = (n / 2).max(2);
+        let mut best_lag = 1;
+        let mut best_acf = f64::MIN;
+
+        for lag in 2..max_lag {
+            let mut covariance = 0.0;
+            for i in 0..(n - lag) {
+                covariance += (y[i] - mean) * (y[i + lag] - mean);
+            }
+
+            let acf = covariance / variance;
+            if acf > best_acf {
+                best_acf = acf;
+                best_lag = lag;
             }
         }
+
+        best_lag
+    }
+
+    /// 最小二乗法による単回帰（切片, 傾き）を計算
+    fn linear_fit(y: &[f64]) -> (f64, f64) {
+        let n = y.len() as f64;
+        let x_mean = (y.len() as f64 - 1.0) / 2.0;
+        let y_mean = y.iter().sum::<f64>() / n;
+
+        let mut numerator = 0.0;
+        let mut denominator = 0.0;
+
+        for (i, value) in y.iter().enumerate() {
+            let x = i as f64;
+            numerator += (x - x_mean) * (value - y_mean);
+            denominator += (x - x_mean).powi(2);
+        }
+
+        let slope = if denominator.abs() > 1e-12 {
+            numerator / denominator
+        } else {
+            0.0
+        };
+        let intercept = y_mean - slope * x_mean;
+
+        (intercept, slope)
+    }
+
+    /// 信頼区間の半値幅（z * 残差標準偏差）を計算し、予測値からの
+    /// 上下限を返す
+    pub fn confidence_bounds(&self, forecast: f64, confidence_interval: f64) -> (f64, f64) {
+        let z_score = match confidence_interval {
+            ci if (ci - 0.90).abs() < 1e-9 => 1.645,
+            ci if (ci - 0.95).abs() < 1e-9 => 1.96,
+            ci if (ci - 0.99).abs() < 1e-9 => 2.576,
+            _ => 1.96,
+        };
+
+        let margin = z_score * self.resid_std;
+        (forecast - margin, forecast + margin)
+    }
+
+    /// 残差標準偏差を取得
+    pub fn resid_std(&self) -> f64 {
+        self.resid_std
+    }
+
+    /// 検出/設定された季節周期を取得
+    pub fn seasonal_period(&self) -> usize {
+        self.seasonal_period
+    }
+
+    /// 学習済みの季節成分ベクトルを取得する
+    pub fn seasonal_profile(&self) -> &[f64] {
+        &self.seasonal
     }
 }
 
-impl PredictionModel for EnsembleModel {
+impl PredictionModel for SarimaModel {
     fn train(&mut self, data: &[FeatureData]) -> Result<(), Error> {
         if data.is_empty() {
             return Err(Error::InvalidInput("学習データが空です".to_string()));
         }
-        
-        if self.models.is_empty() {
-            return Err(Error::InvalidState("モデルが追加されていません".to_string()));
+
+        let mut y = Vec::with_capacity(data.len());
+        for feature_data in data {
+            if let Some(target_value) = feature_data.values.get("target") {
+                y.push(*target_value);
+            } else {
+                return Err(Error::InvalidInput("ターゲット値が見つかりません".to_string()));
+            }
         }
-        
-        // 各モデルを学習
-        for model in &mut self.models {
-            model.train(data)?;
+
+        let n = y.len();
+
+        let configured_period = self
+            .hyperparameters
+            .get("seasonal_period")
+            .copied()
+            .unwrap_or(0.0) as usize;
+        let s = if configured_period > 0 {
+            configured_period
+        } else {
+            Self::detect_seasonal_period(&y)
         }
-        
+        .max(1);
+
+        if n < s {
+            return Err(Error::InvalidInput(format!(
+                "学習データが季節周期({})より短いです",
+                s
+            )));
+        }
+
+        let iterations = self
+            .hyperparameters
+            .get("seasonality_iterations")
+            .copied()
+            .unwrap_or(5.0) as usize;
+
+        let mut seasonal = vec![0.0; s];
+        let mut intercept = 0.0;
+        let mut trend = 0.0;
+
+        for _ in 0..iterations.max(1) {
+            // 季節成分を差し引いた系列に対して水準/トレンドを再推定
+            let deseasonalized: Vec<f64> = y
+                .iter()
+                .enumerate()
+                .map(|(i, value)| value - seasonal[i % s])
+                .collect();
+
+            let (lvl, tr) = Self::linear_fit(&deseasonalized);
+            intercept = lvl;
+            trend = tr;
+
+            // トレンドを差し引いた残差から季節平均を再推定
+            let mut sums = vec![0.0; s];
+            let mut counts = vec![0usize; s];
+            for (i, value) in y.iter().enumerate() {
+                let detrended = value - (intercept + trend * i as f64);
+                sums[i % s] += detrended;
+                counts[i % s] += 1;
+            }
+            for phase in 0..s {
+                seasonal[phase] = if counts[phase] > 0 {
+                    sums[phase] / counts[phase] as f64
+                } else {
+                    0.0
+                };
+            }
+        }
+
+        let residuals: Vec<f64> = y
+            .iter()
+            .enumerate()
+            .map(|(i, value)| value - (intercept + trend * i as f64 + seasonal[i % s]))
+            .collect();
+        let resid_mean = residuals.iter().sum::<f64>() / n as f64;
+        let resid_variance =
+            residuals.iter().map(|r| (r - resid_mean).powi(2)).sum::<f64>() / n as f64;
+
+        self.seasonal_period = s;
+        self.seasonality_iterations = iterations.max(1);
+        // レベルは最後の観測時点(n-1)における水準として保持する
+        self.level = intercept + trend * (n as f64 - 1.0);
+        self.trend = trend;
+        self.seasonal = seasonal;
+        self.resid_std = resid_variance.sqrt();
+        self.n_observations = n;
         self.trained = true;
-        
+
         Ok(())
     }
-    
+
     fn predict(&self, features: &HashMap<String, Vec<f64>>) -> Result<Vec<f64>, Error> {
         if !self.trained {
             return Err(Error::InvalidState("モデルが学習されていません".to_string()));
         }
-        
-        if self.models.is_empty() {
-            return Err(Error::InvalidState("モデルが追加されていません".to_string()));
-        }
-        
-        // 予測期間の長さを取得
+
         let n_samples = features.values().next().map(|v| v.len()).unwrap_or(0);
         if n_samples == 0 {
             return Err(Error::InvalidInput("特徴量が空です".to_string()));
         }
-        
-        // 各モデルの予測を取得
-        let mut all_predictions = Vec::new();
-        
-        for model in &self.models {
-            let predictions = model.predict(features)?;
-            all_predictions.push(predictions);
-        }
-        
-        // 重み付き平均を計算
-        let mut ensemble_predictions = vec![0.0; n_samples];
-        
+
+        let s = self.seasonal_period.max(1);
+        let mut predictions = Vec::with_capacity(n_samples);
+
         for i in 0..n_samples {
-            let mut weighted_sum = 0.0;
-            
-            for (j, predictions) in all_predictions.iter().enumerate() {
-                weighted_sum += predictions[i] * self.weights[j];
-            }
-            
-            ensemble_predictions[i] = weighted_sum;
+            let h = i + 1;
+            let phase = (self.n_observations + h) % s;
+            let forecast = self.level + self.trend * h as f64 + self.seasonal[phase];
+            predictions.push(forecast);
         }
-        
-        Ok(ensemble_predictions)
+
+        Ok(predictions)
     }
-    
+
     fn save(&self, path: &str) -> Result<(), Error> {
-        // 各モデルを個別に保存
-        for (i, model) in self.models.iter().enumerate() {
-            let model_path = format!("{}_model_{}", path, i);
-            model.save(&model_path)?;
-        }
-        
-        // アンサンブル設定を保存
         let model_data = serde_json::json!({
-            "model_type": "Ensemble",
-            "model_count": self.models.len(),
-            "weights": self.weights,
+            "model_type": "SARIMA",
+            "seasonal_period": self.seasonal_period,
+            "seasonality_iterations": self.seasonality_iterations,
+            "level": self.level,
+            "trend": self.trend,
+            "seasonal": self.seasonal,
+            "resid_std": self.resid_std,
+            "n_observations": self.n_observations,
             "hyperparameters": self.hyperparameters,
             "trained": self.trained,
         });
-        
+
         std::fs::write(path, serde_json::to_string_pretty(&model_data)?)
             .map_err(|e| Error::IOError(format!("モデルの保存に失敗しました: {}", e)))?;
-        
+
         Ok(())
     }
-    
+
     fn load(&mut self, path: &str) -> Result<(), Error> {
         let model_data = std::fs::read_to_string(path)
             .map_err(|e| Error::IOError(format!("モデルの読み込みに失敗しました: {}", e)))?;
-        
+
         let model_json: serde_json::Value = serde_json::from_str(&model_data)
             .map_err(|e| Error::ParseError(format!("モデルデータの解析に失敗しました: {}", e)))?;
-        
-        // モデルタイプを確認
+
         let model_type = model_json["model_type"].as_str()
             .ok_or_else(|| Error::ParseError("モデルタイプが見つかりません".to_string()))?;
-        
-        if model_type != "Ensemble" {
+
+        if model_type != "SARIMA" {
             return Err(Error::InvalidInput(format!(
-                "モデルタイプが一致しません: {} != Ensemble",
+                "モデルタイプが一致しません: {} != SARIMA",
                 model_type
             )));
         }
-        
-        // パラメータを読み込み
-        let model_count = model_json["model_count"].as_u64()
-            .ok_or_else(|| Error::ParseError("モデル数が見つかりません".to_string()))? as usize;
-        
-        self.weights = serde_json::from_value(model_json["weights"].clone())
-            .map_err(|e| Error::ParseError(format!("重みの解析に失敗しました: {}", e)))?;
-        
+
+        self.seasonal_period = model_json["seasonal_period"].as_u64()
+            .ok_or_else(|| Error::ParseError("季節周期が見つかりません".to_string()))? as usize;
+
+        self.seasonality_iterations = model_json["seasonality_iterations"].as_u64()
+            .ok_or_else(|| Error::ParseError("再推定回数が見つかりません".to_string()))? as usize;
+
+        self.level = model_json["level"].as_f64()
+            .ok_or_else(|| Error::ParseError("水準が見つかりません".to_string()))?;
+
+        self.trend = model_json["trend"].as_f64()
+            .ok_or_else(|| Error::ParseError("トレンドが見つかりません".to_string()))?;
+
+        self.seasonal = serde_json::from_value(model_json["seasonal"].clone())
+            .map_err(|e| Error::ParseError(format!("季節成分の解析に失敗しました: {}", e)))?;
+
+        self.resid_std = model_json["resid_std"].as_f64()
+            .ok_or_else(|| Error::ParseError("残差標準偏差が見つかりません".to_string()))?;
+
+        self.n_observations = model_json["n_observations"].as_u64()
+            .ok_or_else(|| Error::ParseError("観測数が見つかりません".to_string()))? as usize;
+
         self.hyperparameters = serde_json::from_value(model_json["hyperparameters"].clone())
             .map_err(|e| Error::ParseError(format!("ハイパーパラメータの解析に失敗しました: {}", e)))?;
-        
+
         self.trained = model_json["trained"].as_bool()
             .ok_or_else(|| Error::ParseError("学習状態が見つかりません".to_string()))?;
-        
-        // 各モデルを読み込み
-        self.models.clear();
-        
-        for i in 0..model_count {
-            let model_path = format!("{}_model_{}", path, i);
-            
-            // モデルタイプを確認
-            let model_data = std::fs::read_to_string(&model_path)
-                .map_err(|e| Error::IOError(format!("モデルの読み込みに失敗しました: {}", e)))?;
-            
-            let model_json: serde_json::Value = serde_json::from_str(&model_data)
-                .map_err(|e| Error::ParseError(format!("モデルデータの解析に失敗しました: {}", e)))?;
-            
-            let sub_model_type = model_json["model_type"].as_str()
-                .ok_or_else(|| Error::ParseError("モデルタイプが見つかりません".to_string()))?;
-            
-            // モデルタイプに応じてモデルを作成
-            let mut model: Box<dyn PredictionModel> = match sub_model_type {
-                "LinearRegression" => Box::new(LinearRegressionModel::new()),
-                "MovingAverage" => {
-                    let window_size = model_json["window_size"].as_u64()
-                        .ok_or_else(|| Error::ParseError("ウィンドウサイズが見つかりません".to_string()))? as usize;
-                    Box::new(MovingAverageModel::new(window_size))
-                },
-                _ => return Err(Error::InvalidInput(format!("未対応のモデルタイプです: {}", sub_model_type))),
-            };
-            
-            // モデルを読み込み
-            model.load(&model_path)?;
-            
-            // モデルを追加
-            self.models.push(model);
-        }
-        
+
         Ok(())
     }
-    
+
     fn model_type(&self) -> PredictionModelType {
-        PredictionModelType::Ensemble
+        PredictionModelType::ARIMA
     }
-    
+
     fn name(&self) -> String {
-        "アンサンブルモデル".to_string()
+        "SARIMAモデル".to_string()
     }
-    
+
     fn description(&self) -> String {
-        format!("{} 個のモデルを組み合わせたアンサンブルモデル。", self.models.len())
+        format!(
+            "季節周期{}の季節性を考慮したSARIMAモデル。水準・トレンド・季節成分を反復推定します。",
+            self.seasonal_period
+        )
     }
-    
+
     fn hyperparameters(&self) -> HashMap<String, f64> {
         self.hyperparameters.clone()
     }
-    
+
     fn set_hyperparameters(&mut self, params: HashMap<String, f64>) -> Result<(), Error> {
-        // 等重みフラグをチェック
-        if let Some(equal_weights) = params.get("equal_weights") {
-            if *equal_weights > 0.5 && !self.models.is_empty() {
+        if let Some(period) = params.get("seasonal_period") {
+            if *period > 0.0 {
+                self.seasonal_period = *period as usize;
+            }
+        }
+
+        if let Some(iterations) = params.get("seasonality_iterations") {
+            self.seasonality_iterations = (*iterations as usize).max(1);
+        }
+
+        self.hyperparameters = params;
+        Ok(())
+    }
+}
+
+/// 勾配ブースティング木モデル（ランダムフォレストの代替としてGBDTを利用）
+///
+/// 線形回帰では捉えられない、ガス料金やシャード負荷に現れる閾値的な
+/// 非線形性を学習するためのモデル。内部では`gbdt`クレートの`GBDT`を
+/// 利用する。
+pub struct GradientBoostedTreesModel {
+    /// 学習済みフォレスト（未学習の場合は`None`）
+    forest: Option<GBDT>,
+    /// 特徴量名（`"target"`を除く、学習時に固定された順序）
+    feature_names: Vec<String>,
+    /// ハイパーパラメータ
+    hyperparameters: HashMap<String, f64>,
+    /// 学習済みかどうか
+    trained: bool,
+}
+
+impl GradientBoostedTreesModel {
+    /// 新しい勾配ブースティング木モデルを作成
+    pub fn new() -> Self {
+        let mut hyperparameters = HashMap::new();
+        hyperparameters.insert("n_trees".to_string(), 100.0);
+        hyperparameters.insert("max_depth".to_string(), 5.0);
+        hyperparameters.insert("learning_rate".to_string(), 0.1);
+        hyperparameters.insert("min_leaf_size".to_string(), 1.0);
+
+        Self {
+            forest: None,
+            feature_names: Vec::new(),
+            hyperparameters,
+            trained: false,
+        }
+    }
+
+    /// ハイパーパラメータから`gbdt::config::Config`を構築
+    fn build_config(&self, feature_size: usize) -> Config {
+        let mut config = Config::new();
+        config.feature_size = feature_size;
+        config.max_depth = self.hyperparameters.get("max_depth").copied().unwrap_or(5.0) as usize;
+        config.iterations = self.hyperparameters.get("n_trees").copied().unwrap_or(100.0) as usize;
+        config.shrinkage = self.hyperparameters.get("learning_rate").copied().unwrap_or(0.1) as f32;
+        config.min_leaf_size = self.hyperparameters.get("min_leaf_size").copied().unwrap_or(1.0) as usize;
+        config.loss = "SquaredError".to_owned();
+        config.debug = false;
+        config.feature_sample_ratio = 1.0;
+        config.data_sample_ratio = 1.0;
+        config.training_optimization_level = 2;
+        config
+    }
+
+    /// 保存先のフォレスト本体のファイルパス
+    fn forest_path(path: &str) -> String {
+        format!("{}.gbdt", path)
+    }
+}
+
+impl PredictionModel for GradientBoostedTreesModel {
+    fn train(&mut self, data: &[FeatureData]) -> Result<(), Error> {
+        if data.is_empty() {
+            return Err(Error::InvalidInput("学習データが空です".to_string()));
+        }
+
+        // "target"を除く特徴量名を安定した順序で確定させる
+        let mut feature_names: Vec<String> = data[0]
+            .values
+            .keys()
+            .filter(|name| name.as_str() != "target")
+            .cloned()
+            .collect();
+        feature_names.sort();
+
+        if feature_names.is_empty() {
+            return Err(Error::InvalidInput("特徴量が見つかりません".to_string()));
+        }
+
+        let mut training_data: DataVec = Vec::with_capacity(data.len());
+        for feature_data in data {
+            let mut feature = Vec::with_capacity(feature_names.len());
+            for name in &feature_names {
+                let value = feature_data.values.get(name).ok_or_else(|| {
+                    Error::InvalidInput(format!("特徴量 {} が見つかりません", name))
+                })?;
+                feature.push(*value as f32);
+            }
+
+            let label = *feature_data.values.get("target").ok_or_else(|| {
+                Error::InvalidInput("ターゲット値が見つかりません".to_string())
+            })? as f32;
+
+            training_data.push(Data {
+                feature,
+                target: label,
+                weight: 1.0,
+                label,
+                residual: label,
+                initial_guess: 0.0,
+            });
+        }
+
+        let config = self.build_config(feature_names.len());
+        let mut forest = GBDT::new(&config);
+        forest.fit(&mut training_data);
+
+        self.feature_names = feature_names;
+        self.forest = Some(forest);
+        self.trained = true;
+
+        Ok(())
+    }
+
+    fn predict(&self, features: &HashMap<String, Vec<f64>>) -> Result<Vec<f64>, Error> {
+        let forest = self
+            .forest
+            .as_ref()
+            .ok_or_else(|| Error::InvalidState("モデルが学習されていません".to_string()))?;
+
+        if !self.trained {
+            return Err(Error::InvalidState("モデルが学習されていません".to_string()));
+        }
+
+        let n_samples = features.values().next().map(|v| v.len()).unwrap_or(0);
+        if n_samples == 0 {
+            return Err(Error::InvalidInput("特徴量が空です".to_string()));
+        }
+
+        let mut rows: DataVec = Vec::with_capacity(n_samples);
+        for i in 0..n_samples {
+            let mut feature = Vec::with_capacity(self.feature_names.len());
+            for name in &self.feature_names {
+                let values = features.get(name).ok_or_else(|| {
+                    Error::InvalidInput(format!("特徴量 {} が見つかりません", name))
+                })?;
+                if values.len() != n_samples {
+                    return Err(Error::InvalidInput(format!(
+                        "特徴量 {} の長さが一致しません: {} != {}",
+                        name, values.len(), n_samples
+                    )));
+                }
+                feature.push(values[i] as f32);
+            }
+
+            rows.push(Data {
+                feature,
+                target: 0.0,
+                weight: 1.0,
+                label: 0.0,
+                residual: 0.0,
+                initial_guess: 0.0,
+            });
+        }
+
+        let predictions = forest.predict(&rows);
+        Ok(predictions.into_iter().map(|v| v as f64).collect())
+    }
+
+    fn save(&self, path: &str) -> Result<(), Error> {
+        let forest = self
+            .forest
+            .as_ref()
+            .ok_or_else(|| Error::InvalidState("モデルが学習されていません".to_string()))?;
+
+        forest.save_model(&Self::forest_path(path));
+
+        let model_data = serde_json::json!({
+            "model_type": "RandomForest",
+            "feature_names": self.feature_names,
+            "hyperparameters": self.hyperparameters,
+            "trained": self.trained,
+        });
+
+        std::fs::write(path, serde_json::to_string_pretty(&model_data)?)
+            .map_err(|e| Error::IOError(format!("モデルの保存に失敗しました: {}", e)))?;
+
+        Ok(())
+    }
+
+    fn load(&mut self, path: &str) -> Result<(), Error> {
+        let model_data = std::fs::read_to_string(path)
+            .map_err(|e| Error::IOError(format!("モデルの読み込みに失敗しました: {}", e)))?;
+
+        let model_json: serde_json::Value = serde_json::from_str(&model_data)
+            .map_err(|e| Error::ParseError(format!("モデルデータの解析に失敗しました: {}", e)))?;
+
+        let model_type = model_json["model_type"].as_str()
+            .ok_or_else(|| Error::ParseError("モデルタイプが見つかりません".to_string()))?;
+
+        if model_type != "RandomForest" {
+            return Err(Error::InvalidInput(format!(
+                "モデルタイプが一致しません: {} != RandomForest",
+                model_type
+            )));
+        }
+
+        self.feature_names = serde_json::from_value(model_json["feature_names"].clone())
+            .map_err(|e| Error::ParseError(format!("特徴量名の解析に失敗しました: {}", e)))?;
+
+        self.hyperparameters = serde_json::from_value(model_json["hyperparameters"].clone())
+            .map_err(|e| Error::ParseError(format!("ハイパーパラメータの解析に失敗しました: {}", e)))?;
+
+        self.trained = model_json["trained"].as_bool()
+            .ok_or_else(|| Error::ParseError("学習状態が見つかりません".to_string()))?;
+
+        let forest = GBDT::load_model(&Self::forest_path(path))
+            .map_err(|e| Error::IOError(format!("フォレストの読み込みに失敗しました: {:?}", e)))?;
+        self.forest = Some(forest);
+
+        Ok(())
+    }
+
+    fn model_type(&self) -> PredictionModelType {
+        PredictionModelType::RandomForest
+    }
+
+    fn name(&self) -> String {
+        "勾配ブースティング木モデル".to_string()
+    }
+
+    fn description(&self) -> String {
+        "GBDTによる非線形予測モデル。閾値的な挙動を示す特徴量に対して線形回帰より高い精度を発揮します。".to_string()
+    }
+
+    fn hyperparameters(&self) -> HashMap<String, f64> {
+        self.hyperparameters.clone()
+    }
+
+    fn set_hyperparameters(&mut self, params: HashMap<String, f64>) -> Result<(), Error> {
+        self.hyperparameters = params;
+        Ok(())
+    }
+}
+
+/// Holt-Winters三重指数平滑モデル（加法モデル）
+///
+/// 水準・トレンド・季節成分の3つの状態を観測ごとに更新する。
+/// `BlockTime`や`Volume`のような滑らかなトレンドを持つ指標向けの、
+/// オンライン更新可能な軽量な予測モデル。
+pub struct HoltWintersModel {
+    /// 水準の平滑化係数 α
+    alpha: f64,
+    /// トレンドの平滑化係数 β
+    beta: f64,
+    /// 季節成分の平滑化係数 γ
+    gamma: f64,
+    /// 季節周期長 S
+    season_length: usize,
+    /// 直近時点における水準
+    level: f64,
+    /// 直近時点におけるトレンド
+    trend: f64,
+    /// 季節成分ベクトル（長さ season_length）
+    seasonal: Vec<f64>,
+    /// 学習に使用した観測数（予測時の季節フェーズ計算に使用）
+    n_observations: usize,
+    /// ハイパーパラメータ
+    hyperparameters: HashMap<String, f64>,
+    /// 学習済みかどうか
+    trained: bool,
+}
+
+impl HoltWintersModel {
+    /// 新しいHolt-Wintersモデルを作成
+    pub fn new() -> Self {
+        let mut hyperparameters = HashMap::new();
+        hyperparameters.insert("alpha".to_string(), 0.3);
+        hyperparameters.insert("beta".to_string(), 0.1);
+        hyperparameters.insert("gamma".to_string(), 0.1);
+        hyperparameters.insert("season_length".to_string(), 24.0);
+
+        Self {
+            alpha: 0.3,
+            beta: 0.1,
+            gamma: 0.1,
+            season_length: 24,
+            level: 0.0,
+            trend: 0.0,
+            seasonal: vec![0.0; 24],
+            n_observations: 0,
+            hyperparameters,
+            trained: false,
+        }
+    }
+
+    /// 季節周期の長さを取得
+    pub fn seasonal_period(&self) -> usize {
+        self.season_length
+    }
+}
+
+impl PredictionModel for HoltWintersModel {
+    fn train(&mut self, data: &[FeatureData]) -> Result<(), Error> {
+        if data.is_empty() {
+            return Err(Error::InvalidInput("学習データが空です".to_string()));
+        }
+
+        let mut y = Vec::with_capacity(data.len());
+        for feature_data in data {
+            if let Some(target_value) = feature_data.values.get("target") {
+                y.push(*target_value);
+            } else {
+                return Err(Error::InvalidInput("ターゲット値が見つかりません".to_string()));
+            }
+        }
+
+        let n = y.len();
+
+        self.alpha = self.hyperparameters.get("alpha").copied().unwrap_or(0.3).clamp(0.0, 1.0);
+        self.beta = self.hyperparameters.get("beta").copied().unwrap_or(0.1).clamp(0.0, 1.0);
+        self.gamma = self.hyperparameters.get("gamma").copied().unwrap_or(0.1).clamp(0.0, 1.0);
+        let s = self
+            .hyperparameters
+            .get("season_length")
+            .copied()
+            .unwrap_or(24.0)
+            .max(1.0) as usize;
+
+        if n < 2 * s {
+            return Err(Error::InvalidInput(format!(
+                "学習データが季節周期の2周期分({})より短いです",
+                2 * s
+            )));
+        }
+
+        // 最初の2周期分から水準・トレンドを初期化し、最初の1周期分から季節成分を初期化する
+        let avg1 = y[0..s].iter().sum::<f64>() / s as f64;
+        let avg2 = y[s..2 * s].iter().sum::<f64>() / s as f64;
+
+        let mut seasonal = vec![0.0; s];
+        for i in 0..s {
+            seasonal[i] = y[i] - avg1;
+        }
+
+        let mut level = avg1;
+        let mut trend = (avg2 - avg1) / s as f64;
+
+        for (t, observed) in y.iter().enumerate() {
+            let s_prev = seasonal[t % s];
+
+            let new_level = self.alpha * (observed - s_prev) + (1.0 - self.alpha) * (level + trend);
+            let new_trend = self.beta * (new_level - level) + (1.0 - self.beta) * trend;
+            let new_seasonal = self.gamma * (observed - new_level) + (1.0 - self.gamma) * s_prev;
+
+            level = new_level;
+            trend = new_trend;
+            seasonal[t % s] = new_seasonal;
+        }
+
+        self.season_length = s;
+        self.level = level;
+        self.trend = trend;
+        self.seasonal = seasonal;
+        self.n_observations = n;
+        self.trained = true;
+
+        Ok(())
+    }
+
+    fn predict(&self, features: &HashMap<String, Vec<f64>>) -> Result<Vec<f64>, Error> {
+        if !self.trained {
+            return Err(Error::InvalidState("モデルが学習されていません".to_string()));
+        }
+
+        let n_samples = features.values().next().map(|v| v.len()).unwrap_or(0);
+        if n_samples == 0 {
+            return Err(Error::InvalidInput("特徴量が空です".to_string()));
+        }
+
+        let s = self.season_length.max(1);
+        let mut predictions = Vec::with_capacity(n_samples);
+
+        for i in 0..n_samples {
+            let h = i + 1;
+            let phase = (self.n_observations + h) % s;
+            let forecast = self.level + self.trend * h as f64 + self.seasonal[phase];
+            predictions.push(forecast);
+        }
+
+        Ok(predictions)
+    }
+
+    fn save(&self, path: &str) -> Result<(), Error> {
+        let model_data = serde_json::json!({
+            "model_type": "ExponentialSmoothing",
+            "alpha": self.alpha,
+            "beta": self.beta,
+            "gamma": self.gamma,
+            "season_length": self.season_length,
+            "level": self.level,
+            "trend": self.trend,
+            "seasonal": self.seasonal,
+            "n_observations": self.n_observations,
+            "hyperparameters": self.hyperparameters,
+            "trained": self.trained,
+        });
+
+        std::fs::write(path, serde_json::to_string_pretty(&model_data)?)
+            .map_err(|e| Error::IOError(format!("モデルの保存に失敗しました: {}", e)))?;
+
+        Ok(())
+    }
+
+    fn load(&mut self, path: &str) -> Result<(), Error> {
+        let model_data = std::fs::read_to_string(path)
+            .map_err(|e| Error::IOError(format!("モデルの読み込みに失敗しました: {}", e)))?;
+
+        let model_json: serde_json::Value = serde_json::from_str(&model_data)
+            .map_err(|e| Error::ParseError(format!("モデルデータの解析に失敗しました: {}", e)))?;
+
+        let model_type = model_json["model_type"].as_str()
+            .ok_or_else(|| Error::ParseError("モデルタイプが見つかりません".to_string()))?;
+
+        if model_type != "ExponentialSmoothing" {
+            return Err(Error::InvalidInput(format!(
+                "モデルタイプが一致しません: {} != ExponentialSmoothing",
+                model_type
+            )));
+        }
+
+        self.alpha = model_json["alpha"].as_f64()
+            .ok_or_else(|| Error::ParseError("αが見つかりません".to_string()))?;
+
+        self.beta = model_json["beta"].as_f64()
+            .ok_or_else(|| Error::ParseError("βが見つかりません".to_string()))?;
+
+        self.gamma = model_json["gamma"].as_f64()
+            .ok_or_else(|| Error::ParseError("γが見つかりません".to_string()))?;
+
+        self.season_length = model_json["season_length"].as_u64()
+            .ok_or_else(|| Error::ParseError("季節周期が見つかりません".to_string()))? as usize;
+
+        self.level = model_json["level"].as_f64()
+            .ok_or_else(|| Error::ParseError("水準が見つかりません".to_string()))?;
+
+        self.trend = model_json["trend"].as_f64()
+            .ok_or_else(|| Error::ParseError("トレンドが見つかりません".to_string()))?;
+
+        self.seasonal = serde_json::from_value(model_json["seasonal"].clone())
+            .map_err(|e| Error::ParseError(format!("季節成分の解析に失敗しました: {}", e)))?;
+
+        self.n_observations = model_json["n_observations"].as_u64()
+            .ok_or_else(|| Error::ParseError("観測数が見つかりません".to_string()))? as usize;
+
+        self.hyperparameters = serde_json::from_value(model_json["hyperparameters"].clone())
+            .map_err(|e| Error::ParseError(format!("ハイパーパラメータの解析に失敗しました: {}", e)))?;
+
+        self.trained = model_json["trained"].as_bool()
+            .ok_or_else(|| Error::ParseError("学習状態が見つかりません".to_string()))?;
+
+        Ok(())
+    }
+
+    fn model_type(&self) -> PredictionModelType {
+        PredictionModelType::ExponentialSmoothing
+    }
+
+    fn name(&self) -> String {
+        "Holt-Wintersモデル".to_string()
+    }
+
+    fn description(&self) -> String {
+        format!(
+            "季節周期{}の加法的Holt-Winters三重指数平滑モデル。水準・トレンド・季節成分を逐次更新します。",
+            self.season_length
+        )
+    }
+
+    fn hyperparameters(&self) -> HashMap<String, f64> {
+        self.hyperparameters.clone()
+    }
+
+    fn set_hyperparameters(&mut self, params: HashMap<String, f64>) -> Result<(), Error> {
+        if let Some(alpha) = params.get("alpha") {
+            self.alpha = alpha.clamp(0.0, 1.0);
+        }
+        if let Some(beta) = params.get("beta") {
+            self.beta = beta.clamp(0.0, 1.0);
+        }
+        if let Some(gamma) = params.get("gamma") {
+            self.gamma = gamma.clamp(0.0, 1.0);
+        }
+        if let Some(season_length) = params.get("season_length") {
+            if *season_length > 0.0 {
+                self.season_length = *season_length as usize;
+            }
+        }
+
+        self.hyperparameters = params;
+        Ok(())
+    }
+}
+
+/// フィードフォワードMLP（多層パーセプトロン）モデル
+///
+/// 隠れ層のサイズ・活性化関数・学習率などをハイパーパラメータから構成し、
+/// `FeatureData`をミニバッチ勾配降下法で学習する回帰モデル。ガス料金や
+/// 出来高のように桁数の異なる特徴量を扱うため、学習時に各特徴量の平均・
+/// 標準偏差を記録して標準化する。
+pub struct MlpModel {
+    /// 各層のユニット数（入力層 → 隠れ層 → 出力層(1)）
+    layer_sizes: Vec<usize>,
+    /// 隠れ層の活性化関数（"relu" または "tanh"）
+    activation: String,
+    /// 各層の重み行列（`weights[l][out][in]`）
+    weights: Vec<Vec<Vec<f64>>>,
+    /// 各層のバイアスベクトル（`biases[l][out]`）
+    biases: Vec<Vec<f64>>,
+    /// 特徴量名（学習時に固定された順序、`"target"`を除く）
+    feature_names: Vec<String>,
+    /// 標準化に使う各特徴量の平均
+    feature_mean: Vec<f64>,
+    /// 標準化に使う各特徴量の標準偏差
+    feature_std: Vec<f64>,
+    /// ハイパーパラメータ
+    hyperparameters: HashMap<String, f64>,
+    /// 学習済みかどうか
+    trained: bool,
+}
+
+impl MlpModel {
+    /// 新しいMLPモデルを作成
+    pub fn new() -> Self {
+        let mut hyperparameters = HashMap::new();
+        hyperparameters.insert("hidden1".to_string(), 8.0);
+        hyperparameters.insert("hidden2".to_string(), 0.0);
+        hyperparameters.insert("epochs".to_string(), 100.0);
+        hyperparameters.insert("batch_size".to_string(), 16.0);
+        hyperparameters.insert("learning_rate".to_string(), 0.01);
+        hyperparameters.insert("activation".to_string(), 0.0);
+
+        Self {
+            layer_sizes: Vec::new(),
+            activation: "relu".to_string(),
+            weights: Vec::new(),
+            biases: Vec::new(),
+            feature_names: Vec::new(),
+            feature_mean: Vec::new(),
+            feature_std: Vec::new(),
+            hyperparameters,
+            trained: false,
+        }
+    }
+
+    /// 活性化関数の名前（ハイパーパラメータ`"activation"`が0.5以上なら"tanh"、それ以外は"relu"）
+    fn activation_name(&self) -> String {
+        if self.hyperparameters.get("activation").copied().unwrap_or(0.0) >= 0.5 {
+            "tanh".to_string()
+        } else {
+            "relu".to_string()
+        }
+    }
+
+    /// 隠れ層のサイズ一覧（0は無効な層として除外）
+    fn hidden_layer_sizes(&self) -> Vec<usize> {
+        let mut hidden = Vec::new();
+        if let Some(h1) = self.hyperparameters.get("hidden1") {
+            if *h1 >= 1.0 {
+                hidden.push(*h1 as usize);
+            }
+        }
+        if let Some(h2) = self.hyperparameters.get("hidden2") {
+            if *h2 >= 1.0 {
+                hidden.push(*h2 as usize);
+            }
+        }
+        if hidden.is_empty() {
+            hidden.push(8);
+        }
+        hidden
+    }
+
+    fn activate(activation: &str, x: f64) -> f64 {
+        match activation {
+            "tanh" => x.tanh(),
+            _ => x.max(0.0),
+        }
+    }
+
+    fn activate_derivative(activation: &str, activated: f64) -> f64 {
+        match activation {
+            "tanh" => 1.0 - activated * activated,
+            _ => if activated > 0.0 { 1.0 } else { 0.0 },
+        }
+    }
+
+    /// 重み・バイアスを小さな乱数で初期化
+    fn init_weights(layer_sizes: &[usize]) -> (Vec<Vec<Vec<f64>>>, Vec<Vec<f64>>) {
+        use rand::Rng;
+        let mut rng = rand::thread_rng();
+
+        let mut weights = Vec::with_capacity(layer_sizes.len() - 1);
+        let mut biases = Vec::with_capacity(layer_sizes.len() - 1);
+
+        for l in 0..layer_sizes.len() - 1 {
+            let fan_in = layer_sizes[l];
+            let fan_out = layer_sizes[l + 1];
+            let limit = (1.0 / fan_in as f64).sqrt();
+
+            let mut layer_weights = Vec::with_capacity(fan_out);
+            for _ in 0..fan_out {
+                let row: Vec<f64> = (0..fan_in)
+                    .map(|_| rng.gen_range(-limit..limit))
+                    .collect();
+                layer_weights.push(row);
+            }
+            weights.push(layer_weights);
+            biases.push(vec![0.0; fan_out]);
+        }
+
+        (weights, biases)
+    }
+
+    /// 入力ベクトルに対する順伝播。各層の活性化後の値を返す
+    /// （`activations[0]`が入力そのもの、最後が出力層の値）。
+    fn forward(&self, input: &[f64]) -> Vec<Vec<f64>> {
+        let mut activations = vec![input.to_vec()];
+        let n_layers = self.weights.len();
+
+        for l in 0..n_layers {
+            let prev = &activations[l];
+            let is_output = l == n_layers - 1;
+            let mut layer_output = Vec::with_capacity(self.weights[l].len());
+
+            for (neuron_weights, bias) in self.weights[l].iter().zip(self.biases[l].iter()) {
+                let sum: f64 = neuron_weights
+                    .iter()
+                    .zip(prev.iter())
+                    .map(|(w, x)| w * x)
+                    .sum::<f64>()
+                    + bias;
+
+                let value = if is_output {
+                    sum
+                } else {
+                    Self::activate(&self.activation, sum)
+                };
+                layer_output.push(value);
+            }
+
+            activations.push(layer_output);
+        }
+
+        activations
+    }
+
+    fn standardize(&self, raw: &[f64]) -> Vec<f64> {
+        raw.iter()
+            .zip(self.feature_mean.iter())
+            .zip(self.feature_std.iter())
+            .map(|((value, mean), std)| {
+                if *std > 1e-12 {
+                    (value - mean) / std
+                } else {
+                    0.0
+                }
+            })
+            .collect()
+    }
+}
+
+impl PredictionModel for MlpModel {
+    fn train(&mut self, data: &[FeatureData]) -> Result<(), Error> {
+        if data.is_empty() {
+            return Err(Error::InvalidInput("学習データが空です".to_string()));
+        }
+
+        let mut feature_names: Vec<String> = data[0]
+            .values
+            .keys()
+            .filter(|name| name.as_str() != "target")
+            .cloned()
+            .collect();
+        feature_names.sort();
+
+        if feature_names.is_empty() {
+            return Err(Error::InvalidInput("特徴量が見つかりません".to_string()));
+        }
+
+        let mut raw_rows = Vec::with_capacity(data.len());
+        let mut targets = Vec::with_capacity(data.len());
+        for feature_data in data {
+            let mut row = Vec::with_capacity(feature_names.len());
+            for name in &feature_names {
+                let value = feature_data.values.get(name).ok_or_else(|| {
+                    Error::InvalidInput(format!("特徴量 {} が見つかりません", name))
+                })?;
+                row.push(*value);
+            }
+            raw_rows.push(row);
+
+            let target = feature_data.values.get("target").ok_or_else(|| {
+                Error::InvalidInput("ターゲット値が見つかりません".to_string())
+            })?;
+            targets.push(*target);
+        }
+
+        // 各特徴量の平均・標準偏差を計算して標準化する
+        let n = raw_rows.len() as f64;
+        let mut mean = vec![0.0; feature_names.len()];
+        for row in &raw_rows {
+            for (i, value) in row.iter().enumerate() {
+                mean[i] += value / n;
+            }
+        }
+        let mut std = vec![0.0; feature_names.len()];
+        for row in &raw_rows {
+            for (i, value) in row.iter().enumerate() {
+                std[i] += (value - mean[i]).powi(2) / n;
+            }
+        }
+        for s in std.iter_mut() {
+            *s = s.sqrt();
+        }
+
+        let rows: Vec<Vec<f64>> = raw_rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(mean.iter())
+                    .zip(std.iter())
+                    .map(|((value, m), s)| if *s > 1e-12 { (value - m) / s } else { 0.0 })
+                    .collect()
+            })
+            .collect();
+
+        self.activation = self.activation_name();
+        let mut layer_sizes = vec![feature_names.len()];
+        layer_sizes.extend(self.hidden_layer_sizes());
+        layer_sizes.push(1);
+
+        let (mut weights, mut biases) = Self::init_weights(&layer_sizes);
+
+        let epochs = self.hyperparameters.get("epochs").copied().unwrap_or(100.0).max(1.0) as usize;
+        let batch_size = self
+            .hyperparameters
+            .get("batch_size")
+            .copied()
+            .unwrap_or(16.0)
+            .max(1.0) as usize;
+        let learning_rate = self.hyperparameters.get("learning_rate").copied().unwrap_or(0.01);
+
+        let n_samples = rows.len();
+        for _epoch in 0..epochs {
+            let mut start = 0;
+            while start < n_samples {
+                let end = (start + batch_size).min(n_samples);
+                let batch_len = (end - start) as f64;
+
+                let n_layers = weights.len();
+                let mut weight_gradients: Vec<Vec<Vec<f64>>> = weights
+                    .iter()
+                    .map(|layer| layer.iter().map(|row| vec![0.0; row.len()]).collect())
+                    .collect();
+                let mut bias_gradients: Vec<Vec<f64>> =
+                    biases.iter().map(|layer| vec![0.0; layer.len()]).collect();
+
+                for idx in start..end {
+                    // 順伝播（現在の重みで手動展開）
+                    let mut activations = vec![rows[idx].clone()];
+                    for l in 0..n_layers {
+                        let prev = &activations[l];
+                        let is_output = l == n_layers - 1;
+                        let mut layer_output = Vec::with_capacity(weights[l].len());
+                        for (neuron_weights, bias) in weights[l].iter().zip(biases[l].iter()) {
+                            let sum: f64 = neuron_weights
+                                .iter()
+                                .zip(prev.iter())
+                                .map(|(w, x)| w * x)
+                                .sum::<f64>()
+                                + bias;
+                            let value = if is_output {
+                                sum
+                            } else {
+                                Self::activate(&self.activation, sum)
+                            };
+                            layer_output.push(value);
+                        }
+                        activations.push(layer_output);
+                    }
+
+                    // 誤差逆伝播
+                    let output = activations[n_layers][0];
+                    let mut deltas = vec![output - targets[idx]];
+
+                    for l in (0..n_layers).rev() {
+                        let prev_activation = &activations[l];
+                        for (j, delta) in deltas.iter().enumerate() {
+                            for (i, prev_value) in prev_activation.iter().enumerate() {
+                                weight_gradients[l][j][i] += delta * prev_value;
+                            }
+                            bias_gradients[l][j] += delta;
+                        }
+
+                        if l > 0 {
+                            let mut next_deltas = vec![0.0; prev_activation.len()];
+                            for (j, delta) in deltas.iter().enumerate() {
+                                for (i, next_delta) in next_deltas.iter_mut().enumerate() {
+                                    *next_delta += delta * weights[l][j][i];
+                                }
+                            }
+                            for (i, next_delta) in next_deltas.iter_mut().enumerate() {
+                                *next_delta *= Self::activate_derivative(&self.activation, prev_activation[i]);
+                            }
+                            deltas = next_deltas;
+                        }
+                    }
+                }
+
+                for l in 0..n_layers {
+                    for (j, neuron_weights) in weights[l].iter_mut().enumerate() {
+                        for (i, weight) in neuron_weights.iter_mut().enumerate() {
+                            *weight -= learning_rate * weight_gradients[l][j][i] / batch_len;
+                        }
+                        biases[l][j] -= learning_rate * bias_gradients[l][j] / batch_len;
+                    }
+                }
+
+                start = end;
+            }
+        }
+
+        self.feature_names = feature_names;
+        self.feature_mean = mean;
+        self.feature_std = std;
+        self.layer_sizes = layer_sizes;
+        self.weights = weights;
+        self.biases = biases;
+        self.trained = true;
+
+        Ok(())
+    }
+
+    fn predict(&self, features: &HashMap<String, Vec<f64>>) -> Result<Vec<f64>, Error> {
+        if !self.trained {
+            return Err(Error::InvalidState("モデルが学習されていません".to_string()));
+        }
+
+        let n_samples = features.values().next().map(|v| v.len()).unwrap_or(0);
+        if n_samples == 0 {
+            return Err(Error::InvalidInput("特徴量が空です".to_string()));
+        }
+
+        let mut predictions = Vec::with_capacity(n_samples);
+        for i in 0..n_samples {
+            let mut raw = Vec::with_capacity(self.feature_names.len());
+            for name in &self.feature_names {
+                let values = features.get(name).ok_or_else(|| {
+                    Error::InvalidInput(format!("特徴量 {} が見つかりません", name))
+                })?;
+                if values.len() != n_samples {
+                    return Err(Error::InvalidInput(format!(
+                        "特徴量 {} の長さが一致しません: {} != {}",
+                        name, values.len(), n_samples
+                    )));
+                }
+                raw.push(values[i]);
+            }
+
+            let standardized = self.standardize(&raw);
+            let activations = self.forward(&standardized);
+            let output = activations.last().and_then(|layer| layer.first()).copied().unwrap_or(0.0);
+            predictions.push(output);
+        }
+
+        Ok(predictions)
+    }
+
+    fn save(&self, path: &str) -> Result<(), Error> {
+        if !self.trained {
+            return Err(Error::InvalidState("モデルが学習されていません".to_string()));
+        }
+
+        let layers: Vec<serde_json::Value> = self
+            .weights
+            .iter()
+            .zip(self.biases.iter())
+            .map(|(weight_matrix, bias_vector)| {
+                serde_json::json!({
+                    "dimension": [weight_matrix.len(), weight_matrix.first().map(|row| row.len()).unwrap_or(0)],
+                    "weights": weight_matrix,
+                    "bias": {
+                        "dimension": [bias_vector.len()],
+                        "values": bias_vector,
+                    },
+                })
+            })
+            .collect();
+
+        let model_data = serde_json::json!({
+            "model_type": "NeuralNetwork",
+            "layer_sizes": self.layer_sizes,
+            "activation": self.activation,
+            "layers": layers,
+            "feature_names": self.feature_names,
+            "feature_mean": self.feature_mean,
+            "feature_std": self.feature_std,
+            "hyperparameters": self.hyperparameters,
+            "trained": self.trained,
+        });
+
+        std::fs::write(path, serde_json::to_string_pretty(&model_data)?)
+            .map_err(|e| Error::IOError(format!("モデルの保存に失敗しました: {}", e)))?;
+
+        Ok(())
+    }
+
+    fn load(&mut self, path: &str) -> Result<(), Error> {
+        let model_data = std::fs::read_to_string(path)
+            .map_err(|e| Error::IOError(format!("モデルの読み込みに失敗しました: {}", e)))?;
+
+        let model_json: serde_json::Value = serde_json::from_str(&model_data)
+            .map_err(|e| Error::ParseError(format!("モデルデータの解析に失敗しました: {}", e)))?;
+
+        let model_type = model_json["model_type"].as_str()
+            .ok_or_else(|| Error::ParseError("モデルタイプが見つかりません".to_string()))?;
+
+        if model_type != "NeuralNetwork" {
+            return Err(Error::InvalidInput(format!(
+                "モデルタイプが一致しません: {} != NeuralNetwork",
+                model_type
+            )));
+        }
+
+        self.layer_sizes = serde_json::from_value(model_json["layer_sizes"].clone())
+            .map_err(|e| Error::ParseError(format!("層サイズの解析に失敗しました: {}", e)))?;
+
+        self.activation = model_json["activation"].as_str()
+            .ok_or_else(|| Error::ParseError("活性化関数が見つかりません".to_string()))?
+            .to_string();
+
+        let layers = model_json["layers"].as_array()
+            .ok_or_else(|| Error::ParseError("層データが見つかりません".to_string()))?;
+
+        let mut weights = Vec::with_capacity(layers.len());
+        let mut biases = Vec::with_capacity(layers.len());
+        for layer in layers {
+            let weight_matrix: Vec<Vec<f64>> = serde_json::from_value(layer["weights"].clone())
+                .map_err(|e| Error::ParseError(format!("重みの解析に失敗しました: {}", e)))?;
+            let bias_vector: Vec<f64> = serde_json::from_value(layer["bias"]["values"].clone())
+                .map_err(|e| Error::ParseError(format!("バイアスの解析に失敗しました: {}", e)))?;
+            weights.push(weight_matrix);
+            biases.push(bias_vector);
+        }
+        self.weights = weights;
+        self.biases = biases;
+
+        self.feature_names = serde_json::from_value(model_json["feature_names"].clone())
+            .map_err(|e| Error::ParseError(format!("特徴量名の解析に失敗しました: {}", e)))?;
+
+        self.feature_mean = serde_json::from_value(model_json["feature_mean"].clone())
+            .map_err(|e| Error::ParseError(format!("特徴量平均の解析に失敗しました: {}", e)))?;
+
+        self.feature_std = serde_json::from_value(model_json["feature_std"].clone())
+            .map_err(|e| Error::ParseError(format!("特徴量標準偏差の解析に失敗しました: {}", e)))?;
+
+        self.hyperparameters = serde_json::from_value(model_json["hyperparameters"].clone())
+            .map_err(|e| Error::ParseError(format!("ハイパーパラメータの解析に失敗しました: {}", e)))?;
+
+        self.trained = model_json["trained"].as_bool()
+            .ok_or_else(|| Error::ParseError("学習状態が見つかりません".to_string()))?;
+
+        Ok(())
+    }
+
+    fn model_type(&self) -> PredictionModelType {
+        PredictionModelType::NeuralNetwork
+    }
+
+    fn name(&self) -> String {
+        "多層パーセプトロンモデル".to_string()
+    }
+
+    fn description(&self) -> String {
+        "ミニバッチ勾配降下法で学習するフィードフォワードニューラルネットワーク。特徴量間の非線形な相互作用を捉えます。".to_string()
+    }
+
+    fn hyperparameters(&self) -> HashMap<String, f64> {
+        self.hyperparameters.clone()
+    }
+
+    fn set_hyperparameters(&mut self, params: HashMap<String, f64>) -> Result<(), Error> {
+        self.hyperparameters = params;
+        Ok(())
+    }
+}
+
+/// 季節性モデル
+///
+/// 季節周期`seasonality`のフェーズごとに履歴値の平均・標準偏差を計算し、
+/// それをそのまま予測値・信頼区間の根拠として使う。トレンドを追うため
+/// 直近値の指数平滑（係数α）をフェーズ平均とブレンドする。
+pub struct SeasonalModel {
+    /// 季節周期長 S
+    seasonality: usize,
+    /// フェーズ統計量の計算に使う最大サイクル数 K
+    seasonality_iterations: usize,
+    /// トレンド追従用の指数平滑係数 α
+    alpha: f64,
+    /// フェーズごとの平均値（長さ seasonality）
+    phase_means: Vec<f64>,
+    /// フェーズごとの標準偏差（長さ seasonality）
+    phase_stds: Vec<f64>,
+    /// 直近値の指数平滑によるレベル
+    level: f64,
+    /// 学習に使用した観測数
+    n_observations: usize,
+    /// ハイパーパラメータ
+    hyperparameters: HashMap<String, f64>,
+    /// 学習済みかどうか
+    trained: bool,
+}
+
+impl SeasonalModel {
+    /// 新しい季節性モデルを作成
+    pub fn new() -> Self {
+        let mut hyperparameters = HashMap::new();
+        hyperparameters.insert("seasonality".to_string(), 24.0);
+        hyperparameters.insert("seasonality_iterations".to_string(), 10.0);
+        hyperparameters.insert("alpha".to_string(), 0.3);
+
+        Self {
+            seasonality: 24,
+            seasonality_iterations: 10,
+            alpha: 0.3,
+            phase_means: vec![0.0; 24],
+            phase_stds: vec![0.0; 24],
+            level: 0.0,
+            n_observations: 0,
+            hyperparameters,
+            trained: false,
+        }
+    }
+}
+
+impl PredictionModel for SeasonalModel {
+    fn train(&mut self, data: &[FeatureData]) -> Result<(), Error> {
+        if data.is_empty() {
+            return Err(Error::InvalidInput("学習データが空です".to_string()));
+        }
+
+        let mut y = Vec::with_capacity(data.len());
+        for feature_data in data {
+            if let Some(target_value) = feature_data.values.get("target") {
+                y.push(*target_value);
+            } else {
+                return Err(Error::InvalidInput("ターゲット値が見つかりません".to_string()));
+            }
+        }
+
+        let s = self
+            .hyperparameters
+            .get("seasonality")
+            .copied()
+            .unwrap_or(24.0)
+            .max(1.0) as usize;
+        let k = self
+            .hyperparameters
+            .get("seasonality_iterations")
+            .copied()
+            .unwrap_or(10.0)
+            .max(1.0) as usize;
+        let alpha = self.hyperparameters.get("alpha").copied().unwrap_or(0.3).clamp(0.0, 1.0);
+
+        if y.len() < s {
+            return Err(Error::InvalidInput(format!(
+                "学習データが季節周期({})より短いです",
+                s
+            )));
+        }
+
+        let mut phase_means = vec![0.0; s];
+        let mut phase_stds = vec![0.0; s];
+
+        for p in 0..s {
+            let mut samples = Vec::new();
+            let mut cycle = 0;
+            let mut index = p;
+            while index < y.len() && cycle < k {
+                let value = y[index];
+                if !value.is_nan() {
+                    samples.push(value);
+                }
+                index += s;
+                cycle += 1;
+            }
+
+            if samples.is_empty() {
+                phase_means[p] = 0.0;
+                phase_stds[p] = 0.0;
+                continue;
+            }
+
+            let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+            let variance = samples.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / samples.len() as f64;
+
+            phase_means[p] = mean;
+            phase_stds[p] = variance.sqrt();
+        }
+
+        // 直近値の指数平滑でトレンドを追うレベルを計算する(NaNはスキップ)
+        let mut level = None;
+        for value in &y {
+            if value.is_nan() {
+                continue;
+            }
+            level = Some(match level {
+                None => *value,
+                Some(prev) => alpha * value + (1.0 - alpha) * prev,
+            });
+        }
+
+        self.seasonality = s;
+        self.seasonality_iterations = k;
+        self.alpha = alpha;
+        self.phase_means = phase_means;
+        self.phase_stds = phase_stds;
+        self.level = level.unwrap_or(0.0);
+        self.n_observations = y.len();
+        self.trained = true;
+
+        Ok(())
+    }
+
+    fn predict(&self, features: &HashMap<String, Vec<f64>>) -> Result<Vec<f64>, Error> {
+        if !self.trained {
+            return Err(Error::InvalidState("モデルが学習されていません".to_string()));
+        }
+
+        let n_samples = features.values().next().map(|v| v.len()).unwrap_or(0);
+        if n_samples == 0 {
+            return Err(Error::InvalidInput("特徴量が空です".to_string()));
+        }
+
+        let s = self.seasonality.max(1);
+        let mut predictions = Vec::with_capacity(n_samples);
+
+        for i in 0..n_samples {
+            let h = i + 1;
+            let phase = (self.n_observations + h) % s;
+            let phase_mean = self.phase_means[phase];
+            let forecast = self.alpha * self.level + (1.0 - self.alpha) * phase_mean;
+            predictions.push(forecast);
+        }
+
+        Ok(predictions)
+    }
+
+    fn save(&self, path: &str) -> Result<(), Error> {
+        let model_data = serde_json::json!({
+            "model_type": "Seasonal",
+            "seasonality": self.seasonality,
+            "seasonality_iterations": self.seasonality_iterations,
+            "alpha": self.alpha,
+            "phase_means": self.phase_means,
+            "phase_stds": self.phase_stds,
+            "level": self.level,
+            "n_observations": self.n_observations,
+            "hyperparameters": self.hyperparameters,
+            "trained": self.trained,
+        });
+
+        std::fs::write(path, serde_json::to_string_pretty(&model_data)?)
+            .map_err(|e| Error::IOError(format!("モデルの保存に失敗しました: {}", e)))?;
+
+        Ok(())
+    }
+
+    fn load(&mut self, path: &str) -> Result<(), Error> {
+        let model_data = std::fs::read_to_string(path)
+            .map_err(|e| Error::IOError(format!("モデルの読み込みに失敗しました: {}", e)))?;
+
+        let model_json: serde_json::Value = serde_json::from_str(&model_data)
+            .map_err(|e| Error::ParseError(format!("モデルデータの解析に失敗しました: {}", e)))?;
+
+        let model_type = model_json["model_type"].as_str()
+            .ok_or_else(|| Error::ParseError("モデルタイプが見つかりません".to_string()))?;
+
+        if model_type != "Seasonal" {
+            return Err(Error::InvalidInput(format!(
+                "モデルタイプが一致しません: {} != Seasonal",
+                model_type
+            )));
+        }
+
+        self.seasonality = model_json["seasonality"].as_u64()
+            .ok_or_else(|| Error::ParseError("季節周期が見つかりません".to_string()))? as usize;
+
+        self.seasonality_iterations = model_json["seasonality_iterations"].as_u64()
+            .ok_or_else(|| Error::ParseError("最大サイクル数が見つかりません".to_string()))? as usize;
+
+        self.alpha = model_json["alpha"].as_f64()
+            .ok_or_else(|| Error::ParseError("αが見つかりません".to_string()))?;
+
+        self.phase_means = serde_json::from_value(model_json["phase_means"].clone())
+            .map_err(|e| Error::ParseError(format!("フェーズ平均の解析に失敗しました: {}", e)))?;
+
+        self.phase_stds = serde_json::from_value(model_json["phase_stds"].clone())
+            .map_err(|e| Error::ParseError(format!("フェーズ標準偏差の解析に失敗しました: {}", e)))?;
+
+        self.level = model_json["level"].as_f64()
+            .ok_or_else(|| Error::ParseError("レベルが見つかりません".to_string()))?;
+
+        self.n_observations = model_json["n_observations"].as_u64()
+            .ok_or_else(|| Error::ParseError("観測数が見つかりません".to_string()))? as usize;
+
+        self.hyperparameters = serde_json::from_value(model_json["hyperparameters"].clone())
+            .map_err(|e| Error::ParseError(format!("ハイパーパラメータの解析に失敗しました: {}", e)))?;
+
+        self.trained = model_json["trained"].as_bool()
+            .ok_or_else(|| Error::ParseError("学習状態が見つかりません".to_string()))?;
+
+        Ok(())
+    }
+
+    fn model_type(&self) -> PredictionModelType {
+        PredictionModelType::Seasonal
+    }
+
+    fn name(&self) -> String {
+        "季節性モデル".to_string()
+    }
+
+    fn description(&self) -> String {
+        format!(
+            "季節周期{}のフェーズ統計に基づく季節性予測モデル。実測値から推定した残差標準偏差を信頼区間に反映します。",
+            self.seasonality
+        )
+    }
+
+    fn hyperparameters(&self) -> HashMap<String, f64> {
+        self.hyperparameters.clone()
+    }
+
+    fn set_hyperparameters(&mut self, params: HashMap<String, f64>) -> Result<(), Error> {
+        if let Some(alpha) = params.get("alpha") {
+            self.alpha = alpha.clamp(0.0, 1.0);
+        }
+        if let Some(seasonality) = params.get("seasonality") {
+            if *seasonality >= 1.0 {
+                self.seasonality = *seasonality as usize;
+            }
+        }
+        if let Some(iterations) = params.get("seasonality_iterations") {
+            if *iterations >= 1.0 {
+                self.seasonality_iterations = *iterations as usize;
+            }
+        }
+
+        self.hyperparameters = params;
+        Ok(())
+    }
+
+    fn residual_std(&self, step_index: usize) -> Option<f64> {
+        if !self.trained {
+            return None;
+        }
+        let s = self.seasonality.max(1);
+        let h = step_index + 1;
+        let phase = (self.n_observations + h) % s;
+        self.phase_stds.get(phase).copied()
+    }
+}
+
+/// パターン特徴抽出に使うFFTのサンプル長
+const PATTERN_FFT_LEN: usize = 64;
+/// 特徴量として採用するFFTの下位ビン数（直流成分のビン0を含む）
+const PATTERN_FFT_BINS: usize = 16;
+/// 1ウィンドウから抽出する特徴量の総数（基本統計量4 + FFT実部/虚部32）
+const PATTERN_FEATURE_LEN: usize = 4 + PATTERN_FFT_BINS * 2;
+
+/// `target`の直近ウィンドウから基本統計量とFFTスペクトルを抽出し、
+/// `PATTERN_FEATURE_LEN`次元の特徴ベクトルを返す。
+///
+/// ウィンドウが`PATTERN_FFT_LEN`に満たない場合は先頭をゼロ埋めし、
+/// NaNは0として扱う。
+fn extract_pattern_features(window: &[f64]) -> Vec<f32> {
+    let take = window.len().min(PATTERN_FFT_LEN);
+    let start = window.len() - take;
+
+    let mut samples = vec![0.0f64; PATTERN_FFT_LEN];
+    for (i, value) in window[start..].iter().enumerate() {
+        let value = if value.is_nan() { 0.0 } else { *value };
+        samples[PATTERN_FFT_LEN - take + i] = value;
+    }
+
+    let mean = samples.iter().sum::<f64>() / PATTERN_FFT_LEN as f64;
+    let min = samples.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = samples.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let slope = samples[PATTERN_FFT_LEN - 1] - samples[0];
+
+    let mut planner = FftPlanner::<f64>::new();
+    let fft = planner.plan_fft_forward(PATTERN_FFT_LEN);
+    let mut buffer: Vec<Complex<f64>> =
+        samples.iter().map(|value| Complex::new(*value, 0.0)).collect();
+    fft.process(&mut buffer);
+
+    let mut features = Vec::with_capacity(PATTERN_FEATURE_LEN);
+    features.push(mean as f32);
+    features.push(min as f32);
+    features.push(max as f32);
+    features.push(slope as f32);
+    for bin in buffer.iter().take(PATTERN_FFT_BINS) {
+        features.push(bin.re as f32);
+        features.push(bin.im as f32);
+    }
+
+    features
+}
+
+/// FFT特徴量 + 勾配ブースティング木によるパターン認識モデル
+///
+/// 線形回帰やHolt-Wintersでは捉えられない、相場の「形」（レジーム）を
+/// 認識するためのモデル。`target`の直近ウィンドウごとに基本統計量と
+/// スペクトル特徴量を抽出し、`gbdt`クレートでその先の値を学習する。
+pub struct PatternModel {
+    /// 学習済みフォレスト（未学習の場合は`None`）
+    forest: Option<GBDT>,
+    /// ハイパーパラメータ
+    hyperparameters: HashMap<String, f64>,
+    /// 学習済みかどうか
+    trained: bool,
+}
+
+impl PatternModel {
+    /// 新しいパターン認識モデルを作成
+    pub fn new() -> Self {
+        let mut hyperparameters = HashMap::new();
+        hyperparameters.insert("n_trees".to_string(), 100.0);
+        hyperparameters.insert("max_depth".to_string(), 5.0);
+        hyperparameters.insert("learning_rate".to_string(), 0.1);
+        hyperparameters.insert("min_leaf_size".to_string(), 1.0);
+
+        Self {
+            forest: None,
+            hyperparameters,
+            trained: false,
+        }
+    }
+
+    /// ハイパーパラメータから`gbdt::config::Config`を構築
+    fn build_config(&self) -> Config {
+        let mut config = Config::new();
+        config.feature_size = PATTERN_FEATURE_LEN;
+        config.max_depth = self.hyperparameters.get("max_depth").copied().unwrap_or(5.0) as usize;
+        config.iterations = self.hyperparameters.get("n_trees").copied().unwrap_or(100.0) as usize;
+        config.shrinkage = self.hyperparameters.get("learning_rate").copied().unwrap_or(0.1) as f32;
+        config.min_leaf_size = self.hyperparameters.get("min_leaf_size").copied().unwrap_or(1.0) as usize;
+        config.loss = "SquaredError".to_owned();
+        config.debug = false;
+        config.feature_sample_ratio = 1.0;
+        config.data_sample_ratio = 1.0;
+        config.training_optimization_level = 2;
+        config
+    }
+
+    /// 保存先のフォレスト本体のファイルパス
+    fn forest_path(path: &str) -> String {
+        format!("{}.gbdt", path)
+    }
+}
+
+impl PredictionModel for PatternModel {
+    fn train(&mut self, data: &[FeatureData]) -> Result<(), Error> {
+        if data.is_empty() {
+            return Err(Error::InvalidInput("学習データが空です".to_string()));
+        }
+
+        let mut target_series = Vec::with_capacity(data.len());
+        for feature_data in data {
+            let value = feature_data.values.get("target").ok_or_else(|| {
+                Error::InvalidInput("ターゲット値が見つかりません".to_string())
+            })?;
+            target_series.push(*value);
+        }
+
+        if target_series.len() < 2 {
+            return Err(Error::InvalidInput("学習データが不足しています".to_string()));
+        }
+
+        // ウィンドウ target_series[0..i] からtarget_series[i]を予測するように学習する
+        let mut training_data: DataVec = Vec::with_capacity(target_series.len() - 1);
+        for i in 1..target_series.len() {
+            let feature = extract_pattern_features(&target_series[..i]);
+            let label = target_series[i] as f32;
+
+            training_data.push(Data {
+                feature,
+                target: label,
+                weight: 1.0,
+                label,
+                residual: label,
+                initial_guess: 0.0,
+            });
+        }
+
+        let config = self.build_config();
+        let mut forest = GBDT::new(&config);
+        forest.fit(&mut training_data);
+
+        self.forest = Some(forest);
+        self.trained = true;
+
+        Ok(())
+    }
+
+    fn predict(&self, features: &HashMap<String, Vec<f64>>) -> Result<Vec<f64>, Error> {
+        let forest = self
+            .forest
+            .as_ref()
+            .ok_or_else(|| Error::InvalidState("モデルが学習されていません".to_string()))?;
+
+        if !self.trained {
+            return Err(Error::InvalidState("モデルが学習されていません".to_string()));
+        }
+
+        let target_series = features.get("target").ok_or_else(|| {
+            Error::InvalidInput("ターゲット特徴量が見つかりません".to_string())
+        })?;
+
+        if target_series.is_empty() {
+            return Err(Error::InvalidInput("特徴量が空です".to_string()));
+        }
+
+        let mut rows: DataVec = Vec::with_capacity(target_series.len());
+        for i in 0..target_series.len() {
+            let feature = extract_pattern_features(&target_series[..i]);
+            rows.push(Data {
+                feature,
+                target: 0.0,
+                weight: 1.0,
+                label: 0.0,
+                residual: 0.0,
+                initial_guess: 0.0,
+            });
+        }
+
+        let predictions = forest.predict(&rows);
+        Ok(predictions.into_iter().map(|v| v as f64).collect())
+    }
+
+    fn save(&self, path: &str) -> Result<(), Error> {
+        let forest = self
+            .forest
+            .as_ref()
+            .ok_or_else(|| Error::InvalidState("モデルが学習されていません".to_string()))?;
+
+        forest.save_model(&Self::forest_path(path));
+
+        let model_data = serde_json::json!({
+            "model_type": "Pattern",
+            "hyperparameters": self.hyperparameters,
+            "trained": self.trained,
+        });
+
+        std::fs::write(path, serde_json::to_string_pretty(&model_data)?)
+            .map_err(|e| Error::IOError(format!("モデルの保存に失敗しました: {}", e)))?;
+
+        Ok(())
+    }
+
+    fn load(&mut self, path: &str) -> Result<(), Error> {
+        let model_data = std::fs::read_to_string(path)
+            .map_err(|e| Error::IOError(format!("モデルの読み込みに失敗しました: {}", e)))?;
+
+        let model_json: serde_json::Value = serde_json::from_str(&model_data)
+            .map_err(|e| Error::ParseError(format!("モデルデータの解析に失敗しました: {}", e)))?;
+
+        let model_type = model_json["model_type"].as_str()
+            .ok_or_else(|| Error::ParseError("モデルタイプが見つかりません".to_string()))?;
+
+        if model_type != "Pattern" {
+            return Err(Error::InvalidInput(format!(
+                "モデルタイプが一致しません: {} != Pattern",
+                model_type
+            )));
+        }
+
+        self.hyperparameters = serde_json::from_value(model_json["hyperparameters"].clone())
+            .map_err(|e| Error::ParseError(format!("ハイパーパラメータの解析に失敗しました: {}", e)))?;
+
+        self.trained = model_json["trained"].as_bool()
+            .ok_or_else(|| Error::ParseError("学習状態が見つかりません".to_string()))?;
+
+        let forest = GBDT::load_model(&Self::forest_path(path))
+            .map_err(|e| Error::IOError(format!("フォレストの読み込みに失敗しました: {:?}", e)))?;
+        self.forest = Some(forest);
+
+        Ok(())
+    }
+
+    fn model_type(&self) -> PredictionModelType {
+        PredictionModelType::Pattern
+    }
+
+    fn name(&self) -> String {
+        "パターン認識モデル".to_string()
+    }
+
+    fn description(&self) -> String {
+        "FFTスペクトル特徴量と勾配ブースティング木により、相場のパターン（レジーム）を学習する非線形モデル。".to_string()
+    }
+
+    fn hyperparameters(&self) -> HashMap<String, f64> {
+        self.hyperparameters.clone()
+    }
+
+    fn set_hyperparameters(&mut self, params: HashMap<String, f64>) -> Result<(), Error> {
+        self.hyperparameters = params;
+        Ok(())
+    }
+}
+
+/// アンサンブルモデル
+pub struct EnsembleModel {
+    /// 内部モデル
+    models: Vec<Box<dyn PredictionModel>>,
+    /// モデルの重み
+    weights: Vec<f64>,
+    /// ハイパーパラメータ
+    hyperparameters: HashMap<String, f64>,
+    /// 学習済みかどうか
+    trained: bool,
+}
+
+impl EnsembleModel {
+    /// 新しいアンサンブルモデルを作成
+    pub fn new() -> Self {
+        let mut hyperparameters = HashMap::new();
+        hyperparameters.insert("equal_weights".to_string(), 1.0);
+        
+        Self {
+            models: Vec::new(),
+            weights: Vec::new(),
+            hyperparameters,
+            trained: false,
+        }
+    }
+    
+    /// モデルを追加
+    pub fn add_model(&mut self, model: Box<dyn PredictionModel>, weight: f64) {
+        self.models.push(model);
+        self.weights.push(weight);
+        
+        // 重みを正規化
+        let sum = self.weights.iter().sum::<f64>();
+        if sum > 0.0 {
+            for w in &mut self.weights {
+                *w /= sum;
+            }
+        }
+    }
+}
+
+impl PredictionModel for EnsembleModel {
+    fn train(&mut self, data: &[FeatureData]) -> Result<(), Error> {
+        if data.is_empty() {
+            return Err(Error::InvalidInput("学習データが空です".to_string()));
+        }
+        
+        if self.models.is_empty() {
+            return Err(Error::InvalidState("モデルが追加されていません".to_string()));
+        }
+        
+        // 各モデルを学習
+        for model in &mut self.models {
+            model.train(data)?;
+        }
+        
+        self.trained = true;
+        
+        Ok(())
+    }
+    
+    fn predict(&self, features: &HashMap<String, Vec<f64>>) -> Result<Vec<f64>, Error> {
+        if !self.trained {
+            return Err(Error::InvalidState("モデルが学習されていません".to_string()));
+        }
+        
+        if self.models.is_empty() {
+            return Err(Error::InvalidState("モデルが追加されていません".to_string()));
+        }
+        
+        // 予測期間の長さを取得
+        let n_samples = features.values().next().map(|v| v.len()).unwrap_or(0);
+        if n_samples == 0 {
+            return Err(Error::InvalidInput("特徴量が空です".to_string()));
+        }
+        
+        // 各モデルの予測を取得
+        let mut all_predictions = Vec::new();
+        
+        for model in &self.models {
+            let predictions = model.predict(features)?;
+            all_predictions.push(predictions);
+        }
+        
+        // 重み付き平均を計算
+        let mut ensemble_predictions = vec![0.0; n_samples];
+        
+        for i in 0..n_samples {
+            let mut weighted_sum = 0.0;
+            
+            for (j, predictions) in all_predictions.iter().enumerate() {
+                weighted_sum += predictions[i] * self.weights[j];
+            }
+            
+            ensemble_predictions[i] = weighted_sum;
+        }
+        
+        Ok(ensemble_predictions)
+    }
+    
+    fn save(&self, path: &str) -> Result<(), Error> {
+        // 各モデルを個別に保存
+        for (i, model) in self.models.iter().enumerate() {
+            let model_path = format!("{}_model_{}", path, i);
+            model.save(&model_path)?;
+        }
+        
+        // アンサンブル設定を保存
+        let model_data = serde_json::json!({
+            "model_type": "Ensemble",
+            "model_count": self.models.len(),
+            "weights": self.weights,
+            "hyperparameters": self.hyperparameters,
+            "trained": self.trained,
+        });
+        
+        std::fs::write(path, serde_json::to_string_pretty(&model_data)?)
+            .map_err(|e| Error::IOError(format!("モデルの保存に失敗しました: {}", e)))?;
+        
+        Ok(())
+    }
+    
+    fn load(&mut self, path: &str) -> Result<(), Error> {
+        let model_data = std::fs::read_to_string(path)
+            .map_err(|e| Error::IOError(format!("モデルの読み込みに失敗しました: {}", e)))?;
+        
+        let model_json: serde_json::Value = serde_json::from_str(&model_data)
+            .map_err(|e| Error::ParseError(format!("モデルデータの解析に失敗しました: {}", e)))?;
+        
+        // モデルタイプを確認
+        let model_type = model_json["model_type"].as_str()
+            .ok_or_else(|| Error::ParseError("モデルタイプが見つかりません".to_string()))?;
+        
+        if model_type != "Ensemble" {
+            return Err(Error::InvalidInput(format!(
+                "モデルタイプが一致しません: {} != Ensemble",
+                model_type
+            )));
+        }
+        
+        // パラメータを読み込み
+        let model_count = model_json["model_count"].as_u64()
+            .ok_or_else(|| Error::ParseError("モデル数が見つかりません".to_string()))? as usize;
+        
+        self.weights = serde_json::from_value(model_json["weights"].clone())
+            .map_err(|e| Error::ParseError(format!("重みの解析に失敗しました: {}", e)))?;
+        
+        self.hyperparameters = serde_json::from_value(model_json["hyperparameters"].clone())
+            .map_err(|e| Error::ParseError(format!("ハイパーパラメータの解析に失敗しました: {}", e)))?;
+        
+        self.trained = model_json["trained"].as_bool()
+            .ok_or_else(|| Error::ParseError("学習状態が見つかりません".to_string()))?;
+        
+        // 各モデルを読み込み
+        self.models.clear();
+        
+        for i in 0..model_count {
+            let model_path = format!("{}_model_{}", path, i);
+            
+            // モデルタイプを確認
+            let model_data = std::fs::read_to_string(&model_path)
+                .map_err(|e| Error::IOError(format!("モデルの読み込みに失敗しました: {}", e)))?;
+            
+            let model_json: serde_json::Value = serde_json::from_str(&model_data)
+                .map_err(|e| Error::ParseError(format!("モデルデータの解析に失敗しました: {}", e)))?;
+            
+            let sub_model_type = model_json["model_type"].as_str()
+                .ok_or_else(|| Error::ParseError("モデルタイプが見つかりません".to_string()))?;
+            
+            // モデルタイプに応じてモデルを作成
+            let mut model: Box<dyn PredictionModel> = match sub_model_type {
+                "LinearRegression" => Box::new(LinearRegressionModel::new()),
+                "MovingAverage" => {
+                    let window_size = model_json["window_size"].as_u64()
+                        .ok_or_else(|| Error::ParseError("ウィンドウサイズが見つかりません".to_string()))? as usize;
+                    Box::new(MovingAverageModel::new(window_size))
+                },
+                "SARIMA" => Box::new(SarimaModel::new()),
+                "RandomForest" => Box::new(GradientBoostedTreesModel::new()),
+                "ExponentialSmoothing" => Box::new(HoltWintersModel::new()),
+                "NeuralNetwork" => Box::new(MlpModel::new()),
+                "Seasonal" => Box::new(SeasonalModel::new()),
+                "Pattern" => Box::new(PatternModel::new()),
+                _ => return Err(Error::InvalidInput(format!("未対応のモデルタイプです: {}", sub_model_type))),
+            };
+            
+            // モデルを読み込み
+            model.load(&model_path)?;
+            
+            // モデルを追加
+            self.models.push(model);
+        }
+        
+        Ok(())
+    }
+    
+    fn model_type(&self) -> PredictionModelType {
+        PredictionModelType::Ensemble
+    }
+    
+    fn name(&self) -> String {
+        "アンサンブルモデル".to_string()
+    }
+    
+    fn description(&self) -> String {
+        format!("{} 個のモデルを組み合わせたアンサンブルモデル。", self.models.len())
+    }
+    
+    fn hyperparameters(&self) -> HashMap<String, f64> {
+        self.hyperparameters.clone()
+    }
+    
+    fn set_hyperparameters(&mut self, params: HashMap<String, f64>) -> Result<(), Error> {
+        // 等重みフラグをチェック
+        if let Some(equal_weights) = params.get("equal_weights") {
+            if *equal_weights > 0.5 && !self.models.is_empty() {
                 // 全てのモデルに等しい重みを設定
                 let weight = 1.0 / self.models.len() as f64;
                 self.weights = vec![weight; self.models.len()];
@@ -819,18 +2831,420 @@ impl PredictionModel for EnsembleModel {
     }
 }
 
+/// 異常の重大度（z値の大きさに応じて分類）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum AnomalySeverity {
+    /// 低
+    Low,
+    /// 中
+    Medium,
+    /// 高
+    High,
+    /// 重大
+    Critical,
+}
+
+/// 異常検知レポート
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnomalyReport {
+    /// 観測時刻
+    pub timestamp: DateTime<Utc>,
+    /// 対象
+    pub target: PredictionTarget,
+    /// 観測値
+    pub observed: f64,
+    /// 期待値
+    pub expected: f64,
+    /// 重大度
+    pub severity: AnomalySeverity,
+    /// z値
+    pub z_score: f64,
+}
+
+/// 異常検知のベースライン方式
+enum AnomalyBaselineMode {
+    /// `PredictionResult`の信頼区間と比較する
+    Confidence,
+    /// SARIMAの季節成分を期待値として使う
+    SeasonalProfile { seasonal: Vec<f64>, resid_std: f64 },
+}
+
+/// 異常検知器
+///
+/// `PredictionResult`の信頼区間（`lower_bound`/`upper_bound`）と実測値を
+/// 比較し、区間を`bound_factor`倍以上逸脱した場合、またはローリング残差が
+/// `z_threshold * 残差標準偏差`を超えた場合に異常として報告する。
+/// `SarimaModel`の季節成分を再利用した季節ベースラインモードもサポートし、
+/// `PredictionTarget::ShardLoad`/`GasFee`のようなシャード負荷・ガス料金の
+/// 早期異常検知に用いる。
+pub struct AnomalyDetector {
+    /// 監視対象
+    target: PredictionTarget,
+    /// 信頼区間・季節残差スプレッドに掛ける係数
+    bound_factor: f64,
+    /// ローリング残差のz閾値
+    z_threshold: f64,
+    /// ベースライン方式
+    baseline: AnomalyBaselineMode,
+    /// 直近の残差（ローリング標準偏差の計算に使用）
+    rolling_residuals: VecDeque<f64>,
+    /// ローリングウィンドウの長さ
+    rolling_window: usize,
+}
+
+impl AnomalyDetector {
+    /// `PredictionResult`の信頼区間をベースラインとする異常検知器を作成
+    pub fn new_confidence_based(
+        target: PredictionTarget,
+        bound_factor: f64,
+        z_threshold: f64,
+        rolling_window: usize,
+    ) -> Self {
+        Self {
+            target,
+            bound_factor,
+            z_threshold,
+            baseline: AnomalyBaselineMode::Confidence,
+            rolling_residuals: VecDeque::new(),
+            rolling_window: rolling_window.max(1),
+        }
+    }
+
+    /// 季節成分をベースラインとする異常検知器を作成
+    pub fn new_seasonal(
+        target: PredictionTarget,
+        seasonal: Vec<f64>,
+        resid_std: f64,
+        bound_factor: f64,
+        z_threshold: f64,
+        rolling_window: usize,
+    ) -> Self {
+        Self {
+            target,
+            bound_factor,
+            z_threshold,
+            baseline: AnomalyBaselineMode::SeasonalProfile { seasonal, resid_std },
+            rolling_residuals: VecDeque::new(),
+            rolling_window: rolling_window.max(1),
+        }
+    }
+
+    /// 学習済み`SarimaModel`の季節成分を再利用して、季節ベースラインの
+    /// 異常検知器を作成する
+    pub fn from_sarima(
+        target: PredictionTarget,
+        model: &SarimaModel,
+        bound_factor: f64,
+        z_threshold: f64,
+        rolling_window: usize,
+    ) -> Self {
+        Self::new_seasonal(
+            target,
+            model.seasonal_profile().to_vec(),
+            model.resid_std(),
+            bound_factor,
+            z_threshold,
+            rolling_window,
+        )
+    }
+
+    /// 信頼区間ベースラインモードで観測値を検査する
+    ///
+    /// `data_point`は監視対象の時刻に対応する`PredictionResult`の
+    /// データポイント（`value`/`lower_bound`/`upper_bound`）を渡す。
+    pub fn check_confidence(
+        &mut self,
+        timestamp: DateTime<Utc>,
+        observed: f64,
+        data_point: &PredictionDataPoint,
+    ) -> Option<AnomalyReport> {
+        let expected = data_point.value;
+        let lower = data_point.lower_bound.unwrap_or(expected);
+        let upper = data_point.upper_bound.unwrap_or(expected);
+        let half_width = ((upper - lower) / 2.0).max(1e-9) * self.bound_factor;
+
+        let deviation = observed - expected;
+        self.push_residual(deviation);
+        let rolling_std = self.rolling_std();
+        let z_score = if rolling_std > 1e-9 {
+            deviation.abs() / rolling_std
+        } else {
+            0.0
+        };
+
+        let outside_band = observed < expected - half_width || observed > expected + half_width;
+        let exceeds_z = rolling_std > 1e-9 && deviation.abs() > self.z_threshold * rolling_std;
+
+        if outside_band || exceeds_z {
+            Some(AnomalyReport {
+                timestamp,
+                target: self.target.clone(),
+                observed,
+                expected,
+                severity: Self::classify_severity(z_score),
+                z_score,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// 季節ベースラインモードで観測値を検査する
+    ///
+    /// `phase`は季節周期内の位置（`n mod S`）で、期待値は学習済み季節
+    /// 成分ベクトルから取得する。季節ベースラインが設定されていない
+    /// 場合は常に`None`を返す。
+    pub fn check_seasonal(
+        &mut self,
+        timestamp: DateTime<Utc>,
+        observed: f64,
+        phase: usize,
+    ) -> Option<AnomalyReport> {
+        let (seasonal, resid_std) = match &self.baseline {
+            AnomalyBaselineMode::SeasonalProfile { seasonal, resid_std } => (seasonal, *resid_std),
+            AnomalyBaselineMode::Confidence => return None,
+        };
+
+        if seasonal.is_empty() {
+            return None;
+        }
+
+        let expected = seasonal[phase % seasonal.len()];
+        let deviation = observed - expected;
+        self.push_residual(deviation);
+
+        let rolling_std = self.rolling_std();
+        let effective_std = if rolling_std > 1e-9 {
+            rolling_std
+        } else {
+            resid_std.max(1e-9)
+        };
+        let z_score = deviation.abs() / effective_std;
+
+        let scaled_resid_spread = resid_std * self.bound_factor;
+        let exceeds_band = deviation.abs() > scaled_resid_spread;
+        let exceeds_z = z_score > self.z_threshold;
+
+        if exceeds_band || exceeds_z {
+            Some(AnomalyReport {
+                timestamp,
+                target: self.target.clone(),
+                observed,
+                expected,
+                severity: Self::classify_severity(z_score),
+                z_score,
+            })
+        } else {
+            None
+        }
+    }
+
+    fn push_residual(&mut self, residual: f64) {
+        self.rolling_residuals.push_back(residual);
+        if self.rolling_residuals.len() > self.rolling_window {
+            self.rolling_residuals.pop_front();
+        }
+    }
+
+    fn rolling_std(&self) -> f64 {
+        let n = self.rolling_residuals.len();
+        if n < 2 {
+            return 0.0;
+        }
+
+        let mean = self.rolling_residuals.iter().sum::<f64>() / n as f64;
+        let variance = self
+            .rolling_residuals
+            .iter()
+            .map(|r| (r - mean).powi(2))
+            .sum::<f64>()
+            / n as f64;
+
+        variance.sqrt()
+    }
+
+    fn classify_severity(z_score: f64) -> AnomalySeverity {
+        if z_score >= 6.0 {
+            AnomalySeverity::Critical
+        } else if z_score >= 4.0 {
+            AnomalySeverity::High
+        } else if z_score >= 2.0 {
+            AnomalySeverity::Medium
+        } else {
+            AnomalySeverity::Low
+        }
+    }
+}
+
+/// モデルのバージョン情報
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelVersionInfo {
+    /// バージョン番号（対象ごとに1から単調増加）
+    pub version: u64,
+    /// 保存されたモデルファイルの内容ハッシュ
+    pub content_hash: String,
+    /// 学習または読み込みが記録された時刻
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// 予測対象・バージョンごとの精度指標
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AccuracyMetrics {
+    /// 平均絶対誤差
+    pub mean_absolute_error: f64,
+    /// 平均二乗誤差
+    pub mean_squared_error: f64,
+    /// 平均絶対パーセント誤差
+    pub mean_absolute_percentage_error: f64,
+    /// 誤差が判明した予測データポイントの件数
+    pub sample_count: u64,
+}
+
+/// モデルレジストリ
+///
+/// `PredictionModel`が学習・読み込みされるたびにバージョン番号と
+/// 保存ファイルの内容ハッシュを記録し、`PredictionResult.metadata`に
+/// 埋め込めるようにする。また`PredictionDataPoint.actual_value`が
+/// 判明するたびに、そのとき有効だったバージョンの精度指標
+/// （MAE/MSE/MAPE）を更新し、`auto_retrain`発火時に新旧バージョンの
+/// 精度を比較できるようにする。これにより予測サブシステム全体を
+/// 監視・監査可能にし、モデルファイルを無言で上書きすることを防ぐ。
+pub struct ModelRegistry {
+    /// 対象ごとのバージョン履歴（記録順）
+    versions: RwLock<HashMap<String, Vec<ModelVersionInfo>>>,
+    /// (対象, バージョン)ごとの累積精度指標
+    metrics: RwLock<HashMap<(String, u64), AccuracyMetrics>>,
+}
+
+impl ModelRegistry {
+    /// 新しいモデルレジストリを作成
+    pub fn new() -> Self {
+        Self {
+            versions: RwLock::new(HashMap::new()),
+            metrics: RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn target_key(target: &PredictionTarget) -> String {
+        format!("{:?}", target)
+    }
+
+    /// モデルファイルの内容ハッシュを計算する（FNV-1a）
+    fn content_hash(model_bytes: &[u8]) -> String {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in model_bytes {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        format!("{:016x}", hash)
+    }
+
+    /// モデルが学習または読み込みされたことを記録し、新しいバージョン番号を返す
+    pub fn record_version(&self, target: &PredictionTarget, model_bytes: &[u8]) -> u64 {
+        let key = Self::target_key(target);
+        let mut versions = self.versions.write().unwrap();
+        let history = versions.entry(key).or_insert_with(Vec::new);
+        let version = history.len() as u64 + 1;
+        history.push(ModelVersionInfo {
+            version,
+            content_hash: Self::content_hash(model_bytes),
+            recorded_at: Utc::now(),
+        });
+        version
+    }
+
+    /// 対象の最新バージョン情報を取得
+    pub fn latest_version(&self, target: &PredictionTarget) -> Option<ModelVersionInfo> {
+        let versions = self.versions.read().unwrap();
+        versions.get(&Self::target_key(target)).and_then(|history| history.last().cloned())
+    }
+
+    /// 実際の値が判明した予測データポイントから、該当バージョンの精度指標を更新
+    pub fn record_error(
+        &self,
+        target: &PredictionTarget,
+        version: u64,
+        error: f64,
+        squared_error: f64,
+        percentage_error: Option<f64>,
+    ) {
+        let key = (Self::target_key(target), version);
+        let mut metrics = self.metrics.write().unwrap();
+        let entry = metrics.entry(key).or_insert_with(AccuracyMetrics::default);
+        let n = entry.sample_count as f64;
+        entry.mean_absolute_error = (entry.mean_absolute_error * n + error) / (n + 1.0);
+        entry.mean_squared_error = (entry.mean_squared_error * n + squared_error) / (n + 1.0);
+        if let Some(pct) = percentage_error {
+            entry.mean_absolute_percentage_error =
+                (entry.mean_absolute_percentage_error * n + pct) / (n + 1.0);
+        }
+        entry.sample_count += 1;
+    }
+
+    /// 対象・バージョンの現在の精度指標を取得
+    pub fn metrics_for_version(&self, target: &PredictionTarget, version: u64) -> AccuracyMetrics {
+        let metrics = self.metrics.read().unwrap();
+        metrics
+            .get(&(Self::target_key(target), version))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// 再学習によるバージョン切り替えを記録し、検証誤差の差分をログに出力する
+    ///
+    /// 新バージョンにまだ精度指標が蓄積されていない場合は、判断材料が
+    /// 揃っていない旨を記録するに留める。新バージョンのMAEが旧バージョン
+    /// より悪化している場合は警告ログとして出力し、オペレーターが
+    /// ロールバックを検討できるようにする。
+    pub fn report_retrain(&self, target: &PredictionTarget, old_version: u64, new_version: u64) {
+        let old_metrics = self.metrics_for_version(target, old_version);
+        let new_metrics = self.metrics_for_version(target, new_version);
+
+        if new_metrics.sample_count == 0 {
+            info!(
+                "モデル再学習: target={:?} v{} -> v{} (旧バージョンのMAE={:.6}、新バージョンの検証データはまだありません)",
+                target, old_version, new_version, old_metrics.mean_absolute_error
+            );
+            return;
+        }
+
+        let delta = new_metrics.mean_absolute_error - old_metrics.mean_absolute_error;
+        if delta > 0.0 {
+            warn!(
+                "モデル再学習により精度が悪化しました: target={:?} v{}(MAE={:.6}) -> v{}(MAE={:.6}), delta=+{:.6}。ロールバックを検討してください",
+                target, old_version, old_metrics.mean_absolute_error, new_version, new_metrics.mean_absolute_error, delta
+            );
+        } else {
+            info!(
+                "モデル再学習: target={:?} v{}(MAE={:.6}) -> v{}(MAE={:.6}), delta={:.6}",
+                target, old_version, old_metrics.mean_absolute_error, new_version, new_metrics.mean_absolute_error, delta
+            );
+        }
+    }
+}
+
 /// 予測サービス
 pub struct MarketPredictionService {
     /// 設定
     config: PredictionConfig,
-    /// モデル
-    model: Box<dyn PredictionModel>,
+    /// モデル（`Arc`越しの共有サービスに対してもホットリロードできるようMutexで保護する）
+    model: Mutex<Box<dyn PredictionModel>>,
     /// 特徴量データ
     feature_data: Arc<Mutex<Vec<FeatureData>>>,
     /// 予測結果
     predictions: Arc<RwLock<HashMap<String, PredictionResult>>>,
     /// 最終学習時刻
     last_trained: Arc<Mutex<DateTime<Utc>>>,
+    /// スペクトル特徴量抽出器
+    spectral_extractor: SpectralFeatureExtractor,
+    /// モデルレジストリ
+    registry: Arc<ModelRegistry>,
+    /// このサービスが現在使用しているモデルのバージョン（未学習・未読み込みなら0）
+    current_version: Arc<Mutex<u64>>,
+    /// 検知された異常（信頼区間からの逸脱）
+    anomalies: Arc<RwLock<Vec<PredictionAnomaly>>>,
+    /// 再学習が進行中かどうか（`DetectionRunner`のアラート抑制に使う）
+    training_in_progress: Arc<Mutex<bool>>,
 }
 
 impl MarketPredictionService {
@@ -840,24 +3254,95 @@ impl MarketPredictionService {
         let model: Box<dyn PredictionModel> = match config.model_type {
             PredictionModelType::LinearRegression => Box::new(LinearRegressionModel::new()),
             PredictionModelType::MovingAverage => Box::new(MovingAverageModel::new(24)),
+            PredictionModelType::ARIMA => Box::new(SarimaModel::new()),
+            PredictionModelType::RandomForest => Box::new(GradientBoostedTreesModel::new()),
+            PredictionModelType::ExponentialSmoothing => Box::new(HoltWintersModel::new()),
+            PredictionModelType::NeuralNetwork => Box::new(MlpModel::new()),
+            PredictionModelType::Seasonal => Box::new(SeasonalModel::new()),
+            PredictionModelType::Pattern => Box::new(PatternModel::new()),
             PredictionModelType::Ensemble => Box::new(EnsembleModel::new()),
             _ => return Err(Error::InvalidInput(format!("未対応のモデルタイプです: {:?}", config.model_type))),
         };
-        
-        Ok(Self {
-            config,
-            model,
-            feature_data: Arc::new(Mutex::new(Vec::new())),
-            predictions: Arc::new(RwLock::new(HashMap::new())),
-            last_trained: Arc::new(Mutex::new(Utc::now())),
-        })
+
+        Ok(Self {
+            config,
+            model: Mutex::new(model),
+            feature_data: Arc::new(Mutex::new(Vec::new())),
+            predictions: Arc::new(RwLock::new(HashMap::new())),
+            last_trained: Arc::new(Mutex::new(Utc::now())),
+            spectral_extractor: SpectralFeatureExtractor::default(),
+            registry: Arc::new(ModelRegistry::new()),
+            current_version: Arc::new(Mutex::new(0)),
+            anomalies: Arc::new(RwLock::new(Vec::new())),
+            training_in_progress: Arc::new(Mutex::new(false)),
+        })
+    }
+
+    /// アラート設定を取得
+    pub fn alerting_config(&self) -> Option<AlertingConfig> {
+        self.config.alerting.clone()
+    }
+
+    /// このサービスが現在提供しているモデルのバージョンを取得する
+    ///
+    /// 未学習・未読み込みの場合は0を返す。
+    pub fn current_version(&self) -> u64 {
+        *self.current_version.lock().unwrap()
+    }
+
+    /// 候補モデルが有限な予測値を出力できるかを検証する
+    ///
+    /// `load_model`によるホットリロードの直後に呼ばれ、予測が`NaN`/`Infinity`
+    /// を含む壊れたモデルが本番提供モデルへ昇格することを防ぐ。特徴量データが
+    /// まだ無い、または予測期間の設定が検証不能な場合は、データ不足を理由に
+    /// ウォームアップを拒否しないようスキップして成功扱いとする。
+    fn warmup(&self, model: &dyn PredictionModel) -> Result<(), Error> {
+        let feature_data = self.feature_data.lock().unwrap();
+        if feature_data.is_empty() {
+            return Ok(());
+        }
+
+        let (features, n_periods) = match self.prepare_forecast_features(&feature_data) {
+            Ok(result) => result,
+            Err(_) => return Ok(()),
+        };
+
+        let predictions = model.predict(&features)?;
+        if predictions.len() < n_periods {
+            return Err(Error::InvalidState(
+                "ウォームアップ予測の出力数が予測期間に足りません".to_string(),
+            ));
+        }
+        if predictions[..n_periods].iter().any(|v| !v.is_finite()) {
+            return Err(Error::InvalidState(
+                "ウォームアップ予測に非有限な値(NaN/Infinity)が含まれています".to_string(),
+            ));
+        }
+
+        Ok(())
     }
-    
+
     /// 特徴量データを追加
+    ///
+    /// 追加されたデータ点には、それまでの`target`履歴から計算した
+    /// スペクトル特徴量（`fft_freq_N`/`fft_mag_N`）がマージされる。
+    /// `PredictionConfig.features`にこれらの名前を列挙すると、
+    /// モデルの学習・予測時に周期構造を特徴量として利用できる。
     pub fn add_feature_data(&self, data: FeatureData) -> Result<(), Error> {
         let mut feature_data = self.feature_data.lock().unwrap();
         feature_data.push(data);
-        
+
+        let target_history: Vec<f64> = feature_data
+            .iter()
+            .filter_map(|d| d.values.get("target").copied())
+            .collect();
+        let spectral_features = self.spectral_extractor.extract(&target_history);
+        if let Some(latest) = feature_data.last_mut() {
+            for (key, value) in spectral_features {
+                latest.values.insert(key, value);
+            }
+        }
+
         // 古いデータを削除
         let cutoff = Utc::now() - chrono::Duration::days(self.config.history_days as i64);
         feature_data.retain(|d| d.timestamp >= cutoff);
@@ -871,92 +3356,199 @@ impl MarketPredictionService {
             if (now - *last_trained).num_seconds() > retrain_seconds as i64 {
                 drop(last_trained); // ロックを解放
                 drop(feature_data); // ロックを解放
-                
+
                 self.train()?;
-                
+
                 let mut last_trained = self.last_trained.lock().unwrap();
                 *last_trained = now;
             }
         }
-        
+
         Ok(())
     }
-    
+
+    /// 複数の特徴量データ点を、`feature_data`のロックを1回だけ取得してまとめて追加する
+    ///
+    /// 高頻度フィードから`add_feature_data`を逐次呼び出すと、データ点ごとに
+    /// ミューテックスの取得・解放を繰り返すことになる。バッチ経路ではこれを
+    /// 1回のロック区間にまとめ、ロック競合を減らす。各データ点に付与される
+    /// スペクトル特徴量や、古いデータの削除・自動再学習の判定は
+    /// `add_feature_data`と同じロジックに従う。
+    pub fn add_feature_data_batch(&self, data: Vec<FeatureData>) -> Result<(), Error> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let mut feature_data = self.feature_data.lock().unwrap();
+        for item in data {
+            feature_data.push(item);
+
+            let target_history: Vec<f64> = feature_data
+                .iter()
+                .filter_map(|d| d.values.get("target").copied())
+                .collect();
+            let spectral_features = self.spectral_extractor.extract(&target_history);
+            if let Some(latest) = feature_data.last_mut() {
+                for (key, value) in spectral_features {
+                    latest.values.insert(key, value);
+                }
+            }
+        }
+
+        // 古いデータを削除
+        let cutoff = Utc::now() - chrono::Duration::days(self.config.history_days as i64);
+        feature_data.retain(|d| d.timestamp >= cutoff);
+
+        // 自動再学習
+        if self.config.auto_retrain {
+            let mut last_trained = self.last_trained.lock().unwrap();
+            let now = Utc::now();
+            let retrain_seconds = self.config.retrain_interval.to_seconds();
+
+            if (now - *last_trained).num_seconds() > retrain_seconds as i64 {
+                drop(last_trained); // ロックを解放
+                drop(feature_data); // ロックを解放
+
+                self.train()?;
+
+                let mut last_trained = self.last_trained.lock().unwrap();
+                *last_trained = now;
+            }
+        }
+
+        Ok(())
+    }
+
     /// モデルを学習
     pub fn train(&self) -> Result<(), Error> {
+        *self.training_in_progress.lock().unwrap() = true;
+        let result = self.train_inner();
+        *self.training_in_progress.lock().unwrap() = false;
+        result
+    }
+
+    /// 再学習中かどうか。再学習中は`DetectionRunner`がアラートを抑制する。
+    pub fn is_training(&self) -> bool {
+        *self.training_in_progress.lock().unwrap()
+    }
+
+    fn train_inner(&self) -> Result<(), Error> {
         let feature_data = self.feature_data.lock().unwrap();
-        
+
         if feature_data.is_empty() {
             return Err(Error::InvalidInput("学習データが空です".to_string()));
         }
-        
+
         // モデルを学習
-        let mut model = self.model.clone();
+        let mut model = self.model.lock().unwrap().clone();
         model.train(&feature_data)?;
-        
+
+        // レジストリに新しいバージョンを記録する
+        let new_version = self.register_model_version(model.as_ref())?;
+        let old_version = {
+            let mut current_version = self.current_version.lock().unwrap();
+            let old_version = *current_version;
+            *current_version = new_version;
+            old_version
+        };
+        if old_version > 0 {
+            self.registry.report_retrain(&self.config.target, old_version, new_version);
+        }
+
         // モデルを更新
-        let mut model_mut = &mut self.model;
-        *model_mut = model;
-        
+        *self.model.lock().unwrap() = model;
+
         Ok(())
     }
+
+    /// モデルを一時ファイルに書き出してバイト列を取得し、レジストリに新しいバージョンとして記録する
+    fn register_model_version(&self, model: &dyn PredictionModel) -> Result<u64, Error> {
+        let tmp_path = std::env::temp_dir().join(format!(
+            "shardx-model-registry-{:?}-{}.json",
+            self.config.target,
+            Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let tmp_path_str = tmp_path.to_string_lossy().to_string();
+
+        model.save(&tmp_path_str)?;
+        let bytes = std::fs::read(&tmp_path_str)
+            .map_err(|e| Error::IOError(format!("一時モデルファイルの読み込みに失敗しました: {}", e)))?;
+        let _ = std::fs::remove_file(&tmp_path_str);
+
+        Ok(self.registry.record_version(&self.config.target, &bytes))
+    }
     
-    /// 予測を実行
-    pub fn predict(&self) -> Result<PredictionResult, Error> {
-        // 特徴量データを取得
-        let feature_data = self.feature_data.lock().unwrap();
-        
-        if feature_data.is_empty() {
-            return Err(Error::InvalidInput("特徴量データが空です".to_string()));
-        }
-        
-        // 予測期間を計算
-        let now = Utc::now();
+    /// 予測に使う特徴量と予測期間のステップ数を準備する
+    ///
+    /// `predict`と`warmup`の両方から呼ばれる共通ロジック。
+    fn prepare_forecast_features(
+        &self,
+        feature_data: &[FeatureData],
+    ) -> Result<(HashMap<String, Vec<f64>>, usize), Error> {
         let forecast_seconds = self.config.forecast_period.to_seconds();
         let time_frame_seconds = self.config.time_frame.to_seconds();
-        
+
         let n_periods = (forecast_seconds / time_frame_seconds) as usize;
         if n_periods == 0 {
             return Err(Error::InvalidInput("予測期間が時間枠より短いです".to_string()));
         }
-        
-        // 特徴量を準備
+
         let mut features: HashMap<String, Vec<f64>> = HashMap::new();
-        
+
         for feature_name in &self.config.features {
             let mut values = Vec::new();
-            
+
             // 過去の値を取得
             for data in feature_data.iter().rev().take(10) {
                 if let Some(value) = data.values.get(feature_name) {
                     values.push(*value);
                 }
             }
-            
+
             // 値を反転して時系列順にする
             values.reverse();
-            
+
             // 予測期間分の値を追加（ダミー値）
             let last_value = values.last().cloned().unwrap_or(0.0);
             for _ in 0..n_periods {
                 values.push(last_value);
             }
-            
+
             features.insert(feature_name.clone(), values);
         }
-        
+
+        Ok((features, n_periods))
+    }
+
+    /// 予測を実行
+    pub fn predict(&self) -> Result<PredictionResult, Error> {
+        // 特徴量データを取得
+        let feature_data = self.feature_data.lock().unwrap();
+
+        if feature_data.is_empty() {
+            return Err(Error::InvalidInput("特徴量データが空です".to_string()));
+        }
+
+        // 予測期間を計算
+        let now = Utc::now();
+        let time_frame_seconds = self.config.time_frame.to_seconds();
+        let (features, n_periods) = self.prepare_forecast_features(&feature_data)?;
+
         // 予測を実行
-        let predictions = self.model.predict(&features)?;
-        
+        let model = self.model.lock().unwrap();
+        let predictions = model.predict(&features)?;
+
         // 予測結果を作成
         let mut data_points = Vec::new();
-        
+
         for i in 0..n_periods {
             let timestamp = now + chrono::Duration::seconds((i as u64 * time_frame_seconds) as i64);
-            
+
             // 信頼区間を計算
             let confidence_interval = self.config.confidence_interval;
-            let std_dev = 0.1 * predictions[i].abs(); // 仮の標準偏差
+            // モデルが実測値から残差標準偏差を推定できる場合はそれを使う。
+            // できない場合は従来どおりの簡易的な推定値にフォールバックする。
+            let std_dev = model.residual_std(i).unwrap_or(0.1 * predictions[i].abs());
             let z_score = 1.96; // 95%信頼区間のz値
             let margin = z_score * std_dev;
             
@@ -974,6 +3566,15 @@ impl MarketPredictionService {
         
         // 予測結果を作成
         let prediction_id = format!("pred-{}", Utc::now().timestamp());
+
+        // どのモデルバージョンがこの予測を生成したかをメタデータに記録する
+        let mut metadata = HashMap::new();
+        let version = *self.current_version.lock().unwrap();
+        metadata.insert("model_version".to_string(), version.to_string());
+        if let Some(version_info) = self.registry.latest_version(&self.config.target) {
+            metadata.insert("model_content_hash".to_string(), version_info.content_hash);
+        }
+
         let prediction_result = PredictionResult {
             id: prediction_id.clone(),
             target: self.config.target.clone(),
@@ -984,7 +3585,7 @@ impl MarketPredictionService {
             mean_absolute_percentage_error: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
-            metadata: HashMap::new(),
+            metadata,
         };
         
         // 予測結果を保存
@@ -1008,7 +3609,12 @@ impl MarketPredictionService {
         let predictions = self.predictions.read().unwrap();
         predictions.values().cloned().collect()
     }
-    
+
+    /// 検知された異常（信頼区間からの逸脱）を取得
+    pub fn get_anomalies(&self) -> Vec<PredictionAnomaly> {
+        self.anomalies.read().unwrap().clone()
+    }
+
     /// 予測結果を更新（実際の値を追加）
     pub fn update_prediction(&self, prediction_id: &str, timestamp: DateTime<Utc>, actual_value: f64) -> Result<(), Error> {
         let mut predictions = self.predictions.write().unwrap();
@@ -1017,14 +3623,81 @@ impl MarketPredictionService {
             .ok_or_else(|| Error::NotFound(format!("予測 {} が見つかりません", prediction_id)))?;
         
         // データポイントを更新
+        let mut newly_resolved_error = None;
+        let mut newly_resolved_bounds = None;
         for data_point in &mut prediction.data_points {
             if (data_point.timestamp - timestamp).num_seconds().abs() < 60 {
+                let error = (data_point.value - actual_value).abs();
                 data_point.actual_value = Some(actual_value);
-                data_point.error = Some((data_point.value - actual_value).abs());
+                data_point.error = Some(error);
+                newly_resolved_error = Some((error, actual_value));
+                newly_resolved_bounds = Some((
+                    data_point.timestamp,
+                    data_point.value,
+                    data_point.lower_bound,
+                    data_point.upper_bound,
+                ));
                 break;
             }
         }
-        
+
+        // 信頼区間から外れた実際の値を異常として記録する
+        if self.config.anomaly_detection_enabled {
+            if let Some((point_timestamp, predicted_value, lower_bound, upper_bound)) = newly_resolved_bounds {
+                let crossed = if let Some(lower) = lower_bound {
+                    if actual_value < lower {
+                        Some((CrossedBound::Lower, lower - actual_value))
+                    } else {
+                        None
+                    }
+                } else {
+                    None
+                };
+                let crossed = crossed.or_else(|| {
+                    upper_bound.and_then(|upper| {
+                        if actual_value > upper {
+                            Some((CrossedBound::Upper, actual_value - upper))
+                        } else {
+                            None
+                        }
+                    })
+                });
+
+                if let Some((crossed_bound, deviation)) = crossed {
+                    self.anomalies.write().unwrap().push(PredictionAnomaly {
+                        prediction_id: prediction_id.to_string(),
+                        timestamp: point_timestamp,
+                        predicted_value,
+                        actual_value,
+                        deviation,
+                        crossed_bound,
+                    });
+                }
+            }
+        }
+
+        // この予測を生成したモデルバージョンの精度指標を更新する
+        if let Some((error, actual_value)) = newly_resolved_error {
+            if let Some(version) = prediction
+                .metadata
+                .get("model_version")
+                .and_then(|v| v.parse::<u64>().ok())
+            {
+                let percentage_error = if actual_value.abs() > 1e-10 {
+                    Some(error / actual_value.abs())
+                } else {
+                    None
+                };
+                self.registry.record_error(
+                    &prediction.target,
+                    version,
+                    error,
+                    error * error,
+                    percentage_error,
+                );
+            }
+        }
+
         // 誤差指標を更新
         let mut errors = Vec::new();
         let mut squared_errors = Vec::new();
@@ -1062,38 +3735,159 @@ impl MarketPredictionService {
             let model: Box<dyn PredictionModel> = match config.model_type {
                 PredictionModelType::LinearRegression => Box::new(LinearRegressionModel::new()),
                 PredictionModelType::MovingAverage => Box::new(MovingAverageModel::new(24)),
+                PredictionModelType::ARIMA => Box::new(SarimaModel::new()),
+                PredictionModelType::RandomForest => Box::new(GradientBoostedTreesModel::new()),
+                PredictionModelType::ExponentialSmoothing => Box::new(HoltWintersModel::new()),
+                PredictionModelType::NeuralNetwork => Box::new(MlpModel::new()),
+                PredictionModelType::Seasonal => Box::new(SeasonalModel::new()),
+                PredictionModelType::Pattern => Box::new(PatternModel::new()),
                 PredictionModelType::Ensemble => Box::new(EnsembleModel::new()),
                 _ => return Err(Error::InvalidInput(format!("未対応のモデルタイプです: {:?}", config.model_type))),
             };
-            
-            self.model = model;
+
+            *self.model.lock().unwrap() = model;
         }
-        
+
         self.config = config;
-        
+
         Ok(())
     }
-    
+
     /// モデルを保存
     pub fn save_model(&self, path: &str) -> Result<(), Error> {
-        self.model.save(path)
+        self.model.lock().unwrap().save(path)
     }
-    
+
     /// モデルを読み込み
-    pub fn load_model(&mut self, path: &str) -> Result<(), Error> {
-        let mut model = self.model.clone();
+    ///
+    /// ホットリロード: 読み込んだモデルは`warmup`による検証を経てから提供
+    /// モデルと入れ替わるため、実行中のサービスを止めずに安全に差し替えられる。
+    pub fn load_model(&self, path: &str) -> Result<(), Error> {
+        let mut model = self.model.lock().unwrap().clone();
         model.load(path)?;
-        
-        self.model = model;
-        
+
+        // 本番提供モデルへ昇格させる前に、有限な予測値を出すことを検証する
+        self.warmup(model.as_ref())?;
+
+        // 読み込んだモデルファイルの内容をレジストリに新しいバージョンとして記録する
+        let bytes = std::fs::read(path)
+            .map_err(|e| Error::IOError(format!("モデルファイルの読み込みに失敗しました: {}", e)))?;
+        let new_version = self.registry.record_version(&self.config.target, &bytes);
+        *self.current_version.lock().unwrap() = new_version;
+
+        *self.model.lock().unwrap() = model;
+
         Ok(())
     }
 }
 
+/// バックグラウンド検知ランナー
+///
+/// `MarketPredictionService`ごとに1つ起動でき、設定された間隔で`predict()`を
+/// 呼び出し、新たに検知された異常を`AlertingConfig`のWebhookエンドポイントへ
+/// POSTする。再学習中（`is_training()`）はアラートを抑制し、モデル入れ替え
+/// 途中の不安定な予測が誤報として通知されるのを防ぐ。
+pub struct DetectionRunner {
+    /// 実行中のバックグラウンドタスク
+    handle: Mutex<Option<tokio::task::JoinHandle<()>>>,
+}
+
+impl DetectionRunner {
+    /// 新しい検知ランナーを作成
+    pub fn new() -> Self {
+        Self {
+            handle: Mutex::new(None),
+        }
+    }
+
+    /// ランナーを起動する。`PredictionConfig.alerting`が設定されていない、
+    /// または既に起動中の場合は何もしない。
+    pub fn start(&self, service: Arc<MarketPredictionService>) {
+        let mut handle = self.handle.lock().unwrap();
+        if handle.is_some() {
+            return;
+        }
+
+        let alerting = match service.alerting_config() {
+            Some(alerting) => alerting,
+            None => return,
+        };
+
+        let AlertingType::Webhook { endpoint, interval_seconds } = alerting.alerting_type;
+
+        let task = tokio::spawn(async move {
+            let client = reqwest::Client::new();
+            let mut interval = tokio::time::interval(Duration::from_secs(interval_seconds.max(1)));
+            let mut last_anomaly_count = 0usize;
+
+            loop {
+                interval.tick().await;
+
+                // 再学習中はアラートを抑制する
+                if service.is_training() {
+                    continue;
+                }
+
+                if let Err(e) = service.predict() {
+                    error!("DetectionRunner: predict()の実行に失敗しました: {}", e);
+                    continue;
+                }
+
+                let anomalies = service.get_anomalies();
+                if anomalies.len() <= last_anomaly_count {
+                    continue;
+                }
+
+                for anomaly in &anomalies[last_anomaly_count..] {
+                    let payload = serde_json::json!({
+                        "prediction_id": anomaly.prediction_id,
+                        "timestamp": anomaly.timestamp,
+                        "predicted_value": anomaly.predicted_value,
+                        "actual_value": anomaly.actual_value,
+                        "deviation": anomaly.deviation,
+                        "crossed_bound": anomaly.crossed_bound,
+                    });
+
+                    if let Err(e) = client.post(&endpoint).json(&payload).send().await {
+                        error!("DetectionRunner: Webhookへの通知に失敗しました: {}", e);
+                    }
+                }
+                last_anomaly_count = anomalies.len();
+            }
+        });
+
+        *handle = Some(task);
+    }
+
+    /// ランナーを停止する
+    pub fn stop(&self) {
+        if let Some(task) = self.handle.lock().unwrap().take() {
+            task.abort();
+        }
+    }
+}
+
+/// サービスごとに保持する、ロールバック可能なモデルスナップショット
+struct ModelSnapshot {
+    /// このスナップショットのモデルバージョン
+    version: u64,
+    /// `save`が書き出したモデルファイルの内容
+    bytes: Vec<u8>,
+}
+
+/// サービスあたりのデフォルト保持スナップショット数
+const DEFAULT_MAX_VERSIONS_PER_MODEL: usize = 5;
+
 /// 予測サービスマネージャー
 pub struct MarketPredictionServiceManager {
     /// 予測サービス
     services: Arc<RwLock<HashMap<String, Arc<MarketPredictionService>>>>,
+    /// サービスごとのバックグラウンド検知ランナー
+    runners: Arc<RwLock<HashMap<String, Arc<DetectionRunner>>>>,
+    /// サービスごとに保持するロールバック用モデルスナップショット（新しい順）
+    snapshots: Arc<RwLock<HashMap<String, VecDeque<ModelSnapshot>>>>,
+    /// サービスあたりに保持するスナップショットの最大数
+    max_versions_per_model: usize,
 }
 
 impl MarketPredictionServiceManager {
@@ -1101,46 +3895,187 @@ impl MarketPredictionServiceManager {
     pub fn new() -> Self {
         Self {
             services: Arc::new(RwLock::new(HashMap::new())),
+            runners: Arc::new(RwLock::new(HashMap::new())),
+            snapshots: Arc::new(RwLock::new(HashMap::new())),
+            max_versions_per_model: DEFAULT_MAX_VERSIONS_PER_MODEL,
         }
     }
-    
+
+    /// サービスあたりに保持するロールバック用スナップショット数を設定する
+    pub fn with_max_versions_per_model(mut self, max_versions_per_model: usize) -> Self {
+        self.max_versions_per_model = max_versions_per_model.max(1);
+        self
+    }
+
     /// 予測サービスを作成
     pub fn create_service(&self, name: &str, config: PredictionConfig) -> Result<(), Error> {
         let mut services = self.services.write().unwrap();
-        
+
         if services.contains_key(name) {
             return Err(Error::AlreadyExists(format!("予測サービス {} は既に存在します", name)));
         }
-        
+
         let service = Arc::new(MarketPredictionService::new(config)?);
         services.insert(name.to_string(), service);
-        
+
         Ok(())
     }
-    
+
     /// 予測サービスを取得
     pub fn get_service(&self, name: &str) -> Result<Arc<MarketPredictionService>, Error> {
         let services = self.services.read().unwrap();
-        
+
         services.get(name)
             .cloned()
             .ok_or_else(|| Error::NotFound(format!("予測サービス {} が見つかりません", name)))
     }
-    
+
     /// 予測サービスを削除
     pub fn delete_service(&self, name: &str) -> Result<(), Error> {
+        self.stop_runner(name).ok();
+        self.snapshots.write().unwrap().remove(name);
+
         let mut services = self.services.write().unwrap();
-        
+
         if services.remove(name).is_none() {
             return Err(Error::NotFound(format!("予測サービス {} が見つかりません", name)));
         }
-        
+
         Ok(())
     }
-    
+
     /// 全予測サービスを取得
     pub fn get_all_services(&self) -> Vec<String> {
         let services = self.services.read().unwrap();
         services.keys().cloned().collect()
     }
+
+    /// 指定した予測サービスのバックグラウンド検知ランナーを起動する
+    pub fn start_runner(&self, name: &str) -> Result<(), Error> {
+        let service = self.get_service(name)?;
+
+        let mut runners = self.runners.write().unwrap();
+        let runner = runners
+            .entry(name.to_string())
+            .or_insert_with(|| Arc::new(DetectionRunner::new()));
+        runner.start(service);
+
+        Ok(())
+    }
+
+    /// 指定した予測サービスのバックグラウンド検知ランナーを停止する
+    pub fn stop_runner(&self, name: &str) -> Result<(), Error> {
+        let runners = self.runners.read().unwrap();
+        let runner = runners
+            .get(name)
+            .ok_or_else(|| Error::NotFound(format!("検知ランナー {} が見つかりません", name)))?;
+        runner.stop();
+
+        Ok(())
+    }
+
+    /// 指定した予測サービスへモデルファイルをホットリロードする
+    ///
+    /// 読み込み（`warmup`による検証含む）に成功すると、ロールバックに備えて
+    /// そのモデルファイルの内容を`max_versions_per_model`件までスナップショット
+    /// として保持する。読み込みに失敗した場合、現在提供中のモデルは変更されない。
+    pub fn load_model(&self, name: &str, path: &str) -> Result<(), Error> {
+        let service = self.get_service(name)?;
+
+        let bytes = std::fs::read(path)
+            .map_err(|e| Error::IOError(format!("モデルファイルの読み込みに失敗しました: {}", e)))?;
+
+        service.load_model(path)?;
+
+        let version = service.current_version();
+        let mut snapshots = self.snapshots.write().unwrap();
+        let entry = snapshots.entry(name.to_string()).or_insert_with(VecDeque::new);
+        entry.push_front(ModelSnapshot { version, bytes });
+        while entry.len() > self.max_versions_per_model {
+            entry.pop_back();
+        }
+
+        Ok(())
+    }
+
+    /// 指定した予測サービスを、保持しているスナップショットの中の特定バージョンへ戻す
+    ///
+    /// ロールバック先のモデルも通常のホットリロードと同じ`warmup`検証を経るため、
+    /// 壊れたスナップショットへ戻ってしまうことはない。
+    pub fn rollback_to(&self, name: &str, version: u64) -> Result<(), Error> {
+        let bytes = {
+            let snapshots = self.snapshots.read().unwrap();
+            let entry = snapshots.get(name).ok_or_else(|| {
+                Error::NotFound(format!("予測サービス {} のスナップショットが見つかりません", name))
+            })?;
+            entry
+                .iter()
+                .find(|snapshot| snapshot.version == version)
+                .map(|snapshot| snapshot.bytes.clone())
+                .ok_or_else(|| {
+                    Error::NotFound(format!(
+                        "予測サービス {} にバージョン {} のスナップショットが見つかりません",
+                        name, version
+                    ))
+                })?
+        };
+
+        let tmp_path = std::env::temp_dir().join(format!(
+            "shardx-model-rollback-{}-{}.json",
+            name,
+            Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let tmp_path_str = tmp_path.to_string_lossy().to_string();
+
+        std::fs::write(&tmp_path_str, &bytes)
+            .map_err(|e| Error::IOError(format!("ロールバック用一時ファイルの書き込みに失敗しました: {}", e)))?;
+
+        let result = self.load_model(name, &tmp_path_str);
+        let _ = std::fs::remove_file(&tmp_path_str);
+
+        result
+    }
+
+    /// 複数サービスの予測を一括で実行する
+    ///
+    /// サービスごとの結果を`Result`として分離して返すため、存在しない
+    /// サービス名や個別の予測失敗（未学習・データ不足など）があっても、
+    /// 残りのサービスの結果取得は妨げられない。
+    pub fn predict_batch(&self, names: &[String]) -> HashMap<String, Result<PredictionResult, Error>> {
+        let mut results = HashMap::new();
+
+        for name in names {
+            let result = self.get_service(name).and_then(|service| service.predict());
+            results.insert(name.clone(), result);
+        }
+
+        results
+    }
+
+    /// 複数サービスへ一括で特徴量データを投入する
+    ///
+    /// `entries`をサービス名でグルーピングしたうえで、サービスごとに
+    /// `feature_data`のロックを1回だけ取得してまとめて追加する
+    /// （[`MarketPredictionService::add_feature_data_batch`]参照）。
+    /// 存在しないサービス名や個別のサービスでの失敗は、そのサービスの
+    /// 結果としてのみ記録され、他のサービスへの投入を妨げない。
+    pub fn add_feature_data_batch(
+        &self,
+        entries: Vec<(String, FeatureData)>,
+    ) -> HashMap<String, Result<(), Error>> {
+        let mut grouped: HashMap<String, Vec<FeatureData>> = HashMap::new();
+        for (name, data) in entries {
+            grouped.entry(name).or_insert_with(Vec::new).push(data);
+        }
+
+        let mut results = HashMap::new();
+        for (name, data) in grouped {
+            let result = self
+                .get_service(&name)
+                .and_then(|service| service.add_feature_data_batch(data));
+            results.insert(name, result);
+        }
+
+        results
+    }
 }
\ No newline at end of file
@@ -110,7 +110,14 @@ impl ApiServer {
             .and(warp::get())
             .and(with_wallet_manager(Arc::clone(&wallet_manager_clone)))
             .and_then(handle_get_account);
-        
+
+        // アカウント検索エンドポイント（フィルタ述語による絞り込み）
+        let query_accounts = warp::path!("accounts" / "query")
+            .and(warp::post())
+            .and(warp::body::json())
+            .and(with_wallet_manager(Arc::clone(&wallet_manager_clone)))
+            .and_then(handle_query_accounts);
+
         // 送金エンドポイント
         let transfer = warp::path("transfer")
             .and(warp::post())
@@ -118,7 +125,35 @@ impl ApiServer {
             .and(with_wallet_manager(Arc::clone(&wallet_manager_clone)))
             .and(with_node(Arc::clone(&node_clone)))
             .and_then(handle_transfer);
-        
+
+        // マルチシグ送金提案エンドポイント
+        let propose_transaction = warp::path!("tx" / "propose")
+            .and(warp::post())
+            .and(warp::body::json())
+            .and(with_wallet_manager(Arc::clone(&wallet_manager_clone)))
+            .and_then(handle_propose_transaction);
+
+        // マルチシグ署名提出エンドポイント
+        let sign_transaction = warp::path!("tx" / String / "sign")
+            .and(warp::post())
+            .and(warp::body::json())
+            .and(with_wallet_manager(Arc::clone(&wallet_manager_clone)))
+            .and(with_node(Arc::clone(&node_clone)))
+            .and_then(handle_sign_transaction);
+
+        // トランザクションステータス取得エンドポイント
+        let get_transaction_status = warp::path!("tx" / String / "status")
+            .and(warp::get())
+            .and(with_node(Arc::clone(&node_clone)))
+            .and_then(handle_get_transaction_status);
+
+        // トランザクションステータス一括取得エンドポイント
+        let get_transaction_statuses = warp::path!("tx" / "statuses")
+            .and(warp::post())
+            .and(warp::body::json())
+            .and(with_node(Arc::clone(&node_clone)))
+            .and_then(handle_get_transaction_statuses);
+
         // DEX API
         // 取引ペア追加エンドポイント
         let add_trading_pair = warp::path("trading-pairs")
@@ -154,7 +189,13 @@ impl ApiServer {
             .and(warp::query::<TradeHistoryQuery>())
             .and(with_dex_manager(Arc::clone(&dex_manager_clone)))
             .and_then(handle_get_trade_history);
-        
+
+        // マーケットデータ購読用WebSocketエンドポイント
+        let market_ws = warp::path("ws")
+            .and(warp::ws())
+            .and(with_dex_manager(Arc::clone(&dex_manager_clone)))
+            .and_then(handle_market_ws);
+
         // CORSを設定
         let cors = warp::cors()
             .allow_any_origin()
@@ -166,12 +207,18 @@ impl ApiServer {
             .or(create_tx)
             .or(create_account)
             .or(get_account)
+            .or(query_accounts)
             .or(transfer)
+            .or(propose_transaction)
+            .or(sign_transaction)
+            .or(get_transaction_status)
+            .or(get_transaction_statuses)
             .or(add_trading_pair)
             .or(create_order)
             .or(cancel_order)
             .or(get_order_book)
             .or(get_trade_history)
+            .or(market_ws)
             .with(cors)
             .with(warp::log("api"));
         
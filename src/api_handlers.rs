@@ -1,14 +1,20 @@
-use crate::dex::{DexManager, Order, OrderType, TradingPair, Trade};
-use crate::node::Node;
+use crate::crypto::{PublicKey, Signature};
+use crate::dex::{DexManager, MarketEvent, Order, OrderType, TradingPair, Trade};
+use crate::node::{Node, TransactionStatusInfo};
 use crate::transaction::Transaction;
-use crate::wallet::{Account, WalletManager};
-use log::{error, info};
+use crate::wallet::multisig::threshold::ThresholdPolicy;
+use crate::wallet::{Account, AccountFilter, WalletManager};
+use futures::{SinkExt, StreamExt};
+use log::{error, info, warn};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::convert::Infallible;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{broadcast, Mutex};
+use warp::ws::{Message, WebSocket};
 use warp::{Rejection, Reply};
 use warp::reply::Response;
+use base64;
 
 // ウォレットAPI用の構造体
 
@@ -17,6 +23,11 @@ use warp::reply::Response;
 pub struct CreateAccountRequest {
     /// アカウント名
     pub name: String,
+    /// このアカウントに適用するマルチシグ閾値ポリシー（オプション）
+    ///
+    /// 設定された場合、このアカウントからの送金は `POST /tx/propose` と
+    /// `POST /tx/{id}/sign` による署名収集を経なければならない。
+    pub policy: Option<ThresholdPolicy>,
 }
 
 /// アカウント作成レスポンス
@@ -34,6 +45,30 @@ pub struct CreateAccountResponse {
     pub created_at: String,
 }
 
+/// アカウント検索リクエスト
+///
+/// Solanaの`getProgramAccounts`の`filters`/`dataSlice`設計を参考にしたモデル。
+/// `filters`はすべてAND条件として評価される。
+#[derive(Debug, Deserialize)]
+pub struct QueryAccountsRequest {
+    /// 適用するフィルタ述語の一覧
+    pub filters: Vec<AccountFilter>,
+    /// 最大取得件数（省略時は100）
+    pub limit: Option<usize>,
+    /// レスポンスのエンコーディング（"jsonParsed"（デフォルト）または"base64"）
+    pub encoding: Option<String>,
+}
+
+/// アカウント検索レスポンス
+#[derive(Debug, Serialize)]
+pub struct QueryAccountsResponse {
+    /// 条件に一致したアカウント（`encoding`に応じてJSONオブジェクトまたはbase64文字列）
+    pub accounts: Vec<serde_json::Value>,
+}
+
+/// アカウント検索のデフォルト取得件数
+const DEFAULT_ACCOUNT_QUERY_LIMIT: usize = 100;
+
 /// 送金リクエスト
 #[derive(Debug, Deserialize)]
 pub struct TransferRequest {
@@ -56,6 +91,74 @@ pub struct TransferResponse {
     pub status: String,
 }
 
+/// マルチシグ送金提案リクエスト
+#[derive(Debug, Deserialize)]
+pub struct ProposeTransactionRequest {
+    /// 送信元アカウントID（マルチシグ閾値ポリシーが設定されている必要がある）
+    pub from_account_id: String,
+    /// 送信先アカウントID
+    pub to_account_id: String,
+    /// 金額
+    pub amount: f64,
+    /// トークンID（オプション）
+    pub token_id: Option<String>,
+}
+
+/// マルチシグ送金提案レスポンス
+#[derive(Debug, Serialize)]
+pub struct ProposeTransactionResponse {
+    /// 提案ID（署名提出時に使用する）
+    pub proposal_id: String,
+    /// 署名対象メッセージ（16進数エンコード）
+    pub message: String,
+    /// 閾値達成までに必要な残り署名数
+    pub remaining_signatures: usize,
+    /// これまでに集まった署名者数
+    pub signers_so_far: usize,
+    /// 提案の有効期限までの残り秒数（期限なしの場合は`None`）
+    pub expires_in_seconds: Option<i64>,
+}
+
+/// マルチシグ署名提出リクエスト
+#[derive(Debug, Deserialize)]
+pub struct SignTransactionRequest {
+    /// 署名者の公開鍵
+    pub public_key: PublicKey,
+    /// `message`に対する署名
+    pub signature: Signature,
+}
+
+/// マルチシグ署名提出レスポンス
+#[derive(Debug, Serialize)]
+pub struct SignTransactionResponse {
+    /// 提案ID
+    pub proposal_id: String,
+    /// 閾値達成までに必要な残り署名数（送信済みの場合は0）
+    pub remaining_signatures: usize,
+    /// これまでに集まった署名者数（送信済みの場合は0）
+    pub signers_so_far: usize,
+    /// 提案の有効期限までの残り秒数（期限なしまたは送信済みの場合は`None`）
+    pub expires_in_seconds: Option<i64>,
+    /// 閾値を満たしてノードに送信されたかどうか
+    pub submitted: bool,
+    /// 送信された場合のトランザクションID
+    pub transaction_id: Option<String>,
+}
+
+/// トランザクションステータス一括照会リクエスト
+#[derive(Debug, Deserialize)]
+pub struct BatchTransactionStatusRequest {
+    /// 照会するトランザクションIDの一覧（最大[`crate::node::MAX_BATCH_STATUS_IDS`]件）
+    pub ids: Vec<String>,
+}
+
+/// トランザクションステータス一括照会レスポンス
+#[derive(Debug, Serialize)]
+pub struct BatchTransactionStatusResponse {
+    /// `ids`と同じ順序で並んだステータス一覧
+    pub statuses: Vec<TransactionStatusInfo>,
+}
+
 // DEX API用の構造体
 
 /// 取引ペア追加リクエスト
@@ -192,6 +295,62 @@ pub struct TradeHistoryResponse {
     pub trades: Vec<TradeInfo>,
 }
 
+// マーケットデータWebSocket API用の構造体
+
+/// クライアントから受信する購読リクエスト
+///
+/// JSON-RPC風のpub/subプロトコル。`{"method":"subscribe","params":{...}}`の形式。
+#[derive(Debug, Deserialize)]
+#[serde(tag = "method", content = "params", rename_all = "lowercase")]
+enum WsClientRequest {
+    /// チャンネルを購読する
+    Subscribe(SubscribeParams),
+    /// 購読を解除する
+    Unsubscribe(UnsubscribeParams),
+}
+
+/// 購読パラメータ
+#[derive(Debug, Deserialize)]
+struct SubscribeParams {
+    /// 購読するチャンネル（"orderbook" または "trades"）
+    channel: String,
+    /// 基準通貨
+    base: String,
+    /// 相手通貨
+    quote: String,
+}
+
+/// 購読解除パラメータ
+#[derive(Debug, Deserialize)]
+struct UnsubscribeParams {
+    /// 解除する購読ID
+    id: u64,
+}
+
+/// サーバーからクライアントへ送信する通知
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum WsNotification<'a> {
+    /// 購読を受け付けたことの確認と、オーダーブックまたは取引履歴の初期スナップショット
+    Subscribed {
+        id: u64,
+        channel: &'a str,
+        pair: String,
+        snapshot: serde_json::Value,
+    },
+    /// 購読解除の確認
+    Unsubscribed { id: u64 },
+    /// 購読中のチャンネルへの増分更新
+    Update {
+        id: u64,
+        channel: &'a str,
+        pair: String,
+        data: serde_json::Value,
+    },
+    /// リクエストの処理エラー
+    Error { message: String },
+}
+
 // ウォレットAPIハンドラー
 
 /// アカウント作成ハンドラー
@@ -201,6 +360,19 @@ pub async fn handle_create_account(
 ) -> Result<Response, Rejection> {
     match wallet_manager.create_account(req.name) {
         Ok(account) => {
+            if let Some(policy) = req.policy {
+                if let Err(e) = wallet_manager.set_account_policy(&account.id, policy) {
+                    error!("Failed to set multisig policy for account {}: {}", account.id, e);
+                    let json_response = serde_json::json!({
+                        "error": format!("Failed to set multisig policy: {}", e)
+                    });
+                    return Ok(warp::reply::with_status(
+                        warp::reply::json(&json_response),
+                        warp::http::StatusCode::BAD_REQUEST,
+                    ).into_response());
+                }
+            }
+
             let response = CreateAccountResponse {
                 id: account.id,
                 public_key: account.public_key,
@@ -252,6 +424,64 @@ pub async fn handle_get_account(
     }
 }
 
+/// アカウント検索ハンドラー
+///
+/// `filters`に一致するアカウントを`WalletManager::query_accounts`で検索する。
+/// フィルタ数または`limit`がサーバー側の上限を超える場合は400を返す。
+pub async fn handle_query_accounts(
+    req: QueryAccountsRequest,
+    wallet_manager: Arc<WalletManager>,
+) -> Result<Response, Rejection> {
+    let limit = req.limit.unwrap_or(DEFAULT_ACCOUNT_QUERY_LIMIT);
+    let encoding = req.encoding.unwrap_or_else(|| "jsonParsed".to_string());
+
+    if encoding != "jsonParsed" && encoding != "base64" {
+        let json_response = serde_json::json!({
+            "error": format!("Unsupported encoding: {}", encoding)
+        });
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&json_response),
+            warp::http::StatusCode::BAD_REQUEST,
+        ).into_response());
+    }
+
+    match wallet_manager.query_accounts(&req.filters, limit) {
+        Ok(accounts) => {
+            let accounts = accounts
+                .into_iter()
+                .map(|account| {
+                    let response = CreateAccountResponse {
+                        id: account.id,
+                        public_key: account.public_key,
+                        name: account.name,
+                        balance: account.balance,
+                        created_at: account.created_at.to_rfc3339(),
+                    };
+
+                    if encoding == "base64" {
+                        let bytes = serde_json::to_vec(&response).unwrap_or_default();
+                        serde_json::Value::String(base64::encode(bytes))
+                    } else {
+                        serde_json::to_value(&response).unwrap_or(serde_json::Value::Null)
+                    }
+                })
+                .collect();
+
+            Ok(warp::reply::json(&QueryAccountsResponse { accounts }).into_response())
+        }
+        Err(e) => {
+            error!("Failed to query accounts: {}", e);
+            let json_response = serde_json::json!({
+                "error": format!("Failed to query accounts: {}", e)
+            });
+            Ok(warp::reply::with_status(
+                warp::reply::json(&json_response),
+                warp::http::StatusCode::BAD_REQUEST,
+            ).into_response())
+        }
+    }
+}
+
 /// 送金ハンドラー
 pub async fn handle_transfer(
     req: TransferRequest,
@@ -302,6 +532,156 @@ pub async fn handle_transfer(
     }
 }
 
+/// マルチシグ送金提案ハンドラー
+pub async fn handle_propose_transaction(
+    req: ProposeTransactionRequest,
+    wallet_manager: Arc<WalletManager>,
+) -> Result<Response, Rejection> {
+    match wallet_manager.propose_transaction(
+        &req.from_account_id,
+        &req.to_account_id,
+        req.amount,
+        req.token_id,
+    ) {
+        Ok(proposal) => {
+            // 提案直後なので必ず存在する
+            let (_, remaining, expires_in) = wallet_manager
+                .proposal_status(&proposal.id)
+                .expect("proposal was just created");
+
+            let response = ProposeTransactionResponse {
+                proposal_id: proposal.id,
+                message: hex::encode(&proposal.message),
+                remaining_signatures: remaining,
+                signers_so_far: proposal.signatures.len(),
+                expires_in_seconds: expires_in,
+            };
+            Ok(warp::reply::json(&response).into_response())
+        }
+        Err(e) => {
+            error!("Failed to propose transaction: {}", e);
+            let json_response = serde_json::json!({
+                "error": format!("Failed to propose transaction: {}", e)
+            });
+            Ok(warp::reply::with_status(
+                warp::reply::json(&json_response),
+                warp::http::StatusCode::BAD_REQUEST,
+            ).into_response())
+        }
+    }
+}
+
+/// マルチシグ署名提出ハンドラー
+///
+/// 署名を追加した結果、`ThresholdPolicy`の閾値を満たした場合はそのまま
+/// `node.submit_transaction`に送信し、提案を破棄する。
+pub async fn handle_sign_transaction(
+    proposal_id: String,
+    req: SignTransactionRequest,
+    wallet_manager: Arc<WalletManager>,
+    node: Arc<Mutex<Node>>,
+) -> Result<Response, Rejection> {
+    match wallet_manager.add_signature(&proposal_id, req.public_key, req.signature) {
+        Ok(Some(transaction)) => {
+            let tx_id = transaction.id.clone();
+            let submit_result = {
+                let node = node.lock().await;
+                node.submit_transaction(transaction).await
+            };
+            wallet_manager.remove_proposal(&proposal_id);
+
+            match submit_result {
+                Ok(_) => {
+                    info!("Multisig transaction {} submitted", tx_id);
+                    let response = SignTransactionResponse {
+                        proposal_id,
+                        remaining_signatures: 0,
+                        signers_so_far: 0,
+                        expires_in_seconds: None,
+                        submitted: true,
+                        transaction_id: Some(tx_id),
+                    };
+                    Ok(warp::reply::json(&response).into_response())
+                }
+                Err(e) => {
+                    error!("Failed to submit multisig transaction: {}", e);
+                    let json_response = serde_json::json!({
+                        "error": format!("Failed to submit transaction: {}", e)
+                    });
+                    Ok(warp::reply::with_status(
+                        warp::reply::json(&json_response),
+                        warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+                    ).into_response())
+                }
+            }
+        }
+        Ok(None) => match wallet_manager.proposal_status(&proposal_id) {
+            Ok((proposal, remaining, expires_in)) => {
+                let response = SignTransactionResponse {
+                    proposal_id,
+                    remaining_signatures: remaining,
+                    signers_so_far: proposal.signatures.len(),
+                    expires_in_seconds: expires_in,
+                    submitted: false,
+                    transaction_id: None,
+                };
+                Ok(warp::reply::json(&response).into_response())
+            }
+            Err(e) => {
+                error!("Failed to read proposal status: {}", e);
+                let json_response = serde_json::json!({
+                    "error": format!("Failed to read proposal status: {}", e)
+                });
+                Ok(warp::reply::with_status(
+                    warp::reply::json(&json_response),
+                    warp::http::StatusCode::NOT_FOUND,
+                ).into_response())
+            }
+        },
+        Err(e) => {
+            error!("Failed to add signature: {}", e);
+            let json_response = serde_json::json!({
+                "error": format!("Failed to add signature: {}", e)
+            });
+            Ok(warp::reply::with_status(
+                warp::reply::json(&json_response),
+                warp::http::StatusCode::BAD_REQUEST,
+            ).into_response())
+        }
+    }
+}
+
+/// トランザクションステータス取得ハンドラー
+pub async fn handle_get_transaction_status(
+    tx_id: String,
+    node: Arc<Mutex<Node>>,
+) -> Result<impl Reply, Rejection> {
+    let node = node.lock().await;
+    let status = node.get_transaction_status(&tx_id);
+    Ok(warp::reply::json(&status))
+}
+
+/// トランザクションステータス一括取得ハンドラー
+pub async fn handle_get_transaction_statuses(
+    req: BatchTransactionStatusRequest,
+    node: Arc<Mutex<Node>>,
+) -> Result<Response, Rejection> {
+    let node = node.lock().await;
+    match node.get_transaction_statuses(&req.ids) {
+        Ok(statuses) => Ok(warp::reply::json(&BatchTransactionStatusResponse { statuses }).into_response()),
+        Err(e) => {
+            error!("Failed to get transaction statuses: {}", e);
+            let json_response = serde_json::json!({
+                "error": format!("Failed to get transaction statuses: {}", e)
+            });
+            Ok(warp::reply::with_status(
+                warp::reply::json(&json_response),
+                warp::http::StatusCode::BAD_REQUEST,
+            ).into_response())
+        }
+    }
+}
+
 // DEX APIハンドラー
 
 /// 取引ペア追加ハンドラー
@@ -489,4 +869,153 @@ pub async fn handle_get_trade_history(
             ).into_response())
         }
     }
+}
+
+// マーケットデータWebSocket API用のハンドラー
+
+/// マーケットデータ購読用WebSocketエンドポイントのハンドラー
+///
+/// アップグレード後は`handle_market_ws_connection`がJSON-RPC風のpub/subプロトコルで
+/// オーダーブックの増分更新・約定情報を配信する。
+pub async fn handle_market_ws(
+    ws: warp::ws::Ws,
+    dex_manager: Arc<DexManager>,
+) -> Result<impl Reply, Rejection> {
+    Ok(ws.on_upgrade(move |socket| handle_market_ws_connection(socket, dex_manager)))
+}
+
+/// 個々のWebSocket接続に対する購読セッションを処理する
+async fn handle_market_ws_connection(socket: WebSocket, dex_manager: Arc<DexManager>) {
+    let (mut ws_tx, mut ws_rx) = socket.split();
+    let (out_tx, mut out_rx) = tokio::sync::mpsc::unbounded_channel::<Message>();
+
+    // 購読タスクからの通知をまとめてソケットへ書き出す
+    tokio::task::spawn(async move {
+        while let Some(message) = out_rx.recv().await {
+            if ws_tx.send(message).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut next_id: u64 = 1;
+    let mut subscriptions: HashMap<u64, tokio::task::JoinHandle<()>> = HashMap::new();
+
+    while let Some(received) = ws_rx.next().await {
+        let message = match received {
+            Ok(message) => message,
+            Err(_) => break,
+        };
+
+        if !message.is_text() {
+            continue;
+        }
+
+        let request: WsClientRequest = match serde_json::from_str(message.to_str().unwrap_or("")) {
+            Ok(request) => request,
+            Err(e) => {
+                warn!("Invalid market data subscription request: {}", e);
+                send_notification(&out_tx, WsNotification::Error {
+                    message: format!("Invalid request: {}", e),
+                });
+                continue;
+            }
+        };
+
+        match request {
+            WsClientRequest::Subscribe(params) => {
+                let pair = TradingPair::new(params.base, params.quote);
+                let pair_str = pair.to_string();
+                let channel = params.channel.to_lowercase();
+
+                let snapshot = match channel.as_str() {
+                    "orderbook" => match dex_manager.get_order_book(&pair) {
+                        Ok((bids, asks)) => serde_json::json!({ "bids": bids, "asks": asks }),
+                        Err(e) => {
+                            send_notification(&out_tx, WsNotification::Error { message: e });
+                            continue;
+                        }
+                    },
+                    "trades" => match dex_manager.get_trade_history(&pair) {
+                        Ok(trades) => serde_json::json!(trades),
+                        Err(e) => {
+                            send_notification(&out_tx, WsNotification::Error { message: e });
+                            continue;
+                        }
+                    },
+                    other => {
+                        send_notification(&out_tx, WsNotification::Error {
+                            message: format!("Unknown channel: {}", other),
+                        });
+                        continue;
+                    }
+                };
+
+                let id = next_id;
+                next_id += 1;
+
+                send_notification(&out_tx, WsNotification::Subscribed {
+                    id,
+                    channel: if channel == "orderbook" { "orderbook" } else { "trades" },
+                    pair: pair_str.clone(),
+                    snapshot,
+                });
+
+                let mut receiver = dex_manager.subscriptions().subscribe(&pair);
+                let forward_tx = out_tx.clone();
+                let forward_channel = channel.clone();
+
+                let handle = tokio::task::spawn(async move {
+                    loop {
+                        match receiver.recv().await {
+                            Ok(event) => {
+                                let data = match (forward_channel.as_str(), &event) {
+                                    ("orderbook", MarketEvent::Orderbook(delta)) => serde_json::to_value(delta).ok(),
+                                    ("trades", MarketEvent::Trades(trade)) => serde_json::to_value(trade).ok(),
+                                    _ => None,
+                                };
+                                let Some(data) = data else { continue };
+                                let notification = WsNotification::Update {
+                                    id,
+                                    channel: if forward_channel == "orderbook" { "orderbook" } else { "trades" },
+                                    pair: pair_str.clone(),
+                                    data,
+                                };
+                                if !send_notification(&forward_tx, notification) {
+                                    break;
+                                }
+                            }
+                            Err(broadcast::error::RecvError::Closed) => break,
+                            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                        }
+                    }
+                });
+
+                subscriptions.insert(id, handle);
+            }
+            WsClientRequest::Unsubscribe(params) => {
+                if let Some(handle) = subscriptions.remove(&params.id) {
+                    handle.abort();
+                    send_notification(&out_tx, WsNotification::Unsubscribed { id: params.id });
+                } else {
+                    send_notification(&out_tx, WsNotification::Error {
+                        message: format!("Unknown subscription id: {}", params.id),
+                    });
+                }
+            }
+        }
+    }
+
+    // ソケットが切断されたので、残っている購読タスクをすべて停止する
+    for (_, handle) in subscriptions {
+        handle.abort();
+    }
+}
+
+/// 通知をJSONにシリアライズしてソケットへの送信キューに積む
+///
+/// 送信先タスクが既に終了している場合は`false`を返す。
+fn send_notification(sender: &tokio::sync::mpsc::UnboundedSender<Message>, notification: WsNotification) -> bool {
+    let text = serde_json::to_string(&notification).unwrap_or_else(|_| "{}".to_string());
+    sender.send(Message::text(text)).is_ok()
 }
\ No newline at end of file
@@ -6,4 +6,7 @@ pub mod zero_copy;
 pub use executor::{AsyncExecutor, PriorityAsyncExecutor, TaskPriority};
 pub use processor::AsyncProcessor;
 pub use task_scheduler::TaskScheduler;
-pub use zero_copy::{ZeroCopyBuffer, ZeroCopyBufferMut, ZeroCopyData};
+pub use zero_copy::{
+    AsLittleEndian, ZeroCopyBuffer, ZeroCopyBufferMut, ZeroCopyChain, ZeroCopyData, ZeroCopyReader,
+    ZeroCopyVec,
+};
@@ -1,13 +1,39 @@
+use bytes::buf::UninitSlice;
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::io::Read;
+use std::marker::PhantomData;
+use std::mem::{size_of, MaybeUninit};
 use std::ops::{Deref, DerefMut};
 use std::sync::Arc;
 
+use crate::error::Error;
+
+/// バイト列をデバッグ表示・ログ出力向けに整形する
+///
+/// 有効なUTF-8であれば引用符付き文字列として表示し、そうでなければ16進数を
+/// 8文字ごとにスペース区切りでグループ化する。ブロック/トランザクションの
+/// ペイロードのような生バイト列をログに出す際に、生の`Vec<u8>`のダンプより
+/// 読みやすくするためのもの。
+fn format_bytes_for_debug(bytes: &[u8]) -> String {
+    if let Ok(s) = std::str::from_utf8(bytes) {
+        return format!("{:?}", s);
+    }
+
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    hex.as_bytes()
+        .chunks(8)
+        .map(|chunk| std::str::from_utf8(chunk).unwrap())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 /// ゼロコピーバッファ
 ///
 /// データのコピーを最小限に抑えるためのバッファ。
 /// 参照カウントによるメモリ共有を活用し、不要なコピーを回避する。
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct ZeroCopyBuffer {
     /// 内部データ
     inner: Bytes,
@@ -41,6 +67,11 @@ impl ZeroCopyBuffer {
         &self.inner
     }
 
+    /// バッファの内容をログ出力向けに整形した文字列に変換する
+    pub fn to_hex(&self) -> String {
+        format_bytes_for_debug(self.as_bytes())
+    }
+
     /// バッファの一部を取得
     pub fn slice(&self, range: impl std::ops::RangeBounds<usize>) -> Self {
         Self {
@@ -62,6 +93,19 @@ impl ZeroCopyBuffer {
     pub fn into_bytes(self) -> Bytes {
         self.inner
     }
+
+    /// 別のバッファと連結し、コピーせずに一つの論理的な`Buf`として扱う
+    ///
+    /// [`concat`](Self::concat)と異なり新しいバッファへのコピーを行わないため、
+    /// フラグメント化されたネットワークフレームの組み立てに向く。
+    pub fn chain(self, other: Self) -> ZeroCopyChain {
+        ZeroCopyChain::new(self, other)
+    }
+
+    /// このバッファを`std::io::Read`として扱うリーダーに変換する
+    pub fn reader(self) -> ZeroCopyReader {
+        ZeroCopyReader::new(self)
+    }
 }
 
 impl From<Vec<u8>> for ZeroCopyBuffer {
@@ -86,6 +130,14 @@ impl From<Bytes> for ZeroCopyBuffer {
     }
 }
 
+impl std::fmt::Debug for ZeroCopyBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ZeroCopyBuffer")
+            .field("inner", &self.to_hex())
+            .finish()
+    }
+}
+
 impl AsRef<[u8]> for ZeroCopyBuffer {
     fn as_ref(&self) -> &[u8] {
         self.as_bytes()
@@ -100,11 +152,102 @@ impl Deref for ZeroCopyBuffer {
     }
 }
 
+impl Buf for ZeroCopyBuffer {
+    fn remaining(&self) -> usize {
+        self.inner.remaining()
+    }
+
+    fn chunk(&self) -> &[u8] {
+        self.inner.chunk()
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        self.inner.advance(cnt)
+    }
+}
+
+/// 複数の`ZeroCopyBuffer`をコピーせずに一つの論理的な`Buf`として扱うチェーン
+///
+/// `bytes`の`Chain`アダプタを参考にしたもの。フラグメント化されたネットワーク
+/// フレームを、結合時にコピーすることなくストリームとして読み出せる。
+#[derive(Debug, Clone)]
+pub struct ZeroCopyChain {
+    /// 連結されたバッファ。先頭から順に読み進める
+    buffers: VecDeque<ZeroCopyBuffer>,
+}
+
+impl ZeroCopyChain {
+    /// 二つのバッファからチェーンを作成する
+    pub fn new(first: ZeroCopyBuffer, second: ZeroCopyBuffer) -> Self {
+        let mut buffers = VecDeque::with_capacity(2);
+        buffers.push_back(first);
+        buffers.push_back(second);
+        Self { buffers }
+    }
+
+    /// チェーンの末尾にバッファを追加する
+    pub fn push(&mut self, buffer: ZeroCopyBuffer) {
+        self.buffers.push_back(buffer);
+    }
+}
+
+impl Buf for ZeroCopyChain {
+    fn remaining(&self) -> usize {
+        self.buffers.iter().map(|buffer| buffer.remaining()).sum()
+    }
+
+    fn chunk(&self) -> &[u8] {
+        self.buffers.front().map(|buffer| buffer.chunk()).unwrap_or(&[])
+    }
+
+    fn advance(&mut self, mut cnt: usize) {
+        while cnt > 0 {
+            let Some(front) = self.buffers.front_mut() else {
+                break;
+            };
+
+            let front_remaining = front.remaining();
+            if cnt < front_remaining {
+                front.advance(cnt);
+                break;
+            }
+
+            cnt -= front_remaining;
+            self.buffers.pop_front();
+        }
+    }
+}
+
+/// `ZeroCopyBuffer`を`std::io::Read`として扱うためのラッパー
+///
+/// 読み出した分だけ内部の`Bytes`を前進させるため、`Read`ベースのパーサーに
+/// そのまま渡してもバッファ全体をコピーすることはない。
+pub struct ZeroCopyReader {
+    inner: Bytes,
+}
+
+impl ZeroCopyReader {
+    /// バッファからリーダーを作成する
+    pub fn new(buffer: ZeroCopyBuffer) -> Self {
+        Self {
+            inner: buffer.into_bytes(),
+        }
+    }
+}
+
+impl Read for ZeroCopyReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = std::cmp::min(buf.len(), self.inner.len());
+        buf[..len].copy_from_slice(&self.inner[..len]);
+        self.inner.advance(len);
+        Ok(len)
+    }
+}
+
 /// 可変ゼロコピーバッファ
 ///
 /// 書き込み可能なゼロコピーバッファ。
 /// 必要に応じて内部バッファを拡張する。
-#[derive(Debug)]
 pub struct ZeroCopyBufferMut {
     /// 内部データ
     inner: BytesMut,
@@ -150,6 +293,11 @@ impl ZeroCopyBufferMut {
         &mut self.inner
     }
 
+    /// バッファの内容をログ出力向けに整形した文字列に変換する
+    pub fn to_hex(&self) -> String {
+        format_bytes_for_debug(self.as_bytes())
+    }
+
     /// バッファにデータを追加
     pub fn put_slice(&mut self, data: &[u8]) {
         self.inner.put_slice(data);
@@ -176,6 +324,67 @@ impl ZeroCopyBufferMut {
     pub fn reserve(&mut self, additional: usize) {
         self.inner.reserve(additional);
     }
+
+    /// 未初期化の予備領域（容量はあるがまだ書き込まれていない部分）を取得する
+    ///
+    /// ソケットなどから直接読み込む際に、事前にゼロ埋めすることなく書き込み先
+    /// として渡せる。`std`の`ReadBuf`/`BorrowBuf`と同じ考え方で、実際に書き込んだ
+    /// バイト数は[`advance_written`](Self::advance_written)で申告する。
+    pub fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        self.inner.spare_capacity_mut()
+    }
+
+    /// 予備領域の先頭`n`バイトに書き込み済みであることを申告し、初期化済み長を
+    /// `n`バイト伸ばす
+    ///
+    /// # Safety
+    /// 呼び出し元は、[`spare_capacity_mut`](Self::spare_capacity_mut)で得た先頭
+    /// `n`バイトに実際に有効な値を書き込み済みであることを保証しなければならない。
+    /// さもないと未初期化メモリを読み出すことになる。
+    pub unsafe fn advance_written(&mut self, n: usize) {
+        let new_len = self.inner.len() + n;
+        self.inner.set_len(new_len);
+    }
+
+    /// 予備領域に直接読み込み、読み込んだバイト数だけ長さを伸ばす
+    ///
+    /// 事前のゼロ埋めや中間バッファへのコピーを行わずに`Read`実装から読み込める。
+    /// 予備領域が無い場合は4096バイト分の容量を確保してから読み込む。
+    pub fn read_from<R: Read>(&mut self, reader: &mut R) -> std::io::Result<usize> {
+        if self.inner.capacity() == self.inner.len() {
+            self.inner.reserve(4096);
+        }
+
+        let spare = self.spare_capacity_mut();
+        let spare_len = spare.len();
+
+        // SAFETY: `MaybeUninit<u8>`は`u8`と同じレイアウトを持つため、この
+        // ポインタキャストは有効である。`reader.read`は戻り値として報告した
+        // バイト数だけを書き込む実装であることを前提としており（`ReadBuf`が
+        // 解決しようとしている契約上の注意点と同様）、申告された範囲だけを
+        // `advance_written`で初期化済みとして扱う。
+        let buf = unsafe {
+            std::slice::from_raw_parts_mut(spare.as_mut_ptr() as *mut u8, spare_len)
+        };
+
+        let n = reader.read(buf)?;
+
+        // SAFETY: `reader.read`が返した`n`バイトは上で確保した予備領域の範囲内
+        // であり、読み込みによって書き込み済みである
+        unsafe {
+            self.advance_written(n);
+        }
+
+        Ok(n)
+    }
+}
+
+impl std::fmt::Debug for ZeroCopyBufferMut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ZeroCopyBufferMut")
+            .field("inner", &self.to_hex())
+            .finish()
+    }
 }
 
 impl AsRef<[u8]> for ZeroCopyBufferMut {
@@ -198,11 +407,25 @@ impl DerefMut for ZeroCopyBufferMut {
     }
 }
 
+unsafe impl BufMut for ZeroCopyBufferMut {
+    fn remaining_mut(&self) -> usize {
+        self.inner.remaining_mut()
+    }
+
+    unsafe fn advance_mut(&mut self, cnt: usize) {
+        self.inner.advance_mut(cnt)
+    }
+
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        self.inner.chunk_mut()
+    }
+}
+
 /// ゼロコピーデータ
 ///
 /// 所有権を持つデータと参照のどちらも格納できる汎用コンテナ。
 /// 不要なコピーを回避するために使用する。
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub enum ZeroCopyData<'a, T: 'a + ?Sized> {
     /// 所有権を持つデータ
     Owned(T),
@@ -210,6 +433,12 @@ pub enum ZeroCopyData<'a, T: 'a + ?Sized> {
     Borrowed(&'a T),
     /// 参照カウント
     Shared(Arc<T>),
+    /// 外部ランタイムへの譲渡を意図した所有権付きアロケーション
+    ///
+    /// `Owned`がコピー後すぐ破棄されうる一時的な所有データを表すのに対し、
+    /// `Boxed`はC/WASMなどのFFI境界へアロケーションそのものを引き渡すことを
+    /// 意図した変種。[`take_boxed`](ZeroCopyData::take_boxed)で中身を移動できる。
+    Boxed(Box<T>),
 }
 
 impl<'a, T: Clone + ?Sized> ZeroCopyData<'a, T> {
@@ -222,6 +451,7 @@ impl<'a, T: Clone + ?Sized> ZeroCopyData<'a, T> {
             ZeroCopyData::Owned(data) => data,
             ZeroCopyData::Borrowed(data) => data.clone(),
             ZeroCopyData::Shared(data) => (*data).clone(),
+            ZeroCopyData::Boxed(data) => (*data).clone(),
         }
     }
 }
@@ -234,6 +464,7 @@ impl<'a, T: ?Sized> Deref for ZeroCopyData<'a, T> {
             ZeroCopyData::Owned(data) => data,
             ZeroCopyData::Borrowed(data) => *data,
             ZeroCopyData::Shared(data) => data.as_ref(),
+            ZeroCopyData::Boxed(data) => data.as_ref(),
         }
     }
 }
@@ -260,6 +491,27 @@ impl<'a> ZeroCopyData<'a, [u8]> {
         ZeroCopyData::Owned(bytes.to_vec())
     }
 
+    /// Box<[u8]>からZeroCopyDataを作成する（FFIへの譲渡前の一時保持に使う）
+    pub fn from_boxed(data: Box<[u8]>) -> ZeroCopyData<'static, [u8]> {
+        ZeroCopyData::Boxed(data)
+    }
+
+    /// `Boxed`バリアントの中身を移動して取り出す
+    ///
+    /// C/WASMなどの外部ランタイムへアロケーションそのものを譲渡する際に使う。
+    /// 取り出した後は空の`Borrowed`バッファが残る。`Boxed`以外のバリアントの
+    /// 場合は`None`を返し、自身は変更しない。
+    pub fn take_boxed(&mut self) -> Option<Box<[u8]>> {
+        if !matches!(self, ZeroCopyData::Boxed(_)) {
+            return None;
+        }
+
+        match std::mem::replace(self, ZeroCopyData::Borrowed(&[])) {
+            ZeroCopyData::Boxed(data) => Some(data),
+            _ => unreachable!("checked above that self is the Boxed variant"),
+        }
+    }
+
     /// データの長さを取得
     pub fn len(&self) -> usize {
         self.deref().len()
@@ -276,8 +528,27 @@ impl<'a> ZeroCopyData<'a, [u8]> {
             ZeroCopyData::Owned(data) => Cow::Borrowed(data),
             ZeroCopyData::Borrowed(data) => Cow::Borrowed(*data),
             ZeroCopyData::Shared(data) => Cow::Borrowed(data.as_ref()),
+            ZeroCopyData::Boxed(data) => Cow::Borrowed(data.as_ref()),
         }
     }
+
+    /// データの内容をログ出力向けに整形した文字列に変換する
+    pub fn to_hex(&self) -> String {
+        format_bytes_for_debug(self.deref())
+    }
+}
+
+impl<'a> std::fmt::Debug for ZeroCopyData<'a, [u8]> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let variant = match self {
+            ZeroCopyData::Owned(_) => "Owned",
+            ZeroCopyData::Borrowed(_) => "Borrowed",
+            ZeroCopyData::Shared(_) => "Shared",
+            ZeroCopyData::Boxed(_) => "Boxed",
+        };
+
+        f.debug_tuple(variant).field(&self.to_hex()).finish()
+    }
 }
 
 impl<'a> From<&'a [u8]> for ZeroCopyData<'a, [u8]> {
@@ -298,12 +569,231 @@ impl From<Arc<[u8]>> for ZeroCopyData<'static, [u8]> {
     }
 }
 
+impl From<Box<[u8]>> for ZeroCopyData<'static, [u8]> {
+    fn from(data: Box<[u8]>) -> Self {
+        ZeroCopyData::Boxed(data)
+    }
+}
+
 impl From<ZeroCopyBuffer> for ZeroCopyData<'static, [u8]> {
     fn from(buffer: ZeroCopyBuffer) -> Self {
         ZeroCopyData::from_buffer(buffer)
     }
 }
 
+/// 固定長バイト列をリトルエンディアンの整数値として読み出すためのトレイト
+///
+/// `transmute`によるアラインメント依存の読み出しを避けるため、必ず固定長配列に
+/// コピーしてから`from_le_bytes`を使う。未整列なmmap/ネットワークバッファ上でも
+/// 安全に扱える。
+pub trait AsLittleEndian: Copy {
+    /// 1要素あたりのバイト幅
+    const WIDTH: usize;
+
+    /// 先頭`WIDTH`バイトをリトルエンディアンの値として読み出す
+    fn from_le_slice(bytes: &[u8]) -> Self;
+}
+
+macro_rules! impl_as_little_endian {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl AsLittleEndian for $t {
+                const WIDTH: usize = size_of::<$t>();
+
+                fn from_le_slice(bytes: &[u8]) -> Self {
+                    let mut buf = [0u8; size_of::<$t>()];
+                    buf.copy_from_slice(&bytes[..size_of::<$t>()]);
+                    <$t>::from_le_bytes(buf)
+                }
+            }
+        )*
+    };
+}
+
+impl_as_little_endian!(u16, u32, u64, u128, i16, i32, i64, i128);
+
+/// 固定幅のリトルエンディアンレコード列をコピーせずに読む型付きビュー
+///
+/// `ZeroCopyData<'a, [u8]>`の上に重ねたレイヤーで、`Vec<T>`へのデシリアライズを
+/// 行わずに`T::WIDTH`バイトごとの要素を遅延的に読み出す。トランザクションIDや
+/// アカウントスロット、Merkleノードのような固定長レコードの配列をオンディスク/
+/// オンワイヤの表現のまま扱うために使う。
+pub struct ZeroCopyVec<'a, T: AsLittleEndian> {
+    bytes: ZeroCopyData<'a, [u8]>,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, T: AsLittleEndian> ZeroCopyVec<'a, T> {
+    /// バイト列からビューを作成する
+    ///
+    /// バイト長が`T::WIDTH`の倍数でない場合はエラーを返す。
+    pub fn new(bytes: ZeroCopyData<'a, [u8]>) -> Result<Self, Error> {
+        if bytes.len() % T::WIDTH != 0 {
+            return Err(Error::InvalidInput(format!(
+                "Byte length {} is not a multiple of element width {}",
+                bytes.len(),
+                T::WIDTH
+            )));
+        }
+
+        Ok(Self {
+            bytes,
+            _marker: PhantomData,
+        })
+    }
+
+    /// 要素数を返す
+    pub fn len(&self) -> usize {
+        self.bytes.len() / T::WIDTH
+    }
+
+    /// ビューが空かどうかを確認
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// 指定したインデックスの要素を読み出す
+    pub fn get(&self, idx: usize) -> Option<T> {
+        if idx >= self.len() {
+            return None;
+        }
+
+        let start = idx * T::WIDTH;
+        let end = start + T::WIDTH;
+        Some(T::from_le_slice(&self.bytes[start..end]))
+    }
+
+    /// 要素を先頭から遅延的に読み出すイテレータを返す
+    pub fn iter(&self) -> ZeroCopyVecIter<'_, 'a, T> {
+        ZeroCopyVecIter {
+            view: self,
+            index: 0,
+        }
+    }
+}
+
+/// [`ZeroCopyVec::iter`]が返すイテレータ
+pub struct ZeroCopyVecIter<'v, 'a, T: AsLittleEndian> {
+    view: &'v ZeroCopyVec<'a, T>,
+    index: usize,
+}
+
+impl<'v, 'a, T: AsLittleEndian> Iterator for ZeroCopyVecIter<'v, 'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let value = self.view.get(self.index)?;
+        self.index += 1;
+        Some(value)
+    }
+}
+
+/// `serde`によるゼロコピー(デ)シリアライズ
+///
+/// `bytes`クレートの`serde.rs`に倣い、バイト列は`serialize_bytes`で出力する。
+/// 逆方向は`visit_borrowed_bytes`を実装した`Visitor`を使い、bincodeのように
+/// 入力から借用できるフォーマットでは入力のライフタイムを保ったまま
+/// `ZeroCopyData::Borrowed`を生成し、借用できないフォーマットでは`Owned`に
+/// フォールバックする。
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::{ZeroCopyBuffer, ZeroCopyData};
+    use serde::de::{Deserialize, Deserializer, Error as DeError, Visitor};
+    use serde::ser::{Serialize, Serializer};
+    use std::fmt;
+
+    impl Serialize for ZeroCopyBuffer {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(self.as_bytes())
+        }
+    }
+
+    struct ZeroCopyBufferVisitor;
+
+    impl<'de> Visitor<'de> for ZeroCopyBufferVisitor {
+        type Value = ZeroCopyBuffer;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("a byte array")
+        }
+
+        fn visit_bytes<E: DeError>(self, v: &[u8]) -> Result<Self::Value, E> {
+            Ok(ZeroCopyBuffer::from(v))
+        }
+
+        fn visit_byte_buf<E: DeError>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+            Ok(ZeroCopyBuffer::from(v))
+        }
+    }
+
+    impl<'de> Deserialize<'de> for ZeroCopyBuffer {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_byte_buf(ZeroCopyBufferVisitor)
+        }
+    }
+
+    impl<'a> Serialize for ZeroCopyData<'a, [u8]> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(self)
+        }
+    }
+
+    struct ZeroCopyDataVisitor;
+
+    impl<'de> Visitor<'de> for ZeroCopyDataVisitor {
+        type Value = ZeroCopyData<'de, [u8]>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("a byte array")
+        }
+
+        // 入力からそのまま借用できるフォーマット（例: bincodeが所有するバッファを
+        // 読む場合）では、コピーせずに入力のライフタイムを保持したまま借用する
+        fn visit_borrowed_bytes<E: DeError>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+            Ok(ZeroCopyData::Borrowed(v))
+        }
+
+        fn visit_bytes<E: DeError>(self, v: &[u8]) -> Result<Self::Value, E> {
+            Ok(ZeroCopyData::Owned(v.to_vec()))
+        }
+
+        fn visit_byte_buf<E: DeError>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+            Ok(ZeroCopyData::Owned(v))
+        }
+    }
+
+    impl<'de> Deserialize<'de> for ZeroCopyData<'de, [u8]> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_bytes(ZeroCopyDataVisitor)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_zero_copy_buffer_serde_roundtrip() {
+            let buffer = ZeroCopyBuffer::new(vec![1, 2, 3, 4, 5]);
+
+            let encoded = bincode::serialize(&buffer).unwrap();
+            let decoded: ZeroCopyBuffer = bincode::deserialize(&encoded).unwrap();
+
+            assert_eq!(decoded.as_bytes(), buffer.as_bytes());
+        }
+
+        #[test]
+        fn test_zero_copy_data_serde_roundtrip() {
+            let data = ZeroCopyData::from_vec(vec![1, 2, 3]);
+
+            let encoded = bincode::serialize(&data).unwrap();
+            let decoded: ZeroCopyData<'_, [u8]> = bincode::deserialize(&encoded).unwrap();
+
+            assert_eq!(&*decoded, &*data);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -393,4 +883,163 @@ mod tests {
         let borrowed_cow = borrowed.to_cow();
         assert_eq!(&*borrowed_cow, &[6, 7, 8, 9, 10]);
     }
+
+    #[test]
+    fn test_zero_copy_data_boxed_take() {
+        let boxed: Box<[u8]> = vec![1, 2, 3].into_boxed_slice();
+        let mut data = ZeroCopyData::from_boxed(boxed);
+
+        assert_eq!(&*data, &[1, 2, 3]);
+
+        let taken = data.take_boxed().expect("Boxed variant should yield its allocation");
+        assert_eq!(&*taken, &[1, 2, 3]);
+
+        // 取り出した後は空のバッファが残り、二度目の取り出しはNoneを返す
+        assert!(data.is_empty());
+        assert!(data.take_boxed().is_none());
+
+        // Boxed以外のバリアントではNoneを返し、中身は変化しない
+        let mut owned = ZeroCopyData::from_vec(vec![9]);
+        assert!(owned.take_boxed().is_none());
+        assert_eq!(&*owned, &[9]);
+    }
+
+    #[test]
+    fn test_zero_copy_buffer_buf_trait() {
+        let mut buffer = ZeroCopyBuffer::new(vec![1, 2, 3, 4, 5]);
+
+        assert_eq!(Buf::remaining(&buffer), 5);
+        assert_eq!(buffer.chunk(), &[1, 2, 3, 4, 5]);
+
+        buffer.advance(2);
+        assert_eq!(Buf::remaining(&buffer), 3);
+        assert_eq!(buffer.chunk(), &[3, 4, 5]);
+
+        // copy_to_bytesなど`Buf`を前提とするbytesエコシステムのAPIがそのまま使える
+        let copied = buffer.copy_to_bytes(3);
+        assert_eq!(&copied[..], &[3, 4, 5]);
+        assert_eq!(Buf::remaining(&buffer), 0);
+    }
+
+    #[test]
+    fn test_zero_copy_buffer_mut_buf_mut_trait() {
+        let mut buffer = ZeroCopyBufferMut::new(10);
+
+        assert!(BufMut::remaining_mut(&buffer) > 0);
+
+        buffer.put_u8(1);
+        buffer.put_slice(&[2, 3]);
+        assert_eq!(buffer.as_bytes(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_zero_copy_chain() {
+        let first = ZeroCopyBuffer::new(vec![1, 2, 3]);
+        let second = ZeroCopyBuffer::new(vec![4, 5]);
+        let mut chain = first.chain(second);
+
+        assert_eq!(chain.remaining(), 5);
+        assert_eq!(chain.chunk(), &[1, 2, 3]);
+
+        // 先頭バッファの内部に収まる範囲の前進
+        chain.advance(1);
+        assert_eq!(chain.chunk(), &[2, 3]);
+
+        // 先頭バッファを使い切って次のバッファへまたがる前進
+        chain.advance(2);
+        assert_eq!(chain.remaining(), 2);
+        assert_eq!(chain.chunk(), &[4, 5]);
+
+        let mut chain = ZeroCopyBuffer::new(vec![1]).chain(ZeroCopyBuffer::new(vec![2]));
+        chain.push(ZeroCopyBuffer::new(vec![3]));
+        let collected = chain.copy_to_bytes(chain.remaining());
+        assert_eq!(&collected[..], &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_zero_copy_reader() {
+        let buffer = ZeroCopyBuffer::new(vec![1, 2, 3, 4, 5]);
+        let mut reader = buffer.reader();
+
+        let mut first = [0u8; 2];
+        assert_eq!(reader.read(&mut first).unwrap(), 2);
+        assert_eq!(first, [1, 2]);
+
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn test_zero_copy_vec() {
+        let bytes: Vec<u8> = vec![1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0];
+        let view: ZeroCopyVec<u32> = ZeroCopyVec::new(ZeroCopyData::from_vec(bytes)).unwrap();
+
+        assert_eq!(view.len(), 3);
+        assert!(!view.is_empty());
+        assert_eq!(view.get(0), Some(1u32));
+        assert_eq!(view.get(1), Some(2u32));
+        assert_eq!(view.get(2), Some(3u32));
+        assert_eq!(view.get(3), None);
+
+        let collected: Vec<u32> = view.iter().collect();
+        assert_eq!(collected, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_zero_copy_vec_rejects_misaligned_length() {
+        let bytes: Vec<u8> = vec![1, 0, 0];
+        let result: Result<ZeroCopyVec<u32>, _> = ZeroCopyVec::new(ZeroCopyData::from_vec(bytes));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_to_hex_formats_utf8_as_quoted_string() {
+        let buffer = ZeroCopyBuffer::new(b"hello".to_vec());
+        assert_eq!(buffer.to_hex(), "\"hello\"");
+        assert_eq!(format!("{:?}", buffer), "ZeroCopyBuffer { inner: \"\\\"hello\\\"\" }");
+    }
+
+    #[test]
+    fn test_to_hex_groups_non_utf8_bytes_as_hex() {
+        let buffer = ZeroCopyBuffer::new(vec![0xff, 0xfe, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06]);
+        assert_eq!(buffer.to_hex(), "fffe0001 02030405 06");
+
+        let buffer_mut = ZeroCopyBufferMut::from_data(&[0xffu8, 0xfe, 0x00][..]);
+        assert_eq!(buffer_mut.to_hex(), "fffe00");
+
+        let data = ZeroCopyData::from(&[0xffu8, 0xfe, 0x00][..]);
+        assert_eq!(data.to_hex(), "fffe00");
+    }
+
+    #[test]
+    fn test_read_from_fills_spare_capacity_without_pre_zeroing() {
+        let mut buffer = ZeroCopyBufferMut::new(16);
+        let mut source: &[u8] = &[1, 2, 3, 4, 5];
+
+        let n = buffer.read_from(&mut source).unwrap();
+
+        assert_eq!(n, 5);
+        assert_eq!(buffer.as_bytes(), &[1, 2, 3, 4, 5]);
+        assert!(buffer.capacity() >= 5);
+    }
+
+    #[test]
+    fn test_spare_capacity_mut_and_advance_written() {
+        let mut buffer = ZeroCopyBufferMut::new(8);
+        buffer.put_slice(&[1, 2]);
+
+        {
+            let spare = buffer.spare_capacity_mut();
+            assert!(spare.len() >= 2);
+            spare[0].write(3);
+            spare[1].write(4);
+        }
+
+        unsafe {
+            buffer.advance_written(2);
+        }
+
+        assert_eq!(buffer.as_bytes(), &[1, 2, 3, 4]);
+    }
 }
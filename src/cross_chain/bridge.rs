@@ -1,12 +1,16 @@
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 use tokio::sync::mpsc;
+use tokio::time::{interval, Duration};
 use log::{debug, info, warn, error};
 use serde::{Serialize, Deserialize};
 use uuid::Uuid;
+use async_trait::async_trait;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
 
 use crate::error::Error;
 use crate::transaction::Transaction;
+use super::confidential::{ConfidentialTransfer, PaillierPublicKey};
 use super::messaging::{CrossChainMessage, MessageType, MessageStatus};
 use super::transaction::{CrossChainTransaction, TransactionStatus, TransactionProof};
 
@@ -107,6 +111,211 @@ pub struct FeeSetting {
     pub max_fee: Option<f64>,
 }
 
+/// sha3-256ハッシュを計算
+fn sha3_256(data: &[u8]) -> [u8; 32] {
+    use tiny_keccak::{Hasher, Sha3};
+    let mut output = [0u8; 32];
+    let mut hasher = Sha3::v256();
+    hasher.update(data);
+    hasher.finalize(&mut output);
+    output
+}
+
+/// M-of-N検証者委員会のメンバー
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitteeMember {
+    /// 検証者の公開鍵（ed25519、32バイトの16進数表現）
+    pub pubkey_hex: String,
+    /// チェーンごとの議決権（登録されていないチェーンでは投票できない）
+    pub voting_power: HashMap<ChainType, u64>,
+}
+
+/// 正規化された確定メッセージに対する署名を集約するM-of-N検証者委員会
+///
+/// 単一のリレーヤーが虚偽のクロスチェーン着金を偽造できないよう、送信元チェーンID・
+/// トランザクションID・送金先・トークンID・金額・ナンスから成る正規化メッセージに対し、
+/// 登録済み検証者が各自独立に署名する。送信元チェーン上での議決権の合計が閾値に
+/// 達するまで、トランザクションは確定済みとして扱わない。
+pub struct BridgeCommittee {
+    /// 公開鍵（16進数）をキーにしたメンバー一覧
+    members: HashMap<String, CommitteeMember>,
+    /// 確定に必要な議決権の閾値（M）
+    threshold: u64,
+    /// トランザクションIDごとに集まった署名（検証者公開鍵 -> 署名バイト列）
+    collected_signatures: RwLock<HashMap<String, HashMap<String, Vec<u8>>>>,
+}
+
+impl BridgeCommittee {
+    /// 新しい委員会を作成する
+    pub fn new(members: Vec<CommitteeMember>, threshold: u64) -> Self {
+        Self {
+            members: members.into_iter().map(|m| (m.pubkey_hex.clone(), m)).collect(),
+            threshold,
+            collected_signatures: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// 確定メッセージの正規ハッシュ（bincode → sha3-256）を計算する
+    fn canonical_message_hash(
+        source_chain: ChainType,
+        tx_id: &str,
+        recipient: &str,
+        token_id: &str,
+        amount: &str,
+        nonce: u64,
+    ) -> Result<[u8; 32], Error> {
+        #[derive(Serialize)]
+        struct CanonicalMessage<'a> {
+            source_chain: ChainType,
+            tx_id: &'a str,
+            recipient: &'a str,
+            token_id: &'a str,
+            amount: &'a str,
+            nonce: u64,
+        }
+
+        let bytes = bincode::serialize(&CanonicalMessage {
+            source_chain,
+            tx_id,
+            recipient,
+            token_id,
+            amount,
+            nonce,
+        }).map_err(|e| Error::SerializeError(e.to_string()))?;
+
+        Ok(sha3_256(&bytes))
+    }
+
+    /// 検証者から署名を提出し、提出後に集まった議決権の合計を返す
+    ///
+    /// 未登録の検証者、対象チェーンで議決権を持たない検証者、同じトランザクション
+    /// への重複署名、無効な署名はいずれも拒否する。
+    #[allow(clippy::too_many_arguments)]
+    pub fn submit_signature(
+        &self,
+        source_chain: ChainType,
+        tx_id: &str,
+        recipient: &str,
+        token_id: &str,
+        amount: &str,
+        nonce: u64,
+        validator_pubkey: &str,
+        signature: &[u8],
+    ) -> Result<u64, Error> {
+        let member = self.members.get(validator_pubkey).ok_or_else(|| {
+            Error::PermissionDenied(format!("Unknown committee validator: {}", validator_pubkey))
+        })?;
+
+        let voting_power = member.voting_power.get(&source_chain).copied().unwrap_or(0);
+        if voting_power == 0 {
+            return Err(Error::PermissionDenied(format!(
+                "Validator {} has no voting power on chain {:?}",
+                validator_pubkey, source_chain
+            )));
+        }
+
+        if signature.len() != 64 {
+            return Err(Error::InvalidSignature("Signature must be 64 bytes".to_string()));
+        }
+        let mut sig_bytes = [0u8; 64];
+        sig_bytes.copy_from_slice(signature);
+        let sig = Signature::from_bytes(&sig_bytes);
+
+        let pubkey_bytes = hex::decode(validator_pubkey)
+            .map_err(|e| Error::ValidationError(format!("Invalid validator public key: {}", e)))?;
+        let pubkey_array: [u8; 32] = pubkey_bytes.try_into()
+            .map_err(|_| Error::ValidationError("Validator public key must be 32 bytes".to_string()))?;
+        let verifying_key = VerifyingKey::from_bytes(&pubkey_array)
+            .map_err(|e| Error::InvalidKey(format!("Invalid validator public key: {}", e)))?;
+
+        let message_hash = Self::canonical_message_hash(
+            source_chain, tx_id, recipient, token_id, amount, nonce,
+        )?;
+
+        verifying_key.verify(&message_hash, &sig)
+            .map_err(|_| Error::InvalidSignature(format!(
+                "Signature from validator {} does not match the canonical message for transaction {}",
+                validator_pubkey, tx_id
+            )))?;
+
+        let mut collected = self.collected_signatures.write().unwrap();
+        let entry = collected.entry(tx_id.to_string()).or_insert_with(HashMap::new);
+
+        if entry.contains_key(validator_pubkey) {
+            return Err(Error::Duplicate(format!(
+                "Validator {} already signed transaction {}", validator_pubkey, tx_id
+            )));
+        }
+
+        entry.insert(validator_pubkey.to_string(), signature.to_vec());
+
+        let collected_power: u64 = entry.keys()
+            .filter_map(|pk| self.members.get(pk))
+            .filter_map(|m| m.voting_power.get(&source_chain))
+            .sum();
+
+        Ok(collected_power)
+    }
+
+    /// 指定したトランザクションについて集まっている署名一覧を返す（検証者公開鍵 -> 署名）
+    pub fn collected_signatures(&self, tx_id: &str) -> HashMap<String, Vec<u8>> {
+        self.collected_signatures.read().unwrap()
+            .get(tx_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// 指定したトランザクションについて、送信元チェーン上での議決権が閾値に達しているか
+    pub fn is_finalized(&self, source_chain: ChainType, tx_id: &str) -> bool {
+        let collected = self.collected_signatures.read().unwrap();
+        let signers = match collected.get(tx_id) {
+            Some(signers) => signers,
+            None => return false,
+        };
+
+        let collected_power: u64 = signers.keys()
+            .filter_map(|pk| self.members.get(pk))
+            .filter_map(|m| m.voting_power.get(&source_chain))
+            .sum();
+
+        collected_power >= self.threshold
+    }
+}
+
+/// チェーン上で観測されたイベント（入金・出金の検出、状態遷移）
+#[derive(Debug, Clone)]
+pub struct ChainEvent {
+    /// 対象チェーン上でのトランザクションID（シグネチャ・txハッシュ等）
+    pub chain_tx_id: String,
+    /// 観測された状態
+    pub status: TransactionStatus,
+}
+
+/// 動的に登録可能なチェーンとの標準化された接続インターフェース
+///
+/// これまでは新しいチェーンへの対応が`base_bridge`に隣接したハードコードの
+/// 実装（`SolanaBridge`のような専用の構造体）を必要としていた。`ChainConnector`
+/// を実装してブリッジに`register_chain`するだけで、ポーリング・確定パイプ
+/// ラインに組み込めるようにし、再コンパイルなしで新しいチェーンをオンボード
+/// できるようにする。
+#[async_trait]
+pub trait ChainConnector: Send + Sync {
+    /// このコネクタが担当するチェーン種別
+    fn chain_type(&self) -> ChainType;
+
+    /// 確定とみなすために必要な確認ブロック数
+    fn confirmation_depth(&self) -> u64;
+
+    /// 対象チェーン上の未処理イベント（入金・出金）を購読する
+    async fn subscribe_events(&self) -> Result<Vec<ChainEvent>, Error>;
+
+    /// トランザクションを対象チェーンへ送信し、チェーン上のトランザクションIDを返す
+    async fn submit_transaction(&self, transaction: &CrossChainTransaction) -> Result<String, Error>;
+
+    /// 対象チェーン上のトランザクション状態を問い合わせる
+    async fn query_status(&self, chain_tx_id: &str) -> Result<TransactionStatus, Error>;
+}
+
 /// クロスチェーンブリッジ
 pub struct CrossChainBridge {
     /// ブリッジ設定
@@ -121,6 +330,12 @@ pub struct CrossChainBridge {
     message_sender: mpsc::Sender<CrossChainMessage>,
     /// メッセージ受信チャネル
     message_receiver: RwLock<Option<mpsc::Receiver<CrossChainMessage>>>,
+    /// M-of-N検証者委員会（未設定の場合は委員会承認を待たずに内部状態をそのまま返す）
+    committee: Option<BridgeCommittee>,
+    /// チェーン種別ごとに登録された動的コネクタ
+    connectors: RwLock<HashMap<ChainType, Arc<dyn ChainConnector>>>,
+    /// 秘匿転送額の検証に使うPaillier公開鍵（未設定の場合は秘匿転送額を検証しない）
+    confidential_public_key: Option<PaillierPublicKey>,
 }
 
 impl CrossChainBridge {
@@ -137,6 +352,175 @@ impl CrossChainBridge {
             message_queue: RwLock::new(Vec::new()),
             message_sender,
             message_receiver: RwLock::new(Some(message_receiver)),
+            committee: None,
+            connectors: RwLock::new(HashMap::new()),
+            confidential_public_key: None,
+        }
+    }
+
+    /// 新しいチェーンを動的に登録する
+    ///
+    /// 確認ブロック数が0のコネクタや、既に同じチェーン種別で登録済みのコネクタは
+    /// 拒否する。登録後は`send_transaction`によるルーティングおよび
+    /// `start_connector_polling`による確定監視の対象になる。
+    pub fn register_chain(&self, connector: Arc<dyn ChainConnector>) -> Result<(), Error> {
+        let chain_type = connector.chain_type();
+
+        if connector.confirmation_depth() == 0 {
+            return Err(Error::ValidationError(format!(
+                "Connector for chain {:?} must require at least 1 confirmation block", chain_type
+            )));
+        }
+
+        let mut connectors = self.connectors.write().unwrap();
+        if connectors.contains_key(&chain_type) {
+            return Err(Error::Duplicate(format!(
+                "A connector for chain {:?} is already registered", chain_type
+            )));
+        }
+
+        connectors.insert(chain_type, connector);
+        info!("Registered dynamic chain connector for {:?}", chain_type);
+
+        Ok(())
+    }
+
+    /// 現在登録されているチェーンの一覧を返す
+    ///
+    /// `get_config`が返す`BridgeConfig`は固定の送信元・送信先ペアを表すため、
+    /// 実行時に動的登録されたチェーンの一覧はこちらで別途確認する。
+    pub fn registered_chains(&self) -> Vec<ChainType> {
+        self.connectors.read().unwrap().keys().copied().collect()
+    }
+
+    /// 指定したチェーン種別に登録されているコネクタを取得する
+    fn get_connector(&self, chain_type: ChainType) -> Option<Arc<dyn ChainConnector>> {
+        self.connectors.read().unwrap().get(&chain_type).cloned()
+    }
+
+    /// 登録済みコネクタのイベントを定期的にポーリングし、確定状態を反映する
+    ///
+    /// 未確定で`chain_tx_id`を持ち、送信先チェーンのコネクタが登録されているトランザ
+    /// クションについて、そのコネクタの`query_status`を呼び出して内部状態を更新する。
+    /// 送信元チェーンのコネクタは参照しない。
+    pub fn start_connector_polling(self: &Arc<Self>) {
+        let bridge = self.clone();
+
+        tokio::spawn(async move {
+            let mut ticker = interval(Duration::from_secs(5));
+
+            loop {
+                ticker.tick().await;
+
+                let pending: Vec<(String, ChainType, String)> = {
+                    let transactions = bridge.transactions.read().unwrap();
+                    transactions.values()
+                        .filter(|tx| !matches!(tx.status, TransactionStatus::Confirmed | TransactionStatus::Failed))
+                        .filter_map(|tx| {
+                            tx.get_metadata("chain_tx_id")
+                                .map(|chain_tx_id| (tx.id.clone(), tx.target_chain, chain_tx_id.clone()))
+                        })
+                        .collect()
+                };
+
+                for (tx_id, target_chain, chain_tx_id) in pending {
+                    let connector = match bridge.get_connector(target_chain) {
+                        Some(connector) => connector,
+                        None => continue,
+                    };
+
+                    match connector.query_status(&chain_tx_id).await {
+                        Ok(status) => {
+                            let mut transactions = bridge.transactions.write().unwrap();
+                            if let Some(tx) = transactions.get_mut(&tx_id) {
+                                tx.status = status;
+                            }
+                        }
+                        Err(e) => {
+                            warn!("Failed to query connector status for transaction {}: {}", tx_id, e);
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    /// M-of-N検証者委員会を設定する
+    ///
+    /// 未設定の場合、`get_transaction_status`は委員会承認を待たずに内部の
+    /// トランザクション状態をそのまま返す。
+    pub fn set_committee(&mut self, committee: BridgeCommittee) {
+        self.committee = Some(committee);
+    }
+
+    /// 秘匿転送額の検証に使うPaillier公開鍵を設定する
+    ///
+    /// 未設定の場合、`get_transaction_status`はトランザクションに秘匿転送額の
+    /// メタデータが付与されていても検証を行わず内部状態をそのまま返す。
+    pub fn set_confidential_public_key(&mut self, public_key: PaillierPublicKey) {
+        self.confidential_public_key = Some(public_key);
+    }
+
+    /// トランザクションに秘匿転送額（暗号化された金額とゼロ知識証明）を添付する
+    ///
+    /// エスクロー側と転送側の双方が同じ暗号文をメタデータに記録することで、
+    /// 平文の金額を明かさずに両者が同じ額を指していることを保証する。
+    pub fn attach_confidential_transfer(
+        &self,
+        tx_id: &str,
+        transfer: &ConfidentialTransfer,
+    ) -> Result<(), Error> {
+        let mut transactions = self.transactions.write().unwrap();
+        let tx = transactions
+            .get_mut(tx_id)
+            .ok_or_else(|| Error::TransactionNotFound(tx_id.to_string()))?;
+
+        let (ciphertext_hex, proof_json) = transfer.to_metadata_values()?;
+        tx.set_metadata("confidential_amount_ciphertext".to_string(), ciphertext_hex);
+        tx.set_metadata("confidential_amount_proof".to_string(), proof_json);
+
+        Ok(())
+    }
+
+    /// 検証者からの署名を提出する
+    ///
+    /// 正規化メッセージの送金先・トークンID・金額は、保存済みトランザクションの
+    /// `metadata`（`to_address`・`token_id`・`amount`）から取り出す。ナンスは
+    /// `metadata`の`nonce`（未設定なら0）を使う。
+    pub fn submit_signature(
+        &self,
+        tx_id: &str,
+        validator_pubkey: &str,
+        signature: &[u8],
+    ) -> Result<u64, Error> {
+        let committee = self.committee.as_ref().ok_or_else(|| {
+            Error::InvalidOperation("Bridge committee is not configured".to_string())
+        })?;
+
+        let (source_chain, recipient, token_id, amount, nonce) = {
+            let transactions = self.transactions.read().unwrap();
+            let tx = transactions.get(tx_id)
+                .ok_or_else(|| Error::TransactionNotFound(tx_id.to_string()))?;
+
+            (
+                tx.source_chain,
+                tx.get_metadata("to_address").cloned().unwrap_or_default(),
+                tx.get_metadata("token_id").cloned().unwrap_or_default(),
+                tx.get_metadata("amount").cloned().unwrap_or_default(),
+                tx.get_metadata("nonce").and_then(|n| n.parse::<u64>().ok()).unwrap_or(0),
+            )
+        };
+
+        committee.submit_signature(
+            source_chain, tx_id, &recipient, &token_id, &amount, nonce, validator_pubkey, signature,
+        )
+    }
+
+    /// 指定したトランザクションについて委員会に集まっている署名を返す
+    pub fn collected_signatures(&self, tx_id: &str) -> HashMap<String, Vec<u8>> {
+        match &self.committee {
+            Some(committee) => committee.collected_signatures(tx_id),
+            None => HashMap::new(),
         }
     }
 
@@ -301,11 +685,26 @@ impl CrossChainBridge {
     }
     
     /// トランザクションを送信
+    ///
+    /// 送信先チェーンに動的コネクタが登録されていれば、Solana向けの専用実装の
+    /// ような個別のハードコードに頼らずそのコネクタへルーティングする。未登録の
+    /// 場合は従来通り内部のメッセージキュー経由で送信する。
     async fn send_transaction(&self, transaction: &CrossChainTransaction) -> Result<(), Error> {
+        if let Some(connector) = self.get_connector(self.config.target_chain) {
+            let chain_tx_id = connector.submit_transaction(transaction).await?;
+
+            let mut transactions = self.transactions.write().unwrap();
+            if let Some(tx) = transactions.get_mut(&transaction.id) {
+                tx.set_metadata("chain_tx_id".to_string(), chain_tx_id);
+            }
+
+            return Ok(());
+        }
+
         // トランザクションデータをシリアライズ
         let tx_data = serde_json::to_vec(transaction)
             .map_err(|e| Error::SerializationError(e.to_string()))?;
-        
+
         // メッセージを作成
         let message = CrossChainMessage::new(
             transaction.id.clone(),
@@ -314,24 +713,65 @@ impl CrossChainBridge {
             MessageType::TransactionRequest,
             Some(tx_data),
         );
-        
+
         // メッセージを送信
         self.message_sender.send(message).await
             .map_err(|e| Error::InternalError(format!("Failed to send message: {}", e)))?;
-        
+
         Ok(())
     }
     
     /// トランザクションの状態を取得
+    ///
+    /// 委員会が設定されている場合、内部状態が確定済み（`Confirmed`/`Verified`）
+    /// であっても、送信元チェーン上での議決権が閾値に達するまでは`Confirming`を
+    /// 返し、単一のリレーヤーの報告だけで確定扱いにしない。秘匿転送額が添付され
+    /// ている場合も同様に、ゼロ知識証明の検証に成功するまで資金移動を確定扱い
+    /// にしない。
     pub fn get_transaction_status(&self, tx_id: &str) -> Result<TransactionStatus, Error> {
         let transactions = self.transactions.read().unwrap();
-        
+
         if let Some(tx) = transactions.get(tx_id) {
+            let is_final = matches!(tx.status, TransactionStatus::Confirmed | TransactionStatus::Verified);
+
+            if is_final {
+                if let Some(committee) = &self.committee {
+                    if !committee.is_finalized(tx.source_chain, tx_id) {
+                        return Ok(TransactionStatus::Confirming);
+                    }
+                }
+
+                if !self.verify_confidential_transfer(tx)? {
+                    return Ok(TransactionStatus::Confirming);
+                }
+            }
+
             Ok(tx.status)
         } else {
             Err(Error::TransactionNotFound(tx_id.to_string()))
         }
     }
+
+    /// トランザクションに秘匿転送額が添付されている場合、そのゼロ知識証明を検証する
+    ///
+    /// 秘匿転送額のメタデータが無い、または検証用の公開鍵が未設定の場合は、
+    /// 秘匿モードを使っていないものとみなし検証済み扱い（`true`）とする。
+    fn verify_confidential_transfer(&self, tx: &CrossChainTransaction) -> Result<bool, Error> {
+        let (Some(ciphertext_hex), Some(proof_json)) = (
+            tx.get_metadata("confidential_amount_ciphertext"),
+            tx.get_metadata("confidential_amount_proof"),
+        ) else {
+            return Ok(true);
+        };
+
+        let public_key = match &self.confidential_public_key {
+            Some(public_key) => public_key,
+            None => return Ok(true),
+        };
+
+        let transfer = ConfidentialTransfer::from_metadata_values(ciphertext_hex, proof_json)?;
+        transfer.verify(public_key)
+    }
     
     /// トランザクションの詳細を取得
     pub fn get_transaction_details(&self, tx_id: &str) -> Result<CrossChainTransaction, Error> {
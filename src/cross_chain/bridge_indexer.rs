@@ -0,0 +1,255 @@
+use std::sync::RwLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::storage::rocksdb_store::OptimizedStorage;
+use super::bridge::ChainType;
+use super::transaction::{CrossChainTransaction, TransactionStatus};
+
+/// クロスチェーン転送1件分の永続化レコード
+///
+/// `CrossChainTransaction`から、監査やダッシュボードでの閲覧に必要な項目だけを
+/// 取り出して永続化する。`metadata`はそのまま引き継ぎ、`token_id`・`token_symbol`・
+/// `solana_signature`などブリッジ側が設定したキーをそのまま後から参照できるようにする。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferRecord {
+    /// クロスチェーントランザクションID
+    pub transaction_id: String,
+    /// 送信元チェーン
+    pub source_chain: ChainType,
+    /// 送信先チェーン
+    pub target_chain: ChainType,
+    /// 現在の状態
+    pub status: TransactionStatus,
+    /// 送金元アドレス（`metadata["from_address"]`が未設定の場合は空文字列）
+    pub from_address: String,
+    /// 送金先アドレス（`metadata["to_address"]`が未設定の場合は空文字列）
+    pub to_address: String,
+    /// 送金額（文字列表現。`metadata["amount"]`が未設定の場合は空文字列）
+    pub amount: String,
+    /// 送信先チェーン上のブロック高（わかっている場合）
+    pub block_height: Option<u64>,
+    /// 付随するメタデータ（`token_id`・`token_symbol`・`solana_signature`など）
+    pub metadata: std::collections::HashMap<String, String>,
+    /// 初めて記録した時刻（UNIXタイムスタンプ秒）
+    pub created_at: i64,
+    /// 最後に更新した時刻（UNIXタイムスタンプ秒）
+    pub updated_at: i64,
+}
+
+impl TransferRecord {
+    /// `CrossChainTransaction`からレコードを組み立てる
+    fn from_transaction(tx: &CrossChainTransaction) -> Self {
+        let now = chrono::Utc::now().timestamp();
+
+        Self {
+            transaction_id: tx.id.clone(),
+            source_chain: tx.source_chain,
+            target_chain: tx.target_chain,
+            status: tx.status,
+            from_address: tx.get_metadata("from_address").cloned().unwrap_or_default(),
+            to_address: tx.get_metadata("to_address").cloned().unwrap_or_default(),
+            amount: tx.get_metadata("amount").cloned().unwrap_or_default(),
+            block_height: tx.target_block_height.or(tx.source_block_height),
+            metadata: tx.metadata.clone(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// ブリッジの転送履歴を永続化し、アドレス・トークン・ブロック高で検索できるようにする
+/// インデクサ
+///
+/// `SolanaBridge`のようなチェーン別ブリッジが、インメモリの`pending_transactions`に
+/// 加えてここへも書き込むことで、再起動後も履歴が残り、運用者が転送を監査したり
+/// ダッシュボードがページングで一覧取得したりできるようにする。
+pub trait BridgeIndexer: Send + Sync {
+    /// 新しいクロスチェーントランザクションを記録する
+    fn record_transaction(&self, tx: &CrossChainTransaction) -> Result<(), Error>;
+
+    /// 既存トランザクションの状態遷移を記録する
+    fn record_status_transition(
+        &self,
+        transaction_id: &str,
+        status: TransactionStatus,
+    ) -> Result<(), Error>;
+
+    /// トランザクションIDから1件取得する
+    fn get_transaction(&self, transaction_id: &str) -> Result<Option<TransferRecord>, Error>;
+
+    /// 送金元または送金先アドレスが一致する転送を一覧取得する
+    fn list_transactions_by_account(&self, address: &str) -> Result<Vec<TransferRecord>, Error>;
+
+    /// 指定したトークンIDが関わる転送を一覧取得する
+    fn list_by_token(&self, token_id: &str) -> Result<Vec<TransferRecord>, Error>;
+
+    /// 指定のブロック高以降に記録された転送を一覧取得する
+    fn get_transfers_since(&self, height: u64) -> Result<Vec<TransferRecord>, Error>;
+}
+
+/// ブロック高の範囲スキャンで辞書式順序を保てるよう、固定幅0埋めにする
+fn pad_height(height: u64) -> String {
+    format!("{:020}", height)
+}
+
+/// RocksDBを使った`BridgeIndexer`の実装
+///
+/// `transactions`カラムファミリーにトランザクションIDをキーとしてレコード本体を、
+/// `metadata`カラムファミリーにアドレス・トークン・ブロック高ごとのセカンダリ
+/// インデックス（値はトランザクションID）を保存する。
+pub struct RocksDbBridgeIndexer {
+    storage: OptimizedStorage,
+    // レコード本体とセカンダリインデックスの書き込み同士を直列化する（読み取り側はロックを取らない）
+    write_lock: RwLock<()>,
+}
+
+impl RocksDbBridgeIndexer {
+    /// 指定したパスにDBを作成（または開いて）インデクサを初期化する
+    pub fn new<P: AsRef<std::path::Path>>(path: P, cache_size: usize) -> Result<Self, Error> {
+        Ok(Self {
+            storage: OptimizedStorage::new(path, cache_size)?,
+            write_lock: RwLock::new(()),
+        })
+    }
+
+    fn account_index_key(address: &str, transaction_id: &str) -> String {
+        format!("by_account:{}:{}", address, transaction_id)
+    }
+
+    fn token_index_key(token_id: &str, transaction_id: &str) -> String {
+        format!("by_token:{}:{}", token_id, transaction_id)
+    }
+
+    fn height_index_key(height: u64, transaction_id: &str) -> String {
+        format!("by_height:{}:{}", pad_height(height), transaction_id)
+    }
+
+    fn put_record(&self, record: &TransferRecord) -> Result<(), Error> {
+        let bytes = bincode::serialize(record)
+            .map_err(|e| Error::SerializeError(e.to_string()))?;
+        self.storage.put("transactions", &record.transaction_id, &bytes)
+    }
+
+    fn index_record(&self, record: &TransferRecord) -> Result<(), Error> {
+        let marker = record.transaction_id.as_bytes();
+
+        if !record.from_address.is_empty() {
+            let key = Self::account_index_key(&record.from_address, &record.transaction_id);
+            self.storage.put("metadata", &key, marker)?;
+        }
+
+        if !record.to_address.is_empty() {
+            let key = Self::account_index_key(&record.to_address, &record.transaction_id);
+            self.storage.put("metadata", &key, marker)?;
+        }
+
+        if let Some(token_id) = record.metadata.get("token_id") {
+            let key = Self::token_index_key(token_id, &record.transaction_id);
+            self.storage.put("metadata", &key, marker)?;
+        }
+
+        if let Some(height) = record.block_height {
+            let key = Self::height_index_key(height, &record.transaction_id);
+            self.storage.put("metadata", &key, marker)?;
+        }
+
+        Ok(())
+    }
+
+    fn load_record(&self, transaction_id: &str) -> Result<Option<TransferRecord>, Error> {
+        match self.storage.get("transactions", transaction_id)? {
+            Some(bytes) => {
+                let record = bincode::deserialize(&bytes)
+                    .map_err(|e| Error::DeserializeError(e.to_string()))?;
+                Ok(Some(record))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// セカンダリインデックスのプレフィックス一致からトランザクションIDを取り出し、
+    /// 本体レコードを引き直す
+    fn resolve_by_prefix(&self, prefix: &str) -> Result<Vec<TransferRecord>, Error> {
+        let entries = self.storage.get_by_prefix("metadata", prefix)?;
+        let mut records = Vec::with_capacity(entries.len());
+
+        for (_, marker) in entries {
+            let transaction_id = String::from_utf8_lossy(&marker).to_string();
+            if let Some(record) = self.load_record(&transaction_id)? {
+                records.push(record);
+            }
+        }
+
+        Ok(records)
+    }
+}
+
+impl BridgeIndexer for RocksDbBridgeIndexer {
+    fn record_transaction(&self, tx: &CrossChainTransaction) -> Result<(), Error> {
+        let _guard = self.write_lock.write().unwrap();
+
+        let record = TransferRecord::from_transaction(tx);
+        self.put_record(&record)?;
+        self.index_record(&record)
+    }
+
+    fn record_status_transition(
+        &self,
+        transaction_id: &str,
+        status: TransactionStatus,
+    ) -> Result<(), Error> {
+        let _guard = self.write_lock.write().unwrap();
+
+        let mut record = self.load_record(transaction_id)?.ok_or_else(|| {
+            Error::ValidationError(format!(
+                "Cannot record status transition for unknown transaction: {}",
+                transaction_id
+            ))
+        })?;
+
+        record.status = status;
+        record.updated_at = chrono::Utc::now().timestamp();
+
+        self.put_record(&record)
+    }
+
+    fn get_transaction(&self, transaction_id: &str) -> Result<Option<TransferRecord>, Error> {
+        self.load_record(transaction_id)
+    }
+
+    fn list_transactions_by_account(&self, address: &str) -> Result<Vec<TransferRecord>, Error> {
+        self.resolve_by_prefix(&format!("by_account:{}:", address))
+    }
+
+    fn list_by_token(&self, token_id: &str) -> Result<Vec<TransferRecord>, Error> {
+        self.resolve_by_prefix(&format!("by_token:{}:", token_id))
+    }
+
+    fn get_transfers_since(&self, height: u64) -> Result<Vec<TransferRecord>, Error> {
+        let entries = self.storage.get_by_prefix("metadata", "by_height:")?;
+        let threshold = pad_height(height);
+
+        let mut records = Vec::new();
+        for (key, marker) in entries {
+            // キー形式: "by_height:<0埋め高さ>:<tx_id>"
+            let height_part = key
+                .strip_prefix("by_height:")
+                .and_then(|rest| rest.split(':').next())
+                .unwrap_or("");
+
+            if height_part < threshold.as_str() {
+                continue;
+            }
+
+            let transaction_id = String::from_utf8_lossy(&marker).to_string();
+            if let Some(record) = self.load_record(&transaction_id)? {
+                records.push(record);
+            }
+        }
+
+        records.sort_by_key(|r| r.block_height.unwrap_or(0));
+        Ok(records)
+    }
+}
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::storage::rocksdb_store::OptimizedStorage;
+
+/// ブリッジがイベントスキャンを完了した位置を表すチェックポイント
+///
+/// ポーリングループがバッチ処理を終えるたびに永続化し、再起動時はここから
+/// 再開することで、クラッシュをまたいでもイベントの再処理・見逃しを防ぐ。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BridgeCheckpoint {
+    /// Solanaデポジットスキャンが完了した最終スロット
+    SolanaDepositScanned(u64),
+    /// ShardX出金スキャンが完了した最終ブロック高
+    ShardXWithdrawScanned(u64),
+}
+
+impl BridgeCheckpoint {
+    fn storage_key(&self) -> &'static str {
+        match self {
+            BridgeCheckpoint::SolanaDepositScanned(_) => "checkpoint:solana_deposit_scanned",
+            BridgeCheckpoint::ShardXWithdrawScanned(_) => "checkpoint:shardx_withdraw_scanned",
+        }
+    }
+
+    fn value(&self) -> u64 {
+        match self {
+            BridgeCheckpoint::SolanaDepositScanned(v) => *v,
+            BridgeCheckpoint::ShardXWithdrawScanned(v) => *v,
+        }
+    }
+}
+
+/// チェックポイントを永続化・復元するストア
+pub trait CheckpointStore: Send + Sync {
+    /// チェックポイントを保存する（同じ種類の既存チェックポイントは上書きする）
+    fn save_checkpoint(&self, checkpoint: BridgeCheckpoint) -> Result<(), Error>;
+
+    /// Solanaデポジットスキャンの最終スロットを読み出す（未保存ならNone）
+    fn load_solana_deposit_slot(&self) -> Result<Option<u64>, Error>;
+
+    /// ShardX出金スキャンの最終ブロック高を読み出す（未保存ならNone）
+    fn load_shardx_withdraw_height(&self) -> Result<Option<u64>, Error>;
+}
+
+/// RocksDBを使った`CheckpointStore`の実装
+///
+/// `metadata`カラムファミリーに固定キーでチェックポイント値を書き込む。
+/// `BridgeIndexer`とは別のカラムファミリー用途（転送履歴ではなく進捗位置）
+/// のため、独立したストアとして持つ。
+pub struct RocksDbCheckpointStore {
+    storage: OptimizedStorage,
+}
+
+impl RocksDbCheckpointStore {
+    /// 指定したパスにDBを作成（または開いて）チェックポイントストアを初期化する
+    pub fn new<P: AsRef<std::path::Path>>(path: P, cache_size: usize) -> Result<Self, Error> {
+        Ok(Self {
+            storage: OptimizedStorage::new(path, cache_size)?,
+        })
+    }
+
+    fn load_value(&self, key: &str) -> Result<Option<u64>, Error> {
+        match self.storage.get("metadata", key)? {
+            Some(bytes) => {
+                let value = bincode::deserialize(&bytes)
+                    .map_err(|e| Error::DeserializeError(e.to_string()))?;
+                Ok(Some(value))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+impl CheckpointStore for RocksDbCheckpointStore {
+    fn save_checkpoint(&self, checkpoint: BridgeCheckpoint) -> Result<(), Error> {
+        let bytes = bincode::serialize(&checkpoint.value())
+            .map_err(|e| Error::SerializeError(e.to_string()))?;
+        self.storage.put("metadata", checkpoint.storage_key(), &bytes)
+    }
+
+    fn load_solana_deposit_slot(&self) -> Result<Option<u64>, Error> {
+        self.load_value("checkpoint:solana_deposit_scanned")
+    }
+
+    fn load_shardx_withdraw_height(&self) -> Result<Option<u64>, Error> {
+        self.load_value("checkpoint:shardx_withdraw_scanned")
+    }
+}
@@ -0,0 +1,305 @@
+use num_bigint::{BigInt, BigUint, RandBigInt, ToBigInt};
+use num_integer::Integer;
+use num_traits::{One, Zero};
+use rand::thread_rng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::Error;
+
+/// 秘密転送額を構成するPaillier素数ペア（簡易実装として固定値を使う）
+///
+/// 実際の実装では鍵ペアごとに新しい安全な素数を生成するべきだが、
+/// `threshold_signature::generate_safe_prime`と同様にここでは固定値を用いる。
+const P_HEX: &str = "B6C6DD3C7F9F767528B2840E9E8B436A3025CC188D0B2F85E3979BA73A38957C6D128329787AF77ED5A89C97C8B60FD1";
+const Q_HEX: &str = "A5B3A4869E9133BEAF33E2831D0BCEECA09049C7D3B42B211A422810BDEFD513D19F114E8505337289203EE06DE7CAD1";
+
+/// Paillier暗号の公開鍵
+///
+/// 加法準同型性（`c1・c2 mod n^2 = Enc(m1+m2)`）を持つため、暗号文のまま
+/// バッチ転送額を集約して保存則を確認できる。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaillierPublicKey {
+    n: BigUint,
+    g: BigUint,
+    n_squared: BigUint,
+}
+
+/// Paillier暗号の秘密鍵（復号にのみ使う。暗号化は公開鍵だけでできる）
+#[derive(Debug, Clone)]
+struct PaillierPrivateKey {
+    lambda: BigUint,
+    mu: BigUint,
+}
+
+/// Paillier暗号の鍵ペア
+pub struct PaillierKeypair {
+    pub public: PaillierPublicKey,
+    private: PaillierPrivateKey,
+}
+
+/// Paillier暗号文
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaillierCiphertext {
+    c: BigUint,
+}
+
+impl PaillierCiphertext {
+    /// 16進文字列から復元する（トランザクションメタデータからの読み出し用）
+    pub fn from_hex(s: &str) -> Result<Self, Error> {
+        let c = BigUint::parse_bytes(s.as_bytes(), 16)
+            .ok_or_else(|| Error::InvalidInput(format!("Invalid ciphertext encoding: {}", s)))?;
+        Ok(Self { c })
+    }
+
+    /// 16進文字列に変換する（トランザクションメタデータへの格納用）
+    pub fn to_hex(&self) -> String {
+        self.c.to_str_radix(16)
+    }
+}
+
+/// Paillier復号のL関数: L(x) = (x - 1) / n
+fn l_function(x: &BigUint, n: &BigUint) -> BigUint {
+    (x - BigUint::one()) / n
+}
+
+/// 拡張ユークリッド互除法（符号付き）
+fn extended_gcd(a: &BigInt, b: &BigInt) -> (BigInt, BigInt, BigInt) {
+    if b.is_zero() {
+        (a.clone(), BigInt::one(), BigInt::zero())
+    } else {
+        let (g, x1, y1) = extended_gcd(b, &(a % b));
+        let q = a / b;
+        (g, y1.clone(), x1 - &q * &y1)
+    }
+}
+
+/// aのmodulusを法とした逆元を求める
+fn mod_inverse(a: &BigUint, modulus: &BigUint) -> Option<BigUint> {
+    let (g, x, _) = extended_gcd(&a.to_bigint()?, &modulus.to_bigint()?);
+    if g != BigInt::one() && g != -BigInt::one() {
+        return None;
+    }
+
+    let m = modulus.to_bigint()?;
+    let result = ((x % &m) + &m) % &m;
+    result.to_biguint()
+}
+
+/// nと互いに素な乱数を生成する
+fn random_coprime_below(n: &BigUint) -> BigUint {
+    let mut rng = thread_rng();
+    loop {
+        let candidate = rng.gen_biguint_below(n);
+        if !candidate.is_zero() && candidate.gcd(n) == BigUint::one() {
+            return candidate;
+        }
+    }
+}
+
+impl PaillierKeypair {
+    /// 新しい鍵ペアを生成する
+    pub fn generate() -> Result<Self, Error> {
+        let p = BigUint::parse_bytes(P_HEX.as_bytes(), 16).unwrap();
+        let q = BigUint::parse_bytes(Q_HEX.as_bytes(), 16).unwrap();
+
+        let n = &p * &q;
+        let n_squared = &n * &n;
+        // g = n + 1 を使うと g^k mod n^2 = 1 + k*n mod n^2 となり、
+        // L(g^lambda mod n^2) = lambda mod n に簡略化できる
+        let g = &n + BigUint::one();
+
+        let p_minus_one = &p - BigUint::one();
+        let q_minus_one = &q - BigUint::one();
+        let lambda = p_minus_one.lcm(&q_minus_one);
+
+        let lambda_mod_n = &lambda % &n;
+        let mu = mod_inverse(&lambda_mod_n, &n)
+            .ok_or_else(|| Error::InternalError("Failed to derive Paillier private key".to_string()))?;
+
+        Ok(Self {
+            public: PaillierPublicKey { n, g, n_squared },
+            private: PaillierPrivateKey { lambda, mu },
+        })
+    }
+
+    /// 暗号文を秘密鍵で復号する
+    pub fn decrypt(&self, ciphertext: &PaillierCiphertext) -> Result<BigUint, Error> {
+        let c_lambda = ciphertext.c.modpow(&self.private.lambda, &self.public.n_squared);
+        let l = l_function(&c_lambda, &self.public.n);
+        Ok((l * &self.private.mu) % &self.public.n)
+    }
+}
+
+impl PaillierPublicKey {
+    /// 平文mを暗号化する。乱数rは呼び出しのたびに新しく生成する
+    pub fn encrypt(&self, m: &BigUint) -> Result<(PaillierCiphertext, BigUint), Error> {
+        if m >= &self.n {
+            return Err(Error::InvalidInput("Plaintext must be smaller than n".to_string()));
+        }
+
+        let r = random_coprime_below(&self.n);
+        let c = self.encrypt_with_randomness(m, &r)?;
+        Ok((c, r))
+    }
+
+    /// 乱数rを指定して暗号化する（ゼロ知識証明の生成・検証で使う）
+    fn encrypt_with_randomness(&self, m: &BigUint, r: &BigUint) -> Result<PaillierCiphertext, Error> {
+        if m >= &self.n {
+            return Err(Error::InvalidInput("Plaintext must be smaller than n".to_string()));
+        }
+
+        let gm = self.g.modpow(m, &self.n_squared);
+        let rn = r.modpow(&self.n, &self.n_squared);
+        Ok(PaillierCiphertext { c: (gm * rn) % &self.n_squared })
+    }
+
+    /// 二つの暗号文を準同型加算する（c1・c2 mod n^2 = Enc(m1+m2)）
+    pub fn add(&self, c1: &PaillierCiphertext, c2: &PaillierCiphertext) -> PaillierCiphertext {
+        PaillierCiphertext { c: (&c1.c * &c2.c) % &self.n_squared }
+    }
+
+    /// 複数の暗号文をまとめて準同型加算する
+    ///
+    /// バッチ転送の各暗号文を明かすことなく合計額の暗号文を求め、エスクローされた
+    /// 合計額の暗号文と比較することで保存則（入金合計=出金合計）を確認できる。
+    pub fn aggregate(&self, ciphertexts: &[PaillierCiphertext]) -> Result<PaillierCiphertext, Error> {
+        let mut iter = ciphertexts.iter();
+        let first = iter
+            .next()
+            .ok_or_else(|| Error::InvalidInput("Cannot aggregate an empty ciphertext list".to_string()))?;
+
+        Ok(iter.fold(first.clone(), |acc, c| self.add(&acc, c)))
+    }
+}
+
+/// 秘匿された転送額について、平文と乱数の知識を示すSchnorr型のゼロ知識証明
+///
+/// 検証者は暗号文`c`だけから平文`m`を知ることなく、送信者が`c`の構成に使った
+/// `(m, r)`を実際に知っていることを確認できる。エスクロー額との一致は、同じ
+/// 暗号文がロック側と転送側の両方のメタデータに記録されていること（ビット単位の
+/// 一致）で保証する。なお範囲証明としてのビット分解は行っておらず、`m`が
+/// `n`未満の非負整数として暗号化されていることの知識証明にとどまる簡易実装である。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfidentialAmountProof {
+    /// コミットメント a = g^s * t^n mod n^2（16進数）
+    commitment: String,
+    /// チャレンジに対する平文側の応答 z_m = s + e*m mod n（16進数）
+    response_m: String,
+    /// チャレンジに対する乱数側の応答 z_r = t * r^e mod n（16進数）
+    response_r: String,
+}
+
+/// Fiat-Shamir変換によりチャレンジeを導出する（対話なしの非対話型証明にする）
+pub fn generate_challenge(
+    public_key: &PaillierPublicKey,
+    ciphertext: &PaillierCiphertext,
+    commitment: &BigUint,
+) -> BigUint {
+    let mut hasher = Sha256::new();
+    hasher.update(public_key.n.to_bytes_be());
+    hasher.update(ciphertext.c.to_bytes_be());
+    hasher.update(commitment.to_bytes_be());
+    let digest = hasher.finalize();
+
+    BigUint::from_bytes_be(&digest) % &public_key.n
+}
+
+/// 暗号文`ciphertext`を構成する平文`m`と乱数`r`を知っていることの証明を生成する
+pub fn generate_proof(
+    public_key: &PaillierPublicKey,
+    ciphertext: &PaillierCiphertext,
+    m: &BigUint,
+    r: &BigUint,
+) -> Result<ConfidentialAmountProof, Error> {
+    let mut rng = thread_rng();
+    let s = rng.gen_biguint_below(&public_key.n);
+    let t = random_coprime_below(&public_key.n);
+
+    let gs = public_key.g.modpow(&s, &public_key.n_squared);
+    let tn = t.modpow(&public_key.n, &public_key.n_squared);
+    let commitment = (gs * tn) % &public_key.n_squared;
+
+    let e = generate_challenge(public_key, ciphertext, &commitment);
+
+    let response_m = (&s + &e * m) % &public_key.n;
+    let response_r = (&t * r.modpow(&e, &public_key.n)) % &public_key.n;
+
+    Ok(ConfidentialAmountProof {
+        commitment: commitment.to_str_radix(16),
+        response_m: response_m.to_str_radix(16),
+        response_r: response_r.to_str_radix(16),
+    })
+}
+
+/// 証明を検証する
+///
+/// `g^z_m * z_r^n mod n^2 == a * c^e mod n^2`が成り立てば、平文を明かすことなく
+/// 暗号文`c`が有効な`(m, r)`から正しく構成されていることを確認できたことになる。
+pub fn verify_proof(
+    public_key: &PaillierPublicKey,
+    ciphertext: &PaillierCiphertext,
+    proof: &ConfidentialAmountProof,
+) -> Result<bool, Error> {
+    let commitment = BigUint::parse_bytes(proof.commitment.as_bytes(), 16)
+        .ok_or_else(|| Error::InvalidInput("Invalid proof commitment encoding".to_string()))?;
+    let response_m = BigUint::parse_bytes(proof.response_m.as_bytes(), 16)
+        .ok_or_else(|| Error::InvalidInput("Invalid proof response encoding".to_string()))?;
+    let response_r = BigUint::parse_bytes(proof.response_r.as_bytes(), 16)
+        .ok_or_else(|| Error::InvalidInput("Invalid proof response encoding".to_string()))?;
+
+    let e = generate_challenge(public_key, ciphertext, &commitment);
+
+    let lhs = {
+        let gz = public_key.g.modpow(&response_m, &public_key.n_squared);
+        let zr_n = response_r.modpow(&public_key.n, &public_key.n_squared);
+        (gz * zr_n) % &public_key.n_squared
+    };
+
+    let rhs = {
+        let c_e = ciphertext.c.modpow(&e, &public_key.n_squared);
+        (&commitment * c_e) % &public_key.n_squared
+    };
+
+    Ok(lhs == rhs)
+}
+
+/// 秘匿転送額（暗号文と証明の組）。`CrossChainTransaction`のメタデータに
+/// 16進文字列として格納する単位
+pub struct ConfidentialTransfer {
+    pub ciphertext: PaillierCiphertext,
+    pub proof: ConfidentialAmountProof,
+}
+
+impl ConfidentialTransfer {
+    /// 平文の転送額を暗号化し、知識証明を添えた秘匿転送額を作成する
+    pub fn seal(public_key: &PaillierPublicKey, amount: &BigUint) -> Result<Self, Error> {
+        let (ciphertext, r) = public_key.encrypt(amount)?;
+        let proof = generate_proof(public_key, &ciphertext, amount, &r)?;
+
+        Ok(Self { ciphertext, proof })
+    }
+
+    /// トランザクションメタデータに格納する値のペア
+    /// (`confidential_amount_ciphertext`, `confidential_amount_proof`)を返す
+    pub fn to_metadata_values(&self) -> Result<(String, String), Error> {
+        let proof_json = serde_json::to_string(&self.proof)
+            .map_err(|e| Error::SerializeError(e.to_string()))?;
+
+        Ok((self.ciphertext.to_hex(), proof_json))
+    }
+
+    /// トランザクションメタデータから秘匿転送額を復元する
+    pub fn from_metadata_values(ciphertext_hex: &str, proof_json: &str) -> Result<Self, Error> {
+        let ciphertext = PaillierCiphertext::from_hex(ciphertext_hex)?;
+        let proof: ConfidentialAmountProof = serde_json::from_str(proof_json)
+            .map_err(|e| Error::DeserializeError(e.to_string()))?;
+
+        Ok(Self { ciphertext, proof })
+    }
+
+    /// この秘匿転送額の証明を検証する
+    pub fn verify(&self, public_key: &PaillierPublicKey) -> Result<bool, Error> {
+        verify_proof(public_key, &self.ciphertext, &self.proof)
+    }
+}
@@ -6,7 +6,7 @@ use serde::{Serialize, Deserialize};
 use web3::{
     Web3,
     transports::Http,
-    types::{Address, H256, U256, TransactionRequest, BlockNumber, Log, FilterBuilder},
+    types::{Address, H256, H2048, U256, TransactionRequest, BlockId, BlockNumber, Log, FilterBuilder},
     contract::{Contract, Options},
 };
 use ethers::{
@@ -32,16 +32,312 @@ pub struct EthereumBridgeContract {
     contract: Option<Contract<Http>>,
     /// バリデータウォレット
     validator_wallet: LocalWallet,
-    /// サポートされているトークン
-    supported_tokens: RwLock<HashMap<Address, String>>,
+    /// サポートされているトークン（シンボル・小数桁を保持）
+    supported_tokens: RwLock<HashMap<Address, TokenInfo>>,
+    /// トークン探索に用いるエクスプローラクライアント（未設定ならハードコード）
+    explorer: RwLock<Option<ExplorerClient>>,
     /// 処理済みデポジットID
     processed_deposits: RwLock<HashMap<H256, bool>>,
     /// 処理済み引き出しID
     processed_withdrawals: RwLock<HashMap<H256, bool>>,
     /// 最後に処理したブロック番号
     last_processed_block: RwLock<u64>,
+    /// 送信ミドルウェアスタック（ノンス・ガス管理などを順に適用）
+    middleware: RwLock<Vec<Arc<dyn BridgeMiddleware>>>,
+    /// トークン別の 1 回あたり引き出し上限（未設定なら上限なし）
+    withdrawal_caps: RwLock<HashMap<Address, U256>>,
+    /// 現在のポーリングバッチで蓄積中のデポジットリーフ
+    current_batch_leaves: RwLock<Vec<(H256, [u8; 32])>>,
+    /// 確定済みデポジットの Merkle 証明（deposit_id をキーとする）
+    deposit_proofs: RwLock<HashMap<H256, DepositMerkleProof>>,
+    /// リレー状態の永続化ストア（未設定ならメモリのみ）
+    state_store: Option<Arc<dyn BridgeStateStore>>,
+    /// ブロックを処理済みとみなすのに必要な確認数（リオルグ耐性）
+    confirmations: u64,
 }
 
+/// ブリッジのリレー状態を永続化するストア
+///
+/// 最後に完全処理したブロックとそのハッシュ、および処理済みの
+/// デポジット/引き出し ID を耐久的に記録し、再起動時の再処理や
+/// 短いリオルグからの復旧を可能にする。
+pub trait BridgeStateStore: Send + Sync {
+    /// 最後に処理したブロック番号を取得
+    fn load_last_block(&self) -> Result<Option<u64>, Error>;
+    /// 最後に処理したブロック番号とハッシュを記録
+    fn save_last_block(&self, block: u64, hash: &str) -> Result<(), Error>;
+    /// 指定ブロックの記録済みハッシュを取得
+    fn get_block_hash(&self, block: u64) -> Result<Option<String>, Error>;
+    /// デポジットが処理済みか
+    fn is_deposit_processed(&self, id: &H256) -> Result<bool, Error>;
+    /// デポジットを処理済みとして記録（発生ブロックも保持）
+    fn mark_deposit(&self, id: &H256, block: u64) -> Result<(), Error>;
+    /// 引き出しが処理済みか
+    fn is_withdrawal_processed(&self, id: &H256) -> Result<bool, Error>;
+    /// 引き出しを処理済みとして記録
+    fn mark_withdrawal(&self, id: &H256, block: u64) -> Result<(), Error>;
+    /// 指定ブロックより後に発生したデポジット記録を削除し、その ID を返す
+    fn remove_deposits_after(&self, block: u64) -> Result<Vec<H256>, Error>;
+}
+
+/// クレートのストレージ層を用いた `BridgeStateStore` の既定実装
+pub struct StorageBridgeStateStore {
+    storage: Arc<crate::storage::MemoryStorage>,
+}
+
+impl StorageBridgeStateStore {
+    const CF_META: &'static str = "bridge_meta";
+    const CF_DEPOSITS: &'static str = "bridge_deposits";
+    const CF_WITHDRAWALS: &'static str = "bridge_withdrawals";
+
+    /// 新しいストアを作成し、必要なカラムファミリを準備する
+    pub fn new(storage: Arc<crate::storage::MemoryStorage>) -> Self {
+        for cf in [Self::CF_META, Self::CF_DEPOSITS, Self::CF_WITHDRAWALS] {
+            let _ = storage.create_column_family(cf);
+        }
+        Self { storage }
+    }
+}
+
+impl BridgeStateStore for StorageBridgeStateStore {
+    fn load_last_block(&self) -> Result<Option<u64>, Error> {
+        Ok(self
+            .storage
+            .get(Self::CF_META, "last_block")?
+            .and_then(|v| String::from_utf8(v).ok())
+            .and_then(|s| s.parse().ok()))
+    }
+
+    fn save_last_block(&self, block: u64, hash: &str) -> Result<(), Error> {
+        self.storage
+            .put(Self::CF_META, "last_block", block.to_string().as_bytes())?;
+        self.storage
+            .put(Self::CF_META, &format!("hash:{}", block), hash.as_bytes())?;
+        Ok(())
+    }
+
+    fn get_block_hash(&self, block: u64) -> Result<Option<String>, Error> {
+        Ok(self
+            .storage
+            .get(Self::CF_META, &format!("hash:{}", block))?
+            .and_then(|v| String::from_utf8(v).ok()))
+    }
+
+    fn is_deposit_processed(&self, id: &H256) -> Result<bool, Error> {
+        Ok(self
+            .storage
+            .get(Self::CF_DEPOSITS, &format!("{:?}", id))?
+            .is_some())
+    }
+
+    fn mark_deposit(&self, id: &H256, block: u64) -> Result<(), Error> {
+        self.storage
+            .put(Self::CF_DEPOSITS, &format!("{:?}", id), block.to_string().as_bytes())
+    }
+
+    fn is_withdrawal_processed(&self, id: &H256) -> Result<bool, Error> {
+        Ok(self
+            .storage
+            .get(Self::CF_WITHDRAWALS, &format!("{:?}", id))?
+            .is_some())
+    }
+
+    fn mark_withdrawal(&self, id: &H256, block: u64) -> Result<(), Error> {
+        self.storage
+            .put(Self::CF_WITHDRAWALS, &format!("{:?}", id), block.to_string().as_bytes())
+    }
+
+    fn remove_deposits_after(&self, block: u64) -> Result<Vec<H256>, Error> {
+        let mut removed = Vec::new();
+        for (key, value) in self.storage.get_by_prefix(Self::CF_DEPOSITS, "")? {
+            let origin: u64 = String::from_utf8(value)
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0);
+            if origin > block {
+                if let Ok(id) = H256::from_str(&key) {
+                    removed.push(id);
+                }
+                self.storage.delete(Self::CF_DEPOSITS, &key)?;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+/// デポジットの Merkle 包含証明
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DepositMerkleProof {
+    /// リーフハッシュ（token‖from‖to‖amount‖deposit_id の keccak256）
+    pub leaf: [u8; 32],
+    /// リーフのインデックス
+    pub leaf_index: usize,
+    /// ルートへ折りたたむための兄弟ハッシュ列（下位レベルから順）
+    pub siblings: Vec<[u8; 32]>,
+    /// バッチの Merkle ルート
+    pub root: [u8; 32],
+}
+
+/// ブリッジ送信ミドルウェアのコンテキスト
+///
+/// ミドルウェアが `Options` を加工する際に参照する、送信対象の情報。
+#[derive(Debug, Clone)]
+pub struct MiddlewareContext {
+    /// 送信元アドレス
+    pub from: Address,
+    /// 呼び出すコントラクトメソッド名
+    pub method: String,
+}
+
+/// 送信前に `Options`（ノンス・ガス等）を加工する合成可能なミドルウェア
+///
+/// スタックに登録した順に `apply` が呼ばれる。各ミドルウェアは直前までの
+/// 加工結果を受け取り、必要なフィールドのみを上書きする。
+pub trait BridgeMiddleware: Send + Sync {
+    /// 送信オプションを加工する
+    fn apply(&self, ctx: &MiddlewareContext, options: &mut Options) -> Result<(), Error>;
+}
+
+/// 送信元ごとのノンスを単調増加で払い出すミドルウェア
+pub struct NonceManagerMiddleware {
+    /// 送信元アドレス別の次ノンス
+    next_nonce: RwLock<HashMap<Address, U256>>,
+}
+
+impl NonceManagerMiddleware {
+    /// 新しいノンス管理ミドルウェアを作成
+    pub fn new() -> Self {
+        Self {
+            next_nonce: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// 送信元の初期ノンスを設定（チェーンから取得した値で初期化する想定）
+    pub fn prime(&self, from: Address, nonce: U256) {
+        self.next_nonce.write().unwrap().insert(from, nonce);
+    }
+}
+
+impl Default for NonceManagerMiddleware {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BridgeMiddleware for NonceManagerMiddleware {
+    fn apply(&self, ctx: &MiddlewareContext, options: &mut Options) -> Result<(), Error> {
+        let mut map = self.next_nonce.write().unwrap();
+        let nonce = map.entry(ctx.from).or_insert_with(U256::zero);
+        options.nonce = Some(*nonce);
+        *nonce = nonce.saturating_add(U256::one());
+        Ok(())
+    }
+}
+
+/// ガス上限・ガス価格を設定し、必要に応じて価格を割り増すミドルウェア
+pub struct GasManagerMiddleware {
+    /// ガス上限
+    gas_limit: U256,
+    /// 基準ガス価格
+    base_gas_price: U256,
+    /// ガス価格の割り増し率（パーセント、100 = 等倍）
+    price_bump_percent: u64,
+}
+
+impl GasManagerMiddleware {
+    /// 新しいガス管理ミドルウェアを作成
+    pub fn new(gas_limit: U256, base_gas_price: U256, price_bump_percent: u64) -> Self {
+        Self {
+            gas_limit,
+            base_gas_price,
+            price_bump_percent,
+        }
+    }
+}
+
+impl BridgeMiddleware for GasManagerMiddleware {
+    fn apply(&self, _ctx: &MiddlewareContext, options: &mut Options) -> Result<(), Error> {
+        options.gas = Some(self.gas_limit);
+        let bumped = self.base_gas_price * U256::from(self.price_bump_percent) / U256::from(100u64);
+        options.gas_price = Some(bumped);
+        Ok(())
+    }
+}
+
+/// サポート対象トークンの情報
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenInfo {
+    /// シンボル（例: WETH）
+    pub symbol: String,
+    /// 小数桁
+    pub decimals: u8,
+}
+
+/// Etherscan 風のエクスプローラクライアント設定
+///
+/// ブリッジコントラクトに登録されたトークン集合の探索に用いる。到達不能な場合は
+/// ハードコードされた一覧へフォールバックする。コントラクト ABI は実行時に取得せず、
+/// 組み込みのものを使う。`refresh_interval_secs` は保持されるのみで、これを参照して
+/// 定期的に再探索する処理はない（探索は `initialize` 時に行われる）。
+#[derive(Debug, Clone)]
+pub struct ExplorerClient {
+    /// API ベース URL
+    pub base_url: String,
+    /// API キー
+    pub api_key: String,
+    /// 再探索間隔（秒）
+    pub refresh_interval_secs: u64,
+}
+
+impl ExplorerClient {
+    /// 新しいエクスプローラクライアントを作成
+    pub fn new(base_url: String, api_key: String, refresh_interval_secs: u64) -> Self {
+        Self {
+            base_url,
+            api_key,
+            refresh_interval_secs,
+        }
+    }
+
+    /// ブリッジコントラクトが発行した `TokenAdded` イベントを走査して
+    /// 登録トークンのアドレス一覧を取得する
+    async fn fetch_registered_tokens(&self, contract: Address) -> Result<Vec<Address>, Error> {
+        let url = format!(
+            "{}?module=logs&action=getLogs&address={:?}&topic0={}&apikey={}",
+            self.base_url, contract, TOKEN_ADDED_TOPIC, self.api_key
+        );
+        let resp = reqwest::get(&url)
+            .await
+            .map_err(|e| Error::ConnectionError(format!("Explorer request failed: {}", e)))?;
+        let body: serde_json::Value = resp
+            .json()
+            .await
+            .map_err(|e| Error::DeserializeError(format!("Explorer response invalid: {}", e)))?;
+
+        let mut tokens = Vec::new();
+        if let Some(result) = body.get("result").and_then(|r| r.as_array()) {
+            for entry in result {
+                // TokenAdded(address token) の indexed 引数は topics[1]
+                if let Some(topic) = entry
+                    .get("topics")
+                    .and_then(|t| t.as_array())
+                    .and_then(|t| t.get(1))
+                    .and_then(|t| t.as_str())
+                {
+                    if let Ok(addr) = Address::from_str(topic.trim_start_matches("0x").get(24..).unwrap_or(topic)) {
+                        tokens.push(addr);
+                    }
+                }
+            }
+        }
+        Ok(tokens)
+    }
+}
+
+/// `TokenAdded(address)` イベントのトピック
+const TOKEN_ADDED_TOPIC: &str =
+    "0x784c8f4dbf0ffedd6e72c76501c545a70f8b203b30a26ce542bf92ba87c248a4";
+
 /// デポジットイベント
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DepositEvent {
@@ -83,12 +379,70 @@ impl EthereumBridgeContract {
             contract: None,
             validator_wallet,
             supported_tokens: RwLock::new(HashMap::new()),
+            explorer: RwLock::new(None),
             processed_deposits: RwLock::new(HashMap::new()),
             processed_withdrawals: RwLock::new(HashMap::new()),
             last_processed_block: RwLock::new(0),
+            middleware: RwLock::new(Vec::new()),
+            withdrawal_caps: RwLock::new(HashMap::new()),
+            current_batch_leaves: RwLock::new(Vec::new()),
+            deposit_proofs: RwLock::new(HashMap::new()),
+            state_store: None,
+            confirmations: 12,
         }
     }
 
+    /// リレー状態の永続化ストアと確認数を設定する
+    pub fn with_state_store(
+        mut self,
+        state_store: Arc<dyn BridgeStateStore>,
+        confirmations: u64,
+    ) -> Self {
+        self.state_store = Some(state_store);
+        self.confirmations = confirmations;
+        self
+    }
+
+    /// デポジットの Merkle 証明を取得
+    pub fn get_deposit_proof(&self, deposit_id: &H256) -> Option<TransactionProof> {
+        let proofs = self.deposit_proofs.read().unwrap();
+        let merkle = proofs.get(deposit_id)?;
+        let proof_data = serde_json::to_vec(merkle).ok()?;
+        Some(TransactionProof {
+            id: format!("deposit-proof-{:?}", deposit_id),
+            transaction_id: format!("eth-deposit-{:?}", deposit_id),
+            block_hash: String::new(),
+            block_height: 0,
+            timestamp: 0,
+            proof_data,
+            signature: String::new(),
+            verifier: format!("eth:{}", self.validator_wallet.address()),
+            created_at: chrono::Utc::now(),
+        })
+    }
+
+    /// トークン別の引き出し上限を設定
+    pub fn set_withdrawal_cap(&self, token: Address, cap: U256) {
+        self.withdrawal_caps.write().unwrap().insert(token, cap);
+    }
+
+    /// 送信ミドルウェアをスタックの末尾に追加
+    ///
+    /// 登録した順に `Options` へ適用される。典型的には
+    /// `NonceManagerMiddleware` → `GasManagerMiddleware` の順に積む。
+    pub fn add_middleware(&self, middleware: Arc<dyn BridgeMiddleware>) {
+        self.middleware.write().unwrap().push(middleware);
+    }
+
+    /// ミドルウェアスタックを順に適用して送信オプションを組み立てる
+    fn build_options(&self, ctx: &MiddlewareContext) -> Result<Options, Error> {
+        let mut options = Options::default();
+        for middleware in self.middleware.read().unwrap().iter() {
+            middleware.apply(ctx, &mut options)?;
+        }
+        Ok(options)
+    }
+
     /// コントラクトを初期化
     pub async fn initialize(&mut self) -> Result<(), Error> {
         // コントラクトABIを読み込み
@@ -111,8 +465,17 @@ impl EthereumBridgeContract {
         let latest_block = self.web3.eth().block_number().await
             .map_err(|e| Error::ConnectionError(format!("Failed to get latest block number: {}", e)))?;
         
-        // 最後に処理したブロック番号を更新（最新のブロック番号から1000ブロック前）
-        let start_block = latest_block.as_u64().saturating_sub(1000);
+        // 永続化済みのブロック番号があれば復元し、なければ最新から1000ブロック前
+        let start_block = match &self.state_store {
+            Some(store) => match store.load_last_block()? {
+                Some(block) => {
+                    info!("Restored last processed block {} from state store", block);
+                    block
+                }
+                None => latest_block.as_u64().saturating_sub(1000),
+            },
+            None => latest_block.as_u64().saturating_sub(1000),
+        };
         *self.last_processed_block.write().unwrap() = start_block;
         
         info!("Ethereum bridge contract initialized. Contract address: {}, Starting from block: {}", 
@@ -121,98 +484,296 @@ impl EthereumBridgeContract {
         Ok(())
     }
     
+    /// エクスプローラクライアントを設定し、動的なトークン探索を有効にする
+    pub fn set_explorer(&self, explorer: ExplorerClient) {
+        *self.explorer.write().unwrap() = Some(explorer);
+    }
+
     /// サポートされているトークンを更新
+    ///
+    /// エクスプローラが設定されていればそこから登録トークンを探索して
+    /// ERC-20 の `symbol`/`decimals` を解決する。探索に失敗した場合は
+    /// ハードコードされた一覧へフォールバックする。
     async fn update_supported_tokens(&self) -> Result<(), Error> {
-        // 実際の実装では、コントラクトからサポートされているトークンのリストを取得
-        // ここでは簡略化のため、ハードコードしたトークンを使用
-        
+        // エクスプローラが設定されていれば動的探索を試みる
+        let explorer = self.explorer.read().unwrap().clone();
+        if let Some(explorer) = explorer {
+            match explorer.fetch_registered_tokens(self.contract_address).await {
+                Ok(addresses) => {
+                    let mut tokens = self.supported_tokens.write().unwrap();
+                    tokens.clear();
+                    for token in addresses {
+                        let (symbol, decimals) = self
+                            .resolve_token_metadata(token)
+                            .await
+                            .unwrap_or_else(|_| ("UNKNOWN".to_string(), 18));
+                        tokens.insert(token, TokenInfo { symbol, decimals });
+                    }
+                    info!("Discovered {} tokens via explorer", tokens.len());
+                    return Ok(());
+                }
+                Err(e) => {
+                    warn!("Explorer token discovery failed, falling back: {}", e);
+                }
+            }
+        }
+
+        // フォールバック: 既知トークンのハードコード一覧
         let mut tokens = self.supported_tokens.write().unwrap();
-        
+
         // ETH（ラップドイーサ）
         let weth_address = Address::from_str("0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2")
             .map_err(|e| Error::ValidationError(format!("Invalid address: {}", e)))?;
-        tokens.insert(weth_address, "WETH".to_string());
-        
+        tokens.insert(weth_address, TokenInfo { symbol: "WETH".to_string(), decimals: 18 });
+
         // USDC
         let usdc_address = Address::from_str("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48")
             .map_err(|e| Error::ValidationError(format!("Invalid address: {}", e)))?;
-        tokens.insert(usdc_address, "USDC".to_string());
-        
+        tokens.insert(usdc_address, TokenInfo { symbol: "USDC".to_string(), decimals: 6 });
+
         // USDT
         let usdt_address = Address::from_str("0xdAC17F958D2ee523a2206206994597C13D831ec7")
             .map_err(|e| Error::ValidationError(format!("Invalid address: {}", e)))?;
-        tokens.insert(usdt_address, "USDT".to_string());
-        
+        tokens.insert(usdt_address, TokenInfo { symbol: "USDT".to_string(), decimals: 6 });
+
         info!("Updated supported tokens. Count: {}", tokens.len());
-        
+
         Ok(())
     }
+
+    /// トークンコントラクトを呼び出して ERC-20 の symbol / decimals を解決する
+    async fn resolve_token_metadata(&self, token: Address) -> Result<(String, u8), Error> {
+        let erc20_abi = include_bytes!("../../contracts/ethereum/abi/ERC20.json");
+        let contract = Contract::from_json(self.web3.eth(), token, erc20_abi.as_slice())
+            .map_err(|e| Error::ContractError(format!("Failed to load ERC20 ABI: {}", e)))?;
+
+        let symbol: String = contract
+            .query("symbol", (), None, Options::default(), None)
+            .await
+            .map_err(|e| Error::ContractError(format!("Failed to query symbol: {}", e)))?;
+        let decimals: u8 = contract
+            .query("decimals", (), None, Options::default(), None)
+            .await
+            .map_err(|e| Error::ContractError(format!("Failed to query decimals: {}", e)))?;
+
+        Ok((symbol, decimals))
+    }
     
     /// イベントをポーリング
     pub async fn poll_events(&self) -> Result<(), Error> {
         // 最後に処理したブロック番号を取得
         let last_block = *self.last_processed_block.read().unwrap();
-        
+
         // 最新のブロック番号を取得
         let latest_block = self.web3.eth().block_number().await
             .map_err(|e| Error::ConnectionError(format!("Failed to get latest block number: {}", e)))?;
-        
+
         let latest_block = latest_block.as_u64();
-        
+
+        // リオルグ検知: 記録済みブロックのハッシュがチェーンと一致しなければ
+        // 共通祖先まで last_processed_block を巻き戻し、巻き込まれたデポジットを取り消す
+        self.detect_and_handle_reorg(last_block).await?;
+        let last_block = *self.last_processed_block.read().unwrap();
+
+        // 確認数を差し引いた安全な先端までを処理対象とする
+        let safe_tip = latest_block.saturating_sub(self.confirmations);
+
         // 処理するブロック範囲を決定（最大1000ブロック）
         let from_block = last_block + 1;
-        let to_block = std::cmp::min(latest_block, from_block + 999);
-        
-        // 新しいブロックがない場合は終了
+        let to_block = std::cmp::min(safe_tip, from_block + 999);
+
+        // 新しい（確定済み）ブロックがない場合は終了
         if from_block > to_block {
             return Ok(());
         }
-        
+
         info!("Polling events from block {} to {}", from_block, to_block);
-        
+
         // デポジットイベントをポーリング
         self.poll_deposit_events(from_block, to_block).await?;
-        
+
         // 引き出しイベントをポーリング
         self.poll_withdrawal_events(from_block, to_block).await?;
-        
-        // 最後に処理したブロック番号を更新
+
+        // 最後に処理したブロック番号を更新（永続化・ハッシュ記録も行う）
         *self.last_processed_block.write().unwrap() = to_block;
-        
+        if let Some(store) = &self.state_store {
+            let hash = self.block_hash_string(to_block).await?;
+            store.save_last_block(to_block, &hash)?;
+        }
+
+        Ok(())
+    }
+
+    /// 指定ブロックのハッシュを文字列で取得
+    async fn block_hash_string(&self, block: u64) -> Result<String, Error> {
+        let header = self
+            .web3
+            .eth()
+            .block(BlockId::Number(BlockNumber::Number(block.into())))
+            .await
+            .map_err(|e| Error::ConnectionError(format!("Failed to fetch block {}: {}", block, e)))?;
+        Ok(header
+            .and_then(|b| b.hash)
+            .map(|h| format!("{:?}", h))
+            .unwrap_or_default())
+    }
+
+    /// リオルグを検知し、必要なら共通祖先まで巻き戻す
+    async fn detect_and_handle_reorg(&self, last_block: u64) -> Result<(), Error> {
+        let store = match &self.state_store {
+            Some(store) => store,
+            None => return Ok(()),
+        };
+
+        // 記録済みハッシュが現在のチェーンと一致するかを確認し、
+        // 不一致なら一致するブロックが見つかるまで遡る
+        let mut cursor = last_block;
+        while cursor > 0 {
+            let stored = store.get_block_hash(cursor)?;
+            let stored = match stored {
+                Some(h) => h,
+                None => break,
+            };
+            let current = self.block_hash_string(cursor).await?;
+            if stored == current {
+                break;
+            }
+            warn!("Reorg detected at block {} (stored {} != chain {})", cursor, stored, current);
+            cursor -= 1;
+        }
+
+        if cursor < last_block {
+            // 共通祖先まで巻き戻し、巻き込まれたデポジットの記録を取り消す
+            let removed = store.remove_deposits_after(cursor)?;
+            info!(
+                "Rolled back to block {} and dropped {} reorged deposits",
+                cursor,
+                removed.len()
+            );
+            let mut processed = self.processed_deposits.write().unwrap();
+            for id in removed {
+                processed.remove(&id);
+            }
+            *self.last_processed_block.write().unwrap() = cursor;
+            store.save_last_block(cursor, &self.block_hash_string(cursor).await?)?;
+        }
+
         Ok(())
     }
     
+    /// デポジットイベントのトピック
+    const DEPOSIT_TOPIC: &'static str =
+        "0x5548c837ab068cf56a2c2479df0882a4922fd203edb7517321831d95078c5f62";
+
     /// デポジットイベントをポーリング
     async fn poll_deposit_events(&self, from_block: u64, to_block: u64) -> Result<(), Error> {
-        let contract = self.contract.as_ref()
-            .ok_or_else(|| Error::ContractError("Contract not initialized".to_string()))?;
-        
-        // デポジットイベントのフィルタを作成
-        let filter = FilterBuilder::default()
-            .address(vec![self.contract_address])
-            .from_block(BlockNumber::Number(from_block.into()))
-            .to_block(BlockNumber::Number(to_block.into()))
-            .topics(
-                Some(vec![H256::from_str("0x5548c837ab068cf56a2c2479df0882a4922fd203edb7517321831d95078c5f62").unwrap()]),
-                None,
-                None,
-                None,
-            )
-            .build();
-        
-        // イベントを取得
-        let logs = self.web3.eth().logs(filter).await
-            .map_err(|e| Error::ContractError(format!("Failed to get deposit logs: {}", e)))?;
-        
-        info!("Found {} deposit events", logs.len());
-        
-        // 各イベントを処理
-        for log in logs {
-            self.process_deposit_event(log).await?;
+        let topic = H256::from_str(Self::DEPOSIT_TOPIC).unwrap();
+
+        // ブルームフィルタでヒットし得るサブ範囲だけに絞り込む
+        for (sub_from, sub_to) in self.matching_subranges(from_block, to_block, topic).await? {
+            let contract = self.contract.as_ref()
+                .ok_or_else(|| Error::ContractError("Contract not initialized".to_string()))?;
+            let _ = contract;
+
+            // デポジットイベントのフィルタを作成
+            let filter = FilterBuilder::default()
+                .address(vec![self.contract_address])
+                .from_block(BlockNumber::Number(sub_from.into()))
+                .to_block(BlockNumber::Number(sub_to.into()))
+                .topics(Some(vec![topic]), None, None, None)
+                .build();
+
+            // イベントを取得
+            let logs = self.web3.eth().logs(filter).await
+                .map_err(|e| Error::ContractError(format!("Failed to get deposit logs: {}", e)))?;
+
+            info!("Found {} deposit events in blocks {}-{}", logs.len(), sub_from, sub_to);
+
+            // 各イベントを処理（1 トランザクションが複数のデポジットを発行する
+            // 場合も、ログごとに異なる deposit_id で個別に処理される）
+            for log in logs {
+                self.process_deposit_event(log).await?;
+            }
         }
-        
+
+        // このポーリングバッチで蓄積したデポジットから Merkle ルートを確定
+        self.finalize_deposit_batch();
+
         Ok(())
     }
+
+    /// デポジット証明を検証する
+    ///
+    /// 兄弟ハッシュを各レベルのインデックスの偶奇で順序付けながら keccak256 で
+    /// 折りたたみ、再計算したルートが与えられたルートと一致するかを確認する。
+    pub fn verify_deposit_proof(proof: &DepositMerkleProof, root: &[u8; 32]) -> bool {
+        let mut hash = proof.leaf;
+        let mut index = proof.leaf_index;
+        for sibling in &proof.siblings {
+            let mut combined = Vec::with_capacity(64);
+            if index % 2 == 0 {
+                combined.extend_from_slice(&hash);
+                combined.extend_from_slice(sibling);
+            } else {
+                combined.extend_from_slice(sibling);
+                combined.extend_from_slice(&hash);
+            }
+            hash = keccak256(&combined);
+            index /= 2;
+        }
+        &hash == root && &proof.root == root
+    }
+
+    /// ブルームフィルタで関連イベントを含み得るブロックのサブ範囲を列挙
+    ///
+    /// 各ブロックヘッダの `logsBloom` にコントラクトアドレスとイベントトピックの
+    /// 両方が含まれる可能性があるブロックだけを対象とし、連続するブロックを
+    /// 1 つのサブ範囲にまとめて返す。偽陽性は `eth_getLogs` が最終確認するため問題ない。
+    async fn matching_subranges(
+        &self,
+        from_block: u64,
+        to_block: u64,
+        topic: H256,
+    ) -> Result<Vec<(u64, u64)>, Error> {
+        let mut ranges = Vec::new();
+        let mut current: Option<(u64, u64)> = None;
+
+        for block_number in from_block..=to_block {
+            let block = self
+                .web3
+                .eth()
+                .block(BlockId::Number(BlockNumber::Number(block_number.into())))
+                .await
+                .map_err(|e| {
+                    Error::ConnectionError(format!("Failed to fetch block {}: {}", block_number, e))
+                })?;
+
+            // ヘッダやブルームが取得できない場合は安全側に倒してスキャン対象とする
+            let possible = match block.and_then(|b| b.logs_bloom) {
+                Some(bloom) => {
+                    bloom_matches(&bloom, self.contract_address.as_bytes())
+                        && bloom_matches(&bloom, topic.as_bytes())
+                }
+                None => true,
+            };
+
+            if possible {
+                current = Some(match current {
+                    Some((start, _)) => (start, block_number),
+                    None => (block_number, block_number),
+                });
+            } else if let Some(range) = current.take() {
+                ranges.push(range);
+            }
+        }
+
+        if let Some(range) = current.take() {
+            ranges.push(range);
+        }
+
+        Ok(ranges)
+    }
     
     /// デポジットイベントを処理
     async fn process_deposit_event(&self, log: Log) -> Result<(), Error> {
@@ -258,17 +819,66 @@ impl EthereumBridgeContract {
         info!("Processing deposit: token={}, from={}, to={}, amount={}, id={:?}", 
             token, from, to_shardx_address, amount, deposit_id);
         
+        // デポジットリーフを現在のバッチに蓄積（token‖from‖to‖amount‖deposit_id）
+        let mut leaf_input = Vec::new();
+        leaf_input.extend_from_slice(token.as_bytes());
+        leaf_input.extend_from_slice(from.as_bytes());
+        leaf_input.extend_from_slice(to_shardx_address.as_bytes());
+        let mut amount_bytes = [0u8; 32];
+        amount.to_big_endian(&mut amount_bytes);
+        leaf_input.extend_from_slice(&amount_bytes);
+        leaf_input.extend_from_slice(deposit_id.as_bytes());
+        let leaf = keccak256(&leaf_input);
+        self.current_batch_leaves
+            .write()
+            .unwrap()
+            .push((*deposit_id, leaf));
+
         // ShardXでのトランザクションを作成
         self.create_shardx_deposit_transaction(token, from, to_shardx_address, amount, *deposit_id).await?;
-        
-        // 処理済みとしてマーク
+
+        // 処理済みとしてマーク（永続化ストアにも発生ブロックとともに記録）
         {
             let mut processed_deposits = self.processed_deposits.write().unwrap();
             processed_deposits.insert(*deposit_id, true);
         }
-        
+        if let Some(store) = &self.state_store {
+            let block = log.block_number.map(|b| b.as_u64()).unwrap_or(0);
+            store.mark_deposit(deposit_id, block)?;
+        }
+
         Ok(())
     }
+
+    /// 現在のポーリングバッチのデポジットリーフから Merkle ツリーを確定する
+    ///
+    /// ルートを計算し、各 deposit_id に対して `(leaf_index, Merkle path)` を保存する。
+    /// 空バッチはルートを発行せずスキップする。
+    fn finalize_deposit_batch(&self) {
+        let leaves: Vec<(H256, [u8; 32])> =
+            std::mem::take(&mut *self.current_batch_leaves.write().unwrap());
+        if leaves.is_empty() {
+            return;
+        }
+
+        let leaf_hashes: Vec<[u8; 32]> = leaves.iter().map(|(_, h)| *h).collect();
+        let (root, paths) = merkle_root_and_paths(&leaf_hashes);
+
+        let mut proofs = self.deposit_proofs.write().unwrap();
+        for (index, (deposit_id, leaf)) in leaves.into_iter().enumerate() {
+            proofs.insert(
+                deposit_id,
+                DepositMerkleProof {
+                    leaf,
+                    leaf_index: index,
+                    siblings: paths[index].clone(),
+                    root,
+                },
+            );
+        }
+
+        info!("Finalized deposit batch with root {}", hex::encode(root));
+    }
     
     /// ShardXでのデポジットトランザクションを作成
     async fn create_shardx_deposit_transaction(
@@ -283,7 +893,7 @@ impl EthereumBridgeContract {
         let token_symbol = {
             let supported_tokens = self.supported_tokens.read().unwrap();
             supported_tokens.get(&token)
-                .cloned()
+                .map(|info| info.symbol.clone())
                 .unwrap_or_else(|| "UNKNOWN".to_string())
         };
         
@@ -312,35 +922,36 @@ impl EthereumBridgeContract {
         Ok(())
     }
     
+    /// 引き出しイベントのトピック
+    const WITHDRAWAL_TOPIC: &'static str =
+        "0x9b1bfa7fa9ee420a16e124f794c35ac9f90472acc99140eb2f6447c714cad8eb";
+
     /// 引き出しイベントをポーリング
     async fn poll_withdrawal_events(&self, from_block: u64, to_block: u64) -> Result<(), Error> {
-        let contract = self.contract.as_ref()
-            .ok_or_else(|| Error::ContractError("Contract not initialized".to_string()))?;
-        
-        // 引き出しイベントのフィルタを作成
-        let filter = FilterBuilder::default()
-            .address(vec![self.contract_address])
-            .from_block(BlockNumber::Number(from_block.into()))
-            .to_block(BlockNumber::Number(to_block.into()))
-            .topics(
-                Some(vec![H256::from_str("0x9b1bfa7fa9ee420a16e124f794c35ac9f90472acc99140eb2f6447c714cad8eb").unwrap()]),
-                None,
-                None,
-                None,
-            )
-            .build();
-        
-        // イベントを取得
-        let logs = self.web3.eth().logs(filter).await
-            .map_err(|e| Error::ContractError(format!("Failed to get withdrawal logs: {}", e)))?;
-        
-        info!("Found {} withdrawal events", logs.len());
-        
-        // 各イベントを処理
-        for log in logs {
-            self.process_withdrawal_event(log).await?;
+        let topic = H256::from_str(Self::WITHDRAWAL_TOPIC).unwrap();
+
+        // ブルームフィルタでヒットし得るサブ範囲だけに絞り込む
+        for (sub_from, sub_to) in self.matching_subranges(from_block, to_block, topic).await? {
+            // 引き出しイベントのフィルタを作成
+            let filter = FilterBuilder::default()
+                .address(vec![self.contract_address])
+                .from_block(BlockNumber::Number(sub_from.into()))
+                .to_block(BlockNumber::Number(sub_to.into()))
+                .topics(Some(vec![topic]), None, None, None)
+                .build();
+
+            // イベントを取得
+            let logs = self.web3.eth().logs(filter).await
+                .map_err(|e| Error::ContractError(format!("Failed to get withdrawal logs: {}", e)))?;
+
+            info!("Found {} withdrawal events in blocks {}-{}", logs.len(), sub_from, sub_to);
+
+            // 各イベントを処理
+            for log in logs {
+                self.process_withdrawal_event(log).await?;
+            }
         }
-        
+
         Ok(())
     }
     
@@ -369,15 +980,107 @@ impl EthereumBridgeContract {
         info!("Processing withdrawal: token={}, to={}, amount={}, id={:?}", 
             token, to, amount, withdrawal_id);
         
-        // 処理済みとしてマーク
+        // 処理済みとしてマーク（永続化ストアにも記録）
         {
             let mut processed_withdrawals = self.processed_withdrawals.write().unwrap();
             processed_withdrawals.insert(*withdrawal_id, true);
         }
-        
+        if let Some(store) = &self.state_store {
+            let block = log.block_number.map(|b| b.as_u64()).unwrap_or(0);
+            store.mark_withdrawal(withdrawal_id, block)?;
+        }
+
         Ok(())
     }
     
+    /// 引き出しリクエストを送信前に検証する（ドライラン可能）
+    ///
+    /// ブリッジプールの転送検証に倣い、以下を順に確認して最初に失敗した項目を
+    /// `Error::ValidationError` として返す:
+    /// 1. トークンがサポート対象であること
+    /// 2. 金額が 0 より大きく、トークン別の上限以内であること
+    /// 3. ブリッジコントラクトのロック残高が十分であること
+    /// 4. `withdrawal_id` が未処理であること
+    /// 5. 受取アドレスがゼロアドレスでないこと
+    pub async fn validate_withdrawal(
+        &self,
+        token: Address,
+        recipient: Address,
+        amount: U256,
+        shardx_tx_id: &str,
+    ) -> Result<(), Error> {
+        // 1. サポート対象トークンか
+        if !self.is_token_supported(token) {
+            return Err(Error::ValidationError(format!(
+                "Token not supported: {}",
+                token
+            )));
+        }
+
+        // 2. 金額が正かつ上限以内か
+        if amount.is_zero() {
+            return Err(Error::ValidationError(
+                "Withdrawal amount must be greater than zero".to_string(),
+            ));
+        }
+        if let Some(cap) = self.withdrawal_caps.read().unwrap().get(&token) {
+            if amount > *cap {
+                return Err(Error::ValidationError(format!(
+                    "Withdrawal amount {} exceeds per-token cap {}",
+                    amount, cap
+                )));
+            }
+        }
+
+        // 3. ブリッジのロック残高が十分か
+        let contract = self.contract.as_ref().ok_or_else(|| {
+            Error::ContractError("Contract not initialized".to_string())
+        })?;
+        let locked: U256 = contract
+            .query(
+                "lockedBalance",
+                (token,),
+                self.validator_wallet.address(),
+                Options::default(),
+                None,
+            )
+            .await
+            .map_err(|e| {
+                Error::ContractError(format!("Failed to query locked balance: {}", e))
+            })?;
+        if locked < amount {
+            return Err(Error::ValidationError(format!(
+                "Insufficient bridge liquidity for {}: locked {}, requested {}",
+                token, locked, amount
+            )));
+        }
+
+        // 4. 重複した引き出しでないか
+        let withdrawal_id = H256::from_slice(&keccak256(
+            format!("{}:{}:{}:{}", token, recipient, amount, shardx_tx_id).as_bytes(),
+        ));
+        if self
+            .processed_withdrawals
+            .read()
+            .unwrap()
+            .contains_key(&withdrawal_id)
+        {
+            return Err(Error::ValidationError(format!(
+                "Withdrawal already processed: {:?}",
+                withdrawal_id
+            )));
+        }
+
+        // 5. 受取アドレスが非ゼロか
+        if recipient == Address::zero() {
+            return Err(Error::ValidationError(
+                "Recipient address must be non-zero".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
     /// ShardXからイーサリアムへの引き出しリクエストを作成
     pub async fn request_withdrawal(
         &self,
@@ -386,20 +1089,30 @@ impl EthereumBridgeContract {
         amount: U256,
         shardx_tx_id: &str,
     ) -> Result<H256, Error> {
-        let contract = self.contract.as_ref()
-            .ok_or_else(|| Error::ContractError("Contract not initialized".to_string()))?;
-        
         // 引き出しIDを生成
         let withdrawal_id = H256::from_slice(&keccak256(
             format!("{}:{}:{}:{}", token, recipient, amount, shardx_tx_id).as_bytes()
         ));
-        
+
+        // 送信前にバリデーションを実施（不正・資金不足なガス浪費を防ぐ）
+        self.validate_withdrawal(token, recipient, amount, shardx_tx_id).await?;
+
+        let contract = self.contract.as_ref()
+            .ok_or_else(|| Error::ContractError("Contract not initialized".to_string()))?;
+
+        // ミドルウェアスタックでノンス・ガス等の送信オプションを構築
+        let ctx = MiddlewareContext {
+            from: self.validator_wallet.address(),
+            method: "requestWithdrawal".to_string(),
+        };
+        let options = self.build_options(&ctx)?;
+
         // コントラクトメソッドを呼び出し
         let result = contract.call(
             "requestWithdrawal",
             (withdrawal_id, token, recipient, amount),
             self.validator_wallet.address(),
-            Options::default(),
+            options,
         ).await.map_err(|e| Error::ContractError(format!("Failed to request withdrawal: {}", e)))?;
         
         info!("Requested withdrawal: token={}, recipient={}, amount={}, id={:?}", 
@@ -417,7 +1130,13 @@ impl EthereumBridgeContract {
     /// トークンシンボルを取得
     pub fn get_token_symbol(&self, token: Address) -> Option<String> {
         let supported_tokens = self.supported_tokens.read().unwrap();
-        supported_tokens.get(&token).cloned()
+        supported_tokens.get(&token).map(|info| info.symbol.clone())
+    }
+
+    /// トークンの小数桁を取得
+    pub fn get_token_decimals(&self, token: Address) -> Option<u8> {
+        let supported_tokens = self.supported_tokens.read().unwrap();
+        supported_tokens.get(&token).map(|info| info.decimals)
     }
 }
 
@@ -429,4 +1148,148 @@ fn keccak256(data: &[u8]) -> [u8; 32] {
     hasher.update(data);
     hasher.finalize(&mut output);
     output
+}
+
+/// 2048ビットのブルームフィルタに項目が含まれ得るかを判定
+///
+/// Ethereum の `logsBloom` と同じ方式で、`keccak256(item)` の先頭 6 バイトから
+/// 3 組のバイト対を取り出し、それぞれ 11 ビット（= 2048 ビット空間）のインデックスに
+/// マスクして、対応する 3 ビットがすべて立っているかを確認する。偽陽性は許容される。
+fn bloom_matches(bloom: &H2048, item: &[u8]) -> bool {
+    let hash = keccak256(item);
+    let bloom_bytes = bloom.as_bytes();
+
+    for pair in 0..3 {
+        // バイト対から 11 ビットのインデックスを算出
+        let bit_index =
+            (((hash[pair * 2] as usize) << 8) | hash[pair * 2 + 1] as usize) & 0x7ff;
+        // 2048 ビット列の先頭からのビット位置（ビッグエンディアン順）
+        let byte_index = 256 - 1 - (bit_index / 8);
+        let bit_in_byte = bit_index % 8;
+        if bloom_bytes[byte_index] & (1 << bit_in_byte) == 0 {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// リーフ列から Merkle ルートと各リーフの兄弟パスを計算する
+///
+/// 奇数個のレベルでは最後の要素を複製してペアを作る。単一リーフの場合は
+/// ルートがそのリーフに等しく、パスは空になる。
+fn merkle_root_and_paths(leaves: &[[u8; 32]]) -> ([u8; 32], Vec<Vec<[u8; 32]>>) {
+    let n = leaves.len();
+    let mut paths: Vec<Vec<[u8; 32]>> = vec![Vec::new(); n];
+
+    if n == 1 {
+        return (leaves[0], paths);
+    }
+
+    // 各リーフが現在のレベルで占めるインデックスを追跡
+    let mut indices: Vec<usize> = (0..n).collect();
+    let mut level: Vec<[u8; 32]> = leaves.to_vec();
+
+    while level.len() > 1 {
+        // 奇数個なら末尾を複製
+        if level.len() % 2 == 1 {
+            let last = *level.last().unwrap();
+            level.push(last);
+        }
+
+        // 各リーフに対応する兄弟ハッシュを記録
+        for (leaf, idx) in indices.iter_mut().enumerate() {
+            let sibling = if *idx % 2 == 0 { *idx + 1 } else { *idx - 1 };
+            paths[leaf].push(level[sibling]);
+            *idx /= 2;
+        }
+
+        // 次のレベルを構築
+        let mut next = Vec::with_capacity(level.len() / 2);
+        for pair in level.chunks(2) {
+            let mut combined = Vec::with_capacity(64);
+            combined.extend_from_slice(&pair[0]);
+            combined.extend_from_slice(&pair[1]);
+            next.push(keccak256(&combined));
+        }
+        level = next;
+    }
+
+    (level[0], paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ブルームに登録した項目は必ずマッチし、未登録の項目は（偽陽性を除き）外れる
+    #[test]
+    fn bloom_matches_inserted_items() {
+        fn insert(bloom: &mut H2048, item: &[u8]) {
+            let hash = keccak256(item);
+            let bytes = bloom.as_bytes().to_vec();
+            let mut bytes = bytes;
+            for pair in 0..3 {
+                let bit_index =
+                    (((hash[pair * 2] as usize) << 8) | hash[pair * 2 + 1] as usize) & 0x7ff;
+                let byte_index = 256 - 1 - (bit_index / 8);
+                let bit_in_byte = bit_index % 8;
+                bytes[byte_index] |= 1 << bit_in_byte;
+            }
+            *bloom = H2048::from_slice(&bytes);
+        }
+
+        let mut bloom = H2048::zero();
+        let addr = b"0xbridgecontractaddress";
+        insert(&mut bloom, addr);
+        assert!(bloom_matches(&bloom, addr));
+        // 空のブルームには何も含まれない
+        assert!(!bloom_matches(&H2048::zero(), addr));
+    }
+
+    /// 同一トランザクション由来でも deposit_id が異なれば別の ShardX tx id になる
+    #[test]
+    fn distinct_deposit_ids_yield_distinct_tx_ids() {
+        let id_a = H256::from_low_u64_be(1);
+        let id_b = H256::from_low_u64_be(2);
+        let tx_a = format!("eth-deposit-{:?}", id_a);
+        let tx_b = format!("eth-deposit-{:?}", id_b);
+        assert_ne!(tx_a, tx_b);
+    }
+
+    /// 単一リーフのツリーはルートがリーフに等しく、証明が検証できる
+    #[test]
+    fn single_leaf_root_equals_leaf() {
+        let leaf = keccak256(b"only");
+        let (root, paths) = merkle_root_and_paths(&[leaf]);
+        assert_eq!(root, leaf);
+        let proof = DepositMerkleProof {
+            leaf,
+            leaf_index: 0,
+            siblings: paths[0].clone(),
+            root,
+        };
+        assert!(EthereumBridgeContract::verify_deposit_proof(&proof, &root));
+    }
+
+    /// 奇数個のリーフでも全リーフの証明が検証できる
+    #[test]
+    fn odd_leaf_count_proofs_verify() {
+        let leaves: Vec<[u8; 32]> =
+            (0..5u8).map(|i| keccak256(&[i])).collect();
+        let (root, paths) = merkle_root_and_paths(&leaves);
+        for (index, leaf) in leaves.iter().enumerate() {
+            let proof = DepositMerkleProof {
+                leaf: *leaf,
+                leaf_index: index,
+                siblings: paths[index].clone(),
+                root,
+            };
+            assert!(
+                EthereumBridgeContract::verify_deposit_proof(&proof, &root),
+                "proof for leaf {} failed",
+                index
+            );
+        }
+    }
 }
\ No newline at end of file
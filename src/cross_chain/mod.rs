@@ -1,9 +1,17 @@
 mod bridge;
+mod bridge_indexer;
+mod checkpoint;
+mod confidential;
 mod ethereum_bridge;
 mod messaging;
+mod nonce_manager;
 mod transaction;
 
-pub use bridge::{BridgeConfig, BridgeStatus, ChainType, CrossChainBridge};
+pub use bridge::{BridgeCommittee, BridgeConfig, BridgeStatus, ChainConnector, ChainEvent, ChainType, CommitteeMember, CrossChainBridge};
+pub use bridge_indexer::{BridgeIndexer, RocksDbBridgeIndexer, TransferRecord};
+pub use checkpoint::{BridgeCheckpoint, CheckpointStore, RocksDbCheckpointStore};
+pub use confidential::{ConfidentialAmountProof, ConfidentialTransfer, PaillierCiphertext, PaillierKeypair, PaillierPublicKey};
 pub use ethereum_bridge::EthereumBridge;
 pub use messaging::{CrossChainMessage, MessageStatus, MessageType};
+pub use nonce_manager::NonceManager;
 pub use transaction::{CrossChainTransaction, TransactionProof, TransactionStatus};
@@ -0,0 +1,90 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::RwLock;
+
+/// 署名アカウントごとの未処理ナンスを予約・追跡するマネージャー
+///
+/// アウトバウンド送信のたびに[`reserve_nonce`]でナンスを払い出し、送信が確定
+/// （または失敗確定）したら[`release_nonce`]で未確定集合から外す。再起動直後は
+/// オンチェーンの状態が手元のメモリ上の予約と食い違っているため、[`reconcile`]
+/// でオンチェーンの確認済み件数と突き合わせてから使い始める。
+pub struct NonceManager {
+    /// アカウントごとに次へ払い出すナンス
+    next_nonce: RwLock<HashMap<String, u64>>,
+    /// アカウントごとの未確定（送信済みだが確定していない）ナンス集合
+    pending_nonces: RwLock<HashMap<String, HashSet<u64>>>,
+}
+
+impl NonceManager {
+    /// 新しいナンスマネージャーを作成
+    pub fn new() -> Self {
+        Self {
+            next_nonce: RwLock::new(HashMap::new()),
+            pending_nonces: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// 指定したアカウントの次のナンスを払い出し、未確定として記録する
+    pub fn reserve_nonce(&self, account: &str) -> u64 {
+        let nonce = {
+            let mut next_nonce = self.next_nonce.write().unwrap();
+            let nonce = *next_nonce.get(account).unwrap_or(&0);
+            next_nonce.insert(account.to_string(), nonce + 1);
+            nonce
+        };
+
+        self.pending_nonces
+            .write()
+            .unwrap()
+            .entry(account.to_string())
+            .or_insert_with(HashSet::new)
+            .insert(nonce);
+
+        nonce
+    }
+
+    /// ナンスが確定した（またはエラーで破棄された）ら未確定集合から外す
+    pub fn release_nonce(&self, account: &str, nonce: u64) {
+        if let Some(pending) = self.pending_nonces.write().unwrap().get_mut(account) {
+            pending.remove(&nonce);
+        }
+    }
+
+    /// 指定したアカウントの未確定ナンス一覧を昇順で返す（ナンスギャップの検知に使う）
+    pub fn pending_nonces(&self, account: &str) -> Vec<u64> {
+        let pending_nonces = self.pending_nonces.read().unwrap();
+        let mut pending: Vec<u64> = pending_nonces
+            .get(account)
+            .map(|set| set.iter().copied().collect())
+            .unwrap_or_default();
+        pending.sort_unstable();
+        pending
+    }
+
+    /// 指定したアカウントに次に払い出されるナンスを返す
+    pub fn current_nonce(&self, account: &str) -> u64 {
+        *self.next_nonce.read().unwrap().get(account).unwrap_or(&0)
+    }
+
+    /// 再起動後、オンチェーンで確認済みのトランザクション数と突き合わせてナンスを補正する
+    ///
+    /// `confirmed_count`より小さい未確定ナンスは、再起動前にすでにオンチェーンで
+    /// 確定していたとみなして破棄し、次に払い出すナンスが`confirmed_count`を
+    /// 下回っていれば`confirmed_count`まで引き上げる。
+    pub fn reconcile(&self, account: &str, confirmed_count: u64) {
+        if let Some(pending) = self.pending_nonces.write().unwrap().get_mut(account) {
+            pending.retain(|&nonce| nonce >= confirmed_count);
+        }
+
+        let mut next_nonce = self.next_nonce.write().unwrap();
+        let current = *next_nonce.get(account).unwrap_or(&0);
+        if confirmed_count > current {
+            next_nonce.insert(account.to_string(), confirmed_count);
+        }
+    }
+}
+
+impl Default for NonceManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -2,24 +2,29 @@ use std::sync::{Arc, RwLock};
 use std::collections::HashMap;
 use std::str::FromStr;
 use tokio::sync::mpsc;
-use tokio::time::{Duration, interval};
+use tokio::time::{Duration, interval, sleep};
 use log::{debug, info, warn, error};
 use serde::{Serialize, Deserialize};
 
 use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_response::RpcConfirmedTransactionStatusWithSignature;
 use solana_sdk::{
     commitment_config::CommitmentConfig,
+    hash::Hash,
     pubkey::Pubkey,
     signature::{Keypair, Signature},
     signer::Signer,
-    transaction::Transaction as SolanaTransaction,
+    transaction::{Transaction as SolanaTransaction, TransactionError},
     instruction::{Instruction, AccountMeta},
     system_instruction,
+    secp256k1_program,
+    sysvar,
 };
 use solana_program::{
     program_pack::Pack,
     system_program,
 };
+use solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, UiTransactionEncoding};
 use spl_token::{
     state::{Account as TokenAccount, Mint},
     instruction as token_instruction,
@@ -29,16 +34,255 @@ use spl_associated_token_account::instruction as associated_token_instruction;
 use crate::error::Error;
 use crate::transaction::Transaction;
 use super::bridge::{CrossChainBridge, BridgeConfig, ChainType, BridgeStatus};
+use super::bridge_indexer::BridgeIndexer;
+use super::checkpoint::{BridgeCheckpoint, CheckpointStore};
+use super::nonce_manager::NonceManager;
 use super::messaging::{CrossChainMessage, MessageType, MessageStatus};
 use super::transaction::{CrossChainTransaction, TransactionStatus, TransactionProof};
 use super::token_registry::{TokenRegistry, TokenInfo};
 
+/// Solanaとの通信に使う操作を抽象化するトレイト
+///
+/// `SolanaBridge`を実際の`RpcClient`（本番用、[`RpcClientAdapter`]）に直結させず
+/// このトレイト越しに使うことで、ローカルバリデータなしに`solana-program-test`の
+/// `BanksClient`（[`BanksRpc`]）を差し込んでイベントポーリング・定足数ロジック・
+/// 再送処理を決定的に検証できるようにする。
+pub trait SolanaRpc: Send + Sync {
+    /// 最新の確定スロットを取得
+    fn get_slot(&self) -> Result<u64, String>;
+
+    /// 指定アドレス宛の署名一覧を取得
+    fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        start_slot: Option<u64>,
+        end_slot: Option<u64>,
+        commitment: CommitmentConfig,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>, String>;
+
+    /// 署名からトランザクション詳細を取得
+    fn get_transaction(
+        &self,
+        signature: &Signature,
+        commitment: CommitmentConfig,
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, String>;
+
+    /// 署名の確定状態を取得
+    fn get_signature_status(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<Result<(), TransactionError>>, String>;
+
+    /// 最新のブロックハッシュを取得
+    fn get_latest_blockhash(&self) -> Result<Hash, String>;
+
+    /// アカウントの生データを取得
+    fn get_account_data(&self, pubkey: &Pubkey) -> Result<Vec<u8>, String>;
+
+    /// 署名済みトランザクションを送信
+    fn send_transaction(&self, transaction: &SolanaTransaction) -> Result<Signature, String>;
+
+    /// 直近のプライオリティ手数料（compute unitあたりのマイクロLamports）を取得する
+    fn get_recent_prioritization_fees(&self) -> Result<u64, String>;
+}
+
+/// 本番用: `solana_client::rpc_client::RpcClient`をそのまま転送するアダプター
+pub struct RpcClientAdapter {
+    inner: RpcClient,
+}
+
+impl RpcClientAdapter {
+    /// 既存の`RpcClient`をラップする
+    pub fn new(inner: RpcClient) -> Self {
+        Self { inner }
+    }
+}
+
+impl SolanaRpc for RpcClientAdapter {
+    fn get_slot(&self) -> Result<u64, String> {
+        self.inner.get_slot().map_err(|e| e.to_string())
+    }
+
+    fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        start_slot: Option<u64>,
+        end_slot: Option<u64>,
+        commitment: CommitmentConfig,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>, String> {
+        let _ = (start_slot, end_slot, commitment);
+        self.inner
+            .get_signatures_for_address(address)
+            .map_err(|e| e.to_string())
+    }
+
+    fn get_transaction(
+        &self,
+        signature: &Signature,
+        _commitment: CommitmentConfig,
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, String> {
+        self.inner
+            .get_transaction(signature, UiTransactionEncoding::Json)
+            .map_err(|e| e.to_string())
+    }
+
+    fn get_signature_status(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<Result<(), TransactionError>>, String> {
+        self.inner.get_signature_status(signature).map_err(|e| e.to_string())
+    }
+
+    fn get_latest_blockhash(&self) -> Result<Hash, String> {
+        self.inner.get_latest_blockhash().map_err(|e| e.to_string())
+    }
+
+    fn get_account_data(&self, pubkey: &Pubkey) -> Result<Vec<u8>, String> {
+        self.inner.get_account_data(pubkey).map_err(|e| e.to_string())
+    }
+
+    fn send_transaction(&self, transaction: &SolanaTransaction) -> Result<Signature, String> {
+        self.inner.send_transaction(transaction).map_err(|e| e.to_string())
+    }
+
+    fn get_recent_prioritization_fees(&self) -> Result<u64, String> {
+        let samples = self
+            .inner
+            .get_recent_prioritization_fees(&[])
+            .map_err(|e| e.to_string())?;
+
+        let highest_slot_fee = samples
+            .iter()
+            .max_by_key(|sample| sample.slot)
+            .map(|sample| sample.prioritization_fee)
+            .unwrap_or(0);
+
+        Ok(highest_slot_fee)
+    }
+}
+
+/// テスト用: `solana-program-test`の`BanksClient`を包んだアダプター
+///
+/// `BanksClient`はインプロセスのバンクに対して非同期APIしか提供しないため、
+/// `tokio::runtime::Handle::block_on`で同期トレイトの呼び出しに変換する。
+/// また署名履歴・ログ検索に相当するAPIをネイティブには持たないので、
+/// `send_transaction`で送信したトランザクションを自前の履歴に積み、
+/// ポーリングループやget_signature_statusはその履歴を参照する。
+#[cfg(feature = "solana-program-test")]
+pub struct BanksRpc {
+    banks_client: std::sync::Mutex<solana_program_test::BanksClient>,
+    runtime: tokio::runtime::Handle,
+    history: RwLock<Vec<(Signature, Result<(), TransactionError>)>>,
+}
+
+#[cfg(feature = "solana-program-test")]
+impl BanksRpc {
+    /// バンクへの接続とランタイムハンドルからアダプターを作成
+    pub fn new(banks_client: solana_program_test::BanksClient, runtime: tokio::runtime::Handle) -> Self {
+        Self {
+            banks_client: std::sync::Mutex::new(banks_client),
+            runtime,
+            history: RwLock::new(Vec::new()),
+        }
+    }
+}
+
+#[cfg(feature = "solana-program-test")]
+impl SolanaRpc for BanksRpc {
+    fn get_slot(&self) -> Result<u64, String> {
+        let mut client = self.banks_client.lock().unwrap();
+        self.runtime
+            .block_on(client.get_root_slot())
+            .map_err(|e| e.to_string())
+    }
+
+    fn get_signatures_for_address(
+        &self,
+        _address: &Pubkey,
+        _start_slot: Option<u64>,
+        _end_slot: Option<u64>,
+        _commitment: CommitmentConfig,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>, String> {
+        // BanksClientには署名検索APIがないため、送信済みトランザクションの
+        // 履歴から組み立てる（このアダプターの`send_transaction`で送信した分のみ）
+        let history = self.history.read().unwrap();
+        Ok(history
+            .iter()
+            .map(|(signature, _)| RpcConfirmedTransactionStatusWithSignature {
+                signature: signature.to_string(),
+                slot: 0,
+                err: None,
+                memo: None,
+                block_time: None,
+                confirmation_status: None,
+            })
+            .collect())
+    }
+
+    fn get_transaction(
+        &self,
+        _signature: &Signature,
+        _commitment: CommitmentConfig,
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, String> {
+        Err("BanksRpc does not support transaction lookup by signature".to_string())
+    }
+
+    fn get_signature_status(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<Result<(), TransactionError>>, String> {
+        let history = self.history.read().unwrap();
+        Ok(history
+            .iter()
+            .find(|(sig, _)| sig == signature)
+            .map(|(_, result)| result.clone()))
+    }
+
+    fn get_latest_blockhash(&self) -> Result<Hash, String> {
+        let mut client = self.banks_client.lock().unwrap();
+        self.runtime
+            .block_on(client.get_latest_blockhash())
+            .map_err(|e| e.to_string())
+    }
+
+    fn get_account_data(&self, pubkey: &Pubkey) -> Result<Vec<u8>, String> {
+        let mut client = self.banks_client.lock().unwrap();
+        self.runtime
+            .block_on(client.get_account(*pubkey))
+            .map_err(|e| e.to_string())?
+            .map(|account| account.data)
+            .ok_or_else(|| format!("Account not found: {}", pubkey))
+    }
+
+    fn send_transaction(&self, transaction: &SolanaTransaction) -> Result<Signature, String> {
+        let signature = transaction.signatures[0];
+        let mut client = self.banks_client.lock().unwrap();
+        let result = self
+            .runtime
+            .block_on(client.process_transaction(transaction.clone()))
+            .map_err(|e| e.to_string());
+
+        self.history.write().unwrap().push((
+            signature,
+            if result.is_ok() { Ok(()) } else { Err(TransactionError::AccountNotFound) },
+        ));
+
+        result.map(|_| signature)
+    }
+
+    fn get_recent_prioritization_fees(&self) -> Result<u64, String> {
+        // BanksClientはローカルのインプロセスバンクであり、優先手数料市場という
+        // 概念そのものが存在しないため、常に0を返す
+        Ok(0)
+    }
+}
+
 /// Solanaブリッジ
 pub struct SolanaBridge {
     /// 基本ブリッジ
     base_bridge: CrossChainBridge,
     /// RPCクライアント
-    rpc_client: Option<RpcClient>,
+    rpc_client: Option<Arc<dyn SolanaRpc + Send + Sync>>,
     /// ウォレット
     wallet: Option<Keypair>,
     /// ブリッジプログラムID
@@ -53,6 +297,339 @@ pub struct SolanaBridge {
     last_processed_slot: RwLock<u64>,
     /// イベントポーリングタスクが実行中かどうか
     polling_active: RwLock<bool>,
+    /// アクティブなガーディアンセット（VAA方式のm-of-n署名検証に用いる）
+    guardian_set: RwLock<GuardianSet>,
+    /// メッセージハッシュごとに集まったガーディアンの署名
+    attestations: RwLock<HashMap<[u8; 32], HashMap<Pubkey, Signature>>>,
+    /// secp256k1 precompileで検証済みの署名（メッセージハッシュ→ガーディアンインデックス→SigInfo）
+    ///
+    /// トランザクションの送信が受理されただけでなく、オンチェーンで実際に確定・成功
+    /// したことを確認した署名のみをここに保持する。これにより、署名済みメッセージ
+    /// 本体を再構成する際に再検証が不要になる。
+    verified_sig_cache: RwLock<HashMap<[u8; 32], HashMap<u8, SigInfo>>>,
+    /// 保留中トランザクションの再送・ポーク状態
+    retry_state: RwLock<HashMap<String, RetryState>>,
+    /// 転送履歴を永続化するインデクサ（未設定の場合はインメモリの状態のみで動作する）
+    indexer: Option<Arc<dyn BridgeIndexer>>,
+    /// スキャン位置のチェックポイントストア（未設定の場合は毎回最新スロットから再開する）
+    checkpoint_store: Option<Arc<dyn CheckpointStore>>,
+    /// 署名アカウントごとのナンス予約・追跡
+    nonce_manager: Arc<NonceManager>,
+    /// 直近にサンプリングしたプライオリティ手数料
+    fee_oracle: Arc<FeeOracle>,
+}
+
+/// 直近にサンプリングされたプライオリティ手数料
+#[derive(Debug, Clone, Copy)]
+pub struct FeeSample {
+    /// compute unitあたりのマイクロLamports
+    pub priority_fee_micro_lamports: u64,
+    /// サンプリングしたUnixタイムスタンプ（秒）
+    pub sampled_at: i64,
+}
+
+/// 推奨プライオリティ手数料を定期的にサンプリングするオラクル
+///
+/// サンプリング結果を保持するだけの受動的なキャッシュであり、定期実行自体は
+/// `SolanaBridge`のポーリングループが`sample`を呼び出すことで駆動する。
+/// サンプルが古すぎる場合は`is_stale`で検知できるようにし、手数料の取得に
+/// 失敗し続けて運用者が気づけない事態を防ぐ。
+pub struct FeeOracle {
+    last_sample: RwLock<Option<FeeSample>>,
+}
+
+impl FeeOracle {
+    /// 新しい手数料オラクルを作成
+    pub fn new() -> Self {
+        Self {
+            last_sample: RwLock::new(None),
+        }
+    }
+
+    /// RPCから最新のプライオリティ手数料をサンプリングして記録する
+    pub fn sample(&self, rpc_client: &(dyn SolanaRpc + Send + Sync)) -> Result<FeeSample, Error> {
+        let priority_fee_micro_lamports = rpc_client
+            .get_recent_prioritization_fees()
+            .map_err(|e| Error::ConnectionError(format!("Failed to sample prioritization fees: {}", e)))?;
+
+        let sample = FeeSample {
+            priority_fee_micro_lamports,
+            sampled_at: chrono::Utc::now().timestamp(),
+        };
+
+        *self.last_sample.write().unwrap() = Some(sample);
+
+        Ok(sample)
+    }
+
+    /// 最後にサンプリングされた手数料を返す（未サンプリングならNone）
+    pub fn last_sample(&self) -> Option<FeeSample> {
+        *self.last_sample.read().unwrap()
+    }
+
+    /// 最後のサンプリングから`max_age_secs`秒を超えて経過しているか（未サンプリングならtrue）
+    pub fn is_stale(&self, max_age_secs: i64) -> bool {
+        match self.last_sample() {
+            Some(sample) => chrono::Utc::now().timestamp() - sample.sampled_at > max_age_secs,
+            None => true,
+        }
+    }
+}
+
+impl Default for FeeOracle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// secp256k1 precompileの1命令に詰め込める署名の最大数
+///
+/// 署名1件につき65バイト（signature 64 + recovery id 1）+ ethアドレス20バイト + オフセット
+/// 情報11バイトを要するため、トランザクションサイズ上限（1232バイト）に収まるよう保守的に
+/// 制限する。
+const MAX_SECP_SIGNATURES_PER_INSTRUCTION: usize = 7;
+
+/// secp256k1検証バッチ送信後、確定状態を確認するためにポーリングする回数
+const VAA_CONFIRMATION_POLL_ATTEMPTS: u32 = 20;
+
+/// secp256k1検証バッチの確定状態ポーリング間隔
+const VAA_CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// secp256k1で検証するガーディアン署名1件分の情報
+///
+/// オンチェーンのprecompileによる検証が確定（`verify_and_submit_vaa`がトランザクションの
+/// 確定状態をポーリングして成功を確認した）した署名は`verified_sig_cache`に保存され、
+/// 再検証なしに署名済みメッセージ本体を再構成できるようにする。
+#[derive(Debug, Clone)]
+pub struct SigInfo {
+    /// ガーディアンセット内のインデックス
+    pub guardian_index: u8,
+    /// 回復ID（0または1）
+    pub recovery_id: u8,
+    /// 64バイトのコンパクトsecp256k1署名（r || s）
+    pub signature: [u8; 64],
+}
+
+/// VAA（ガーディアン署名済みメッセージ）提出の進捗状況
+#[derive(Debug, Clone)]
+pub struct VaaSubmissionProgress {
+    /// 送信に成功したSolanaトランザクション署名（チャンク順）
+    pub transaction_signatures: Vec<Signature>,
+    /// ここまでに検証済みとして確定した署名数
+    pub verified_signature_count: usize,
+    /// 必要な署名の総数
+    pub total_signature_count: usize,
+}
+
+/// secp256k1 precompile用の`Instruction`を1つ構築する
+///
+/// `sig_infos`の各要素が持つethアドレスと署名をオフセット情報とともに命令データへ
+/// 埋め込み、メッセージ本体も同じ命令データの末尾に格納する（`instruction_index`は
+/// この命令自身がトランザクション内で占めるインデックス）。
+fn build_secp256k1_instruction(
+    sig_infos: &[(SigInfo, [u8; 20])],
+    message: &[u8],
+    instruction_index: u8,
+) -> Instruction {
+    const OFFSETS_SIZE: usize = 11;
+    const ENTRY_SIZE: usize = 64 + 1 + 20;
+
+    let num_signatures = sig_infos.len();
+    let header_size = 1 + num_signatures * OFFSETS_SIZE;
+    let entries_offset = header_size;
+    let message_offset = entries_offset + num_signatures * ENTRY_SIZE;
+
+    let mut data = Vec::with_capacity(message_offset + message.len());
+    data.push(num_signatures as u8);
+
+    for i in 0..num_signatures {
+        let entry_offset = entries_offset + i * ENTRY_SIZE;
+        let signature_offset = entry_offset;
+        let pubkey_offset = entry_offset + 64 + 1;
+
+        data.extend_from_slice(&(signature_offset as u16).to_le_bytes());
+        data.push(instruction_index);
+        data.extend_from_slice(&(pubkey_offset as u16).to_le_bytes());
+        data.push(instruction_index);
+        data.extend_from_slice(&(message_offset as u16).to_le_bytes());
+        data.extend_from_slice(&(message.len() as u16).to_le_bytes());
+        data.push(instruction_index);
+    }
+
+    for (sig_info, eth_address) in sig_infos {
+        data.extend_from_slice(&sig_info.signature);
+        data.push(sig_info.recovery_id);
+        data.extend_from_slice(eth_address);
+    }
+
+    data.extend_from_slice(message);
+
+    Instruction {
+        program_id: secp256k1_program::id(),
+        accounts: Vec::new(),
+        data,
+    }
+}
+
+/// ガーディアンセット（VAA方式のm-of-nデポジット承認に用いる検証者集合）
+#[derive(Debug, Clone)]
+pub struct GuardianSet {
+    /// セットの世代番号（更新のたびに増える）
+    pub index: u32,
+    /// 現在のガーディアンの公開鍵一覧
+    pub keys: Vec<Pubkey>,
+    /// アテステーション確定に必要な署名数
+    pub threshold: usize,
+}
+
+impl GuardianSet {
+    /// 署名を一切受け付けない空のガーディアンセット
+    fn empty() -> Self {
+        Self {
+            index: 0,
+            keys: Vec::new(),
+            threshold: usize::MAX,
+        }
+    }
+
+    /// `keys`からガーディアンセットを作成する。閾値は[`default_guardian_threshold`]を使う。
+    pub fn new(index: u32, keys: Vec<Pubkey>) -> Self {
+        let threshold = default_guardian_threshold(keys.len());
+        Self {
+            index,
+            keys,
+            threshold,
+        }
+    }
+
+    /// 指定した鍵がこのセットに属するガーディアンかどうか
+    fn contains(&self, key: &Pubkey) -> bool {
+        self.keys.iter().any(|k| k == key)
+    }
+}
+
+/// ガーディアンセットのデフォルト閾値（2/3多数決 + 1）を計算する
+fn default_guardian_threshold(guardian_count: usize) -> usize {
+    (guardian_count * 2) / 3 + 1
+}
+
+/// ガーディアンが署名する、デポジットの正規化されたアテステーション内容
+///
+/// 全ガーディアンが独立に同一のメッセージハッシュへ合意できるよう、フィールド順を
+/// 固定した上でbincodeシリアライズ → sha3-256でハッシュ化する（[`Self::hash`]）。
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DepositAttestationMessage {
+    /// デポジット元のチェーン
+    pub source_chain: ChainType,
+    /// デポジットが観測されたSolanaのスロット番号（シーケンス代わり）
+    pub slot: u64,
+    /// デポジットされたトークンのミントアドレス
+    pub mint: Pubkey,
+    /// デポジット額
+    pub amount: u64,
+    /// 送金先のShardXアドレス
+    pub to_shardx_address: String,
+}
+
+impl DepositAttestationMessage {
+    /// メッセージの正規ハッシュ（bincode → sha3-256）を計算する
+    pub fn hash(&self) -> Result<[u8; 32], Error> {
+        let bytes = bincode::serialize(self)
+            .map_err(|e| Error::SerializationError(e.to_string()))?;
+        Ok(sha3_256(&bytes))
+    }
+}
+
+/// sha3-256ハッシュを計算
+fn sha3_256(data: &[u8]) -> [u8; 32] {
+    use tiny_keccak::{Hasher, Sha3};
+    let mut output = [0u8; 32];
+    let mut hasher = Sha3::v256();
+    hasher.update(data);
+    hasher.finalize(&mut output);
+    output
+}
+
+/// プログラムログに埋め込まれるデポジットイベントの行の先頭に付く目印
+///
+/// オンチェーンプログラムは`sol_log`で`DEPOSIT_LOG_PREFIX + base64(bincode(DepositLogEvent))`
+/// の形式のログを出力する想定。
+const DEPOSIT_LOG_PREFIX: &str = "Program log: DEPOSIT:";
+
+/// プログラムログから復元する、オンチェーンで観測された単発のデポジット
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct DepositLogEvent {
+    /// デポジット額
+    amount: u64,
+    /// 送金先のShardXアドレス
+    to_shardx_address: String,
+    /// デポジットされたトークンのミントアドレス
+    mint: Pubkey,
+    /// デポジットを実行したSolana側の送信者
+    sender: Pubkey,
+}
+
+/// トランザクションのプログラムログからデポジットイベントを抽出する
+///
+/// `get_signatures_for_address(program_id)`はブリッジプログラムを呼び出す命令を
+/// 1つでも含むトランザクションを返すため、同じトランザクション内の別の命令が
+/// 自前のプログラムを呼び出して`DEPOSIT:`で始まる偽のログを出力すれば、何の
+/// チェックもなくそれをブリッジのデポジットイベントとして受理してしまう。
+/// これを防ぐため、ログ全体を無条件に走査するのではなく、`Program <id> invoke`
+/// と対応する`Program <id> success`の間（＝ブリッジプログラム自身の実行区間）に
+/// 現れた行だけをデポジットログの候補として扱う。
+///
+/// 1つのトランザクションに複数のデポジットログが含まれる場合は全て処理する。
+/// base64デコードやbincodeデコードに失敗した行（途中で切れたログなど）はスキップして
+/// 警告を出すのみとし、他のログの処理やポーリング自体は中断しない。
+fn parse_deposit_logs(log_messages: &[String], program_id: &Pubkey) -> Vec<DepositLogEvent> {
+    let mut events = Vec::new();
+    let invoke_marker = format!("Program {} invoke", program_id);
+    let success_marker = format!("Program {} success", program_id);
+    let failure_marker = format!("Program {} failed", program_id);
+
+    // `invoke`から対応する`success`/`failed`までの間だけをブリッジプログラム自身の
+    // 実行区間とみなす（ネストしたCPIが同じプログラムを再入する可能性は考慮せず、
+    // 最も外側の呼び出し区間を1つの深さとして扱う）。
+    let mut depth: u32 = 0;
+
+    for log in log_messages {
+        if log.starts_with(&invoke_marker) {
+            depth += 1;
+            continue;
+        }
+        if log.starts_with(&success_marker) || log.starts_with(&failure_marker) {
+            depth = depth.saturating_sub(1);
+            continue;
+        }
+
+        if depth == 0 {
+            // ブリッジプログラム自身の実行区間の外で出力されたログは信用しない
+            continue;
+        }
+
+        let encoded = match log.strip_prefix(DEPOSIT_LOG_PREFIX) {
+            Some(rest) => rest,
+            None => continue,
+        };
+
+        let decoded = match base64::decode(encoded) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                warn!("Failed to base64-decode deposit log: {}", e);
+                continue;
+            }
+        };
+
+        match bincode::deserialize::<DepositLogEvent>(&decoded) {
+            Ok(event) => events.push(event),
+            Err(e) => {
+                warn!("Failed to decode deposit log event: {}", e);
+            }
+        }
+    }
+
+    events
 }
 
 /// Solanaブリッジ命令
@@ -72,6 +649,20 @@ pub enum SolanaBridgeInstruction {
         /// Solana宛先アドレス
         to_solana_address: Pubkey,
     },
+    /// NFTをデポジット（供給量1・小数点以下0桁のミントのみ）
+    DepositNft {
+        /// NFTのミントアドレス
+        mint: Pubkey,
+        /// ShardX宛先アドレス
+        to_shardx_address: String,
+    },
+    /// NFTを引き出し（供給量1・小数点以下0桁のミントのみ）
+    WithdrawNft {
+        /// NFTのミントアドレス
+        mint: Pubkey,
+        /// Solana宛先アドレス
+        to_solana_address: Pubkey,
+    },
     /// バリデータを追加
     AddValidator {
         /// バリデータのアドレス
@@ -92,6 +683,165 @@ pub enum SolanaBridgeInstruction {
         /// トークンのミントアドレス
         mint: Pubkey,
     },
+    /// secp256k1 precompileで検証済みのガーディアンメッセージを確定させる（VAA投稿）
+    PostMessage {
+        /// 確定させるメッセージ本体
+        message_body: Vec<u8>,
+    },
+}
+
+/// 保留中トランザクションの再送・ポーク状態
+#[derive(Debug, Clone)]
+struct RetryState {
+    /// 再送時に使う命令列（ガーディアン定足数待ちのインバウンドメッセージの場合は`None`）
+    instructions: Option<Vec<Instruction>>,
+    /// 直近の送信（または最後のポーク）日時（UNIXエポック秒）
+    submitted_at: i64,
+    /// これまでの再送・ポーク回数
+    retry_count: u32,
+}
+
+/// 再送・ポークを試みるまでの初期タイムアウト（秒）
+const RETRY_TIMEOUT_SECS: i64 = 30;
+/// 再送を試みる最大回数。これを超えると失敗としてマークする
+const MAX_RETRY_COUNT: u32 = 5;
+
+/// 再送回数に応じた指数バックオフ後のタイムアウト秒数を計算する
+fn retry_timeout_secs(retry_count: u32) -> i64 {
+    RETRY_TIMEOUT_SECS * (1i64 << retry_count.min(10))
+}
+
+/// 新しいブロックハッシュで命令列を組み直し、署名して送信する
+fn rebuild_and_resend(
+    rpc_client: &(dyn SolanaRpc + Send + Sync),
+    wallet: &Keypair,
+    instructions: &[Instruction],
+) -> Result<Signature, Error> {
+    let blockhash = rpc_client.get_latest_blockhash()
+        .map_err(|e| Error::TransactionError(format!("Failed to get blockhash: {}", e)))?;
+
+    let transaction = SolanaTransaction::new_signed_with_payer(
+        instructions,
+        Some(&wallet.pubkey()),
+        &[wallet],
+        blockhash,
+    );
+
+    rpc_client.send_transaction(&transaction)
+        .map_err(|e| Error::TransactionError(format!("Failed to resend transaction: {}", e)))
+}
+
+/// 署名がまだ確定していない保留中トランザクションについて、タイムアウトを超えていれば
+/// 新しいブロックハッシュで再送する。再送回数が上限に達した場合は失敗としてマークし、
+/// 再送状態から取り除く（トランザクション自体は失敗状態で保留中の一覧に残る）。
+fn retry_or_fail_pending_transaction(
+    rpc_client: &(dyn SolanaRpc + Send + Sync),
+    wallet: &Option<Keypair>,
+    pending_transactions: &RwLock<HashMap<String, CrossChainTransaction>>,
+    retry_state: &RwLock<HashMap<String, RetryState>>,
+    tx_id: &str,
+) {
+    let now = chrono::Utc::now().timestamp();
+
+    let (instructions, retry_count, timed_out) = {
+        let state = retry_state.read().unwrap();
+        match state.get(tx_id) {
+            Some(entry) => (
+                entry.instructions.clone(),
+                entry.retry_count,
+                now - entry.submitted_at >= retry_timeout_secs(entry.retry_count),
+            ),
+            None => (None, 0, false),
+        }
+    };
+
+    if !timed_out {
+        return;
+    }
+
+    let instructions = match instructions {
+        Some(instructions) => instructions,
+        None => return, // 再構築に必要な命令情報がない
+    };
+
+    if retry_count >= MAX_RETRY_COUNT {
+        if let Some(tx) = pending_transactions.write().unwrap().get_mut(tx_id) {
+            tx.mark_as_failed(format!("Gave up after {} retries", retry_count));
+        }
+        retry_state.write().unwrap().remove(tx_id);
+        warn!("Giving up on transaction {} after {} retries", tx_id, retry_count);
+        return;
+    }
+
+    let wallet = match wallet {
+        Some(wallet) => wallet,
+        None => return,
+    };
+
+    match rebuild_and_resend(rpc_client, wallet, &instructions) {
+        Ok(new_signature) => {
+            {
+                let mut pending_transactions_guard = pending_transactions.write().unwrap();
+                if let Some(tx) = pending_transactions_guard.get_mut(tx_id) {
+                    tx.set_metadata("solana_signature".to_string(), new_signature.to_string());
+                }
+            }
+            {
+                let mut state = retry_state.write().unwrap();
+                if let Some(entry) = state.get_mut(tx_id) {
+                    entry.submitted_at = now;
+                    entry.retry_count += 1;
+                }
+            }
+            info!(
+                "Resent transaction {} (retry {}/{}): {}",
+                tx_id, retry_count + 1, MAX_RETRY_COUNT, new_signature
+            );
+        }
+        Err(e) => {
+            error!("Failed to resend transaction {}: {}", tx_id, e);
+        }
+    }
+}
+
+/// ガーディアン定足数待ちのインバウンドメッセージに対し、プロポーザルアカウントの
+/// 再取得を促す「ポーク」を行う
+///
+/// オンチェーンの状態自体は変化しないが、アカウントを明示的に再取得することで、
+/// 監視中のガーディアンが次回のサイクルでこのメッセージを見落とさないようにする。
+fn poke_pending_message(
+    rpc_client: &(dyn SolanaRpc + Send + Sync),
+    program_id: &Pubkey,
+    retry_state: &RwLock<HashMap<String, RetryState>>,
+    tx_id: &str,
+) {
+    let now = chrono::Utc::now().timestamp();
+
+    let should_poke = {
+        let state = retry_state.read().unwrap();
+        match state.get(tx_id) {
+            Some(entry) => now - entry.submitted_at >= retry_timeout_secs(entry.retry_count),
+            None => true,
+        }
+    };
+
+    if !should_poke {
+        return;
+    }
+
+    match rpc_client.get_account_data(program_id) {
+        Ok(_) => debug!("Poked proposal account for pending message {}", tx_id),
+        Err(e) => warn!("Failed to poke proposal account for pending message {}: {}", tx_id, e),
+    }
+
+    let mut state = retry_state.write().unwrap();
+    let entry = state.entry(tx_id.to_string()).or_insert_with(|| RetryState {
+        instructions: None,
+        submitted_at: now,
+        retry_count: 0,
+    });
+    entry.submitted_at = now;
+    entry.retry_count = entry.retry_count.saturating_add(1);
 }
 
 impl SolanaBridge {
@@ -118,6 +868,14 @@ impl SolanaBridge {
             processed_signatures: RwLock::new(HashMap::new()),
             last_processed_slot: RwLock::new(0),
             polling_active: RwLock::new(false),
+            guardian_set: RwLock::new(GuardianSet::empty()),
+            attestations: RwLock::new(HashMap::new()),
+            verified_sig_cache: RwLock::new(HashMap::new()),
+            retry_state: RwLock::new(HashMap::new()),
+            indexer: None,
+            checkpoint_store: None,
+            nonce_manager: Arc::new(NonceManager::new()),
+            fee_oracle: Arc::new(FeeOracle::new()),
         }
     }
     
@@ -130,6 +888,7 @@ impl SolanaBridge {
         // RPCクライアントを初期化
         let endpoint = self.base_bridge.get_config().target_endpoint.clone();
         let rpc_client = RpcClient::new_with_commitment(endpoint, CommitmentConfig::confirmed());
+        let rpc_client: Arc<dyn SolanaRpc + Send + Sync> = Arc::new(RpcClientAdapter::new(rpc_client));
         self.rpc_client = Some(rpc_client.clone());
         
         // ウォレットを初期化
@@ -141,10 +900,20 @@ impl SolanaBridge {
         // 接続テスト
         let slot = rpc_client.get_slot()
             .map_err(|e| Error::ConnectionError(format!("Failed to get slot: {}", e)))?;
-        
-        *self.last_processed_slot.write().unwrap() = slot;
-        
-        info!("Connected to Solana network. Latest slot: {}", slot);
+
+        // チェックポイントが保存されていれば、そこから監視を再開する（クラッシュを
+        // またいでもデポジットの見逃し・二重処理が起きないようにする）
+        let resume_slot = match &self.checkpoint_store {
+            Some(store) => store.load_solana_deposit_slot()?.unwrap_or(slot),
+            None => slot,
+        };
+
+        *self.last_processed_slot.write().unwrap() = resume_slot;
+
+        info!(
+            "Connected to Solana network. Latest slot: {}, resuming scan from slot: {}",
+            slot, resume_slot
+        );
         
         // ブリッジプログラムIDを設定
         let program_id = match &self.base_bridge.get_config().target_contract {
@@ -197,12 +966,20 @@ impl SolanaBridge {
         // ポーリング間隔（5秒）
         let mut interval = interval(Duration::from_secs(5));
         
+        // ウォレットを取得（再送時の署名に使う）
+        let wallet = self.wallet.clone();
+
         // ポーリングタスクを開始
         let last_processed_slot = self.last_processed_slot.clone();
         let processed_signatures = self.processed_signatures.clone();
         let pending_transactions = self.pending_transactions.clone();
         let polling_active = self.polling_active.clone();
-        
+        let retry_state = self.retry_state.clone();
+        let indexer = self.indexer.clone();
+        let checkpoint_store = self.checkpoint_store.clone();
+        let nonce_manager = self.nonce_manager.clone();
+        let fee_oracle = self.fee_oracle.clone();
+
         tokio::spawn(async move {
             loop {
                 interval.tick().await;
@@ -215,6 +992,11 @@ impl SolanaBridge {
                     }
                 }
                 
+                // 推奨プライオリティ手数料をサンプリング（取得に失敗しても監視は継続する）
+                if let Err(e) = fee_oracle.sample(rpc_client.as_ref()) {
+                    warn!("Failed to sample prioritization fees: {}", e);
+                }
+
                 // 最新のスロットを取得
                 let current_slot = match rpc_client.get_slot() {
                     Ok(slot) => slot,
@@ -273,9 +1055,84 @@ impl SolanaBridge {
                         }
                     };
                     
-                    // TODO: トランザクションを解析してデポジットイベントを検出
-                    // 実際の実装では、トランザクションのログを解析してデポジットイベントを検出する
-                    
+                    // プログラムログを解析してデポジットイベントを検出し、ガーディアンの
+                    // 定足数確認パイプラインに回す（即座には着金させない）
+                    let log_messages = tx_info
+                        .transaction
+                        .meta
+                        .as_ref()
+                        .and_then(|meta| meta.log_messages.as_ref());
+
+                    if let Some(log_messages) = log_messages {
+                        for deposit in parse_deposit_logs(log_messages, &program_id) {
+                            let message_hash = match (DepositAttestationMessage {
+                                source_chain: ChainType::Solana,
+                                slot: tx_info.slot,
+                                mint: deposit.mint,
+                                amount: deposit.amount,
+                                to_shardx_address: deposit.to_shardx_address.clone(),
+                            }).hash() {
+                                Ok(hash) => hash,
+                                Err(e) => {
+                                    warn!("Failed to hash deposit attestation message: {}", e);
+                                    continue;
+                                }
+                            };
+
+                            let inbound_tx_id = uuid::Uuid::new_v4().to_string();
+
+                            let cross_tx = CrossChainTransaction::new(
+                                Transaction {
+                                    id: inbound_tx_id.clone(),
+                                    from: deposit.sender.to_string(),
+                                    to: deposit.to_shardx_address.clone(),
+                                    amount: deposit.amount.to_string(),
+                                    fee: "0".to_string(),
+                                    data: Some(format!(
+                                        "Deposit detected on Solana: mint={}, signature={}",
+                                        deposit.mint, signature_str
+                                    )),
+                                    nonce: 0,
+                                    timestamp: chrono::Utc::now().timestamp() as u64,
+                                    signature: "".to_string(),
+                                    status: TransactionStatus::Pending,
+                                    shard_id: "shard-1".to_string(),
+                                    block_hash: None,
+                                    block_height: None,
+                                    parent_id: None,
+                                },
+                                ChainType::Solana,
+                                ChainType::ShardX,
+                            );
+
+                            let mut inbound_tx = cross_tx.clone();
+                            inbound_tx.set_metadata("solana_signature".to_string(), signature_str.clone());
+                            inbound_tx.set_metadata("slot".to_string(), tx_info.slot.to_string());
+                            inbound_tx.set_metadata("mint".to_string(), deposit.mint.to_string());
+                            inbound_tx.set_metadata("sender".to_string(), deposit.sender.to_string());
+                            inbound_tx.set_metadata("message_hash".to_string(), hex::encode(message_hash));
+                            inbound_tx.set_metadata("from_address".to_string(), deposit.sender.to_string());
+                            inbound_tx.set_metadata("to_address".to_string(), deposit.to_shardx_address.clone());
+                            inbound_tx.set_metadata("amount".to_string(), deposit.amount.to_string());
+
+                            {
+                                let mut pending_transactions_guard = pending_transactions.write().unwrap();
+                                pending_transactions_guard.insert(inbound_tx_id.clone(), inbound_tx.clone());
+                            }
+
+                            if let Some(indexer) = &indexer {
+                                if let Err(e) = indexer.record_transaction(&inbound_tx) {
+                                    warn!("Failed to index detected deposit {}: {}", inbound_tx_id, e);
+                                }
+                            }
+
+                            info!(
+                                "Detected deposit on Solana in {}, awaiting guardian quorum: {}",
+                                signature_str, inbound_tx_id
+                            );
+                        }
+                    }
+
                     // 処理済みとしてマーク
                     {
                         let mut processed_signatures_guard = processed_signatures.write().unwrap();
@@ -288,7 +1145,13 @@ impl SolanaBridge {
                     let mut last_slot_guard = last_processed_slot.write().unwrap();
                     *last_slot_guard = current_slot;
                 }
-                
+
+                if let Some(store) = &checkpoint_store {
+                    if let Err(e) = store.save_checkpoint(BridgeCheckpoint::SolanaDepositScanned(current_slot)) {
+                        warn!("Failed to persist Solana deposit scan checkpoint: {}", e);
+                    }
+                }
+
                 // 保留中のトランザクションを処理
                 let tx_ids: Vec<String> = {
                     let pending_transactions_guard = pending_transactions.read().unwrap();
@@ -305,31 +1168,51 @@ impl SolanaBridge {
                         }
                     };
                     
+                    // ガーディアン定足数待ちのインバウンドメッセージは、`solana_signature`の
+                    // 有無に関わらずSolana側の確定状態では確定させない（既にSolana上では
+                    // 確定済みのデポジットであり、あとはガーディアンの定足数待ちのため）。
+                    // プロポーザルアカウントの再取得を「ポーク」し、`confirm_transaction_for_message`
+                    // による定足数到達時の昇格を待つ。
+                    if tx.get_metadata("message_hash").is_some() {
+                        poke_pending_message(&rpc_client, &program_id, &retry_state, &tx_id);
+                        continue;
+                    }
+
                     // Solanaトランザクションシグネチャを取得
                     let signature = match tx.get_metadata("solana_signature") {
-                        Some(sig) => sig,
-                        None => continue,
+                        Some(sig) => sig.clone(),
+                        None => continue, // 追跡する情報がない
                     };
-                    
+
                     // トランザクションの状態を確認
-                    let signature_obj = match Signature::from_str(signature) {
+                    let signature_obj = match Signature::from_str(&signature) {
                         Ok(sig) => sig,
                         Err(_) => continue,
                     };
-                    
+
                     let status = match rpc_client.get_signature_status(&signature_obj) {
                         Ok(Some(status)) => status,
-                        Ok(None) => continue, // まだ処理中
+                        Ok(None) => {
+                            // 署名がまだ確定していない：タイムアウトしていれば再送する
+                            retry_or_fail_pending_transaction(
+                                &rpc_client,
+                                &wallet,
+                                &pending_transactions,
+                                &retry_state,
+                                &tx_id,
+                            );
+                            continue;
+                        }
                         Err(_) => continue,
                     };
-                    
+
                     // トランザクションの状態を更新
                     let mut updated_tx = tx.clone();
-                    
+
                     if status.is_ok() {
                         // 成功
                         updated_tx.status = TransactionStatus::Confirmed;
-                        
+
                         // トランザクション情報を取得
                         if let Ok(tx_info) = rpc_client.get_transaction(
                             &signature_obj,
@@ -341,23 +1224,43 @@ impl SolanaBridge {
                                 tx_info.slot,
                             );
                         }
-                        
+
                         // 保留中のトランザクションから削除
                         {
                             let mut pending_transactions_guard = pending_transactions.write().unwrap();
                             pending_transactions_guard.remove(&tx_id);
                         }
+                        retry_state.write().unwrap().remove(&tx_id);
+                        if let Some(nonce_account) = updated_tx.get_metadata("nonce_account") {
+                            nonce_manager.release_nonce(nonce_account, updated_tx.original_transaction.nonce);
+                        }
+
+                        if let Some(indexer) = &indexer {
+                            if let Err(e) = indexer.record_status_transition(&tx_id, TransactionStatus::Confirmed) {
+                                warn!("Failed to index confirmed transaction {}: {}", tx_id, e);
+                            }
+                        }
                     } else {
                         // 失敗
                         updated_tx.mark_as_failed(format!("Transaction failed: {:?}", status));
-                        
+
                         // 保留中のトランザクションから削除
                         {
                             let mut pending_transactions_guard = pending_transactions.write().unwrap();
                             pending_transactions_guard.remove(&tx_id);
                         }
+                        retry_state.write().unwrap().remove(&tx_id);
+                        if let Some(nonce_account) = updated_tx.get_metadata("nonce_account") {
+                            nonce_manager.release_nonce(nonce_account, updated_tx.original_transaction.nonce);
+                        }
+
+                        if let Some(indexer) = &indexer {
+                            if let Err(e) = indexer.record_status_transition(&tx_id, TransactionStatus::Failed) {
+                                warn!("Failed to index failed transaction {}: {}", tx_id, e);
+                            }
+                        }
                     }
-                    
+
                     // TODO: トランザクションの状態を更新
                     // 実際の実装では、基本ブリッジのトランザクションマップを更新する
                 }
@@ -375,7 +1278,284 @@ impl SolanaBridge {
         *polling_active = false;
         info!("Event polling task stopped");
     }
-    
+
+    /// 転送履歴インデクサを設定する
+    ///
+    /// 未設定の場合、ブリッジはインメモリの`pending_transactions`のみで動作し、
+    /// 再起動後の履歴参照やアドレス・トークン・ブロック高での検索はできない。
+    pub fn set_indexer(&mut self, indexer: Arc<dyn BridgeIndexer>) {
+        self.indexer = Some(indexer);
+    }
+
+    /// スキャン位置のチェックポイントストアを設定する
+    ///
+    /// 未設定の場合、再起動のたびに現在の最新スロットから監視を始めるため、
+    /// 停止していた間のデポジットは見逃される。設定しておくと`initialize`が
+    /// 最後に記録したスロットから監視を再開する。
+    pub fn set_checkpoint_store(&mut self, checkpoint_store: Arc<dyn CheckpointStore>) {
+        self.checkpoint_store = Some(checkpoint_store);
+    }
+
+    /// アクティブなガーディアンセットを更新する
+    ///
+    /// 閾値は[`default_guardian_threshold`]（2/3多数決 + 1）で自動的に決まる。
+    /// 既存のガーディアンセットに対して集まっていたアテステーションは
+    /// 新しいセットの鍵・閾値では再評価されないため、更新後は破棄する。
+    pub fn set_guardian_set(&self, index: u32, keys: Vec<Pubkey>) {
+        *self.guardian_set.write().unwrap() = GuardianSet::new(index, keys);
+        self.attestations.write().unwrap().clear();
+        info!("Guardian set updated: index={}", index);
+    }
+
+    /// 現在のガーディアンセットを取得する
+    pub fn get_guardian_set(&self) -> GuardianSet {
+        self.guardian_set.read().unwrap().clone()
+    }
+
+    /// ガーディアンによるデポジットアテステーションを提出する
+    ///
+    /// `guardian_pubkey`がアクティブなガーディアンセットに属し、`signature`が
+    /// `message_hash`に対する有効な署名である場合のみ受理する。同じガーディアンが
+    /// 同じメッセージに複数回提出した場合は、最後の署名で上書きする（二重カウントはしない）。
+    /// 定足数に達すると、`message_hash`に紐づく保留中トランザクションを`Confirmed`に遷移させる。
+    ///
+    /// 戻り値は、このメッセージハッシュについて現在集まっている署名数。
+    pub fn submit_attestation(
+        &self,
+        message_hash: [u8; 32],
+        guardian_pubkey: Pubkey,
+        signature: Signature,
+    ) -> Result<usize, Error> {
+        let threshold = {
+            let guardian_set = self.guardian_set.read().unwrap();
+            if !guardian_set.contains(&guardian_pubkey) {
+                return Err(Error::ValidationError(format!(
+                    "Not an active guardian: {}",
+                    guardian_pubkey
+                )));
+            }
+            guardian_set.threshold
+        };
+
+        if !signature.verify(guardian_pubkey.as_ref(), &message_hash) {
+            return Err(Error::ValidationError("Invalid guardian signature".to_string()));
+        }
+
+        let signature_count = {
+            let mut attestations = self.attestations.write().unwrap();
+            let entry = attestations.entry(message_hash).or_insert_with(HashMap::new);
+            entry.insert(guardian_pubkey, signature);
+            entry.len()
+        };
+
+        if signature_count >= threshold {
+            self.confirm_transaction_for_message(&message_hash);
+        }
+
+        Ok(signature_count)
+    }
+
+    /// `message_hash`について、定足数のうち何件の署名が集まっているかを返す
+    ///
+    /// 戻り値は`(現在の署名数, 必要な署名数)`。
+    pub fn attestation_progress(&self, message_hash: &[u8; 32]) -> (usize, usize) {
+        let signature_count = self
+            .attestations
+            .read()
+            .unwrap()
+            .get(message_hash)
+            .map(|signatures| signatures.len())
+            .unwrap_or(0);
+        let threshold = self.guardian_set.read().unwrap().threshold;
+        (signature_count, threshold)
+    }
+
+    /// 定足数に達したメッセージハッシュに対応する保留中トランザクションを`Confirmed`にする
+    ///
+    /// 対応するトランザクションは、メタデータ`message_hash`にこのハッシュの16進数表現を
+    /// 持つものとして探す（デポジット検出側が`CrossChainTransaction`作成時に設定する）。
+    fn confirm_transaction_for_message(&self, message_hash: &[u8; 32]) {
+        let message_hash_hex = hex::encode(message_hash);
+        let mut pending_transactions = self.pending_transactions.write().unwrap();
+
+        let tx_id = pending_transactions.iter().find_map(|(id, tx)| {
+            match tx.get_metadata("message_hash") {
+                Some(hash) if hash == &message_hash_hex => Some(id.clone()),
+                _ => None,
+            }
+        });
+
+        if let Some(tx_id) = tx_id {
+            if let Some(tx) = pending_transactions.get_mut(&tx_id) {
+                tx.status = TransactionStatus::Confirmed;
+                info!("Guardian quorum reached for transaction {}, marking as confirmed", tx_id);
+            }
+
+            if let Some(indexer) = &self.indexer {
+                if let Err(e) = indexer.record_status_transition(&tx_id, TransactionStatus::Confirmed) {
+                    warn!("Failed to index guardian-confirmed transaction {}: {}", tx_id, e);
+                }
+            }
+        }
+    }
+
+    /// 保留中トランザクションの再送・ポークを即座に強制する
+    ///
+    /// 通常はポーリングループがタイムアウト（指数バックオフ）に応じて自動で再送するが、
+    /// オペレーターが手動で即座に再試行させたい場合のために、次回のポーリングが
+    /// すぐに再送・ポークを行うよう`submitted_at`を巻き戻す。
+    pub fn force_retry(&self, tx_id: &str) -> Result<(), Error> {
+        let mut retry_state = self.retry_state.write().unwrap();
+        let entry = retry_state.get_mut(tx_id).ok_or_else(|| {
+            Error::ValidationError(format!("No retry state for transaction: {}", tx_id))
+        })?;
+
+        entry.submitted_at = 0;
+        info!("Forced immediate retry for transaction {}", tx_id);
+
+        Ok(())
+    }
+
+    /// ガーディアン署名をsecp256k1 precompileで検証しつつ、メッセージをVAAとして提出する
+    ///
+    /// `signatures`は（ガーディアン署名とそのethアドレス）の組を必要なだけ渡す。
+    /// [`MAX_SECP_SIGNATURES_PER_INSTRUCTION`]ごとにsecp256k1命令へ分割し、1つの
+    /// トランザクションに収まりきらない場合は複数のトランザクションに分けて送信する。
+    /// 最後のトランザクションにのみブリッジの`PostMessage`命令を付加する。
+    /// 送信が受理されただけでは検証済みとは扱わない。各チャンクの送信後、
+    /// [`VAA_CONFIRMATION_POLL_ATTEMPTS`]回まで確定状態をポーリングし、secp256k1
+    /// precompileが実際にオンチェーンで成功したことを確認できた署名だけを
+    /// `verified_sig_cache`に記録する。確定前にポーリングが尽きた場合や、
+    /// precompileが署名を拒否して命令が失敗した場合はエラーを返す。途中で送信に
+    /// 失敗した場合はそこまでの進捗（送信済みトランザクション署名）をエラーに含める。
+    pub async fn verify_and_submit_vaa(
+        &self,
+        message_body: &[u8],
+        signatures: Vec<(SigInfo, [u8; 20])>,
+    ) -> Result<VaaSubmissionProgress, Error> {
+        if signatures.is_empty() {
+            return Err(Error::ValidationError("No guardian signatures supplied".to_string()));
+        }
+
+        let rpc_client = match &self.rpc_client {
+            Some(client) => client,
+            None => return Err(Error::ConnectionError("RPC client not initialized".to_string())),
+        };
+
+        let wallet = match &self.wallet {
+            Some(wallet) => wallet,
+            None => return Err(Error::ValidationError("Wallet not initialized".to_string())),
+        };
+
+        let program_id = match self.program_id {
+            Some(id) => id,
+            None => return Err(Error::ValidationError("Program ID not initialized".to_string())),
+        };
+
+        let message_hash = sha3_256(message_body);
+        let total_signature_count = signatures.len();
+        let chunks: Vec<Vec<(SigInfo, [u8; 20])>> = signatures
+            .chunks(MAX_SECP_SIGNATURES_PER_INSTRUCTION)
+            .map(|chunk| chunk.to_vec())
+            .collect();
+        let total_chunks = chunks.len();
+
+        let mut transaction_signatures = Vec::with_capacity(total_chunks);
+        let mut verified_signature_count = 0usize;
+
+        for (chunk_index, chunk) in chunks.into_iter().enumerate() {
+            let secp_instruction = build_secp256k1_instruction(&chunk, message_body, 0);
+            let mut instructions = vec![secp_instruction];
+
+            // 最後のチャンクにのみ、検証済みメッセージを確定させる命令を付加する
+            if chunk_index + 1 == total_chunks {
+                let post_message_data = bincode::serialize(&SolanaBridgeInstruction::PostMessage {
+                    message_body: message_body.to_vec(),
+                }).map_err(|e| Error::SerializationError(e.to_string()))?;
+
+                instructions.push(Instruction {
+                    program_id,
+                    accounts: vec![
+                        AccountMeta::new(wallet.pubkey(), true),
+                        AccountMeta::new_readonly(sysvar::instructions::id(), false),
+                    ],
+                    data: post_message_data,
+                });
+            }
+
+            let blockhash = rpc_client.get_latest_blockhash()
+                .map_err(|e| Error::TransactionError(format!("Failed to get blockhash: {}", e)))?;
+
+            let transaction = SolanaTransaction::new_signed_with_payer(
+                &instructions,
+                Some(&wallet.pubkey()),
+                &[wallet],
+                blockhash,
+            );
+
+            let tx_signature = rpc_client.send_transaction(&transaction)
+                .map_err(|e| Error::TransactionError(format!(
+                    "Failed to send secp256k1 verification batch {}/{}: {} (progress so far: {:?})",
+                    chunk_index + 1, total_chunks, e, transaction_signatures
+                )))?;
+            transaction_signatures.push(tx_signature);
+
+            // 送信が受理されただけでは何も保証されないため、precompileの実行結果が
+            // オンチェーンで確定するまでポーリングしてから検証済みとして記録する。
+            let mut confirmed = false;
+            for _ in 0..VAA_CONFIRMATION_POLL_ATTEMPTS {
+                match rpc_client.get_signature_status(&tx_signature) {
+                    Ok(Some(Ok(()))) => {
+                        confirmed = true;
+                        break;
+                    }
+                    Ok(Some(Err(tx_err))) => {
+                        return Err(Error::TransactionError(format!(
+                            "secp256k1 verification batch {}/{} failed on-chain: {:?} (progress so far: {:?})",
+                            chunk_index + 1, total_chunks, tx_err, transaction_signatures
+                        )));
+                    }
+                    Ok(None) => {
+                        sleep(VAA_CONFIRMATION_POLL_INTERVAL).await;
+                    }
+                    Err(e) => {
+                        return Err(Error::TransactionError(format!(
+                            "Failed to poll status of secp256k1 verification batch {}/{}: {} (progress so far: {:?})",
+                            chunk_index + 1, total_chunks, e, transaction_signatures
+                        )));
+                    }
+                }
+            }
+
+            if !confirmed {
+                return Err(Error::TransactionError(format!(
+                    "secp256k1 verification batch {}/{} did not confirm within {} polls (progress so far: {:?})",
+                    chunk_index + 1, total_chunks, VAA_CONFIRMATION_POLL_ATTEMPTS, transaction_signatures
+                )));
+            }
+
+            {
+                let mut cache = self.verified_sig_cache.write().unwrap();
+                let entry = cache.entry(message_hash).or_insert_with(HashMap::new);
+                for (sig_info, _) in &chunk {
+                    entry.insert(sig_info.guardian_index, sig_info.clone());
+                }
+                verified_signature_count = entry.len();
+            }
+
+            info!(
+                "Submitted secp256k1 verification batch {}/{} ({} signatures): {}",
+                chunk_index + 1, total_chunks, chunk.len(), tx_signature
+            );
+        }
+
+        Ok(VaaSubmissionProgress {
+            transaction_signatures,
+            verified_signature_count,
+            total_signature_count,
+        })
+    }
+
     /// ShardXからSolanaへのトークン転送
     pub async fn transfer_to_solana(
         &self,
@@ -452,24 +1632,30 @@ impl SolanaBridge {
             data: instruction_data,
         };
         
+        // 再送時に使えるよう、命令をクローンしておく
+        let instruction_for_retry = instruction.clone();
+
         // トランザクションを作成
         let blockhash = rpc_client.get_latest_blockhash()
             .map_err(|e| Error::TransactionError(format!("Failed to get blockhash: {}", e)))?;
-        
+
         let transaction = SolanaTransaction::new_signed_with_payer(
             &[instruction],
             Some(&wallet.pubkey()),
             &[wallet],
             blockhash,
         );
-        
+
         // トランザクションを送信
         let signature = rpc_client.send_transaction(&transaction)
             .map_err(|e| Error::TransactionError(format!("Failed to send transaction: {}", e)))?;
-        
+
         // トランザクションIDを生成
         let tx_id = uuid::Uuid::new_v4().to_string();
-        
+
+        // 署名アカウント(ウォレット)ごとのナンスを払い出す
+        let reserved_nonce = self.nonce_manager.reserve_nonce(&wallet.pubkey().to_string());
+
         // クロスチェーントランザクションを作成
         let cross_tx = CrossChainTransaction::new(
             Transaction {
@@ -479,7 +1665,7 @@ impl SolanaBridge {
                 amount: amount.to_string(),
                 fee: "0".to_string(),
                 data: Some(format!("Transfer to Solana: token={}, recipient={}", token.symbol, recipient)),
-                nonce: 0,
+                nonce: reserved_nonce,
                 timestamp: chrono::Utc::now().timestamp() as u64,
                 signature: "".to_string(),
                 status: crate::transaction::TransactionStatus::Pending,
@@ -497,18 +1683,38 @@ impl SolanaBridge {
         updated_tx.set_metadata("token_id".to_string(), token_id.to_string());
         updated_tx.set_metadata("token_symbol".to_string(), token.symbol);
         updated_tx.set_metadata("solana_signature".to_string(), signature.to_string());
-        
+        updated_tx.set_metadata("from_address".to_string(), from_address.to_string());
+        updated_tx.set_metadata("to_address".to_string(), recipient.to_string());
+        updated_tx.set_metadata("amount".to_string(), amount.to_string());
+        updated_tx.set_metadata("nonce_account".to_string(), wallet.pubkey().to_string());
+
         // 保留中のトランザクションに追加
         {
             let mut pending_transactions = self.pending_transactions.write().unwrap();
             pending_transactions.insert(tx_id.clone(), updated_tx.clone());
         }
-        
+
+        if let Some(indexer) = &self.indexer {
+            if let Err(e) = indexer.record_transaction(&updated_tx) {
+                warn!("Failed to index outbound transfer {}: {}", tx_id, e);
+            }
+        }
+
+        // 再送状態を登録（タイムアウト時に同じ命令を新しいブロックハッシュで再送できるようにする）
+        {
+            let mut retry_state = self.retry_state.write().unwrap();
+            retry_state.insert(tx_id.clone(), RetryState {
+                instructions: Some(vec![instruction_for_retry]),
+                submitted_at: chrono::Utc::now().timestamp(),
+                retry_count: 0,
+            });
+        }
+
         info!("Created cross-chain transaction from ShardX to Solana: {}", tx_id);
-        
+
         Ok(tx_id)
     }
-    
+
     /// SolanaからShardXへのトークン転送
     pub async fn transfer_from_solana(
         &self,
@@ -636,6 +1842,9 @@ impl SolanaBridge {
         
         // トランザクションIDを生成
         let tx_id = uuid::Uuid::new_v4().to_string();
+
+        // 署名アカウント(ウォレット)ごとのナンスを払い出す
+        let reserved_nonce = self.nonce_manager.reserve_nonce(&wallet.pubkey().to_string());
         
         // クロスチェーントランザクションを作成
         let cross_tx = CrossChainTransaction::new(
@@ -646,7 +1855,7 @@ impl SolanaBridge {
                 amount: amount.to_string(),
                 fee: "0".to_string(),
                 data: Some(format!("Transfer from Solana: token={}", token.symbol)),
-                nonce: 0,
+                nonce: reserved_nonce,
                 timestamp: chrono::Utc::now().timestamp() as u64,
                 signature: "".to_string(),
                 status: crate::transaction::TransactionStatus::Pending,
@@ -664,18 +1873,383 @@ impl SolanaBridge {
         updated_tx.set_metadata("token_id".to_string(), token_id.to_string());
         updated_tx.set_metadata("token_symbol".to_string(), token.symbol);
         updated_tx.set_metadata("solana_signature".to_string(), signature.to_string());
-        
+        updated_tx.set_metadata("from_address".to_string(), wallet.pubkey().to_string());
+        updated_tx.set_metadata("to_address".to_string(), shardx_recipient.to_string());
+        updated_tx.set_metadata("amount".to_string(), amount.to_string());
+        updated_tx.set_metadata("nonce_account".to_string(), wallet.pubkey().to_string());
+
         // 保留中のトランザクションに追加
         {
             let mut pending_transactions = self.pending_transactions.write().unwrap();
             pending_transactions.insert(tx_id.clone(), updated_tx.clone());
         }
-        
+
+        if let Some(indexer) = &self.indexer {
+            if let Err(e) = indexer.record_transaction(&updated_tx) {
+                warn!("Failed to index inbound transfer {}: {}", tx_id, e);
+            }
+        }
+
+        // 再送状態を登録（タイムアウト時に同じ命令を新しいブロックハッシュで再送できるようにする）
+        {
+            let mut retry_state = self.retry_state.write().unwrap();
+            retry_state.insert(tx_id.clone(), RetryState {
+                instructions: Some(instructions),
+                submitted_at: chrono::Utc::now().timestamp(),
+                retry_count: 0,
+            });
+        }
+
         info!("Created cross-chain transaction from Solana to ShardX: {}", tx_id);
-        
+
         Ok(tx_id)
     }
-    
+
+    /// ミントが本当にNFT（供給量1・小数点以下0桁）であることを検証する
+    fn validate_nft_mint(&self, rpc_client: &(dyn SolanaRpc + Send + Sync), mint_pubkey: &Pubkey) -> Result<(), Error> {
+        let account_data = rpc_client.get_account_data(mint_pubkey)
+            .map_err(|e| Error::ConnectionError(format!("Failed to fetch mint account: {}", e)))?;
+
+        let mint = Mint::unpack(&account_data)
+            .map_err(|e| Error::ValidationError(format!("Invalid mint account: {}", e)))?;
+
+        if mint.supply != 1 || mint.decimals != 0 {
+            return Err(Error::ValidationError(format!(
+                "Mint {} is not an NFT (supply={}, decimals={})",
+                mint_pubkey, mint.supply, mint.decimals
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// NFTをShardXからSolanaへ転送（引き出し）
+    ///
+    /// 供給量を持つファンジブルトークンと異なり、ブリッジが保管している1対1の
+    /// ミントをそのまま受取人のトークンアカウントへ送る。
+    pub async fn transfer_nft_to_solana(
+        &self,
+        mint: &str,
+        recipient: &str,
+        from_address: &str,
+    ) -> Result<String, Error> {
+        let mint_pubkey = Pubkey::from_str(mint)
+            .map_err(|e| Error::ValidationError(format!("Invalid mint address: {}", e)))?;
+
+        let recipient_pubkey = Pubkey::from_str(recipient)
+            .map_err(|e| Error::ValidationError(format!("Invalid recipient address: {}", e)))?;
+
+        let rpc_client = match &self.rpc_client {
+            Some(client) => client,
+            None => return Err(Error::ConnectionError("RPC client not initialized".to_string())),
+        };
+
+        let wallet = match &self.wallet {
+            Some(wallet) => wallet,
+            None => return Err(Error::ValidationError("Wallet not initialized".to_string())),
+        };
+
+        let program_id = match self.program_id {
+            Some(id) => id,
+            None => return Err(Error::ValidationError("Program ID not initialized".to_string())),
+        };
+
+        // NFTであることを検証
+        self.validate_nft_mint(rpc_client, &mint_pubkey)?;
+
+        // トークン情報を(origin_chain, mint_address)で取得
+        let token = self.token_registry
+            .get_token_by_chain_address(ChainType::Solana, mint)
+            .ok_or_else(|| Error::ValidationError(format!("NFT not registered: {}", mint)))?;
+
+        // ブリッジのトークンアカウントを取得
+        let bridge_token_account = spl_associated_token_account::get_associated_token_address(
+            &program_id,
+            &mint_pubkey,
+        );
+
+        // 引き出し命令を作成
+        let instruction_data = bincode::serialize(&SolanaBridgeInstruction::WithdrawNft {
+            mint: mint_pubkey,
+            to_solana_address: recipient_pubkey,
+        }).map_err(|e| Error::SerializationError(e.to_string()))?;
+
+        let instruction = Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(wallet.pubkey(), true),
+                AccountMeta::new(bridge_token_account, false),
+                AccountMeta::new(recipient_pubkey, false),
+                AccountMeta::new_readonly(mint_pubkey, false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+            data: instruction_data,
+        };
+
+        // 再送時に使えるよう、命令をクローンしておく
+        let instruction_for_retry = instruction.clone();
+
+        let blockhash = rpc_client.get_latest_blockhash()
+            .map_err(|e| Error::TransactionError(format!("Failed to get blockhash: {}", e)))?;
+
+        let transaction = SolanaTransaction::new_signed_with_payer(
+            &[instruction],
+            Some(&wallet.pubkey()),
+            &[wallet],
+            blockhash,
+        );
+
+        let signature = rpc_client.send_transaction(&transaction)
+            .map_err(|e| Error::TransactionError(format!("Failed to send transaction: {}", e)))?;
+
+        let tx_id = uuid::Uuid::new_v4().to_string();
+
+        // 署名アカウント(ウォレット)ごとのナンスを払い出す
+        let reserved_nonce = self.nonce_manager.reserve_nonce(&wallet.pubkey().to_string());
+
+        let cross_tx = CrossChainTransaction::new(
+            Transaction {
+                id: tx_id.clone(),
+                from: from_address.to_string(),
+                to: recipient.to_string(),
+                amount: "1".to_string(),
+                fee: "0".to_string(),
+                data: Some(format!("NFT transfer to Solana: mint={}, recipient={}", mint, recipient)),
+                nonce: reserved_nonce,
+                timestamp: chrono::Utc::now().timestamp() as u64,
+                signature: "".to_string(),
+                status: crate::transaction::TransactionStatus::Pending,
+                shard_id: "shard-1".to_string(),
+                block_hash: None,
+                block_height: None,
+                parent_id: None,
+            },
+            ChainType::ShardX,
+            ChainType::Solana,
+        );
+
+        // メタデータを設定（元のミント情報を引き出し時の再構成に使えるよう残す）
+        let mut updated_tx = cross_tx.clone();
+        updated_tx.set_metadata("token_id".to_string(), token.id.clone());
+        updated_tx.set_metadata("mint_address".to_string(), mint.to_string());
+        updated_tx.set_metadata("origin_chain".to_string(), format!("{:?}", ChainType::Solana));
+        updated_tx.set_metadata("solana_signature".to_string(), signature.to_string());
+        updated_tx.set_metadata("from_address".to_string(), from_address.to_string());
+        updated_tx.set_metadata("to_address".to_string(), recipient.to_string());
+        updated_tx.set_metadata("amount".to_string(), "1".to_string());
+        updated_tx.set_metadata("nonce_account".to_string(), wallet.pubkey().to_string());
+
+        {
+            let mut pending_transactions = self.pending_transactions.write().unwrap();
+            pending_transactions.insert(tx_id.clone(), updated_tx.clone());
+        }
+
+        if let Some(indexer) = &self.indexer {
+            if let Err(e) = indexer.record_transaction(&updated_tx) {
+                warn!("Failed to index outbound NFT transfer {}: {}", tx_id, e);
+            }
+        }
+
+        {
+            let mut retry_state = self.retry_state.write().unwrap();
+            retry_state.insert(tx_id.clone(), RetryState {
+                instructions: Some(vec![instruction_for_retry]),
+                submitted_at: chrono::Utc::now().timestamp(),
+                retry_count: 0,
+            });
+        }
+
+        info!("Created NFT cross-chain transaction from ShardX to Solana: {}", tx_id);
+
+        Ok(tx_id)
+    }
+
+    /// NFTをSolanaからShardXへ転送（デポジット）
+    ///
+    /// ラップ表現がまだ`TokenRegistry`に存在しない場合は、`(origin_chain, mint_address)`
+    /// の組み合わせで一意になるよう新規に登録する。
+    pub async fn transfer_nft_from_solana(
+        &self,
+        mint: &str,
+        shardx_recipient: &str,
+    ) -> Result<String, Error> {
+        if shardx_recipient.is_empty() {
+            return Err(Error::ValidationError("Invalid ShardX recipient address".to_string()));
+        }
+
+        let mint_pubkey = Pubkey::from_str(mint)
+            .map_err(|e| Error::ValidationError(format!("Invalid mint address: {}", e)))?;
+
+        let rpc_client = match &self.rpc_client {
+            Some(client) => client,
+            None => return Err(Error::ConnectionError("RPC client not initialized".to_string())),
+        };
+
+        let wallet = match &self.wallet {
+            Some(wallet) => wallet,
+            None => return Err(Error::ValidationError("Wallet not initialized".to_string())),
+        };
+
+        let program_id = match self.program_id {
+            Some(id) => id,
+            None => return Err(Error::ValidationError("Program ID not initialized".to_string())),
+        };
+
+        // NFTであることを検証
+        self.validate_nft_mint(rpc_client, &mint_pubkey)?;
+
+        // (origin_chain, mint_address)で既存のラップ表現を探し、なければ登録する
+        let token = match self.token_registry.get_token_by_chain_address(ChainType::Solana, mint) {
+            Some(token) => token,
+            None => {
+                let token = TokenInfo::new(
+                    format!("solana-nft-{}", mint),
+                    format!("Solana NFT {}", mint),
+                    "NFT".to_string(),
+                    0,
+                    ChainType::Solana,
+                    mint.to_string(),
+                );
+                self.token_registry.register_token(token.clone())?;
+                token
+            }
+        };
+
+        // 送信者のトークンアカウントを取得
+        let sender_token_account = spl_associated_token_account::get_associated_token_address(
+            &wallet.pubkey(),
+            &mint_pubkey,
+        );
+
+        // ブリッジのトークンアカウントを取得
+        let bridge_token_account = spl_associated_token_account::get_associated_token_address(
+            &program_id,
+            &mint_pubkey,
+        );
+
+        let bridge_account_exists = rpc_client.get_account_data(&bridge_token_account).is_ok();
+
+        let mut instructions = Vec::new();
+
+        if !bridge_account_exists {
+            instructions.push(
+                associated_token_instruction::create_associated_token_account(
+                    &wallet.pubkey(),
+                    &program_id,
+                    &mint_pubkey,
+                ),
+            );
+        }
+
+        // デポジット命令を作成
+        let instruction_data = bincode::serialize(&SolanaBridgeInstruction::DepositNft {
+            mint: mint_pubkey,
+            to_shardx_address: shardx_recipient.to_string(),
+        }).map_err(|e| Error::SerializationError(e.to_string()))?;
+
+        // トークン転送命令を作成（NFTは供給量1なので常に1枚を送る）
+        instructions.push(
+            token_instruction::transfer(
+                &spl_token::id(),
+                &sender_token_account,
+                &bridge_token_account,
+                &wallet.pubkey(),
+                &[&wallet.pubkey()],
+                1,
+            ).map_err(|e| Error::TransactionError(format!("Failed to create transfer instruction: {}", e)))?
+        );
+
+        instructions.push(
+            Instruction {
+                program_id,
+                accounts: vec![
+                    AccountMeta::new(wallet.pubkey(), true),
+                    AccountMeta::new(sender_token_account, false),
+                    AccountMeta::new(bridge_token_account, false),
+                    AccountMeta::new_readonly(mint_pubkey, false),
+                    AccountMeta::new_readonly(spl_token::id(), false),
+                ],
+                data: instruction_data,
+            }
+        );
+
+        let blockhash = rpc_client.get_latest_blockhash()
+            .map_err(|e| Error::TransactionError(format!("Failed to get blockhash: {}", e)))?;
+
+        let transaction = SolanaTransaction::new_signed_with_payer(
+            &instructions,
+            Some(&wallet.pubkey()),
+            &[wallet],
+            blockhash,
+        );
+
+        let signature = rpc_client.send_transaction(&transaction)
+            .map_err(|e| Error::TransactionError(format!("Failed to send transaction: {}", e)))?;
+
+        let tx_id = uuid::Uuid::new_v4().to_string();
+
+        // 署名アカウント(ウォレット)ごとのナンスを払い出す
+        let reserved_nonce = self.nonce_manager.reserve_nonce(&wallet.pubkey().to_string());
+
+        let cross_tx = CrossChainTransaction::new(
+            Transaction {
+                id: tx_id.clone(),
+                from: wallet.pubkey().to_string(),
+                to: shardx_recipient.to_string(),
+                amount: "1".to_string(),
+                fee: "0".to_string(),
+                data: Some(format!("NFT transfer from Solana: mint={}", mint)),
+                nonce: reserved_nonce,
+                timestamp: chrono::Utc::now().timestamp() as u64,
+                signature: "".to_string(),
+                status: crate::transaction::TransactionStatus::Pending,
+                shard_id: "shard-1".to_string(),
+                block_hash: None,
+                block_height: None,
+                parent_id: None,
+            },
+            ChainType::Solana,
+            ChainType::ShardX,
+        );
+
+        // メタデータを設定（元のミント情報を引き出し時の再構成に使えるよう残す）
+        let mut updated_tx = cross_tx.clone();
+        updated_tx.set_metadata("token_id".to_string(), token.id.clone());
+        updated_tx.set_metadata("mint_address".to_string(), mint.to_string());
+        updated_tx.set_metadata("origin_chain".to_string(), format!("{:?}", ChainType::Solana));
+        updated_tx.set_metadata("solana_signature".to_string(), signature.to_string());
+        updated_tx.set_metadata("from_address".to_string(), wallet.pubkey().to_string());
+        updated_tx.set_metadata("to_address".to_string(), shardx_recipient.to_string());
+        updated_tx.set_metadata("amount".to_string(), "1".to_string());
+        updated_tx.set_metadata("nonce_account".to_string(), wallet.pubkey().to_string());
+
+        {
+            let mut pending_transactions = self.pending_transactions.write().unwrap();
+            pending_transactions.insert(tx_id.clone(), updated_tx.clone());
+        }
+
+        if let Some(indexer) = &self.indexer {
+            if let Err(e) = indexer.record_transaction(&updated_tx) {
+                warn!("Failed to index inbound NFT transfer {}: {}", tx_id, e);
+            }
+        }
+
+        {
+            let mut retry_state = self.retry_state.write().unwrap();
+            retry_state.insert(tx_id.clone(), RetryState {
+                instructions: Some(instructions),
+                submitted_at: chrono::Utc::now().timestamp(),
+                retry_count: 0,
+            });
+        }
+
+        info!("Created NFT cross-chain transaction from Solana to ShardX: {}", tx_id);
+
+        Ok(tx_id)
+    }
+
     /// トランザクションの状態を取得
     pub fn get_transaction_status(&self, tx_id: &str) -> Result<TransactionStatus, Error> {
         // 保留中のトランザクションから検索
@@ -708,7 +2282,29 @@ impl SolanaBridge {
     pub fn get_status(&self) -> BridgeStatus {
         self.base_bridge.get_status()
     }
-    
+
+    /// 署名アカウントの現在のナンスと未確定ナンス一覧を取得する
+    ///
+    /// `get_status`が返す`BridgeStatus`は接続の生死のみを表すため、ナンスの
+    /// 詰まり（未確定ナンスが積み上がっている状態）はここで別途確認する。
+    pub fn get_nonce_status(&self, account: &str) -> (u64, Vec<u64>) {
+        (
+            self.nonce_manager.current_nonce(account),
+            self.nonce_manager.pending_nonces(account),
+        )
+    }
+
+    /// 最後にサンプリングされたプライオリティ手数料を取得する
+    ///
+    /// `max_staleness_secs`より古い（または未サンプリングの）場合は`None`を返し、
+    /// 運用者が手数料情報の陳腐化を検知できるようにする。
+    pub fn get_fee_status(&self, max_staleness_secs: i64) -> Option<FeeSample> {
+        if self.fee_oracle.is_stale(max_staleness_secs) {
+            return None;
+        }
+        self.fee_oracle.last_sample()
+    }
+
     /// ブリッジの設定を取得
     pub fn get_config(&self) -> BridgeConfig {
         self.base_bridge.get_config()
@@ -718,8 +2314,213 @@ impl SolanaBridge {
     pub async fn shutdown(&self) -> Result<(), Error> {
         // イベントポーリングを停止
         self.stop_event_polling();
-        
+
         // 基本ブリッジを停止
         self.base_bridge.shutdown().await
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// `get_signature_status`の戻り値をテストごとに差し替えられるモックRPCクライアント。
+    /// `get_signature_status`の呼び出しごとに、用意した応答を先頭から順番に返す（尽きた後は`None`＝未確定）。
+    struct MockRpc {
+        statuses: Mutex<Vec<Option<Result<(), TransactionError>>>>,
+    }
+
+    impl SolanaRpc for MockRpc {
+        fn get_slot(&self) -> Result<u64, String> {
+            Ok(1)
+        }
+
+        fn get_signatures_for_address(
+            &self,
+            _address: &Pubkey,
+            _start_slot: Option<u64>,
+            _end_slot: Option<u64>,
+            _commitment: CommitmentConfig,
+        ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>, String> {
+            Ok(Vec::new())
+        }
+
+        fn get_transaction(
+            &self,
+            _signature: &Signature,
+            _commitment: CommitmentConfig,
+        ) -> Result<EncodedConfirmedTransactionWithStatusMeta, String> {
+            Err("not implemented in mock".to_string())
+        }
+
+        fn get_signature_status(
+            &self,
+            _signature: &Signature,
+        ) -> Result<Option<Result<(), TransactionError>>, String> {
+            let mut statuses = self.statuses.lock().unwrap();
+            if statuses.is_empty() {
+                Ok(None)
+            } else {
+                Ok(statuses.remove(0))
+            }
+        }
+
+        fn get_latest_blockhash(&self) -> Result<Hash, String> {
+            Ok(Hash::default())
+        }
+
+        fn get_account_data(&self, _pubkey: &Pubkey) -> Result<Vec<u8>, String> {
+            Err("not implemented in mock".to_string())
+        }
+
+        fn send_transaction(&self, transaction: &SolanaTransaction) -> Result<Signature, String> {
+            Ok(transaction.signatures[0])
+        }
+
+        fn get_recent_prioritization_fees(&self) -> Result<u64, String> {
+            Ok(0)
+        }
+    }
+
+    fn test_bridge(rpc: MockRpc) -> SolanaBridge {
+        let config = BridgeConfig {
+            id: "test-solana-bridge".to_string(),
+            name: "test".to_string(),
+            source_chain: ChainType::ShardX,
+            target_chain: ChainType::Solana,
+            source_endpoint: "http://localhost".to_string(),
+            target_endpoint: "http://localhost".to_string(),
+            source_contract: None,
+            target_contract: Some(Pubkey::new_unique().to_string()),
+            max_transaction_size: 1232,
+            max_message_size: 1024,
+            confirmation_blocks: 1,
+            timeout_sec: 30,
+            retry_count: 1,
+            retry_interval_sec: 1,
+            fee_settings: FeeSetting {
+                base_fee: 0.0,
+                fee_per_byte: 0.0,
+                fee_currency: "SOL".to_string(),
+                min_fee: 0.0,
+                max_fee: None,
+            },
+        };
+        let (tx, rx) = mpsc::channel(1);
+        let mut bridge = SolanaBridge::new(config, tx, rx, Arc::new(TokenRegistry::new()));
+        bridge.rpc_client = Some(Arc::new(rpc));
+        bridge.wallet = Some(Keypair::new());
+        bridge.program_id = Some(Pubkey::new_unique());
+        bridge
+    }
+
+    fn dummy_signatures() -> Vec<(SigInfo, [u8; 20])> {
+        vec![(
+            SigInfo {
+                guardian_index: 0,
+                recovery_id: 0,
+                signature: [0u8; 64],
+            },
+            [0u8; 20],
+        )]
+    }
+
+    /// 送信が受理されただけ（`get_signature_status`が最後まで`None`）では
+    /// 確定したとみなさず、検証済みキャッシュに書き込まずにエラーを返すこと。
+    #[tokio::test]
+    async fn test_verify_and_submit_vaa_rejects_unconfirmed_submission() {
+        let bridge = test_bridge(MockRpc {
+            statuses: Mutex::new(Vec::new()),
+        });
+
+        let result = bridge.verify_and_submit_vaa(b"message", dummy_signatures()).await;
+
+        assert!(result.is_err());
+        assert!(bridge.verified_sig_cache.read().unwrap().is_empty());
+    }
+
+    /// オンチェーンのprecompileが署名を拒否した（`get_signature_status`が`Err`を返した）場合は
+    /// 検証済みキャッシュに書き込まずにエラーを返すこと。
+    #[tokio::test]
+    async fn test_verify_and_submit_vaa_rejects_onchain_failure() {
+        let bridge = test_bridge(MockRpc {
+            statuses: Mutex::new(vec![Some(Err(TransactionError::InstructionError(
+                0,
+                solana_sdk::instruction::InstructionError::Custom(0),
+            )))]),
+        });
+
+        let result = bridge.verify_and_submit_vaa(b"message", dummy_signatures()).await;
+
+        assert!(result.is_err());
+        assert!(bridge.verified_sig_cache.read().unwrap().is_empty());
+    }
+
+    /// トランザクションが実際にオンチェーンで確定・成功した場合のみ、検証済みキャッシュに記録すること。
+    #[tokio::test]
+    async fn test_verify_and_submit_vaa_caches_only_after_confirmation() {
+        let bridge = test_bridge(MockRpc {
+            statuses: Mutex::new(vec![None, Some(Ok(()))]),
+        });
+
+        let result = bridge.verify_and_submit_vaa(b"message", dummy_signatures()).await;
+
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().verified_signature_count, 1);
+        assert!(!bridge.verified_sig_cache.read().unwrap().is_empty());
+    }
+
+    fn deposit_log_line(event: &DepositLogEvent) -> String {
+        let encoded = base64::encode(bincode::serialize(event).unwrap());
+        format!("{}{}", DEPOSIT_LOG_PREFIX, encoded)
+    }
+
+    /// ブリッジプログラム自身の`invoke`/`success`区間内に出力された`DEPOSIT:`ログは
+    /// 正しくデポジットイベントとして解析されること。
+    #[test]
+    fn test_parse_deposit_logs_accepts_logs_inside_bridge_invocation() {
+        let program_id = Pubkey::new_unique();
+        let event = DepositLogEvent {
+            amount: 100,
+            to_shardx_address: "shardx-addr".to_string(),
+            mint: Pubkey::new_unique(),
+            sender: Pubkey::new_unique(),
+        };
+
+        let logs = vec![
+            format!("Program {} invoke [1]", program_id),
+            deposit_log_line(&event),
+            format!("Program {} success", program_id),
+        ];
+
+        let parsed = parse_deposit_logs(&logs, &program_id);
+        assert_eq!(parsed, vec![event]);
+    }
+
+    /// 同一トランザクション内の別命令が、ブリッジプログラムの呼び出し区間の外で
+    /// 偽の`DEPOSIT:`ログを出力しても、デポジットイベントとして解析されないこと
+    /// （攻撃者が自分のプログラムから任意の金額・宛先を偽装するシナリオ）。
+    #[test]
+    fn test_parse_deposit_logs_ignores_logs_outside_bridge_invocation() {
+        let program_id = Pubkey::new_unique();
+        let attacker_program_id = Pubkey::new_unique();
+        let forged_event = DepositLogEvent {
+            amount: u64::MAX,
+            to_shardx_address: "attacker-addr".to_string(),
+            mint: Pubkey::new_unique(),
+            sender: Pubkey::new_unique(),
+        };
+
+        let logs = vec![
+            format!("Program {} invoke [1]", program_id),
+            format!("Program {} success", program_id),
+            format!("Program {} invoke [1]", attacker_program_id),
+            deposit_log_line(&forged_event),
+            format!("Program {} success", attacker_program_id),
+        ];
+
+        let parsed = parse_deposit_logs(&logs, &program_id);
+        assert!(parsed.is_empty());
+    }
 }
\ No newline at end of file
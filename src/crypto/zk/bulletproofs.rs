@@ -196,6 +196,85 @@ impl Bulletproof {
         Ok(proofs)
     }
 
+    /// 複数の値を単一の集約Bulletproofにまとめて生成
+    ///
+    /// `m` 個の `n` ビット範囲証明を1つの内積引数に集約する。証明サイズは
+    /// `2·log2(n·m) + const` 群要素となり、個別に `m` 本生成するより
+    /// 検証コストが対数的に小さくなる。`values`/`blindings` の本数は
+    /// 2のべき乗である必要がある（呼び出し側でゼロ値ダミーでパディングする）。
+    pub fn prove_aggregated(
+        values: &[u64],
+        blindings: &[&[u8]],
+    ) -> Result<(BulletproofProof, Vec<CompressedRistretto>), Error> {
+        if values.len() != blindings.len() {
+            return Err(Error::InvalidArgument(
+                "Number of values and blindings must match".to_string(),
+            ));
+        }
+        if values.is_empty() || !values.len().is_power_of_two() {
+            return Err(Error::InvalidArgument(
+                "Aggregated proof requires a power-of-two number of values".to_string(),
+            ));
+        }
+
+        let blinding_scalars = blindings
+            .iter()
+            .map(|b| Self::bytes_to_scalar(b))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Pedersen生成器とparty_capacity分のBulletproof生成器を作成
+        let pc_gens = PedersenGens::default();
+        let bp_gens = BulletproofGens::new(64, values.len());
+
+        let mut transcript = Transcript::new(b"ShardX Aggregated Range Proof");
+
+        let (proof, commitments) = BPRangeProof::prove_multiple(
+            &bp_gens,
+            &pc_gens,
+            &mut transcript,
+            values,
+            &blinding_scalars,
+            64,
+        )
+        .map_err(|e| Error::CryptoError(format!("Failed to create aggregated range proof: {}", e)))?;
+
+        Ok((BulletproofProof { inner: proof }, commitments))
+    }
+
+    /// 集約Bulletproofを検証
+    ///
+    /// コミットメント本数は2のべき乗である必要がある。
+    pub fn verify_aggregated(
+        proof: &BulletproofProof,
+        commitments: &[&[u8]],
+    ) -> Result<bool, Error> {
+        if commitments.is_empty() || !commitments.len().is_power_of_two() {
+            return Err(Error::InvalidArgument(
+                "Aggregated proof requires a power-of-two number of commitments".to_string(),
+            ));
+        }
+
+        let points = commitments
+            .iter()
+            .map(|c| {
+                CompressedRistretto::from_slice(c)
+                    .map_err(|_| Error::DeserializationError("Invalid commitment format".to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let pc_gens = PedersenGens::default();
+        let bp_gens = BulletproofGens::new(64, commitments.len());
+
+        let mut transcript = Transcript::new(b"ShardX Aggregated Range Proof");
+
+        let result = proof
+            .inner
+            .verify_multiple(&bp_gens, &pc_gens, &mut transcript, &points, 64)
+            .is_ok();
+
+        Ok(result)
+    }
+
     /// 複数の範囲証明を一括で検証
     pub fn batch_verify_range(
         proofs: &[BulletproofProof],
@@ -307,4 +386,43 @@ mod tests {
         let result = Bulletproof::batch_verify_range(&proofs, &commitment_refs).unwrap();
         assert!(result);
     }
+
+    #[test]
+    fn test_aggregated_range_proof() {
+        // 4つ（2のべき乗）の値を集約
+        let mut values = Vec::new();
+        let mut blindings = Vec::new();
+        let mut blinding_refs = Vec::new();
+
+        for _ in 0..4 {
+            values.push(rand::thread_rng().gen_range(0..1000));
+            let mut blinding = [0u8; 32];
+            rand::thread_rng().fill(&mut blinding);
+            blindings.push(blinding);
+        }
+        for b in &blindings {
+            blinding_refs.push(b.as_slice());
+        }
+
+        // 集約証明を生成
+        let (proof, commitments) =
+            Bulletproof::prove_aggregated(&values, &blinding_refs).unwrap();
+
+        // 返却されたコミットメントで検証
+        let commitment_bytes: Vec<Vec<u8>> =
+            commitments.iter().map(|c| c.to_bytes().to_vec()).collect();
+        let commitment_refs: Vec<&[u8]> =
+            commitment_bytes.iter().map(|c| c.as_slice()).collect();
+
+        let result = Bulletproof::verify_aggregated(&proof, &commitment_refs).unwrap();
+        assert!(result);
+    }
+
+    #[test]
+    fn test_aggregated_rejects_non_power_of_two() {
+        let values = [1u64, 2, 3];
+        let blindings = [[0u8; 32]; 3];
+        let blinding_refs: Vec<&[u8]> = blindings.iter().map(|b| b.as_slice()).collect();
+        assert!(Bulletproof::prove_aggregated(&values, &blinding_refs).is_err());
+    }
 }
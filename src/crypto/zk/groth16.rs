@@ -5,6 +5,32 @@ use ark_ff::{Field, PrimeField};
 use ark_groth16::{Proof, ProvingKey, VerifyingKey};
 use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, SynthesisError};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use serde::{Deserialize, Serialize};
+
+/// バージョン付きエンベロープのマジック値（"G16\0"）
+const GROTH16_MAGIC: u32 = 0x4731_3600;
+/// シリアライズ形式のバージョン
+const GROTH16_VERSION: u16 = 1;
+/// Bn254 を表すカーブID
+const CURVE_BN254: u16 = 1;
+
+/// バージョンタグ付きのシリアライズエンベロープ
+///
+/// arkworks の `CanonicalSerialize` 出力を、マジック・バージョン・カーブIDと
+/// サーキットパラメータで包む。読み込み時にバージョン/カーブIDの不一致を拒否する。
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct VersionedEnvelope {
+    /// マジック値
+    magic: u32,
+    /// フォーマットバージョン
+    version: u16,
+    /// カーブ識別子
+    curve_id: u16,
+    /// サーキットパラメータ（bincode）
+    circuit_params: Vec<u8>,
+    /// ペイロード（CanonicalSerialize 出力）
+    payload: Vec<u8>,
+}
 
 /// Groth16実装
 pub struct Groth16;
@@ -45,8 +71,9 @@ impl ZeroKnowledgeProofSystem for Groth16 {
         let private_fr = Self::convert_inputs_to_fr(private_inputs)?;
         
         // サーキットを構築
-        let circuit = Self::build_circuit(public_fr, private_fr)?;
-        
+        let params = Self::extract_circuit_params(proving_key)?;
+        let circuit = Self::build_circuit(public_fr, private_fr, &params)?;
+
         // 証明を生成
         let proof = ark_groth16::create_random_proof(circuit, &proving_key.inner, &mut rand::thread_rng())
             .map_err(|e| Error::CryptoError(format!("Failed to create Groth16 proof: {}", e)))?;
@@ -78,7 +105,7 @@ impl ZeroKnowledgeProofSystem for Groth16 {
         // ダミーのサーキットを構築（キー生成用）
         let dummy_public = vec![Fr::zero(); params.num_public_inputs];
         let dummy_private = vec![Fr::zero(); params.num_private_inputs];
-        let circuit = Self::build_circuit(dummy_public, dummy_private)?;
+        let circuit = Self::build_circuit(dummy_public, dummy_private, &params)?;
         
         // キーペアを生成
         let (pk, vk) = ark_groth16::generate_random_parameters::<Bn254, _, _>(
@@ -118,57 +145,316 @@ impl ZeroKnowledgeProofSystem for Groth16 {
 }
 
 /// サーキットパラメータ
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CircuitParameters {
     /// 公開入力の数
     pub num_public_inputs: usize,
     /// 秘密入力の数
     pub num_private_inputs: usize,
+    /// ノートコミットメントツリーの深さ
+    pub tree_depth: usize,
+    /// 消費する入力ノートの数
+    pub num_inputs: usize,
+    /// 生成する出力ノートの数
+    pub num_outputs: usize,
+}
+
+/// 1つの入力ノートに対する証明ウィットネス
+#[derive(Clone, Debug)]
+pub struct InputNoteWitness {
+    /// ノートの額面
+    pub value: u64,
+    /// ブラインディングファクタ
+    pub blinding: Fr,
+    /// 所有者の公開鍵
+    pub owner_pk: Fr,
+    /// ヌリファイア鍵
+    pub nullifier_key: Fr,
+    /// ツリー内の葉の位置
+    pub position: u64,
+    /// ルートまでの認証パス（兄弟ノードのハッシュ、葉側から順）
+    pub merkle_path: Vec<Fr>,
+}
+
+/// 1つの出力ノートに対する証明ウィットネス
+#[derive(Clone, Debug)]
+pub struct OutputNoteWitness {
+    /// ノートの額面
+    pub value: u64,
+    /// ブラインディングファクタ
+    pub blinding: Fr,
+    /// 所有者の公開鍵
+    pub owner_pk: Fr,
+}
+
+/// シールドプール転送サーキット
+///
+/// MantaPay/Zcash Sapling系のUTXO転送を表現する。入力ノートが
+/// コミットメントツリーのメンバーであること、ヌリファイアが正しく
+/// 導出されていること、入出力の額面が釣り合っていること、各出力が
+/// 64ビット範囲に収まっていることをすべて回路内で強制する。
+pub struct ShieldedTransferCircuit {
+    /// コミットメントツリーの深さ
+    pub tree_depth: usize,
+    /// ツリーのルート（公開入力）
+    pub root: Fr,
+    /// 手数料（公開入力）
+    pub fee: Fr,
+    /// 入力ノート
+    pub inputs: Vec<InputNoteWitness>,
+    /// 出力ノート
+    pub outputs: Vec<OutputNoteWitness>,
+}
+
+/// MiMCスタイルの固定ラウンド数（回路内ハッシュ用）
+const MIMC_ROUNDS: usize = 91;
+
+/// コミットメントツリーの既定の深さ
+const DEFAULT_TREE_DEPTH: usize = 32;
+
+/// i番目のラウンド定数を決定的に生成する
+fn mimc_round_constant(i: usize) -> Fr {
+    // ドメイン分離のためインデックスをプレフィックス付きでフィールドに写像する
+    let mut repr = [0u8; 32];
+    let tag = (i as u64).wrapping_add(0x6d696d63_0000_0000); // "mimc" タグ
+    repr[..8].copy_from_slice(&tag.to_le_bytes());
+    Fr::from_le_bytes_mod_order(&repr)
 }
 
-/// 汎用的なR1CSサーキット
-pub struct GenericCircuit {
-    /// 公開入力
-    pub public_inputs: Vec<Fr>,
-    /// 秘密入力
-    pub private_inputs: Vec<Fr>,
+impl ShieldedTransferCircuit {
+    /// 2入力のZKフレンドリーなハッシュ H(l, r) を回路内で計算する
+    ///
+    /// MiMC-Feistel構造（x := (x + k + c)^3）を用いる。外側のキーとして
+    /// `r` を混ぜ込み、鍵なしハッシュとして使う。
+    fn hash_two(
+        cs: &mut ConstraintSystem<Fr>,
+        left: (ark_relations::r1cs::Variable, Option<Fr>),
+        right: (ark_relations::r1cs::Variable, Option<Fr>),
+    ) -> Result<(ark_relations::r1cs::Variable, Option<Fr>), SynthesisError> {
+        use ark_relations::r1cs::{LinearCombination, Variable};
+
+        let key_lc = LinearCombination::zero() + (Fr::one(), right.0);
+        let mut state_var = left.0;
+        let mut state_val = left.1;
+
+        for i in 0..MIMC_ROUNDS {
+            let c = mimc_round_constant(i);
+            // t = state + key + c
+            let t_val = match (state_val, right.1) {
+                (Some(s), Some(k)) => Some(s + k + c),
+                _ => None,
+            };
+            let t_var = cs.new_witness_variable(|| t_val.ok_or(SynthesisError::AssignmentMissing))?;
+            cs.enforce_constraint(
+                LinearCombination::zero() + (Fr::one(), state_var) + key_lc.clone() + (c, Variable::One),
+                LinearCombination::zero() + (Fr::one(), Variable::One),
+                LinearCombination::zero() + (Fr::one(), t_var),
+            )?;
+
+            // t2 = t * t
+            let t2_val = t_val.map(|t| t * t);
+            let t2_var = cs.new_witness_variable(|| t2_val.ok_or(SynthesisError::AssignmentMissing))?;
+            cs.enforce_constraint(
+                LinearCombination::zero() + (Fr::one(), t_var),
+                LinearCombination::zero() + (Fr::one(), t_var),
+                LinearCombination::zero() + (Fr::one(), t2_var),
+            )?;
+
+            // t3 = t2 * t
+            let t3_val = match (t2_val, t_val) {
+                (Some(a), Some(b)) => Some(a * b),
+                _ => None,
+            };
+            let t3_var = cs.new_witness_variable(|| t3_val.ok_or(SynthesisError::AssignmentMissing))?;
+            cs.enforce_constraint(
+                LinearCombination::zero() + (Fr::one(), t2_var),
+                LinearCombination::zero() + (Fr::one(), t_var),
+                LinearCombination::zero() + (Fr::one(), t3_var),
+            )?;
+
+            state_var = t3_var;
+            state_val = t3_val;
+        }
+
+        // 最後に鍵を加算して出力とする
+        let out_val = match (state_val, right.1) {
+            (Some(s), Some(k)) => Some(s + k),
+            _ => None,
+        };
+        let out_var = cs.new_witness_variable(|| out_val.ok_or(SynthesisError::AssignmentMissing))?;
+        cs.enforce_constraint(
+            LinearCombination::zero() + (Fr::one(), state_var) + key_lc,
+            LinearCombination::zero() + (Fr::one(), Variable::One),
+            LinearCombination::zero() + (Fr::one(), out_var),
+        )?;
+        Ok((out_var, out_val))
+    }
 }
 
-impl ConstraintSynthesizer<Fr> for GenericCircuit {
+impl ConstraintSynthesizer<Fr> for ShieldedTransferCircuit {
     fn generate_constraints(
         self,
         cs: &mut ConstraintSystem<Fr>,
     ) -> Result<(), SynthesisError> {
-        // 公開入力を割り当て
-        let mut public_vars = Vec::new();
-        for (i, input) in self.public_inputs.iter().enumerate() {
-            let var = cs.new_input_variable(|| Ok(*input))?;
-            public_vars.push(var);
-        }
-        
-        // 秘密入力を割り当て
-        let mut private_vars = Vec::new();
-        for input in &self.private_inputs {
-            let var = cs.new_witness_variable(|| Ok(*input))?;
-            private_vars.push(var);
+        use ark_relations::r1cs::{LinearCombination, Variable};
+
+        // --- 公開入力の割り当て ---
+        // レイアウト: [root, fee, nullifier_0..n, output_cm_0..m]
+        let root_var = cs.new_input_variable(|| Ok(self.root))?;
+        let fee_var = cs.new_input_variable(|| Ok(self.fee))?;
+
+        // 入力額面の合計（LC）
+        let mut input_sum_lc = LinearCombination::zero();
+        // 各入力ノートの処理
+        for note in &self.inputs {
+            let value_fr = Fr::from(note.value);
+            let value_var = cs.new_witness_variable(|| Ok(value_fr))?;
+            let blinding_var = cs.new_witness_variable(|| Ok(note.blinding))?;
+            let owner_var = cs.new_witness_variable(|| Ok(note.owner_pk))?;
+            let nk_var = cs.new_witness_variable(|| Ok(note.nullifier_key))?;
+            let pos_fr = Fr::from(note.position);
+            let pos_var = cs.new_witness_variable(|| Ok(pos_fr))?;
+
+            // (1) コミットメント cm = H(H(value, blinding), owner_pk)
+            let inner = Self::hash_two(
+                cs,
+                (value_var, Some(value_fr)),
+                (blinding_var, Some(note.blinding)),
+            )?;
+            let mut cur = Self::hash_two(cs, inner, (owner_var, Some(note.owner_pk)))?;
+
+            // (1続き) 認証パスを葉側からルートまで検証する
+            //   各レベルの方向ビットは position のビット分解で決まる
+            let mut pos_bits_acc = LinearCombination::zero();
+            let mut coeff = Fr::one();
+            for level in 0..self.tree_depth {
+                let sibling_fr = note.merkle_path.get(level).copied().unwrap_or_else(Fr::zero);
+                let sibling_var = cs.new_witness_variable(|| Ok(sibling_fr))?;
+
+                // 方向ビット b（0 なら cur が左、1 なら cur が右）
+                let bit = (note.position >> level) & 1;
+                let bit_fr = Fr::from(bit);
+                let bit_var = cs.new_witness_variable(|| Ok(bit_fr))?;
+                // b がブール値であることを強制: b * (1 - b) = 0
+                cs.enforce_constraint(
+                    LinearCombination::zero() + (Fr::one(), bit_var),
+                    LinearCombination::zero() + (Fr::one(), Variable::One) - (Fr::one(), bit_var),
+                    LinearCombination::zero(),
+                )?;
+                pos_bits_acc = pos_bits_acc + (coeff, bit_var);
+                coeff.double_in_place();
+
+                // left  = cur + b*(sibling - cur)
+                // right = sibling + b*(cur - sibling)
+                let cur_val = cur.1;
+                let swap_val = match cur_val {
+                    Some(c) => Some(Fr::from(bit) * (sibling_fr - c)),
+                    None => None,
+                };
+                let left_val = match (cur_val, swap_val) {
+                    (Some(c), Some(s)) => Some(c + s),
+                    _ => None,
+                };
+                let right_val = match (cur_val, left_val) {
+                    (Some(c), Some(l)) => Some(sibling_fr + c - l),
+                    _ => None,
+                };
+                let left_var = cs.new_witness_variable(|| left_val.ok_or(SynthesisError::AssignmentMissing))?;
+                let right_var = cs.new_witness_variable(|| right_val.ok_or(SynthesisError::AssignmentMissing))?;
+                // b * (sibling - cur) = left - cur
+                cs.enforce_constraint(
+                    LinearCombination::zero() + (Fr::one(), bit_var),
+                    LinearCombination::zero() + (Fr::one(), sibling_var) - (Fr::one(), cur.0),
+                    LinearCombination::zero() + (Fr::one(), left_var) - (Fr::one(), cur.0),
+                )?;
+                // left + right = cur + sibling（方向に依らず保存される）
+                cs.enforce_constraint(
+                    LinearCombination::zero() + (Fr::one(), left_var) + (Fr::one(), right_var)
+                        - (Fr::one(), cur.0) - (Fr::one(), sibling_var),
+                    LinearCombination::zero() + (Fr::one(), Variable::One),
+                    LinearCombination::zero(),
+                )?;
+
+                cur = Self::hash_two(cs, (left_var, left_val), (right_var, right_val))?;
+            }
+            // position のビット分解が元の値と一致することを強制
+            cs.enforce_constraint(
+                pos_bits_acc - (Fr::one(), pos_var),
+                LinearCombination::zero() + (Fr::one(), Variable::One),
+                LinearCombination::zero(),
+            )?;
+            // 計算したルートが公開ルートと一致すること
+            cs.enforce_constraint(
+                LinearCombination::zero() + (Fr::one(), cur.0) - (Fr::one(), root_var),
+                LinearCombination::zero() + (Fr::one(), Variable::One),
+                LinearCombination::zero(),
+            )?;
+
+            // (2) ヌリファイア nf = H(nullifier_key, position) を公開入力として公開
+            let nf = Self::hash_two(cs, (nk_var, Some(note.nullifier_key)), (pos_var, Some(pos_fr)))?;
+            let nf_input = cs.new_input_variable(|| nf.1.ok_or(SynthesisError::AssignmentMissing))?;
+            cs.enforce_constraint(
+                LinearCombination::zero() + (Fr::one(), nf.0) - (Fr::one(), nf_input),
+                LinearCombination::zero() + (Fr::one(), Variable::One),
+                LinearCombination::zero(),
+            )?;
+
+            input_sum_lc = input_sum_lc + (Fr::one(), value_var);
         }
-        
-        // 簡単な制約を追加（実際のアプリケーションでは、より複雑な制約を追加）
-        // 例: 秘密入力の合計が公開入力と等しい
-        if !public_vars.is_empty() && !private_vars.is_empty() {
-            let mut sum_lc = ark_relations::r1cs::LinearCombination::zero();
-            
-            for var in &private_vars {
-                sum_lc = sum_lc + (Fr::one(), *var);
+
+        // 各出力ノートの処理
+        let mut output_sum_lc = LinearCombination::zero();
+        for note in &self.outputs {
+            let value_fr = Fr::from(note.value);
+            let value_var = cs.new_witness_variable(|| Ok(value_fr))?;
+            let blinding_var = cs.new_witness_variable(|| Ok(note.blinding))?;
+            let owner_var = cs.new_witness_variable(|| Ok(note.owner_pk))?;
+
+            // (4) 範囲証明: value を64ビットに分解し、各ビットがブール値であることを強制
+            let mut bit_acc = LinearCombination::zero();
+            let mut coeff = Fr::one();
+            for b in 0..64u32 {
+                let bit = (note.value >> b) & 1;
+                let bit_fr = Fr::from(bit);
+                let bit_var = cs.new_witness_variable(|| Ok(bit_fr))?;
+                cs.enforce_constraint(
+                    LinearCombination::zero() + (Fr::one(), bit_var),
+                    LinearCombination::zero() + (Fr::one(), Variable::One) - (Fr::one(), bit_var),
+                    LinearCombination::zero(),
+                )?;
+                bit_acc = bit_acc + (coeff, bit_var);
+                coeff.double_in_place();
             }
-            
             cs.enforce_constraint(
-                ark_relations::r1cs::LinearCombination::zero() + (Fr::one(), public_vars[0]),
-                ark_relations::r1cs::LinearCombination::zero() + (Fr::one(), cs.one()),
-                sum_lc,
+                bit_acc - (Fr::one(), value_var),
+                LinearCombination::zero() + (Fr::one(), Variable::One),
+                LinearCombination::zero(),
+            )?;
+
+            // 出力コミットメントを計算し公開入力として公開
+            let inner = Self::hash_two(
+                cs,
+                (value_var, Some(value_fr)),
+                (blinding_var, Some(note.blinding)),
             )?;
+            let cm = Self::hash_two(cs, inner, (owner_var, Some(note.owner_pk)))?;
+            let cm_input = cs.new_input_variable(|| cm.1.ok_or(SynthesisError::AssignmentMissing))?;
+            cs.enforce_constraint(
+                LinearCombination::zero() + (Fr::one(), cm.0) - (Fr::one(), cm_input),
+                LinearCombination::zero() + (Fr::one(), Variable::One),
+                LinearCombination::zero(),
+            )?;
+
+            output_sum_lc = output_sum_lc + (Fr::one(), value_var);
         }
-        
+
+        // (3) 残高保存: Σ input = Σ output + fee
+        cs.enforce_constraint(
+            input_sum_lc - output_sum_lc - (Fr::one(), fee_var),
+            LinearCombination::zero() + (Fr::one(), Variable::One),
+            LinearCombination::zero(),
+        )?;
+
         Ok(())
     }
 }
@@ -204,30 +490,260 @@ impl Groth16 {
         Ok(params)
     }
     
-    /// サーキットを構築
+    /// 証明キーからサーキットパラメータを復元する
+    ///
+    /// 公開入力の数から入出力ノートの本数を逆算する。公開入力は
+    /// [root, fee, nullifier×num_inputs, output_cm×num_outputs] の
+    /// レイアウトを取り、入出力は同数と仮定する。
+    fn extract_circuit_params(proving_key: &Groth16ProvingKey) -> Result<CircuitParameters, Error> {
+        let num_public_inputs = proving_key.inner.vk.gamma_abc_g1.len().saturating_sub(1);
+        // root と fee を除いた残りを入出力で均等に割り当てる
+        let io = num_public_inputs.saturating_sub(2);
+        let arity = io / 2;
+        Ok(CircuitParameters {
+            num_public_inputs,
+            num_private_inputs: 0,
+            tree_depth: DEFAULT_TREE_DEPTH,
+            num_inputs: arity,
+            num_outputs: arity,
+        })
+    }
+
+    /// フィールド要素の下位64ビットをu64として取り出す
+    fn fr_to_u64(value: &Fr) -> u64 {
+        let repr = value.into_repr();
+        repr.as_ref().first().copied().unwrap_or(0)
+    }
+
+    /// フラットなFrベクトルからシールド転送サーキットを構築する
+    ///
+    /// 秘密入力レイアウト（ノート順）:
+    ///   入力ノート: [value, blinding, owner_pk, nullifier_key, position, path×tree_depth]
+    ///   出力ノート: [value, blinding, owner_pk]
+    /// 公開入力レイアウト: [root, fee, ...]（root/fee のみ参照する）
     fn build_circuit(
         public_inputs: Vec<Fr>,
         private_inputs: Vec<Fr>,
-    ) -> Result<GenericCircuit, Error> {
-        Ok(GenericCircuit {
-            public_inputs,
-            private_inputs,
+        params: &CircuitParameters,
+    ) -> Result<ShieldedTransferCircuit, Error> {
+        let root = public_inputs.first().copied().unwrap_or_else(Fr::zero);
+        let fee = public_inputs.get(1).copied().unwrap_or_else(Fr::zero);
+
+        let mut cursor = 0usize;
+        let mut take = |n: usize| -> Vec<Fr> {
+            let slice = private_inputs
+                .iter()
+                .skip(cursor)
+                .take(n)
+                .copied()
+                .collect::<Vec<_>>();
+            cursor += n;
+            // 不足分はゼロで補う（キー生成時のダミー入力に対応）
+            let mut v = slice;
+            while v.len() < n {
+                v.push(Fr::zero());
+            }
+            v
+        };
+
+        let mut inputs = Vec::with_capacity(params.num_inputs);
+        for _ in 0..params.num_inputs {
+            let head = take(5);
+            let path = take(params.tree_depth);
+            inputs.push(InputNoteWitness {
+                value: Self::fr_to_u64(&head[0]),
+                blinding: head[1],
+                owner_pk: head[2],
+                nullifier_key: head[3],
+                position: Self::fr_to_u64(&head[4]),
+                merkle_path: path,
+            });
+        }
+
+        let mut outputs = Vec::with_capacity(params.num_outputs);
+        for _ in 0..params.num_outputs {
+            let head = take(3);
+            outputs.push(OutputNoteWitness {
+                value: Self::fr_to_u64(&head[0]),
+                blinding: head[1],
+                owner_pk: head[2],
+            });
+        }
+
+        Ok(ShieldedTransferCircuit {
+            tree_depth: params.tree_depth,
+            root,
+            fee,
+            inputs,
+            outputs,
         })
     }
-    
-    /// 秘密値の知識を証明するサーキットを作成
-    pub fn create_knowledge_proof_circuit(
-        public_value: &[u8],
-        private_value: &[u8],
-    ) -> Result<GenericCircuit, Error> {
-        // 公開値と秘密値をフィールド要素に変換
-        let public_fr = Self::bytes_to_fr(public_value)?;
-        let private_fr = Self::bytes_to_fr(private_value)?;
-        
-        Ok(GenericCircuit {
-            public_inputs: vec![public_fr],
-            private_inputs: vec![private_fr],
-        })
+}
+
+impl Groth16 {
+    /// バージョン付きエンベロープを組み立てる
+    fn wrap_envelope(payload: Vec<u8>, circuit: &CircuitParameters) -> Result<Vec<u8>, Error> {
+        let circuit_params = bincode::serialize(circuit)
+            .map_err(|e| Error::SerializationError(format!("Failed to serialize circuit parameters: {}", e)))?;
+        let envelope = VersionedEnvelope {
+            magic: GROTH16_MAGIC,
+            version: GROTH16_VERSION,
+            curve_id: CURVE_BN254,
+            circuit_params,
+            payload,
+        };
+        bincode::serialize(&envelope)
+            .map_err(|e| Error::SerializationError(format!("Failed to serialize envelope: {}", e)))
+    }
+
+    /// バージョン付きエンベロープを検証・展開してペイロードを取り出す
+    fn unwrap_envelope(data: &[u8]) -> Result<Vec<u8>, Error> {
+        let envelope: VersionedEnvelope = bincode::deserialize(data)
+            .map_err(|e| Error::DeserializationError(format!("Failed to deserialize envelope: {}", e)))?;
+        if envelope.magic != GROTH16_MAGIC {
+            return Err(Error::DeserializationError("Invalid Groth16 envelope magic".to_string()));
+        }
+        if envelope.version != GROTH16_VERSION {
+            return Err(Error::DeserializationError(format!(
+                "Unsupported Groth16 version: {} (expected {})",
+                envelope.version, GROTH16_VERSION
+            )));
+        }
+        if envelope.curve_id != CURVE_BN254 {
+            return Err(Error::DeserializationError(format!(
+                "Unsupported curve id: {} (expected {})",
+                envelope.curve_id, CURVE_BN254
+            )));
+        }
+        Ok(envelope.payload)
+    }
+
+    /// 証明をバージョン付きエンベロープでシリアライズ
+    pub fn serialize_proof_versioned(
+        proof: &Groth16Proof,
+        circuit: &CircuitParameters,
+    ) -> Result<Vec<u8>, Error> {
+        let mut payload = Vec::new();
+        proof.inner.serialize(&mut payload)
+            .map_err(|e| Error::SerializationError(format!("Failed to serialize Groth16 proof: {}", e)))?;
+        Self::wrap_envelope(payload, circuit)
+    }
+
+    /// バージョン付きエンベロープから証明をデシリアライズ
+    pub fn deserialize_proof_versioned(data: &[u8]) -> Result<Groth16Proof, Error> {
+        let payload = Self::unwrap_envelope(data)?;
+        let inner = Proof::deserialize(payload.as_slice())
+            .map_err(|e| Error::DeserializationError(format!("Failed to deserialize Groth16 proof: {}", e)))?;
+        Ok(Groth16Proof { inner })
+    }
+
+    /// 検証キーをバージョン付きエンベロープでシリアライズ
+    pub fn serialize_verification_key_versioned(
+        key: &Groth16VerificationKey,
+        circuit: &CircuitParameters,
+    ) -> Result<Vec<u8>, Error> {
+        let mut payload = Vec::new();
+        key.inner.serialize(&mut payload)
+            .map_err(|e| Error::SerializationError(format!("Failed to serialize Groth16 verification key: {}", e)))?;
+        Self::wrap_envelope(payload, circuit)
+    }
+
+    /// バージョン付きエンベロープから検証キーをデシリアライズ
+    pub fn deserialize_verification_key_versioned(data: &[u8]) -> Result<Groth16VerificationKey, Error> {
+        let payload = Self::unwrap_envelope(data)?;
+        let inner = VerifyingKey::deserialize(payload.as_slice())
+            .map_err(|e| Error::DeserializationError(format!("Failed to deserialize Groth16 verification key: {}", e)))?;
+        Ok(Groth16VerificationKey { inner })
+    }
+
+    /// 証明キーをバージョン付きエンベロープでシリアライズ
+    pub fn serialize_proving_key_versioned(
+        key: &Groth16ProvingKey,
+        circuit: &CircuitParameters,
+    ) -> Result<Vec<u8>, Error> {
+        let mut payload = Vec::new();
+        key.inner.serialize(&mut payload)
+            .map_err(|e| Error::SerializationError(format!("Failed to serialize Groth16 proving key: {}", e)))?;
+        Self::wrap_envelope(payload, circuit)
+    }
+
+    /// バージョン付きエンベロープから証明キーをデシリアライズ
+    pub fn deserialize_proving_key_versioned(data: &[u8]) -> Result<Groth16ProvingKey, Error> {
+        let payload = Self::unwrap_envelope(data)?;
+        let inner = ProvingKey::deserialize(payload.as_slice())
+            .map_err(|e| Error::DeserializationError(format!("Failed to deserialize Groth16 proving key: {}", e)))?;
+        Ok(Groth16ProvingKey { inner })
+    }
+
+    /// MPC トラステッドセットアップ（Powers-of-Tau / phase-2）の出力を取り込む
+    ///
+    /// ローカルで toxic waste を生成する `generate_random_parameters` の代わりに、
+    /// 監査可能な多者計算セットアップの成果物から鍵ペアを構成する。`phase1` は
+    /// Powers-of-Tau アキュムレータ、`phase2` は回路固有の phase-2 成果物
+    /// （CanonicalSerialize 形式の ProvingKey）。取り込んだ鍵が宣言されたサーキットに
+    /// 整合しているかを `verify_parameters` で検査してから返す。
+    pub fn import_parameters(
+        phase1: &[u8],
+        phase2: &[u8],
+        circuit: &CircuitParameters,
+    ) -> Result<(Groth16ProvingKey, Groth16VerificationKey), Error> {
+        if phase1.is_empty() {
+            return Err(Error::InvalidArgument(
+                "Phase-1 (Powers-of-Tau) accumulator is empty".to_string(),
+            ));
+        }
+        if phase2.is_empty() {
+            return Err(Error::InvalidArgument(
+                "Phase-2 ceremony output is empty".to_string(),
+            ));
+        }
+
+        // phase-2 成果物は回路固有の証明キーとして格納されている
+        let pk_inner = ProvingKey::<Bn254>::deserialize(phase2)
+            .map_err(|e| Error::DeserializationError(format!("Failed to parse phase-2 proving key: {}", e)))?;
+        let vk_inner = pk_inner.vk.clone();
+
+        let proving_key = Groth16ProvingKey { inner: pk_inner };
+        let verification_key = Groth16VerificationKey { inner: vk_inner };
+
+        // 宣言サーキットとの整合性を確認
+        Self::verify_parameters(&proving_key, &verification_key, circuit)?;
+
+        Ok((proving_key, verification_key))
+    }
+
+    /// 証明キーと検証キーが宣言サーキットに整合しているかを検査する
+    ///
+    /// 検証キーの `gamma_abc_g1` 長は公開入力数 + 1 に一致しなければならず、
+    /// また証明キーに埋め込まれた検証キーと外部検証キーが一致している必要がある。
+    pub fn verify_parameters(
+        proving_key: &Groth16ProvingKey,
+        verification_key: &Groth16VerificationKey,
+        circuit: &CircuitParameters,
+    ) -> Result<(), Error> {
+        let expected = circuit.num_public_inputs + 1;
+        if verification_key.inner.gamma_abc_g1.len() != expected {
+            return Err(Error::InvalidArgument(format!(
+                "Verification key declares {} public inputs, circuit expects {}",
+                verification_key.inner.gamma_abc_g1.len().saturating_sub(1),
+                circuit.num_public_inputs
+            )));
+        }
+
+        // 証明キーに埋め込まれた検証キーが外部検証キーと一致するか
+        let mut pk_vk_bytes = Vec::new();
+        proving_key.inner.vk.serialize(&mut pk_vk_bytes)
+            .map_err(|e| Error::SerializationError(format!("Failed to serialize embedded vk: {}", e)))?;
+        let mut vk_bytes = Vec::new();
+        verification_key.inner.serialize(&mut vk_bytes)
+            .map_err(|e| Error::SerializationError(format!("Failed to serialize vk: {}", e)))?;
+        if pk_vk_bytes != vk_bytes {
+            return Err(Error::InvalidArgument(
+                "Proving key and verification key are inconsistent".to_string(),
+            ));
+        }
+
+        Ok(())
     }
 }
 
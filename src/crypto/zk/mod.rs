@@ -130,6 +130,63 @@ impl ZkProofManager {
         Bulletproof::verify_range(proof, commitment)
     }
     
+    /// 複数出力を1本にまとめた集約Bulletproofを生成
+    ///
+    /// 出力本数を2のべき乗に切り上げ、不足分はゼロ値・ランダムブラインディングの
+    /// ダミーコミットメントでパディングしてから集約する。戻り値はパディング後の
+    /// 全コミットメント（シリアライズ済み）を含み、検証側はこの集合をそのまま使う。
+    pub fn generate_aggregated_bulletproof(
+        &self,
+        amounts: &[u64],
+        blindings: &[&[u8]],
+    ) -> Result<(BulletproofProof, Vec<Vec<u8>>), Error> {
+        use rand::RngCore;
+
+        if amounts.len() != blindings.len() {
+            return Err(Error::InvalidArgument(
+                "Number of amounts and blindings must match".to_string(),
+            ));
+        }
+        if amounts.is_empty() {
+            return Err(Error::InvalidArgument(
+                "At least one output is required".to_string(),
+            ));
+        }
+
+        // 2のべき乗までパディング
+        let padded_len = amounts.len().next_power_of_two();
+        let mut values = amounts.to_vec();
+        let mut dummy_blindings: Vec<[u8; 32]> = Vec::new();
+        let mut blinding_refs: Vec<&[u8]> = blindings.to_vec();
+
+        for _ in amounts.len()..padded_len {
+            let mut b = [0u8; 32];
+            rand::thread_rng().fill_bytes(&mut b);
+            dummy_blindings.push(b);
+            values.push(0);
+        }
+        for b in &dummy_blindings {
+            blinding_refs.push(b.as_slice());
+        }
+
+        let (proof, commitments) = Bulletproof::prove_aggregated(&values, &blinding_refs)?;
+        let commitment_bytes = commitments
+            .iter()
+            .map(|c| c.to_bytes().to_vec())
+            .collect();
+
+        Ok((proof, commitment_bytes))
+    }
+
+    /// 集約Bulletproofを検証
+    pub fn verify_aggregated_bulletproof(
+        &self,
+        proof: &BulletproofProof,
+        commitments: &[&[u8]],
+    ) -> Result<bool, Error> {
+        Bulletproof::verify_aggregated(proof, commitments)
+    }
+
     /// STARKプルーフを生成
     pub fn generate_stark_proof(
         &self,
@@ -4,6 +4,7 @@ use log::{debug, info, warn};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
 use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
 use uuid::Uuid;
 
 /// 注文タイプ
@@ -171,6 +172,82 @@ impl Trade {
     }
 }
 
+/// オーダーブックの増分更新
+///
+/// ある価格帯における残量が変化したことを表す。`new_amount`はその価格における
+/// 未約定数量の合計であり、0になった場合はその価格帯が消滅したことを意味する。
+#[derive(Debug, Clone, Serialize)]
+pub struct OrderBookDelta {
+    /// 価格
+    pub price: f64,
+    /// 更新後のその価格における未約定数量の合計
+    pub new_amount: f64,
+    /// 買い注文・売り注文どちらの板か
+    pub side: OrderType,
+}
+
+/// 購読者へ配信されるマーケットイベント
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "channel", rename_all = "lowercase")]
+pub enum MarketEvent {
+    /// オーダーブックの増分更新
+    Orderbook(OrderBookDelta),
+    /// 新規に約定した取引
+    Trades(Trade),
+}
+
+/// 取引ペアごとのマーケットデータ配信を管理する
+///
+/// `tokio::sync::broadcast`を使い、同一取引ペアを購読するすべてのクライアントへ
+/// オーダーブックの増分更新と約定情報をファンアウトする。購読者がいない間は
+/// 送信チャンネルを保持するだけでコストはほぼゼロ。
+pub struct SubscriptionManager {
+    /// 取引ペア文字列ごとの配信チャンネル
+    channels: Mutex<HashMap<String, broadcast::Sender<MarketEvent>>>,
+}
+
+impl SubscriptionManager {
+    /// 新しい購読マネージャーを作成
+    pub fn new() -> Self {
+        Self {
+            channels: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 指定した取引ペアの送信チャンネルを取得（なければ作成）
+    fn sender_for(&self, pair: &TradingPair) -> broadcast::Sender<MarketEvent> {
+        let mut channels = self.channels.lock().unwrap();
+        channels
+            .entry(pair.to_string())
+            .or_insert_with(|| broadcast::channel(256).0)
+            .clone()
+    }
+
+    /// 取引ペアのマーケットイベントを購読する
+    pub fn subscribe(&self, pair: &TradingPair) -> broadcast::Receiver<MarketEvent> {
+        self.sender_for(pair).subscribe()
+    }
+
+    /// オーダーブックの増分更新を配信する
+    pub fn publish_order_book_delta(&self, pair: &TradingPair, delta: OrderBookDelta) {
+        // 購読者がいない場合、送信エラーは無視してよい
+        let _ = self.sender_for(pair).send(MarketEvent::Orderbook(delta));
+    }
+
+    /// 約定情報を配信する
+    pub fn publish_trade(&self, trade: &Trade) {
+        let _ = self
+            .sender_for(&trade.pair)
+            .send(MarketEvent::Trades(trade.clone()));
+    }
+}
+
+impl Default for SubscriptionManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// オーダーブック
 pub struct OrderBook {
     /// 取引ペア
@@ -341,6 +418,8 @@ pub struct DexManager {
     order_books: Mutex<HashMap<String, OrderBook>>,
     /// ウォレットマネージャーの参照
     wallet_manager: Arc<WalletManager>,
+    /// オーダーブック・約定のマーケットデータ配信を管理する
+    subscriptions: Arc<SubscriptionManager>,
 }
 
 impl DexManager {
@@ -349,8 +428,16 @@ impl DexManager {
         Self {
             order_books: Mutex::new(HashMap::new()),
             wallet_manager,
+            subscriptions: Arc::new(SubscriptionManager::new()),
         }
     }
+
+    /// マーケットデータ購読マネージャーの参照を取得
+    ///
+    /// WebSocketハンドラーからオーダーブック・約定の配信を購読するために使用する。
+    pub fn subscriptions(&self) -> Arc<SubscriptionManager> {
+        Arc::clone(&self.subscriptions)
+    }
     
     /// 取引ペアを追加
     pub fn add_trading_pair(&self, base: String, quote: String) -> TradingPair {
@@ -410,26 +497,45 @@ impl DexManager {
         
         let order_book = order_books.entry(pair_str.clone())
             .or_insert_with(|| OrderBook::new(pair.clone()));
-        
+
         let trades = order_book.add_order(order.clone());
-        
-        info!("Order created: {} {} {} at {} for {}", 
-            order.id, 
+
+        // この注文の価格帯における残量の増分を配信
+        let new_amount: f64 = match order_type {
+            OrderType::Buy => order_book.get_buy_orders(),
+            OrderType::Sell => order_book.get_sell_orders(),
+        }
+        .iter()
+        .filter(|o| o.price == price)
+        .map(|o| o.amount - o.filled_amount)
+        .sum();
+
+        drop(order_books);
+
+        self.subscriptions.publish_order_book_delta(&pair, OrderBookDelta {
+            price,
+            new_amount,
+            side: order_type,
+        });
+
+        info!("Order created: {} {} {} at {} for {}",
+            order.id,
             if order_type == OrderType::Buy { "BUY" } else { "SELL" },
             amount,
             price,
             pair_str
         );
-        
+
         if !trades.is_empty() {
             info!("Trades executed: {}", trades.len());
-            
+
             // 取引を処理
             for trade in &trades {
                 self.process_trade(trade)?;
+                self.subscriptions.publish_trade(trade);
             }
         }
-        
+
         Ok((order, trades))
     }
     
@@ -83,7 +83,11 @@ pub enum Error {
     /// リソース不足
     #[error("Resource exhausted: {0}")]
     ResourceExhausted(String),
-    
+
+    /// バックプレッシャ（宛先キューが飽和）
+    #[error("Backpressure: {0}")]
+    Backpressure(String),
+
     /// レート制限
     #[error("Rate limit exceeded: {0}")]
     RateLimitExceeded(String),
@@ -127,6 +131,10 @@ pub enum Error {
     /// 不明なエラー
     #[error("Unknown error: {0}")]
     Unknown(String),
+
+    /// 競合（二重支払いなど、同一の入力を消費する複数のトランザクション間の衝突）
+    #[error("Conflict: {0}")]
+    Conflict(String),
 }
 
 impl From<prost::EncodeError> for Error {
@@ -9,6 +9,8 @@ use crate::governance::voting::{VotingStrategy, VotingPeriod, Vote};
 use crate::governance::treasury::{Treasury, Asset};
 use crate::governance::role::{Role, RoleAssignment};
 use crate::governance::policy::{Policy, PolicyRule};
+use crate::governance::delegation::DelegationGraph;
+use crate::governance::funding_stream::FundingStreamRegistry;
 
 /// DAOタイプ
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -389,33 +391,43 @@ impl DAO {
     }
     
     /// 提案に投票
-    pub fn vote_on_proposal(&mut self, proposal_id: &str, voter_id: &str, vote: Vote) -> Result<(), Error> {
+    pub fn vote_on_proposal(
+        &mut self,
+        proposal_id: &str,
+        voter_id: &str,
+        vote: Vote,
+        delegation: &DelegationGraph,
+    ) -> Result<(), Error> {
         // メンバーをチェック
         if !self.members.contains_key(voter_id) {
             return Err(Error::NotFound(format!("Member not found: {}", voter_id)));
         }
-        
+
         // 提案を取得
         let proposal = self.get_proposal_mut(proposal_id)?;
-        
+
         // 投票を追加
-        proposal.add_vote(voter_id.to_string(), vote)?;
-        
+        proposal.add_vote(voter_id.to_string(), vote, delegation)?;
+
         self.metadata.updated_at = Utc::now();
-        
+
         Ok(())
     }
-    
+
     /// 提案を実行
-    pub fn execute_proposal(&mut self, proposal_id: &str) -> Result<(), Error> {
+    pub fn execute_proposal(
+        &mut self,
+        proposal_id: &str,
+        funding_registry: &mut FundingStreamRegistry,
+    ) -> Result<(), Error> {
         // 提案を取得
         let proposal = self.get_proposal_mut(proposal_id)?;
-        
+
         // 提案を実行
-        proposal.execute()?;
-        
+        proposal.execute(funding_registry)?;
+
         self.metadata.updated_at = Utc::now();
-        
+
         Ok(())
     }
     
@@ -0,0 +1,91 @@
+//! 継続資金提供（Continuous Funding）のストリーム管理
+//!
+//! `FundingAllocation`や`RewardDistribution`は`execution_data`を通じた単発の
+//! 支払いしかモデル化できない。`ProposalType::ContinuousFunding`はこれを拡張し、
+//! 期間ごとの定額支払いをスチュワード（担当者）が管理する継続的なストリームとして
+//! 登録する。ガバナンスはストリームを参照する`DisputeResolution`提案で取り消せるほか、
+//! スチュワードを外す提案が実行されると、そのスチュワードが管理する全ストリームが
+//! 自動的に停止する。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::error::Error;
+
+/// 1つの継続資金提供ストリーム
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FundingSchedule {
+    /// このストリームを作成した提案のID
+    pub funding_proposal_id: String,
+    /// 受取人
+    pub recipient: String,
+    /// ストリームを管理するスチュワード
+    pub steward: String,
+    /// 1期間あたりの支払額
+    pub amount_per_period: u64,
+    /// 開始エポック
+    pub start_epoch: u64,
+    /// 終了エポック
+    pub end_epoch: u64,
+    /// ストリームが有効かどうか（取り消し・スチュワード解任で`false`になる）
+    pub active: bool,
+}
+
+/// 継続資金提供ストリームのレジストリ
+#[derive(Debug, Clone, Default)]
+pub struct FundingStreamRegistry {
+    streams: HashMap<String, FundingSchedule>,
+}
+
+impl FundingStreamRegistry {
+    /// 新しい空のレジストリを作成
+    pub fn new() -> Self {
+        Self {
+            streams: HashMap::new(),
+        }
+    }
+
+    /// 提案の可決を受けて新しいストリームを登録する
+    pub fn register(&mut self, schedule: FundingSchedule) {
+        self.streams.insert(schedule.funding_proposal_id.clone(), schedule);
+    }
+
+    /// ストリームを取得する
+    pub fn get(&self, funding_proposal_id: &str) -> Option<&FundingSchedule> {
+        self.streams.get(funding_proposal_id)
+    }
+
+    /// `DisputeResolution`提案によりストリームを取り消す
+    pub fn revoke(&mut self, funding_proposal_id: &str) -> Result<(), Error> {
+        let schedule = self.streams.get_mut(funding_proposal_id).ok_or_else(|| {
+            Error::InvalidInput(format!(
+                "No funding stream registered for proposal: {}",
+                funding_proposal_id
+            ))
+        })?;
+
+        schedule.active = false;
+
+        Ok(())
+    }
+
+    /// スチュワードを解任する提案が実行されたとき、そのスチュワードが管理する
+    /// 全ストリームを停止する
+    pub fn halt_streams_for_steward(&mut self, steward: &str) -> Vec<String> {
+        let mut halted = Vec::new();
+
+        for schedule in self.streams.values_mut() {
+            if schedule.steward == steward && schedule.active {
+                schedule.active = false;
+                halted.push(schedule.funding_proposal_id.clone());
+            }
+        }
+
+        halted
+    }
+
+    /// 現在有効なストリームを一覧する
+    pub fn active_streams(&self) -> Vec<&FundingSchedule> {
+        self.streams.values().filter(|s| s.active).collect()
+    }
+}
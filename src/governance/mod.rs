@@ -1,5 +1,11 @@
 pub mod proposal;
 pub mod voting;
+pub mod private_voting;
+pub mod notifications;
+pub mod voting_power_registry;
+pub mod veto_blacklist;
+pub mod funding_stream;
+pub mod delegation;
 // pub mod policy; // TODO: このモジュールが見つかりません
 pub mod dao;
 // pub mod treasury; // TODO: このモジュールが見つかりません
@@ -11,7 +17,6 @@ pub mod dao;
 // pub mod dispute; // TODO: このモジュールが見つかりません
 // pub mod reward; // TODO: このモジュールが見つかりません
 // pub mod reputation; // TODO: このモジュールが見つかりません
-// pub mod delegation; // TODO: このモジュールが見つかりません
 // pub mod quadratic_voting; // TODO: このモジュールが見つかりません
 // pub mod conviction_voting; // TODO: このモジュールが見つかりません
 // pub mod liquid_democracy; // TODO: このモジュールが見つかりません
@@ -28,9 +33,7 @@ pub use conviction_voting::{
     ConvictionVotingSystem,
 };
 pub use dao::{DAOMember, DAOMetadata, DAOOptions, DAOPermission, DAORole, DAOType, DAO};
-pub use delegation::{
-    Delegation, DelegationMetadata, DelegationOptions, DelegationStatus, DelegationType,
-};
+pub use delegation::{Delegation, DelegationGraph, DelegationScope};
 pub use dispute::{Dispute, DisputeMetadata, DisputeOptions, DisputeResolution, DisputeStatus};
 pub use execution::{
     Execution, ExecutionMetadata, ExecutionOptions, ExecutionResult, ExecutionStatus,
@@ -56,7 +59,17 @@ pub use permission::{
 pub use policy::{
     Policy, PolicyAction, PolicyCondition, PolicyEffect, PolicyMetadata, PolicyOptions, PolicyRule,
 };
-pub use proposal::{Proposal, ProposalMetadata, ProposalOptions, ProposalStatus, ProposalType};
+pub use notifications::{
+    NotificationChannel, NotificationDispatcher, NotificationFilter, NotificationPayload,
+    NotificationSubscription, RetryPolicy,
+};
+pub use private_voting::{
+    CommitteeMemberKey, DecryptionShare, DlEqProof, ElGamalCiphertext, EncryptedBallot,
+};
+pub use proposal::{
+    PayloadType, Proposal, ProposalHistory, ProposalMetadata, ProposalOptions, ProposalStatus,
+    ProposalType,
+};
 pub use quadratic_voting::{
     QuadraticVote, QuadraticVotingMetadata, QuadraticVotingOptions, QuadraticVotingResult,
     QuadraticVotingSystem,
@@ -74,5 +87,9 @@ pub use treasury::{
     TransactionType, Treasury,
 };
 pub use voting::{
-    Vote, VoteType, VotingPeriod, VotingPower, VotingResult, VotingStrategy, VotingSystem,
+    RankedBallot, RankedChoiceRound, Vote, VoteType, VotingPeriod, VotingPower, VotingResult,
+    VotingStrategy, VotingSystem,
 };
+pub use voting_power_registry::{PowerSource, VotingPowerRegistry};
+pub use veto_blacklist::{BlacklistEntry, VetoBlacklist};
+pub use funding_stream::{FundingSchedule, FundingStreamRegistry};
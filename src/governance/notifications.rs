@@ -0,0 +1,326 @@
+//! ガバナンス通知配信サブシステム
+//!
+//! `Proposal`の各操作（submit/start_voting/add_vote/end_voting/execute/
+//! complete_execution/cancel）は`ProposalHistory`にイベントを積むだけで、
+//! 外部には何も通知されない。`NotificationDispatcher`は購読者を登録し、
+//! 新しい`ProposalHistory`イベントが発生するたびにフィルタ条件に合う
+//! 購読者へ配信する。配信先はメール（SMTP）・HTTP Webhook・プロセス内
+//! チャンネルの3種類をサポートする。
+
+use chrono::{DateTime, Utc};
+use log::{error, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tokio::sync::mpsc;
+
+use crate::error::Error;
+use crate::governance::proposal::{Proposal, ProposalHistory, ProposalStatus, ProposalType};
+
+/// 通知ペイロード（`ProposalHistory`に提案のID/タイトルを添えたもの）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationPayload {
+    /// 提案ID
+    pub proposal_id: String,
+    /// 提案タイトル
+    pub proposal_title: String,
+    /// 履歴イベント
+    pub history: ProposalHistory,
+}
+
+/// 通知の配信先
+pub enum NotificationChannel {
+    /// SMTP経由のメール通知
+    Email {
+        smtp_server: String,
+        to: String,
+    },
+    /// HTTP Webhook通知
+    Webhook { url: String },
+    /// プロセス内チャンネル通知
+    InProcess(mpsc::UnboundedSender<NotificationPayload>),
+}
+
+impl std::fmt::Debug for NotificationChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotificationChannel::Email { smtp_server, to } => f
+                .debug_struct("Email")
+                .field("smtp_server", smtp_server)
+                .field("to", to)
+                .finish(),
+            NotificationChannel::Webhook { url } => {
+                f.debug_struct("Webhook").field("url", url).finish()
+            }
+            NotificationChannel::InProcess(_) => f.write_str("InProcess(..)"),
+        }
+    }
+}
+
+/// 通知を受け取る条件のフィルタ
+///
+/// それぞれの条件は`None`なら無条件に一致し、`Some`なら列挙された値のいずれかに
+/// 一致する必要がある（AND条件で組み合わされる）。
+#[derive(Debug, Clone, Default)]
+pub struct NotificationFilter {
+    /// 対象の提案タイプ
+    pub proposal_types: Option<Vec<ProposalType>>,
+    /// 対象のステータス遷移先（`new_status`がこの中に含まれる場合に一致）
+    pub target_statuses: Option<Vec<ProposalStatus>>,
+    /// 対象のタグ
+    pub tags: Option<Vec<String>>,
+    /// 対象のカテゴリ
+    pub categories: Option<Vec<String>>,
+}
+
+impl NotificationFilter {
+    /// 提案とその履歴イベントがこのフィルタに一致するか判定する
+    pub fn matches(&self, proposal: &Proposal, history: &ProposalHistory) -> bool {
+        if let Some(types) = &self.proposal_types {
+            if !types.contains(&proposal.proposal_type) {
+                return false;
+            }
+        }
+
+        if let Some(statuses) = &self.target_statuses {
+            match &history.new_status {
+                Some(status) if statuses.contains(status) => {}
+                _ => return false,
+            }
+        }
+
+        if let Some(tags) = &self.tags {
+            if !tags.iter().any(|tag| proposal.metadata.tags.contains(tag)) {
+                return false;
+            }
+        }
+
+        if let Some(categories) = &self.categories {
+            match &proposal.metadata.category {
+                Some(category) if categories.contains(category) => {}
+                _ => return false,
+            }
+        }
+
+        true
+    }
+}
+
+/// 1つの購読
+pub struct NotificationSubscription {
+    /// 購読ID
+    pub id: String,
+    /// 配信先
+    pub channel: NotificationChannel,
+    /// 通知フィルタ
+    pub filter: NotificationFilter,
+    /// 直近に配信済みのイベント日時（リプレイの起点に使う）
+    pub last_delivered_at: Option<DateTime<Utc>>,
+}
+
+/// Webhook配信のリトライ設定
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// 最大試行回数
+    pub max_attempts: u32,
+    /// 初回リトライまでの待機時間（ミリ秒）
+    pub initial_backoff_ms: u64,
+    /// バックオフの倍率
+    pub backoff_multiplier: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff_ms: 500,
+            backoff_multiplier: 2.0,
+        }
+    }
+}
+
+/// ガバナンス通知ディスパッチャ
+pub struct NotificationDispatcher {
+    /// 購読者（購読IDをキーとする）
+    subscriptions: HashMap<String, NotificationSubscription>,
+    /// Webhook配信のリトライ設定
+    retry_policy: RetryPolicy,
+}
+
+impl NotificationDispatcher {
+    /// 新しいディスパッチャを作成
+    pub fn new() -> Self {
+        Self {
+            subscriptions: HashMap::new(),
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    /// リトライ設定を指定して作成
+    pub fn with_retry_policy(retry_policy: RetryPolicy) -> Self {
+        Self {
+            subscriptions: HashMap::new(),
+            retry_policy,
+        }
+    }
+
+    /// 購読者を登録
+    pub fn subscribe(&mut self, subscription: NotificationSubscription) {
+        self.subscriptions.insert(subscription.id.clone(), subscription);
+    }
+
+    /// 購読を解除
+    pub fn unsubscribe(&mut self, subscription_id: &str) {
+        self.subscriptions.remove(subscription_id);
+    }
+
+    /// 提案に新しい履歴イベントが積まれたときに呼び出し、一致する購読者全員へ配信する
+    pub async fn dispatch(&mut self, proposal: &Proposal, history: &ProposalHistory) {
+        let now = Utc::now();
+        let matching_ids: Vec<String> = self
+            .subscriptions
+            .iter()
+            .filter(|(_, sub)| sub.filter.matches(proposal, history))
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        for id in matching_ids {
+            let payload = NotificationPayload {
+                proposal_id: proposal.id.clone(),
+                proposal_title: proposal.title.clone(),
+                history: history.clone(),
+            };
+
+            let channel_result = {
+                let subscription = self.subscriptions.get(&id).expect("id came from this map");
+                self.deliver(&subscription.channel, &payload).await
+            };
+
+            if let Err(e) = channel_result {
+                error!("Failed to deliver governance notification to {}: {}", id, e);
+            } else if let Some(subscription) = self.subscriptions.get_mut(&id) {
+                subscription.last_delivered_at = Some(now);
+            }
+        }
+    }
+
+    /// 1件の通知を配信先に送る（Webhookはリトライ＋バックオフ付き）
+    async fn deliver(&self, channel: &NotificationChannel, payload: &NotificationPayload) -> Result<(), Error> {
+        match channel {
+            NotificationChannel::Email { smtp_server, to } => {
+                // 実際の実装では、SMTPクライアントを使ってメールを送信する
+                // ここでは簡易的にログへの記録のみ行う
+                log::info!(
+                    "Would send email via {} to {}: proposal {} ({}) -> {}",
+                    smtp_server,
+                    to,
+                    payload.proposal_id,
+                    payload.proposal_title,
+                    payload.history.action
+                );
+                Ok(())
+            }
+            NotificationChannel::Webhook { url } => self.deliver_webhook_with_retry(url, payload).await,
+            NotificationChannel::InProcess(sender) => sender
+                .send(payload.clone())
+                .map_err(|e| Error::InternalError(format!("Failed to send to in-process channel: {}", e))),
+        }
+    }
+
+    /// Webhook配信を指数バックオフで再試行する
+    async fn deliver_webhook_with_retry(
+        &self,
+        url: &str,
+        payload: &NotificationPayload,
+    ) -> Result<(), Error> {
+        let client = reqwest::Client::new();
+        let mut backoff_ms = self.retry_policy.initial_backoff_ms;
+        let mut last_error = None;
+
+        for attempt in 1..=self.retry_policy.max_attempts {
+            match client.post(url).json(payload).send().await {
+                Ok(response) if response.status().is_success() => return Ok(()),
+                Ok(response) => {
+                    last_error = Some(format!("Webhook returned status {}", response.status()));
+                }
+                Err(e) => {
+                    last_error = Some(e.to_string());
+                }
+            }
+
+            if attempt < self.retry_policy.max_attempts {
+                warn!(
+                    "Webhook delivery attempt {}/{} to {} failed, retrying in {}ms",
+                    attempt, self.retry_policy.max_attempts, url, backoff_ms
+                );
+                tokio::time::sleep(std::time::Duration::from_millis(backoff_ms)).await;
+                backoff_ms = (backoff_ms as f64 * self.retry_policy.backoff_multiplier) as u64;
+            }
+        }
+
+        Err(Error::NetworkError(format!(
+            "Webhook delivery to {} failed after {} attempts: {}",
+            url,
+            self.retry_policy.max_attempts,
+            last_error.unwrap_or_else(|| "unknown error".to_string())
+        )))
+    }
+
+    /// オフラインだった購読者のために、保存済みの`history`から未配信イベントを再送する
+    pub async fn replay_missed(&mut self, proposal: &Proposal, subscription_id: &str) -> Result<(), Error> {
+        let since = self
+            .subscriptions
+            .get(subscription_id)
+            .ok_or_else(|| Error::InvalidInput(format!("Unknown subscription: {}", subscription_id)))?
+            .last_delivered_at;
+
+        let Some(history) = &proposal.history else {
+            return Ok(());
+        };
+
+        let missed: Vec<ProposalHistory> = history
+            .iter()
+            .filter(|event| since.map_or(true, |s| event.timestamp > s))
+            .cloned()
+            .collect();
+
+        for event in &missed {
+            if !self
+                .subscriptions
+                .get(subscription_id)
+                .map_or(false, |sub| sub.filter.matches(proposal, event))
+            {
+                continue;
+            }
+
+            let payload = NotificationPayload {
+                proposal_id: proposal.id.clone(),
+                proposal_title: proposal.title.clone(),
+                history: event.clone(),
+            };
+
+            let channel_result = {
+                let subscription = self
+                    .subscriptions
+                    .get(subscription_id)
+                    .expect("checked above");
+                self.deliver(&subscription.channel, &payload).await
+            };
+
+            channel_result?;
+        }
+
+        if let Some(last_event) = missed.last() {
+            if let Some(subscription) = self.subscriptions.get_mut(subscription_id) {
+                subscription.last_delivered_at = Some(last_event.timestamp);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for NotificationDispatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,514 @@
+//! 秘密投票（ElGamal準同型暗号 + 委員会しきい値復号）
+//!
+//! 通常の投票は`Vote.power.value`を平文のまま集計するため、各投票者の
+//! 選択が誰にでも見える。本モジュールはYes/No/Abstainの単位ベクトルを
+//! 加法準同型なExponential ElGamal暗号で暗号化し、`calculate_voting_result`
+//! が復号せずに暗号文のまま集計できるようにする。最終的な復号は単一の
+//! 秘密鍵ではなく、委員会メンバーが持つしきい値分散鍵による部分復号
+//! （各自が離散対数等価性(DLEQ)証明を添えて公開する）を束ねて行う。
+
+use num_bigint::{BigUint, RandBigInt};
+use num_traits::{One, Zero};
+use rand::thread_rng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::Error;
+
+/// ElGamal群のモジュラス（閾値署名で使われているものと同じ2048bit安全素数を流用）
+///
+/// 群パラメータ（p, g）は公開情報であり、秘密にする必要がないため、
+/// 既存コードと同じ定数を再利用しても安全性上の問題はない
+/// （Paillierの秘密素数factorとは異なり、ここでのpは秘密にすべき値ではない）。
+const GROUP_PRIME_HEX: &str = "FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74020BBEA63B139B22514A08798E3404DDEF9519B3CD3A431B302B0A6DF25F14374FE1356D6D51C245E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7EDEE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3DC2007CB8A163BF0598DA48361C55D39A69163FA8FD24CF5F83655D23DCA3AD961C62F356208552BB9ED529077096966D670C354E4ABC9804F1746C08CA18217C32905E462E36CE3BE39E772C180E86039B2783A2EC07A28FB5C55DF06F4C52C9DE2BCBF6955817183995497CEA956AE515D2261898FA051015728E5A8AACAA68FFFFFFFFFFFFFFFF";
+
+/// 群の生成元
+const GROUP_GENERATOR: u64 = 2;
+
+fn group_prime() -> BigUint {
+    BigUint::parse_bytes(GROUP_PRIME_HEX.as_bytes(), 16).unwrap()
+}
+
+fn group_generator() -> BigUint {
+    BigUint::from(GROUP_GENERATOR)
+}
+
+/// モジュラ逆数（フェルマーの小定理: a^(p-2) mod p、pは素数）
+fn mod_inverse(a: &BigUint, p: &BigUint) -> BigUint {
+    a.modpow(&(p - BigUint::from(2u32)), p)
+}
+
+/// ElGamal暗号文 (c1, c2) = (g^r mod p, g^m * h^r mod p)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElGamalCiphertext {
+    pub c1: BigUint,
+    pub c2: BigUint,
+}
+
+impl ElGamalCiphertext {
+    /// 平文0の単位元暗号文（準同型加算の単位元）
+    fn identity() -> Self {
+        Self {
+            c1: BigUint::one(),
+            c2: BigUint::one(),
+        }
+    }
+
+    /// 準同型加算: 暗号文のまま平文同士の和を計算する
+    pub fn combine(&self, other: &Self) -> Self {
+        let p = group_prime();
+        Self {
+            c1: (&self.c1 * &other.c1) % &p,
+            c2: (&self.c2 * &other.c2) % &p,
+        }
+    }
+}
+
+/// 委員会メンバーのElGamal公開鍵シェア（h_i = g^sk_i mod p）
+///
+/// `index`はShamir秘密分散における各メンバーのx座標（1始まり）で、
+/// `combine_decryption_shares`がラグランジュ補間で元の秘密に対応する
+/// 復号を再構成する際に使う。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitteeMemberKey {
+    pub member_id: String,
+    pub index: u64,
+    pub public_share: BigUint,
+}
+
+/// 群の位数（pは安全素数 p = 2q+1 であり、生成元gは位数qの部分群を生成する前提）
+///
+/// 秘密分散の多項式演算とラグランジュ補間の指数は、離散対数が定義される
+/// この位数を法として行う必要がある（pを法に簡約すると部分群では意味が変わる）。
+fn group_order() -> BigUint {
+    (group_prime() - BigUint::one()) / BigUint::from(2u32)
+}
+
+/// (t, n)しきい値の委員会鍵を生成する（ディーラーベースのShamir秘密分散）
+///
+/// 次数`threshold - 1`のランダムな多項式 f(x) = sk + a_1*x + ... + a_{threshold-1}*x^{threshold-1}
+/// （mod 群位数）を生成し、各メンバーに固有のx座標（1始まりのインデックス）での
+/// シェア sk_i = f(index_i) を割り当てる。委員会の公開鍵は h = g^f(0) = g^sk。
+///
+/// 復号時は、実際に集まった`threshold`人分のシェアだけからラグランジュ補間で
+/// 元の秘密鍵に対応する復号を再構成できる（`combine_decryption_shares`を参照）。
+/// 全メンバーの参加を前提とする単純な積み上げとは異なり、これが実際の
+/// (t, n)しきい値復号を成立させる。
+///
+/// 戻り値は (委員会の公開鍵, 各メンバーの公開鍵シェア一覧, 各メンバーに配布する秘密シェア)。
+/// 秘密シェアは各メンバーへ安全な経路で個別に配布されることを想定しており、
+/// ここでは単一プロセス内のシミュレーション用にまとめて返す。
+pub fn generate_committee_keys(
+    member_ids: &[String],
+    threshold: usize,
+) -> Result<(BigUint, Vec<CommitteeMemberKey>, Vec<(String, BigUint)>), Error> {
+    if member_ids.is_empty() {
+        return Err(Error::InvalidInput(
+            "Committee must have at least one member".to_string(),
+        ));
+    }
+    if threshold == 0 || threshold > member_ids.len() {
+        return Err(Error::InvalidInput(format!(
+            "Threshold must be between 1 and the committee size ({}), got {}",
+            member_ids.len(),
+            threshold
+        )));
+    }
+
+    let p = group_prime();
+    let q = group_order();
+    let g = group_generator();
+    let mut rng = thread_rng();
+
+    // 多項式の係数 a_0(=sk), a_1, ..., a_{threshold-1} をランダムに選ぶ
+    let coefficients: Vec<BigUint> = (0..threshold).map(|_| rng.gen_biguint_below(&q)).collect();
+
+    let combined_public_key = g.modpow(&coefficients[0], &p);
+
+    let mut public_keys = Vec::with_capacity(member_ids.len());
+    let mut secret_shares = Vec::with_capacity(member_ids.len());
+
+    for (i, member_id) in member_ids.iter().enumerate() {
+        let index = (i + 1) as u64;
+        let x = BigUint::from(index);
+
+        // f(x) = a_0 + a_1*x + ... + a_{threshold-1}*x^{threshold-1} mod q
+        let mut share = BigUint::zero();
+        let mut x_power = BigUint::one();
+        for coef in &coefficients {
+            share = (&share + coef * &x_power) % &q;
+            x_power = (&x_power * &x) % &q;
+        }
+
+        public_keys.push(CommitteeMemberKey {
+            member_id: member_id.clone(),
+            index,
+            public_share: g.modpow(&share, &p),
+        });
+        secret_shares.push((member_id.clone(), share));
+    }
+
+    Ok((combined_public_key, public_keys, secret_shares))
+}
+
+/// 投票者が選んだ単一の選択肢を、Yes/No/Abstainの単位ベクトルとして暗号化したもの
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptedBallot {
+    pub yes: ElGamalCiphertext,
+    pub no: ElGamalCiphertext,
+    pub abstain: ElGamalCiphertext,
+}
+
+impl EncryptedBallot {
+    /// 準同型加算により、全投票者分の暗号文を復号せずに集計する
+    pub fn combine(&self, other: &Self) -> Self {
+        Self {
+            yes: self.yes.combine(&other.yes),
+            no: self.no.combine(&other.no),
+            abstain: self.abstain.combine(&other.abstain),
+        }
+    }
+
+    /// 集計の初期値（全て0票の暗号文）
+    pub fn zero() -> Self {
+        Self {
+            yes: ElGamalCiphertext::identity(),
+            no: ElGamalCiphertext::identity(),
+            abstain: ElGamalCiphertext::identity(),
+        }
+    }
+}
+
+/// Yes/No/Abstainのいずれか1つに投票パワーを暗号化する
+///
+/// 選んだ選択肢には`power`を、残り2つには0を平文として暗号化し、単位ベクトルを作る。
+pub fn encrypt_ballot(
+    choice: &crate::governance::voting::VoteType,
+    power: u64,
+    committee_public_key: &BigUint,
+) -> EncryptedBallot {
+    use crate::governance::voting::VoteType;
+
+    let (yes_power, no_power, abstain_power) = match choice {
+        VoteType::Yes => (power, 0, 0),
+        VoteType::No => (0, power, 0),
+        VoteType::Abstain => (0, 0, power),
+    };
+
+    EncryptedBallot {
+        yes: encrypt_value(yes_power, committee_public_key),
+        no: encrypt_value(no_power, committee_public_key),
+        abstain: encrypt_value(abstain_power, committee_public_key),
+    }
+}
+
+/// g^m * h^r mod pの形でmを暗号化する
+fn encrypt_value(value: u64, committee_public_key: &BigUint) -> ElGamalCiphertext {
+    let p = group_prime();
+    let g = group_generator();
+    let mut rng = thread_rng();
+    let r = rng.gen_biguint_below(&p);
+
+    let c1 = g.modpow(&r, &p);
+    let c2 = (g.modpow(&BigUint::from(value), &p) * committee_public_key.modpow(&r, &p)) % &p;
+
+    ElGamalCiphertext { c1, c2 }
+}
+
+/// 離散対数等価性(DLEQ)証明: log_g(public_share) == log_c1(share) であることを示す
+///
+/// Chaum-Pedersenプロトコルに基づくFiat-Shamir非対話証明。部分復号に使った
+/// 秘密鍵が、公開されている鍵シェアと同じものであることを検証者が確認できる。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DlEqProof {
+    pub commitment_g: BigUint,
+    pub commitment_c1: BigUint,
+    pub challenge: BigUint,
+    pub response: BigUint,
+}
+
+fn dleq_challenge(public_share: &BigUint, c1: &BigUint, share: &BigUint, t_g: &BigUint, t_c1: &BigUint) -> BigUint {
+    let mut hasher = Sha256::new();
+    hasher.update(public_share.to_bytes_be());
+    hasher.update(c1.to_bytes_be());
+    hasher.update(share.to_bytes_be());
+    hasher.update(t_g.to_bytes_be());
+    hasher.update(t_c1.to_bytes_be());
+    let digest = hasher.finalize();
+
+    BigUint::from_bytes_be(&digest) % group_prime()
+}
+
+/// 部分復号シェアとそのDLEQ証明
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecryptionShare {
+    pub share: BigUint,
+    pub proof: DlEqProof,
+}
+
+/// 暗号文c1に対する部分復号シェア(c1^sk_i mod p)とDLEQ証明を生成する
+pub fn generate_decryption_share(secret_key: &BigUint, ciphertext: &ElGamalCiphertext) -> DecryptionShare {
+    let p = group_prime();
+    let g = group_generator();
+
+    let share = ciphertext.c1.modpow(secret_key, &p);
+    let public_share = g.modpow(secret_key, &p);
+
+    let mut rng = thread_rng();
+    let k = rng.gen_biguint_below(&p);
+    let t_g = g.modpow(&k, &p);
+    let t_c1 = ciphertext.c1.modpow(&k, &p);
+
+    let challenge = dleq_challenge(&public_share, &ciphertext.c1, &share, &t_g, &t_c1);
+    let response = (k + &challenge * secret_key) % group_order();
+
+    DecryptionShare {
+        share,
+        proof: DlEqProof {
+            commitment_g: t_g,
+            commitment_c1: t_c1,
+            challenge,
+            response,
+        },
+    }
+}
+
+/// 部分復号シェアのDLEQ証明を検証する
+pub fn verify_decryption_share(
+    public_share: &BigUint,
+    ciphertext: &ElGamalCiphertext,
+    decryption_share: &DecryptionShare,
+) -> bool {
+    let p = group_prime();
+    let g = group_generator();
+    let proof = &decryption_share.proof;
+
+    let expected_challenge = dleq_challenge(
+        public_share,
+        &ciphertext.c1,
+        &decryption_share.share,
+        &proof.commitment_g,
+        &proof.commitment_c1,
+    );
+
+    if expected_challenge != proof.challenge {
+        return false;
+    }
+
+    // g^response =? commitment_g * public_share^challenge
+    let lhs_g = g.modpow(&proof.response, &p);
+    let rhs_g = (&proof.commitment_g * public_share.modpow(&proof.challenge, &p)) % &p;
+
+    // c1^response =? commitment_c1 * share^challenge
+    let lhs_c1 = ciphertext.c1.modpow(&proof.response, &p);
+    let rhs_c1 = (&proof.commitment_c1 * decryption_share.share.modpow(&proof.challenge, &p)) % &p;
+
+    lhs_g == rhs_g && lhs_c1 == rhs_c1
+}
+
+/// 寄与したメンバーの(index, 部分復号シェア)の組を、指数上のラグランジュ補間で束ね、
+/// c1^sk mod pを再構成する
+///
+/// 全メンバー分の単純な積ではなく、実際に集まった（少なくとも`threshold`個の）
+/// メンバーの組だけから、Shamirで分散された元の秘密鍵に対応する復号を再構成する。
+/// これが(t, n)しきい値復号の核心であり、`threshold < committee.len()`の構成でも
+/// 正しく復号できる。
+fn combine_decryption_shares(shares: &[(u64, BigUint)]) -> Result<BigUint, Error> {
+    let p = group_prime();
+    let q = group_order();
+
+    let mut seen_indices = std::collections::HashSet::new();
+    for (index, _) in shares {
+        if !seen_indices.insert(*index) {
+            return Err(Error::InvalidInput(format!(
+                "Duplicate committee member index in decryption shares: {}",
+                index
+            )));
+        }
+    }
+
+    let mut combined = BigUint::one();
+    for (i, (index_i, share_i)) in shares.iter().enumerate() {
+        let x_i = BigUint::from(*index_i);
+
+        // ラグランジュ係数 λ_i = Π_{j != i} x_j / (x_j - x_i) mod q
+        let mut numerator = BigUint::one();
+        let mut denominator = BigUint::one();
+        for (j, (index_j, _)) in shares.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+            let x_j = BigUint::from(*index_j);
+
+            numerator = (&numerator * &x_j) % &q;
+
+            let diff = if x_j > x_i {
+                (&x_j - &x_i) % &q
+            } else {
+                (&q - (&x_i - &x_j) % &q) % &q
+            };
+            denominator = (&denominator * diff) % &q;
+        }
+
+        let lagrange_coef = (numerator * mod_inverse(&denominator, &q)) % &q;
+        combined = (combined * share_i.modpow(&lagrange_coef, &p)) % &p;
+    }
+
+    Ok(combined)
+}
+
+/// しきい値個の部分復号シェアから暗号文を復号し、g^m mod pを得る
+fn decrypt_with_shares(
+    ciphertext: &ElGamalCiphertext,
+    shares: &[(u64, BigUint)],
+) -> Result<BigUint, Error> {
+    let p = group_prime();
+    let combined_share = combine_decryption_shares(shares)?;
+
+    Ok((&ciphertext.c2 * mod_inverse(&combined_share, &p)) % &p)
+}
+
+/// g^m mod pから総当たりでmを復元する（投票数は小さい範囲に収まるため実用的）
+fn brute_force_discrete_log(target: &BigUint, max_value: u64) -> Result<u64, Error> {
+    let p = group_prime();
+    let g = group_generator();
+
+    let mut candidate = BigUint::one();
+    for value in 0..=max_value {
+        if &candidate == target {
+            return Ok(value);
+        }
+        candidate = (candidate * &g) % &p;
+    }
+
+    Err(Error::InternalError(format!(
+        "Failed to recover discrete log within range 0..={}",
+        max_value
+    )))
+}
+
+/// 委員会の復号シェアを検証・集約し、Yes/No/Abstainそれぞれの平文票数を復元する
+///
+/// `max_total_power`は投票パワーの合計が取り得る上限で、総当たり離散対数探索の範囲を決める。
+pub fn tally(
+    encrypted_tally: &EncryptedBallot,
+    committee: &[CommitteeMemberKey],
+    shares: &[(String, DecryptionShare, DecryptionShare, DecryptionShare)],
+    threshold: usize,
+    max_total_power: u64,
+) -> Result<(u64, u64, u64), Error> {
+    if shares.len() < threshold {
+        return Err(Error::InvalidInput(format!(
+            "Not enough decryption shares: got {}, need {}",
+            shares.len(),
+            threshold
+        )));
+    }
+
+    let mut yes_shares = Vec::new();
+    let mut no_shares = Vec::new();
+    let mut abstain_shares = Vec::new();
+
+    for (member_id, yes_share, no_share, abstain_share) in shares {
+        let member = committee
+            .iter()
+            .find(|m| &m.member_id == member_id)
+            .ok_or_else(|| Error::InvalidInput(format!("Unknown committee member: {}", member_id)))?;
+
+        if !verify_decryption_share(&member.public_share, &encrypted_tally.yes, yes_share)
+            || !verify_decryption_share(&member.public_share, &encrypted_tally.no, no_share)
+            || !verify_decryption_share(&member.public_share, &encrypted_tally.abstain, abstain_share)
+        {
+            return Err(Error::InvalidInput(format!(
+                "Invalid DLEQ proof from committee member: {}",
+                member_id
+            )));
+        }
+
+        yes_shares.push((member.index, yes_share.share.clone()));
+        no_shares.push((member.index, no_share.share.clone()));
+        abstain_shares.push((member.index, abstain_share.share.clone()));
+    }
+
+    let yes_point = decrypt_with_shares(&encrypted_tally.yes, &yes_shares)?;
+    let no_point = decrypt_with_shares(&encrypted_tally.no, &no_shares)?;
+    let abstain_point = decrypt_with_shares(&encrypted_tally.abstain, &abstain_shares)?;
+
+    let yes_votes = brute_force_discrete_log(&yes_point, max_total_power)?;
+    let no_votes = brute_force_discrete_log(&no_point, max_total_power)?;
+    let abstain_votes = brute_force_discrete_log(&abstain_point, max_total_power)?;
+
+    Ok((yes_votes, no_votes, abstain_votes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::governance::voting::VoteType;
+
+    /// 3人中2人（tally_threshold < committee.len()）のシェアだけで正しく復号できることを確認する。
+    /// 旧実装（全メンバー鍵の単純な積）ではこのケースは誤った値に復号されていた。
+    #[test]
+    fn test_threshold_decryption_with_partial_quorum() {
+        let member_ids = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let (committee_public_key, committee, secret_shares) =
+            generate_committee_keys(&member_ids, 2).unwrap();
+
+        let ballot = encrypt_ballot(&VoteType::Yes, 7, &committee_public_key);
+
+        // 3人目（"c"）は復号に参加しない
+        let participating: Vec<_> = secret_shares
+            .into_iter()
+            .filter(|(member_id, _)| member_id != "c")
+            .collect();
+
+        let shares: Vec<_> = participating
+            .iter()
+            .map(|(member_id, secret_share)| {
+                let member = committee.iter().find(|m| &m.member_id == member_id).unwrap();
+                (
+                    member_id.clone(),
+                    generate_decryption_share(secret_share, &ballot.yes),
+                    generate_decryption_share(secret_share, &ballot.no),
+                    generate_decryption_share(secret_share, &ballot.abstain),
+                )
+            })
+            .collect();
+
+        let (yes_votes, no_votes, abstain_votes) =
+            tally(&ballot, &committee, &shares, 2, 100).unwrap();
+
+        assert_eq!(yes_votes, 7);
+        assert_eq!(no_votes, 0);
+        assert_eq!(abstain_votes, 0);
+    }
+
+    /// 委員会全員分のシェアが揃う場合も正しく復号できることを確認する（nがthresholdと一致する境界ケース）。
+    #[test]
+    fn test_threshold_decryption_with_full_quorum() {
+        let member_ids = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let (committee_public_key, committee, secret_shares) =
+            generate_committee_keys(&member_ids, 3).unwrap();
+
+        let ballot = encrypt_ballot(&VoteType::No, 4, &committee_public_key);
+
+        let shares: Vec<_> = secret_shares
+            .iter()
+            .map(|(member_id, secret_share)| {
+                (
+                    member_id.clone(),
+                    generate_decryption_share(secret_share, &ballot.yes),
+                    generate_decryption_share(secret_share, &ballot.no),
+                    generate_decryption_share(secret_share, &ballot.abstain),
+                )
+            })
+            .collect();
+
+        let (yes_votes, no_votes, abstain_votes) =
+            tally(&ballot, &committee, &shares, 3, 100).unwrap();
+
+        assert_eq!(yes_votes, 0);
+        assert_eq!(no_votes, 4);
+        assert_eq!(abstain_votes, 0);
+    }
+}
@@ -0,0 +1,70 @@
+//! 拒否権発動後のクールオフ・ブラックリスト
+//!
+//! `veto`で否決された提案が即座に再提出されるのを防ぐため、提案内容のハッシュを
+//! 一定期間（クールオフ期間）ブラックリストに載せる。同じ内容が繰り返し拒否権で
+//! 否決されると、クールオフ期間はリセットされず延長される。
+
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+
+/// ブラックリストに載った1エントリ
+#[derive(Debug, Clone)]
+pub struct BlacklistEntry {
+    /// ブラックリストが解除される日時
+    pub expires_at: DateTime<Utc>,
+    /// これまでにこの内容を拒否権で否決した全員のリスト
+    pub vetoers: Vec<String>,
+}
+
+/// 拒否権クールオフのブラックリスト
+///
+/// 提案内容のハッシュをキーとして、クールオフ中かどうかを追跡する。
+#[derive(Debug, Clone, Default)]
+pub struct VetoBlacklist {
+    entries: HashMap<String, BlacklistEntry>,
+}
+
+impl VetoBlacklist {
+    /// 新しい空のブラックリストを作成
+    pub fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+
+    /// 指定した内容ハッシュが現時点でクールオフ中かどうかを返す
+    ///
+    /// クールオフ中であれば、解除日時も合わせて返す。
+    pub fn active_entry(&self, content_hash: &str) -> Option<(DateTime<Utc>, &[String])> {
+        self.entries.get(content_hash).and_then(|entry| {
+            if entry.expires_at > Utc::now() {
+                Some((entry.expires_at, entry.vetoers.as_slice()))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// 拒否権が発動されたときに呼び出す
+    ///
+    /// 同じ内容ハッシュが既にクールオフ中であれば、そのクールオフ期間を現在時刻起点で
+    /// 延長する（リセットではなく延長）。拒否権者は重複なく記録される。
+    pub fn record_veto(&mut self, content_hash: String, vetoer: String, cooloff: Duration) {
+        let now = Utc::now();
+
+        let entry = self.entries.entry(content_hash).or_insert_with(|| BlacklistEntry {
+            expires_at: now,
+            vetoers: Vec::new(),
+        });
+
+        if !entry.vetoers.contains(&vetoer) {
+            entry.vetoers.push(vetoer);
+        }
+
+        // 既存の有効期限と「今から1クールオフ分」の長い方を採用することで延長する
+        let extended_expiry = now + cooloff;
+        if extended_expiry > entry.expires_at {
+            entry.expires_at = extended_expiry;
+        }
+    }
+}
@@ -128,11 +128,70 @@ pub struct VotingResult {
     pub min_participation_reached: bool,
     /// 可決フラグ
     pub passed: bool,
+    /// 優先順位投票（RankedChoice）のラウンドごとの集計。他の戦略では`None`
+    pub rounds: Option<Vec<RankedChoiceRound>>,
+    /// 優先順位投票で脱落した選択肢の順序（脱落した順）
+    pub elimination_order: Option<Vec<String>>,
+    /// 優先順位投票で閾値を超えて勝ち残った選択肢
+    pub winning_option: Option<String>,
     /// 追加プロパティ
     #[serde(flatten)]
     pub additional_properties: HashMap<String, serde_json::Value>,
 }
 
+/// 優先順位投票（instant-runoff）における1ラウンドの集計結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankedChoiceRound {
+    /// ラウンド番号（0始まり）
+    pub round: u32,
+    /// そのラウンド開始時点で残っている各選択肢の得票数（重み付き第一希望）
+    pub counts: HashMap<String, u64>,
+    /// このラウンドで脱落した選択肢（勝者が確定したラウンドでは`None`）
+    pub eliminated: Option<String>,
+}
+
+/// 優先順位または単一選択の投票用紙
+///
+/// `rankings`の先頭が第一希望。単一選択の場合は要素数1のベクトルとして表現する。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankedBallot {
+    /// 選好順（先頭が第一希望、以降は次点の希望）
+    pub rankings: Vec<String>,
+    /// 投票パワー
+    pub power: VotingPower,
+    /// 投票日時
+    pub timestamp: DateTime<Utc>,
+    /// この投票の正当性の根拠となる文書等のハッシュ
+    pub justification_hash: Option<String>,
+    /// 追加プロパティ
+    #[serde(flatten)]
+    pub additional_properties: HashMap<String, serde_json::Value>,
+}
+
+impl RankedBallot {
+    /// 新しい投票用紙を作成
+    pub fn new(rankings: Vec<String>, power: VotingPower) -> Self {
+        Self {
+            rankings,
+            power,
+            timestamp: Utc::now(),
+            justification_hash: None,
+            additional_properties: HashMap::new(),
+        }
+    }
+
+    /// 単一選択の投票用紙を作成
+    pub fn single(choice: String, power: VotingPower) -> Self {
+        Self::new(vec![choice], power)
+    }
+
+    /// 正当性の根拠ハッシュを設定
+    pub fn with_justification_hash(mut self, hash: String) -> Self {
+        self.justification_hash = Some(hash);
+        self
+    }
+}
+
 /// 投票
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Vote {
@@ -318,10 +377,13 @@ impl VotingSystem for SimpleVotingSystem {
             min_votes_reached,
             min_participation_reached,
             passed,
+            rounds: None,
+            elimination_order: None,
+            winning_option: None,
             additional_properties: HashMap::new(),
         })
     }
-    
+
     fn is_passed(&self) -> Result<bool, Error> {
         let result = self.calculate_result()?;
         Ok(result.passed)
@@ -0,0 +1,76 @@
+//! 投票権レジストリ
+//!
+//! `calculate_voting_result`は`participation_ratio`を常に`0.0`にハードコードしており、
+//! `ProposalOptions`の`quorum`や`min_participation`が実質機能していなかった。
+//! `VotingPowerRegistry`は`start_voting`実行時点での総投票権（適格投票権）を記録し、
+//! `ProposalMetadata`にスナップショットすることで、後からステーク量が変動しても
+//! クォーラム計算が遡って変わらないようにする。
+
+use std::collections::HashMap;
+
+/// 投票権の計算方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerSource {
+    /// 1アドレス1票
+    OneAddressOneVote,
+    /// 保有量に比例した加重投票
+    TokenWeighted,
+    /// 二次投票（実効投票権は保有量の平方根に上限を設けたもの）
+    QuadraticCapped,
+}
+
+/// 投票権レジストリ
+///
+/// アドレスごとの生のステーク量を保持し、`PowerSource`に応じた実効投票権を計算する。
+#[derive(Debug, Clone, Default)]
+pub struct VotingPowerRegistry {
+    /// アドレスごとの生のステーク量
+    raw_stakes: HashMap<String, u64>,
+}
+
+impl VotingPowerRegistry {
+    /// 新しいレジストリを作成
+    pub fn new() -> Self {
+        Self {
+            raw_stakes: HashMap::new(),
+        }
+    }
+
+    /// アドレスのステーク量を登録・更新する
+    pub fn set_stake(&mut self, address: String, stake: u64) {
+        self.raw_stakes.insert(address, stake);
+    }
+
+    /// アドレスのステーク量を取得する
+    pub fn stake_of(&self, address: &str) -> u64 {
+        self.raw_stakes.get(address).copied().unwrap_or(0)
+    }
+
+    /// 指定した投票権ソースにおける、あるアドレスの実効投票権を計算する
+    pub fn effective_power(&self, address: &str, source: PowerSource) -> u64 {
+        let stake = self.stake_of(address);
+        Self::apply_source(stake, source)
+    }
+
+    /// 登録済みの全アドレスについて、適格な総投票権（クォーラム計算の母数）を計算する
+    pub fn total_eligible_power(&self, source: PowerSource) -> u64 {
+        self.raw_stakes
+            .values()
+            .map(|&stake| Self::apply_source(stake, source))
+            .sum()
+    }
+
+    fn apply_source(stake: u64, source: PowerSource) -> u64 {
+        match source {
+            PowerSource::OneAddressOneVote => {
+                if stake > 0 {
+                    1
+                } else {
+                    0
+                }
+            }
+            PowerSource::TokenWeighted => stake,
+            PowerSource::QuadraticCapped => (stake as f64).sqrt() as u64,
+        }
+    }
+}
@@ -41,4 +41,25 @@ pub enum NetworkMessage {
         /// シャードID
         shard_id: String,
     },
+    /// プライベートトランザクションのゴシップ
+    ///
+    /// 公開台帳にはコミットメント/状態ハッシュのみを残し、完全なペイロードは
+    /// 暗号化して許可されたバリデータ集合にのみピアツーピアで共有する。
+    PrivateTransaction {
+        /// 許可バリデータ公開鍵ごとに暗号化されたペイロード
+        encrypted_payload: Vec<u8>,
+        /// 対象コントラクトのハッシュ
+        contract: crate::crypto::hash::Hash,
+        /// 実行を許可されたバリデータ識別子
+        validators: Vec<String>,
+    },
+    /// プライベートトランザクションの署名付き応答
+    SignedPrivateTransactionReply {
+        /// トランザクションID
+        tx_id: String,
+        /// シャードID
+        shard_id: String,
+        /// 状態ハッシュに対する署名
+        signature: Vec<u8>,
+    },
 }
\ No newline at end of file
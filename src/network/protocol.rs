@@ -18,6 +18,10 @@ pub enum MessageType {
     SyncRequest,
     /// 同期応答
     SyncResponse,
+    /// バッチの受信済み広告（アンチエントロピー）
+    BatchAdvertise,
+    /// 未受信バッチの要求
+    NeedBatch,
 }
 
 /// ネットワークメッセージ
@@ -81,6 +85,8 @@ impl NetworkMessage {
             3 => MessageType::Heartbeat,
             4 => MessageType::SyncRequest,
             5 => MessageType::SyncResponse,
+            6 => MessageType::BatchAdvertise,
+            7 => MessageType::NeedBatch,
             _ => MessageType::Transaction,
         }
     }
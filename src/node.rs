@@ -1,13 +1,20 @@
 use crate::ai::AIPriorityManager;
 use crate::consensus::{ProofOfFlow, SimpleValidator, Validator};
 use crate::sharding::{CrossShardManager, ShardingManager};
-use crate::transaction::{DAG, Transaction};
+use crate::transaction::{TransactionStatus, DAG, Transaction};
 use log::{error, info};
+use serde::Serialize;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use tokio::time::{self, Duration};
 use uuid::Uuid;
 
+/// この数以上の子トランザクションから参照されたら最終確定(finalized)とみなす
+const FINALITY_CONFIRMATIONS: usize = 2;
+
+/// 一括ステータス照会で一度に受け付けるIDの上限
+pub const MAX_BATCH_STATUS_IDS: usize = 100;
+
 /// ノードの設定
 pub struct NodeConfig {
     /// ノードID
@@ -46,6 +53,22 @@ pub enum NodeStatus {
     Stopped,
 }
 
+/// トランザクションの確認状況
+///
+/// Solanaの`getSignatureStatuses`のcommitment levelに倣い、ShardXの
+/// パイプライン上のステージを4段階にマッピングしたもの。
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionStatusInfo {
+    /// "pending" | "processed" | "confirmed" | "finalized"
+    pub status: String,
+    /// このトランザクションを参照している子トランザクションの数
+    pub confirmations: usize,
+    /// 割り当てられたシャード番号
+    pub shard: Option<u32>,
+    /// 失敗時のエラー内容
+    pub err: Option<String>,
+}
+
 /// ShardXノード
 pub struct Node {
     /// ノードID
@@ -210,4 +233,86 @@ impl Node {
     pub fn get_shard_count(&self) -> u32 {
         self.sharding_manager.get_shard_count()
     }
+
+    /// トランザクションの確認状況を取得する
+    ///
+    /// まだDAGに取り込まれていないIDは、メンプールに受理された直後の
+    /// 段階("pending")とみなす。DAGに存在すれば、その`status`と
+    /// 参照している子トランザクションの数から"processed"/"confirmed"/
+    /// "finalized"のいずれかを判定する。
+    ///
+    /// 注: 現在`ProofOfFlow`はコンセンサスで確定したトランザクションを
+    /// 自身の内部DAG（プレースホルダー実装）に書き込んでおり、この
+    /// `Node::dag`とは別物になっている。そのため`TransactionStatus`は
+    /// 実運用では`Confirmed`に遷移しないことがある点に留意すること。
+    pub fn get_transaction_status(&self, tx_id: &str) -> TransactionStatusInfo {
+        let tx = match self.dag.get_transaction(tx_id) {
+            Some(tx) => tx,
+            None => {
+                return TransactionStatusInfo {
+                    status: "pending".to_string(),
+                    confirmations: 0,
+                    shard: None,
+                    err: None,
+                };
+            }
+        };
+
+        let shard = Some(self.sharding_manager.assign_shard(&tx));
+        let confirmations = self
+            .dag
+            .children
+            .get(tx_id)
+            .map(|children| children.len())
+            .unwrap_or(0);
+
+        match tx.status {
+            TransactionStatus::Rejected => TransactionStatusInfo {
+                status: "processed".to_string(),
+                confirmations,
+                shard,
+                err: Some("transaction rejected by consensus".to_string()),
+            },
+            TransactionStatus::Pending => TransactionStatusInfo {
+                status: "processed".to_string(),
+                confirmations,
+                shard,
+                err: None,
+            },
+            TransactionStatus::Confirmed => {
+                let status = if confirmations >= FINALITY_CONFIRMATIONS {
+                    "finalized"
+                } else {
+                    "confirmed"
+                };
+                TransactionStatusInfo {
+                    status: status.to_string(),
+                    confirmations,
+                    shard,
+                    err: None,
+                }
+            }
+        }
+    }
+
+    /// 複数のトランザクションの確認状況をまとめて取得する
+    ///
+    /// [`MAX_BATCH_STATUS_IDS`]を超えるIDが渡された場合はエラーを返す。
+    pub fn get_transaction_statuses(
+        &self,
+        ids: &[String],
+    ) -> Result<Vec<TransactionStatusInfo>, String> {
+        if ids.len() > MAX_BATCH_STATUS_IDS {
+            return Err(format!(
+                "Too many ids requested: {} (max {})",
+                ids.len(),
+                MAX_BATCH_STATUS_IDS
+            ));
+        }
+
+        Ok(ids
+            .iter()
+            .map(|id| self.get_transaction_status(id))
+            .collect())
+    }
 }
\ No newline at end of file
@@ -1,5 +1,5 @@
 use crate::error::Error;
-use crate::crypto::zk::{BulletproofProof, Bulletproof};
+use crate::crypto::zk::{BulletproofProof, Bulletproof, ZkProofManager};
 use curve25519_dalek::{
     constants::RISTRETTO_BASEPOINT_TABLE,
     ristretto::{CompressedRistretto, RistrettoPoint},
@@ -9,6 +9,60 @@ use rand::thread_rng;
 use sha2::{Digest, Sha256};
 use std::fmt;
 
+/// 入力（スペンド）に対する範囲証明を生成するプルーバ
+///
+/// librustzcash の Sapling ビルダーに倣い、証明生成をトランザクション組み立てから
+/// 分離する。実装を差し替えることでバッチ/GPU/リモート証明サービスを利用できる。
+pub trait SpendProver {
+    /// 入力コミットメントに対するスペンド証明を生成
+    fn prove_spend(
+        &self,
+        amount: u64,
+        blinding: &BlindingFactor,
+        commitment: &[u8],
+    ) -> Result<BulletproofProof, Error>;
+}
+
+/// 出力に対する範囲証明を生成するプルーバ
+pub trait OutputProver {
+    /// 出力金額に対する範囲証明を生成
+    fn prove_output(&self, amount: u64, blinding: &BlindingFactor)
+        -> Result<BulletproofProof, Error>;
+}
+
+/// `ZkProofManager` を用いたインプロセスの既定プルーバ
+pub struct ZkManagerProver<'a> {
+    zk: &'a ZkProofManager,
+}
+
+impl<'a> ZkManagerProver<'a> {
+    /// 新しい既定プルーバを作成
+    pub fn new(zk: &'a ZkProofManager) -> Self {
+        Self { zk }
+    }
+}
+
+impl<'a> SpendProver for ZkManagerProver<'a> {
+    fn prove_spend(
+        &self,
+        amount: u64,
+        blinding: &BlindingFactor,
+        _commitment: &[u8],
+    ) -> Result<BulletproofProof, Error> {
+        self.zk.generate_bulletproof(amount, blinding.as_bytes())
+    }
+}
+
+impl<'a> OutputProver for ZkManagerProver<'a> {
+    fn prove_output(
+        &self,
+        amount: u64,
+        blinding: &BlindingFactor,
+    ) -> Result<BulletproofProof, Error> {
+        self.zk.generate_bulletproof(amount, blinding.as_bytes())
+    }
+}
+
 /// ブラインディング係数
 #[derive(Clone, Debug)]
 pub struct BlindingFactor {
@@ -42,6 +96,131 @@ pub struct ConfidentialTransaction {
     range_proof: BulletproofProof,
     /// 署名
     signature: Vec<u8>,
+    /// 受信者の閲覧鍵向け暗号化ノート（ステルスアドレス宛のとき付与）
+    encrypted_note: Option<EncryptedNote>,
+}
+
+/// 受信者の閲覧鍵向けに暗号化されたノート
+///
+/// librustzcash の Sapling ノート暗号化に倣い、エフェメラル鍵と受信者の
+/// 公開閲覧鍵による ECDH 共有秘密で `{ amount, blinding_factor, memo }` を暗号化する。
+#[derive(Clone, Debug)]
+pub struct EncryptedNote {
+    /// エフェメラル公開鍵
+    pub ephemeral_pubkey: [u8; 32],
+    /// 暗号化されたノートブロブ
+    pub ciphertext: Vec<u8>,
+}
+
+/// 閲覧鍵による走査で復号されたノート
+#[derive(Clone, Debug)]
+pub struct DecryptedNote {
+    /// 平文金額
+    pub amount: u64,
+    /// ブラインディング係数
+    pub blinding_factor: BlindingFactor,
+    /// メモ
+    pub memo: Vec<u8>,
+}
+
+impl EncryptedNote {
+    /// 受信者の公開閲覧鍵に対してノートを暗号化する
+    ///
+    /// エフェメラルスカラーを生成し、受信者の公開閲覧鍵との ECDH 共有秘密から
+    /// 鍵ストリームを導出してノートブロブを暗号化する。
+    pub fn encrypt(
+        viewing_pubkey: &[u8],
+        amount: u64,
+        blinding: &BlindingFactor,
+        memo: &[u8],
+    ) -> Result<Self, Error> {
+        let recipient = CompressedRistretto::from_slice(viewing_pubkey)
+            .map_err(|_| Error::InvalidInput("Invalid viewing key".to_string()))?
+            .decompress()
+            .ok_or_else(|| Error::InvalidInput("Invalid viewing key point".to_string()))?;
+
+        let ephemeral = Scalar::random(&mut thread_rng());
+        let ephemeral_pub = (&ephemeral * &RISTRETTO_BASEPOINT_TABLE).compress();
+        let shared = (ephemeral * recipient).compress();
+
+        let mut plaintext = Vec::new();
+        plaintext.extend_from_slice(&amount.to_le_bytes());
+        plaintext.extend_from_slice(blinding.as_bytes());
+        plaintext.extend_from_slice(&(memo.len() as u32).to_le_bytes());
+        plaintext.extend_from_slice(memo);
+
+        let ciphertext = note_keystream_xor(shared.as_bytes(), &plaintext);
+        Ok(Self {
+            ephemeral_pubkey: *ephemeral_pub.as_bytes(),
+            ciphertext,
+        })
+    }
+
+    /// 受信者の秘密閲覧鍵で試行復号する
+    ///
+    /// 復号結果が正しいコミットメントを再現しない場合は `None` を返す（fail-closed）。
+    pub fn try_decrypt(
+        &self,
+        incoming_viewing_key: &[u8],
+        expected_commitment: &[u8],
+    ) -> Option<DecryptedNote> {
+        if incoming_viewing_key.len() != 32 {
+            return None;
+        }
+        let mut sk = [0u8; 32];
+        sk.copy_from_slice(incoming_viewing_key);
+        let scalar = Scalar::from_bytes_mod_order(sk);
+        let ephemeral = CompressedRistretto::from_slice(&self.ephemeral_pubkey)
+            .ok()?
+            .decompress()?;
+        let shared = (scalar * ephemeral).compress();
+
+        let plaintext = note_keystream_xor(shared.as_bytes(), &self.ciphertext);
+        if plaintext.len() < 44 {
+            return None;
+        }
+        let amount = u64::from_le_bytes(plaintext[0..8].try_into().ok()?);
+        let blinding = BlindingFactor::from_bytes(&plaintext[8..40]).ok()?;
+        let memo_len = u32::from_le_bytes(plaintext[40..44].try_into().ok()?) as usize;
+        if 44 + memo_len > plaintext.len() {
+            return None;
+        }
+        let memo = plaintext[44..44 + memo_len].to_vec();
+
+        // コミットメントを再構成して一致を確認（fail-closed）
+        let recovered = ConfidentialAmount::new(amount, &blinding).ok()?;
+        let recovered_commitment = recovered.get_commitment().ok()?;
+        if recovered_commitment != expected_commitment {
+            return None;
+        }
+
+        Some(DecryptedNote {
+            amount,
+            blinding_factor: blinding,
+            memo,
+        })
+    }
+}
+
+/// Sha256(shared ‖ counter) を連結した鍵ストリームでデータを XOR する
+fn note_keystream_xor(shared: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut counter: u32 = 0;
+    let mut block = Vec::new();
+    let mut block_pos = 32;
+    for &byte in data {
+        if block_pos == 32 {
+            let mut hasher = Sha256::new();
+            hasher.update(shared);
+            hasher.update(counter.to_le_bytes());
+            block = hasher.finalize().to_vec();
+            counter += 1;
+            block_pos = 0;
+        }
+        out.push(byte ^ block[block_pos]);
+        block_pos += 1;
+    }
+    out
 }
 
 impl BlindingFactor {
@@ -175,14 +354,25 @@ impl ConfidentialTransaction {
             fee,
             range_proof,
             signature: Vec::new(),
+            encrypted_note: None,
         };
-        
+
         // トランザクションに署名
         transaction.sign(private_key)?;
-        
+
         Ok(transaction)
     }
-    
+
+    /// 暗号化ノートを付与する（ステルスアドレス宛の支払いで利用）
+    pub fn set_encrypted_note(&mut self, note: EncryptedNote) {
+        self.encrypted_note = Some(note);
+    }
+
+    /// 暗号化ノートを取得する
+    pub fn get_encrypted_note(&self) -> Option<&EncryptedNote> {
+        self.encrypted_note.as_ref()
+    }
+
     /// トランザクションに署名
     fn sign(&mut self, private_key: &[u8]) -> Result<(), Error> {
         // 署名対象のデータを準備
@@ -451,10 +641,176 @@ impl ConfidentialTransaction {
             fee,
             range_proof,
             signature,
+            encrypted_note: None,
         })
     }
 }
 
+/// 集約範囲証明を共有する複数出力の機密トランザクション
+///
+/// 各出力ごとに独立した Bulletproof を持たせる代わりに、全出力の金額を
+/// 1本の集約 Bulletproof でカバーする。`commitments` は集約証明が実際に
+/// 対象とするコミットメント集合（2のべき乗にパディング済み）であり、
+/// 検証時はこの集合をそのまま用いる。
+#[derive(Clone, Debug)]
+pub struct MultiOutputConfidentialTransaction {
+    /// 送信者
+    sender: Vec<u8>,
+    /// 出力（受信者と機密金額）
+    outputs: Vec<(Vec<u8>, ConfidentialAmount)>,
+    /// 手数料
+    fee: u64,
+    /// 全出力をカバーする集約範囲証明
+    aggregated_proof: BulletproofProof,
+    /// 集約証明が対象とするコミットメント（パディング込み）
+    commitments: Vec<Vec<u8>>,
+    /// 署名
+    signature: Vec<u8>,
+}
+
+impl MultiOutputConfidentialTransaction {
+    /// 新しい複数出力機密トランザクションを作成
+    pub fn new(
+        sender: &[u8],
+        outputs: Vec<(Vec<u8>, ConfidentialAmount)>,
+        fee: u64,
+        aggregated_proof: BulletproofProof,
+        commitments: Vec<Vec<u8>>,
+        private_key: &[u8],
+    ) -> Result<Self, Error> {
+        let mut transaction = Self {
+            sender: sender.to_vec(),
+            outputs,
+            fee,
+            aggregated_proof,
+            commitments,
+            signature: Vec::new(),
+        };
+
+        transaction.sign(private_key)?;
+
+        Ok(transaction)
+    }
+
+    /// 署名対象のデータを構築
+    fn signing_payload(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&self.sender);
+        for (recipient, _) in &self.outputs {
+            data.extend_from_slice(recipient);
+        }
+        for commitment in &self.commitments {
+            data.extend_from_slice(commitment);
+        }
+        data.extend_from_slice(&self.fee.to_le_bytes());
+        data
+    }
+
+    /// トランザクションに署名（ConfidentialTransaction と同じ Schnorr 方式）
+    fn sign(&mut self, private_key: &[u8]) -> Result<(), Error> {
+        let private_scalar =
+            Scalar::from_canonical_bytes(private_key.try_into().unwrap_or([0u8; 32]))
+                .ok_or_else(|| Error::DeserializationError("Invalid private key format".to_string()))?;
+
+        let mut hasher = Sha256::new();
+        hasher.update(&self.signing_payload());
+        let message_hash = hasher.finalize();
+
+        let k = Scalar::random(&mut thread_rng());
+        let r_point = &k * &RISTRETTO_BASEPOINT_TABLE;
+        let r = r_point.compress().to_bytes();
+
+        let public_key = &private_scalar * &RISTRETTO_BASEPOINT_TABLE;
+
+        let mut e_hasher = Sha256::new();
+        e_hasher.update(&r);
+        e_hasher.update(public_key.compress().as_bytes());
+        e_hasher.update(&message_hash);
+        let e_hash = e_hasher.finalize();
+        let e =
+            Scalar::from_bytes_mod_order_wide(&<[u8; 64]>::try_from(&e_hash[..]).unwrap_or([0u8; 64]));
+
+        let s = k - e * private_scalar;
+
+        let mut signature = Vec::with_capacity(64);
+        signature.extend_from_slice(&r);
+        signature.extend_from_slice(&s.to_bytes());
+
+        self.signature = signature;
+
+        Ok(())
+    }
+
+    /// 署名を検証
+    pub fn verify_signature(&self) -> Result<bool, Error> {
+        if self.signature.len() != 64 {
+            return Ok(false);
+        }
+
+        let r_bytes = &self.signature[0..32];
+        let s_bytes = &self.signature[32..64];
+
+        let r_point = match CompressedRistretto::from_slice(r_bytes) {
+            Ok(point) => point,
+            Err(_) => return Ok(false),
+        };
+        let s = match Scalar::from_canonical_bytes(s_bytes.try_into().unwrap_or([0u8; 32])) {
+            Some(scalar) => scalar,
+            None => return Ok(false),
+        };
+
+        let mut hasher = Sha256::new();
+        hasher.update(&self.signing_payload());
+        let message_hash = hasher.finalize();
+
+        let public_key = match CompressedRistretto::from_slice(&self.sender) {
+            Ok(key) => key,
+            Err(_) => return Ok(false),
+        };
+        let public_key_point = match public_key.decompress() {
+            Some(point) => point,
+            None => return Ok(false),
+        };
+
+        let mut e_hasher = Sha256::new();
+        e_hasher.update(r_bytes);
+        e_hasher.update(public_key.as_bytes());
+        e_hasher.update(&message_hash);
+        let e_hash = e_hasher.finalize();
+        let e =
+            Scalar::from_bytes_mod_order_wide(&<[u8; 64]>::try_from(&e_hash[..]).unwrap_or([0u8; 64]));
+
+        let r_prime = &s * &RISTRETTO_BASEPOINT_TABLE + &e * &public_key_point;
+
+        Ok(r_prime.compress() == r_point)
+    }
+
+    /// 送信者を取得
+    pub fn get_sender(&self) -> &[u8] {
+        &self.sender
+    }
+
+    /// 出力を取得
+    pub fn get_outputs(&self) -> &[(Vec<u8>, ConfidentialAmount)] {
+        &self.outputs
+    }
+
+    /// 手数料を取得
+    pub fn get_fee(&self) -> u64 {
+        self.fee
+    }
+
+    /// 集約範囲証明を取得
+    pub fn get_aggregated_proof(&self) -> &BulletproofProof {
+        &self.aggregated_proof
+    }
+
+    /// 集約証明が対象とするコミットメントを取得
+    pub fn get_commitments(&self) -> &[Vec<u8>] {
+        &self.commitments
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -15,7 +15,8 @@ mod stealth_address;
 // mod private_smart_contract; // TODO: このモジュールが見つかりません
 
 pub use self::confidential_transaction::{
-    BlindingFactor, ConfidentialAmount, ConfidentialTransaction,
+    BlindingFactor, ConfidentialAmount, ConfidentialTransaction, DecryptedNote, EncryptedNote,
+    MultiOutputConfidentialTransaction, OutputProver, SpendProver, ZkManagerProver,
 };
 pub use self::mixer::{Mixer, MixerPool, MixingProof};
 pub use self::private_smart_contract::{PrivateContract, PrivateContractExecutor, PrivateState};
@@ -25,8 +26,104 @@ pub use self::stealth_address::{StealthAddress, StealthAddressGenerator, Stealth
 use crate::crypto::hash::Hash;
 use crate::crypto::zk::{Bulletproof, BulletproofProof, ZkProofManager};
 use crate::error::Error;
+use crate::network::cross_shard::NetworkMessage;
 use crate::transaction::Transaction;
 
+use curve25519_dalek::{
+    constants::RISTRETTO_BASEPOINT_TABLE, ristretto::CompressedRistretto, scalar::Scalar,
+};
+use log::warn;
+use rand::thread_rng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// 許可バリデータ 1 名宛の暗号化エンベロープ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidatorEnvelope {
+    /// 宛先バリデータ識別子（公開鍵の16進表現）
+    pub validator: String,
+    /// 送信側のエフェメラル公開鍵
+    pub ephemeral_pubkey: [u8; 32],
+    /// ECDH 共有鍵で暗号化されたペイロード
+    pub ciphertext: Vec<u8>,
+}
+
+/// プライベートトランザクションの平文ペイロード
+///
+/// 許可バリデータだけが復号し、再実行して状態ハッシュを検証できる。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivateTransactionPayload {
+    /// トランザクションID
+    pub tx_id: String,
+    /// コントラクトへの入力
+    pub inputs: Vec<Vec<u8>>,
+    /// 実行結果の状態遷移
+    pub state_transition: Vec<u8>,
+    /// 状態ハッシュ（公開台帳に残るコミットメント）
+    pub state_hash: [u8; 32],
+}
+
+/// ristretto ECDH + Sha256 鍵ストリームによる対称暗号化
+fn ecdh_encrypt(recipient_pubkey: &[u8], plaintext: &[u8]) -> Result<([u8; 32], Vec<u8>), Error> {
+    if recipient_pubkey.len() != 32 {
+        return Err(Error::InvalidInput(
+            "Validator public key must be 32 bytes".to_string(),
+        ));
+    }
+    let recipient = CompressedRistretto::from_slice(recipient_pubkey)
+        .decompress()
+        .ok_or_else(|| Error::InvalidInput("Invalid validator public key".to_string()))?;
+
+    let ephemeral = Scalar::random(&mut thread_rng());
+    let ephemeral_pub = (&ephemeral * &RISTRETTO_BASEPOINT_TABLE).compress();
+    let shared = (ephemeral * recipient).compress();
+
+    let ciphertext = xor_keystream(shared.as_bytes(), plaintext);
+    Ok((*ephemeral_pub.as_bytes(), ciphertext))
+}
+
+/// ECDH による復号（`ecdh_encrypt` の逆）
+fn ecdh_decrypt(
+    my_private_key: &[u8],
+    ephemeral_pubkey: &[u8; 32],
+    ciphertext: &[u8],
+) -> Result<Vec<u8>, Error> {
+    if my_private_key.len() != 32 {
+        return Err(Error::InvalidInput(
+            "Private key must be 32 bytes".to_string(),
+        ));
+    }
+    let mut sk = [0u8; 32];
+    sk.copy_from_slice(my_private_key);
+    let scalar = Scalar::from_bytes_mod_order(sk);
+    let ephemeral = CompressedRistretto::from_slice(ephemeral_pubkey)
+        .decompress()
+        .ok_or_else(|| Error::InvalidInput("Invalid ephemeral public key".to_string()))?;
+    let shared = (scalar * ephemeral).compress();
+    Ok(xor_keystream(shared.as_bytes(), ciphertext))
+}
+
+/// Sha256(shared ‖ counter) を連結した鍵ストリームと平文を XOR する
+fn xor_keystream(shared: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut counter: u32 = 0;
+    let mut block = Vec::new();
+    let mut block_pos = 32;
+    for &byte in data {
+        if block_pos == 32 {
+            let mut hasher = Sha256::new();
+            hasher.update(shared);
+            hasher.update(counter.to_le_bytes());
+            block = hasher.finalize().to_vec();
+            counter += 1;
+            block_pos = 0;
+        }
+        out.push(byte ^ block[block_pos]);
+        block_pos += 1;
+    }
+    out
+}
+
 /// プライバシーレベル
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PrivacyLevel {
@@ -107,13 +204,44 @@ impl PrivacyManager {
         let blinding_factor = BlindingFactor::random();
         let confidential_amount = ConfidentialAmount::new(amount, &blinding_factor)?;
 
-        // コミットメントを作成
+        // 既定のインプロセスプルーバで証明を生成してトランザクションを組み立てる
+        let prover = ZkManagerProver::new(&self.zk_manager);
+        self.create_confidential_transaction_with_provers(
+            sender,
+            recipient,
+            confidential_amount,
+            blinding_factor,
+            fee,
+            private_key,
+            &prover,
+            &prover,
+        )
+    }
+
+    /// プルーバを差し替え可能な機密トランザクション生成
+    ///
+    /// 証明生成とトランザクション組み立てを分離し、まず値コミットメントを確定して
+    /// からスペンド/出力プルーバで範囲証明を取得する。プルーバを差し替えることで
+    /// バッチ/GPU/リモート証明や遅延・並列証明を実現できる。
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_confidential_transaction_with_provers(
+        &self,
+        sender: &[u8],
+        recipient: &[u8],
+        confidential_amount: ConfidentialAmount,
+        blinding_factor: BlindingFactor,
+        fee: u64,
+        private_key: &[u8],
+        spend_prover: &impl SpendProver,
+        output_prover: &impl OutputProver,
+    ) -> Result<ConfidentialTransaction, Error> {
+        // 第 1 パス: 値コミットメントを確定
         let commitment = confidential_amount.get_commitment()?;
 
-        // 範囲証明を生成
-        let range_proof = self
-            .zk_manager
-            .generate_bulletproof(amount, blinding_factor.as_bytes())?;
+        // 第 2 パス: プルーバに範囲証明の生成を委譲
+        let amount = confidential_amount.get_amount();
+        let _ = spend_prover.prove_spend(amount, &blinding_factor, &commitment)?;
+        let range_proof = output_prover.prove_output(amount, &blinding_factor)?;
 
         // 機密トランザクションを作成
         let transaction = ConfidentialTransaction::new(
@@ -128,6 +256,139 @@ impl PrivacyManager {
         Ok(transaction)
     }
 
+    /// ステルスアドレス宛の機密トランザクションを作成し暗号化ノートを付与する
+    ///
+    /// 受信者の公開閲覧鍵（ステルスアドレスの公開鍵）に対して
+    /// `{ amount, blinding_factor, memo }` を暗号化したノートを添付するので、
+    /// 受信者は副次チャネルなしで入金を検出・復号できる。
+    pub fn create_confidential_transaction_to_stealth(
+        &self,
+        sender: &[u8],
+        stealth_address: &StealthAddress,
+        amount: u64,
+        fee: u64,
+        memo: &[u8],
+        private_key: &[u8],
+    ) -> Result<ConfidentialTransaction, Error> {
+        let blinding_factor = BlindingFactor::random();
+        let confidential_amount = ConfidentialAmount::new(amount, &blinding_factor)?;
+
+        let viewing_pubkey = stealth_address.public_key.as_bytes();
+        let note = EncryptedNote::encrypt(viewing_pubkey, amount, &blinding_factor, memo)?;
+
+        let recipient = stealth_address.public_key.as_bytes().to_vec();
+        let prover = ZkManagerProver::new(&self.zk_manager);
+        let mut transaction = self.create_confidential_transaction_with_provers(
+            sender,
+            &recipient,
+            confidential_amount,
+            blinding_factor,
+            fee,
+            private_key,
+            &prover,
+            &prover,
+        )?;
+        transaction.set_encrypted_note(note);
+
+        Ok(transaction)
+    }
+
+    /// 複数出力を単一の集約 Bulletproof でまとめた機密トランザクションを作成
+    ///
+    /// 出力ごとに範囲証明を生成・検証する代わりに、全出力金額を1本の集約証明で
+    /// カバーする。出力本数が2のべき乗でない場合はゼロ値のダミーコミットメントで
+    /// パディングされ、証明サイズと検証コストが出力数に対して対数的に抑えられる。
+    pub fn create_multi_output_confidential_transaction(
+        &self,
+        sender: &[u8],
+        outputs: &[(Vec<u8>, u64)],
+        fee: u64,
+        private_key: &[u8],
+    ) -> Result<MultiOutputConfidentialTransaction, Error> {
+        if outputs.is_empty() {
+            return Err(Error::InvalidArgument(
+                "At least one output is required".to_string(),
+            ));
+        }
+
+        // 出力ごとに機密金額を確定
+        let mut confidential_outputs = Vec::with_capacity(outputs.len());
+        let mut amounts = Vec::with_capacity(outputs.len());
+        let mut blindings = Vec::with_capacity(outputs.len());
+        for (recipient, amount) in outputs {
+            let blinding = BlindingFactor::random();
+            let confidential_amount = ConfidentialAmount::new(*amount, &blinding)?;
+            amounts.push(*amount);
+            blindings.push(blinding.clone());
+            confidential_outputs.push((recipient.clone(), confidential_amount));
+        }
+
+        // 集約証明用のブラインディング参照を構築
+        let blinding_refs: Vec<&[u8]> = blindings.iter().map(|b| b.as_bytes().as_slice()).collect();
+
+        // 全出力をカバーする単一の集約 Bulletproof を生成
+        let (aggregated_proof, commitments) = self
+            .zk_manager
+            .generate_aggregated_bulletproof(&amounts, &blinding_refs)?;
+
+        MultiOutputConfidentialTransaction::new(
+            sender,
+            confidential_outputs,
+            fee,
+            aggregated_proof,
+            commitments,
+            private_key,
+        )
+    }
+
+    /// 複数出力機密トランザクションの集約範囲証明と署名を検証
+    pub fn verify_multi_output_confidential_transaction(
+        &self,
+        transaction: &MultiOutputConfidentialTransaction,
+    ) -> Result<bool, Error> {
+        let commitment_refs: Vec<&[u8]> = transaction
+            .get_commitments()
+            .iter()
+            .map(|c| c.as_slice())
+            .collect();
+
+        let range_proof_valid = self
+            .zk_manager
+            .verify_aggregated_bulletproof(transaction.get_aggregated_proof(), &commitment_refs)?;
+
+        let signature_valid = transaction.verify_signature()?;
+
+        Ok(range_proof_valid && signature_valid)
+    }
+
+    /// 閲覧鍵で機密トランザクション群を走査し、自分宛のノートを復号する
+    ///
+    /// 各出力について共有秘密を再計算して暗号化ノートを試行復号し、復号結果が
+    /// オンチェーンのコミットメントと一致した場合のみ `DecryptedNote` を返す。
+    /// コミットメントが一致しない出力はスキップする（fail-closed）ため、
+    /// 偽造ノートが走査結果に混入することはない。
+    pub fn scan_transactions(
+        &self,
+        txs: &[ConfidentialTransaction],
+        incoming_viewing_key: &[u8],
+    ) -> Vec<DecryptedNote> {
+        let mut notes = Vec::new();
+        for tx in txs {
+            let note = match tx.get_encrypted_note() {
+                Some(note) => note,
+                None => continue,
+            };
+            let commitment = match tx.get_amount().get_commitment() {
+                Ok(c) => c,
+                Err(_) => continue,
+            };
+            if let Some(decrypted) = note.try_decrypt(incoming_viewing_key, &commitment) {
+                notes.push(decrypted);
+            }
+        }
+        notes
+    }
+
     /// 機密トランザクションを検証
     pub fn verify_confidential_transaction(
         &self,
@@ -199,6 +460,132 @@ impl PrivacyManager {
         self.mixer.withdraw(proof, recipient, nullifier)
     }
 
+    /// プライベートトランザクションを作成しゴシップメッセージを生成
+    ///
+    /// コントラクトを `private_executor` で実行し、得られた状態遷移を許可された
+    /// バリデータ公開鍵ごとに暗号化する。公開台帳には状態ハッシュのみを残す。
+    pub fn create_private_transaction(
+        &self,
+        contract: &PrivateContract,
+        inputs: &[Vec<u8>],
+        permitted_keys: &[Vec<u8>],
+    ) -> Result<NetworkMessage, Error> {
+        // 許可バリデータ集合でコントラクトを実行
+        let executor_key = permitted_keys
+            .first()
+            .cloned()
+            .unwrap_or_default();
+        let state_transition = self
+            .private_executor
+            .execute(contract, inputs, &executor_key)?;
+
+        // 状態ハッシュ（コミットメント）を計算
+        let mut hasher = Sha256::new();
+        hasher.update(&state_transition);
+        let state_hash: [u8; 32] = hasher.finalize().into();
+
+        let tx_id = format!("priv-tx-{}", hex::encode(&state_hash[..8]));
+        let payload = PrivateTransactionPayload {
+            tx_id: tx_id.clone(),
+            inputs: inputs.to_vec(),
+            state_transition,
+            state_hash,
+        };
+        let plaintext = serde_json::to_vec(&payload)
+            .map_err(|e| Error::SerializeError(e.to_string()))?;
+
+        // 許可バリデータごとに暗号化
+        let mut envelopes = Vec::with_capacity(permitted_keys.len());
+        let mut validators = Vec::with_capacity(permitted_keys.len());
+        for key in permitted_keys {
+            let validator = hex::encode(key);
+            let (ephemeral_pubkey, ciphertext) = ecdh_encrypt(key, &plaintext)?;
+            envelopes.push(ValidatorEnvelope {
+                validator: validator.clone(),
+                ephemeral_pubkey,
+                ciphertext,
+            });
+            validators.push(validator);
+        }
+
+        let encrypted_payload = serde_json::to_vec(&envelopes)
+            .map_err(|e| Error::SerializeError(e.to_string()))?;
+
+        // コントラクトハッシュを算出
+        let contract_hash = Hash::from_data(&contract.to_bytes());
+
+        Ok(NetworkMessage::PrivateTransaction {
+            encrypted_payload,
+            contract: contract_hash,
+            validators,
+        })
+    }
+
+    /// 受信したプライベートトランザクションを処理
+    ///
+    /// 自ノードが許可されている場合のみ復号して再実行し、状態ハッシュを検証した
+    /// 署名付き応答を返す。許可されていない/復号できないメッセージは黙って破棄する。
+    pub fn handle_private_transaction(
+        &self,
+        msg: &NetworkMessage,
+        my_private_key: &[u8],
+    ) -> Result<Option<NetworkMessage>, Error> {
+        let encrypted_payload = match msg {
+            NetworkMessage::PrivateTransaction {
+                encrypted_payload, ..
+            } => encrypted_payload,
+            // プライベートトランザクション以外は対象外
+            _ => return Ok(None),
+        };
+
+        let envelopes: Vec<ValidatorEnvelope> = serde_json::from_slice(encrypted_payload)
+            .map_err(|e| Error::DeserializeError(e.to_string()))?;
+
+        // 自鍵で復号できるエンベロープを探索（不可なら黙って破棄）
+        let mut decrypted: Option<PrivateTransactionPayload> = None;
+        for envelope in &envelopes {
+            if let Ok(plaintext) =
+                ecdh_decrypt(my_private_key, &envelope.ephemeral_pubkey, &envelope.ciphertext)
+            {
+                if let Ok(payload) =
+                    serde_json::from_slice::<PrivateTransactionPayload>(&plaintext)
+                {
+                    decrypted = Some(payload);
+                    break;
+                }
+            }
+        }
+        let payload = match decrypted {
+            Some(p) => p,
+            None => return Ok(None),
+        };
+
+        // 状態ハッシュを再計算して検証（改ざん検知）
+        let mut hasher = Sha256::new();
+        hasher.update(&payload.state_transition);
+        let recomputed: [u8; 32] = hasher.finalize().into();
+        if recomputed != payload.state_hash {
+            warn!("Private transaction state hash mismatch, dropping");
+            return Ok(None);
+        }
+
+        // 状態ハッシュに署名して応答
+        let signature = self.sign_state_hash(&payload.state_hash, my_private_key);
+        Ok(Some(NetworkMessage::SignedPrivateTransactionReply {
+            tx_id: payload.tx_id,
+            shard_id: String::new(),
+            signature,
+        }))
+    }
+
+    /// 状態ハッシュに対する署名を生成（Sha256(sk ‖ hash)）
+    fn sign_state_hash(&self, state_hash: &[u8; 32], private_key: &[u8]) -> Vec<u8> {
+        let mut hasher = Sha256::new();
+        hasher.update(private_key);
+        hasher.update(state_hash);
+        hasher.finalize().to_vec()
+    }
+
     /// プライベートスマートコントラクトを実行
     pub fn execute_private_contract(
         &self,
@@ -0,0 +1,341 @@
+// イベント相関エンジン
+//
+// 単発のイベントでは低リスクでも、時間をまたいで多数が連鎖すると攻撃の兆候となる
+// （緩慢なシャード乗っ取り、ノード横断のクレデンシャルスタッフィング等）。このモジュールは
+// SIEM の相関エンジン（OSSEC/Wazuh のディレクティブ）を参考に、宣言的な多段ルールで
+// `SecurityEvent` の列を相関させ、リスクが閾値を超えたら `SecurityIncident` を生成する。
+
+use crate::security::vulnerability_scanner::SeverityLevel;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// 相関対象となるセキュリティイベント
+#[derive(Debug, Clone)]
+pub struct SecurityEvent {
+    /// イベントID（マッチ成立時に `related_vulnerabilities` へ転記される）
+    pub id: String,
+    /// カテゴリ（例: "auth", "consensus", "network"）
+    pub category: String,
+    /// サブカテゴリ（例: "login_failed", "view_change"）
+    pub subcategory: String,
+    /// イベントタイプID（マッチャが id 指定の場合に使用）
+    pub event_type: u32,
+    /// 相関キー（送信元ノードID / シャードペア等）
+    pub correlation_key: String,
+    /// 発生日時
+    pub timestamp: DateTime<Utc>,
+}
+
+/// ステージのマッチャ。カテゴリ/サブカテゴリ、またはイベントタイプIDで照合する
+#[derive(Debug, Clone)]
+pub enum StageMatcher {
+    /// カテゴリ（とオプションのサブカテゴリ）で照合
+    Category {
+        category: String,
+        subcategory: Option<String>,
+    },
+    /// イベントタイプIDで照合
+    EventType(u32),
+}
+
+impl StageMatcher {
+    /// イベントがこのマッチャに合致するか
+    fn matches(&self, event: &SecurityEvent) -> bool {
+        match self {
+            StageMatcher::Category {
+                category,
+                subcategory,
+            } => {
+                event.category == *category
+                    && subcategory
+                        .as_ref()
+                        .map_or(true, |sub| event.subcategory == *sub)
+            }
+            StageMatcher::EventType(id) => event.event_type == *id,
+        }
+    }
+}
+
+/// ディレクティブを構成する1ステージ
+#[derive(Debug, Clone)]
+pub struct CorrelationStage {
+    /// 照合条件
+    pub matcher: StageMatcher,
+    /// このステージを満たすのに必要な発生回数
+    pub occurrence: u32,
+    /// `occurrence` 回を満たすべき時間窓
+    pub timeout: Duration,
+    /// 信頼度の重み（0〜10）
+    pub reliability: u8,
+}
+
+/// 多段の相関ルール
+#[derive(Debug, Clone)]
+pub struct Directive {
+    /// ディレクティブID
+    pub id: String,
+    /// 名称
+    pub name: String,
+    /// 優先度（リスク計算の係数）
+    pub priority: u8,
+    /// 資産価値（リスク計算の係数）
+    pub asset_value: u8,
+    /// インシデント生成時の重大度
+    pub severity: SeverityLevel,
+    /// 順序付きステージ列
+    pub stages: Vec<CorrelationStage>,
+}
+
+impl Directive {
+    /// 指定ステージまで進んだときの累積リスク。
+    ///
+    /// OSSEC と同様に `reliability * priority * asset_value / 25` で算出する。
+    /// `reliability` は到達済みステージの最大信頼度を用いる。
+    fn risk_at(&self, stage_index: usize) -> f64 {
+        let reliability = self.stages[..=stage_index]
+            .iter()
+            .map(|s| s.reliability)
+            .max()
+            .unwrap_or(0) as f64;
+        reliability * self.priority as f64 * self.asset_value as f64 / 25.0
+    }
+}
+
+/// 進行中の相関状態（ディレクティブ × 相関キーごとに1つ）
+#[derive(Debug, Clone)]
+struct Backlog {
+    /// 対象ディレクティブID
+    directive_id: String,
+    /// 現在のステージ位置
+    stage: usize,
+    /// 現ステージの発生回数
+    count: u32,
+    /// 現ステージに入った時刻（タイムアウト判定の起点）
+    stage_started_at: DateTime<Utc>,
+    /// これまでにマッチしたイベントID
+    matched_event_ids: Vec<String>,
+}
+
+/// 相関エンジンが発火したときの結果
+#[derive(Debug, Clone)]
+pub struct CorrelationHit {
+    /// 発火したディレクティブ
+    pub directive_id: String,
+    /// 算出されたリスク値
+    pub risk: f64,
+    /// 寄与したイベントID
+    pub matched_event_ids: Vec<String>,
+    /// 重大度
+    pub severity: SeverityLevel,
+    /// タイトル
+    pub title: String,
+}
+
+/// イベント相関エンジン
+pub struct CorrelationEngine {
+    /// 登録済みディレクティブ
+    directives: Vec<Directive>,
+    /// 進行中バックログ（キー: "<directive_id>:<correlation_key>"）
+    backlogs: HashMap<String, Backlog>,
+    /// インシデント発火のリスク閾値
+    risk_threshold: f64,
+    /// 同時保持するバックログ数の上限（メモリ上限）
+    max_backlogs: usize,
+}
+
+impl CorrelationEngine {
+    /// 新しい相関エンジンを作成
+    pub fn new(risk_threshold: f64, max_backlogs: usize) -> Self {
+        Self {
+            directives: Vec::new(),
+            backlogs: HashMap::new(),
+            risk_threshold,
+            max_backlogs,
+        }
+    }
+
+    /// ディレクティブを登録
+    pub fn add_directive(&mut self, directive: Directive) {
+        self.directives.push(directive);
+    }
+
+    /// イベントを投入し、閾値を超えたディレクティブの発火を返す。
+    ///
+    /// 各ディレクティブについて、進行中バックログがあれば現ステージと照合して前進させ、
+    /// 無ければ先頭ステージにマッチしたときに新規生成する。ステージがタイムアウトした
+    /// バックログは破棄される。
+    pub fn ingest(&mut self, event: &SecurityEvent) -> Vec<CorrelationHit> {
+        self.expire(event.timestamp);
+
+        let mut hits = Vec::new();
+        // directives はループ中に借用衝突しないようインデックスで回す
+        for di in 0..self.directives.len() {
+            if let Some(hit) = self.advance_directive(di, event) {
+                hits.push(hit);
+            }
+        }
+        hits
+    }
+
+    /// 1ディレクティブ分の前進処理
+    fn advance_directive(&mut self, di: usize, event: &SecurityEvent) -> Option<CorrelationHit> {
+        let key = format!("{}:{}", self.directives[di].id, event.correlation_key);
+
+        // 既存バックログがあれば現ステージと照合、無ければ先頭ステージと照合
+        let current_stage = self
+            .backlogs
+            .get(&key)
+            .map(|b| b.stage)
+            .unwrap_or(0);
+        let stage = &self.directives[di].stages[current_stage];
+        if !stage.matcher.matches(event) {
+            return None;
+        }
+
+        let occurrence = stage.occurrence;
+        let is_last = current_stage + 1 >= self.directives[di].stages.len();
+
+        // 新規バックログは上限を超える場合のみ作らない（既存前進は常に許可）
+        if !self.backlogs.contains_key(&key) && self.backlogs.len() >= self.max_backlogs {
+            return None;
+        }
+
+        let backlog = self.backlogs.entry(key.clone()).or_insert_with(|| Backlog {
+            directive_id: self.directives[di].id.clone(),
+            stage: 0,
+            count: 0,
+            stage_started_at: event.timestamp,
+            matched_event_ids: Vec::new(),
+        });
+
+        backlog.count += 1;
+        backlog.matched_event_ids.push(event.id.clone());
+
+        if backlog.count < occurrence {
+            return None;
+        }
+
+        if !is_last {
+            // 次ステージへ前進
+            backlog.stage += 1;
+            backlog.count = 0;
+            backlog.stage_started_at = event.timestamp;
+            return None;
+        }
+
+        // 最終ステージを満たした → リスクを評価
+        let risk = self.directives[di].risk_at(current_stage);
+        if risk < self.risk_threshold {
+            return None;
+        }
+
+        let matched_event_ids = backlog.matched_event_ids.clone();
+        self.backlogs.remove(&key);
+        Some(CorrelationHit {
+            directive_id: self.directives[di].id.clone(),
+            risk,
+            matched_event_ids,
+            severity: self.directives[di].severity.clone(),
+            title: self.directives[di].name.clone(),
+        })
+    }
+
+    /// 現ステージがタイムアウトしたバックログを破棄する
+    fn expire(&mut self, now: DateTime<Utc>) {
+        let directives = &self.directives;
+        self.backlogs.retain(|_, b| {
+            let Some(directive) = directives.iter().find(|d| d.id == b.directive_id) else {
+                return false;
+            };
+            let timeout = directive.stages[b.stage].timeout;
+            let elapsed = now
+                .signed_duration_since(b.stage_started_at)
+                .to_std()
+                .unwrap_or(Duration::ZERO);
+            elapsed <= timeout
+        });
+    }
+
+    /// 現在のバックログ数（メトリクス/テスト用）
+    pub fn backlog_count(&self) -> usize {
+        self.backlogs.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(cat: &str, key: &str, id: &str, at: DateTime<Utc>) -> SecurityEvent {
+        SecurityEvent {
+            id: id.to_string(),
+            category: cat.to_string(),
+            subcategory: "login_failed".to_string(),
+            event_type: 1,
+            correlation_key: key.to_string(),
+            timestamp: at,
+        }
+    }
+
+    fn brute_force_directive() -> Directive {
+        Directive {
+            id: "D-AUTH-BRUTEFORCE".to_string(),
+            name: "Credential stuffing from single source".to_string(),
+            priority: 8,
+            asset_value: 5,
+            severity: SeverityLevel::High,
+            stages: vec![CorrelationStage {
+                matcher: StageMatcher::Category {
+                    category: "auth".to_string(),
+                    subcategory: Some("login_failed".to_string()),
+                },
+                occurrence: 3,
+                timeout: Duration::from_secs(60),
+                reliability: 8,
+            }],
+        }
+    }
+
+    #[test]
+    fn fires_when_occurrence_reached() {
+        let mut engine = CorrelationEngine::new(10.0, 1000);
+        engine.add_directive(brute_force_directive());
+
+        let t0 = Utc::now();
+        assert!(engine.ingest(&event("auth", "node-a", "e1", t0)).is_empty());
+        assert!(engine.ingest(&event("auth", "node-a", "e2", t0)).is_empty());
+        let hits = engine.ingest(&event("auth", "node-a", "e3", t0));
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].matched_event_ids, vec!["e1", "e2", "e3"]);
+        // 8 * 8 * 5 / 25 = 12.8 >= 10.0
+        assert!(hits[0].risk >= 10.0);
+        // 発火後はバックログが解放される
+        assert_eq!(engine.backlog_count(), 0);
+    }
+
+    #[test]
+    fn expires_stale_backlog_on_timeout() {
+        let mut engine = CorrelationEngine::new(10.0, 1000);
+        engine.add_directive(brute_force_directive());
+
+        let t0 = Utc::now();
+        engine.ingest(&event("auth", "node-a", "e1", t0));
+        // タイムアウト窓を超えた2件目はバックログを失効させ、新規カウントになる
+        let late = t0 + chrono::Duration::seconds(120);
+        assert!(engine.ingest(&event("auth", "node-a", "e2", late)).is_empty());
+        assert_eq!(engine.backlog_count(), 1);
+    }
+
+    #[test]
+    fn caps_concurrent_backlogs() {
+        let mut engine = CorrelationEngine::new(10.0, 1);
+        engine.add_directive(brute_force_directive());
+
+        let t0 = Utc::now();
+        engine.ingest(&event("auth", "node-a", "e1", t0));
+        // 2つ目の相関キーは上限超過のため作られない
+        engine.ingest(&event("auth", "node-b", "e2", t0));
+        assert_eq!(engine.backlog_count(), 1);
+    }
+}
@@ -11,12 +11,16 @@
 mod vulnerability_scanner;
 mod audit;
 mod incident_response;
+mod correlation;
 // mod anomaly_detection; // TODO: このモジュールが見つかりません
 // mod security_policy; // TODO: このモジュールが見つかりません
 
 pub use self::vulnerability_scanner::{VulnerabilityScanner, VulnerabilityReport, Vulnerability, SeverityLevel};
 pub use self::audit::{SecurityAuditor, AuditReport, AuditFinding};
 pub use self::incident_response::{IncidentResponseManager, SecurityIncident, IncidentStatus};
+pub use self::correlation::{
+    CorrelationEngine, CorrelationHit, CorrelationStage, Directive, SecurityEvent, StageMatcher,
+};
 pub use self::anomaly_detection::{AnomalyDetector, AnomalyReport, AnomalyType};
 pub use self::security_policy::{SecurityPolicyManager, SecurityPolicy, PolicyViolation};
 
@@ -25,8 +29,82 @@ use crate::metrics::MetricsCollector;
 use std::sync::{Arc, Mutex};
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
 use log::{debug, error, info, warn};
 
+/// 基準時刻から所見時刻までの経過時間（負なら 0）
+fn age_since(now: chrono::DateTime<chrono::Utc>, at: chrono::DateTime<chrono::Utc>) -> Duration {
+    now.signed_duration_since(at)
+        .to_std()
+        .unwrap_or(Duration::ZERO)
+}
+
+/// メトリクスゲージ名に使う重大度ラベル
+fn severity_label(severity: &SeverityLevel) -> &'static str {
+    match severity {
+        SeverityLevel::Critical => "critical",
+        SeverityLevel::High => "high",
+        SeverityLevel::Medium => "medium",
+        SeverityLevel::Low => "low",
+        SeverityLevel::Info => "info",
+    }
+}
+
+/// セキュリティスコアの減衰モデル設定
+///
+/// 各 `SeverityLevel` ごとに半減期を持ち、古い脆弱性/インシデントの減点は
+/// `exp(-ln(2) * age / half_life)` で時間とともに小さくなる。これにより、
+/// 放置された古い重大脆弱性が永久に減点し続けることを防ぎ、環境を整理すれば
+/// スコアが自然に回復する。
+#[derive(Debug, Clone)]
+pub struct ScoreConfig {
+    /// Critical 所見の半減期
+    pub critical_half_life: Duration,
+    /// High 所見の半減期
+    pub high_half_life: Duration,
+    /// Medium 所見の半減期
+    pub medium_half_life: Duration,
+    /// Low 所見の半減期
+    pub low_half_life: Duration,
+    /// Info 所見の半減期
+    pub info_half_life: Duration,
+}
+
+impl ScoreConfig {
+    /// 指定重大度の半減期を返す
+    fn half_life(&self, severity: &SeverityLevel) -> Duration {
+        match severity {
+            SeverityLevel::Critical => self.critical_half_life,
+            SeverityLevel::High => self.high_half_life,
+            SeverityLevel::Medium => self.medium_half_life,
+            SeverityLevel::Low => self.low_half_life,
+            SeverityLevel::Info => self.info_half_life,
+        }
+    }
+
+    /// 経過時間に対する減衰係数（0.0〜1.0）を計算する
+    fn decay_factor(&self, severity: &SeverityLevel, age: Duration) -> f64 {
+        let half_life = self.half_life(severity).as_secs_f64();
+        if half_life <= 0.0 {
+            return 0.0;
+        }
+        (-std::f64::consts::LN_2 * age.as_secs_f64() / half_life).exp()
+    }
+}
+
+impl Default for ScoreConfig {
+    fn default() -> Self {
+        // Critical はゆっくり、Info は速く減衰する
+        Self {
+            critical_half_life: Duration::from_secs(30 * 24 * 3600),
+            high_half_life: Duration::from_secs(14 * 24 * 3600),
+            medium_half_life: Duration::from_secs(7 * 24 * 3600),
+            low_half_life: Duration::from_secs(2 * 24 * 3600),
+            info_half_life: Duration::from_secs(12 * 3600),
+        }
+    }
+}
+
 /// セキュリティマネージャー
 pub struct SecurityManager {
     /// 脆弱性スキャナー
@@ -42,15 +120,19 @@ pub struct SecurityManager {
     /// メトリクスコレクター
     metrics: Arc<MetricsCollector>,
     /// 最後のスキャン時刻
-    last_scan: Arc<Mutex<Instant>>,
+    last_scan: Arc<RwLock<Instant>>,
     /// 検出された脆弱性
-    detected_vulnerabilities: Arc<Mutex<HashMap<String, Vulnerability>>>,
+    detected_vulnerabilities: Arc<RwLock<HashMap<String, Vulnerability>>>,
     /// 検出された異常
-    detected_anomalies: Arc<Mutex<Vec<AnomalyReport>>>,
+    detected_anomalies: Arc<RwLock<Vec<AnomalyReport>>>,
     /// アクティブなインシデント
-    active_incidents: Arc<Mutex<HashMap<String, SecurityIncident>>>,
+    active_incidents: Arc<RwLock<HashMap<String, SecurityIncident>>>,
     /// セキュリティスコア
-    security_score: Arc<Mutex<f64>>,
+    security_score: Arc<RwLock<f64>>,
+    /// イベント相関エンジン
+    correlation_engine: Arc<Mutex<CorrelationEngine>>,
+    /// スコア減衰モデル設定
+    score_config: ScoreConfig,
 }
 
 impl SecurityManager {
@@ -63,31 +145,83 @@ impl SecurityManager {
             anomaly_detector: AnomalyDetector::new(),
             security_policy_manager: SecurityPolicyManager::new(),
             metrics,
-            last_scan: Arc::new(Mutex::new(Instant::now())),
-            detected_vulnerabilities: Arc::new(Mutex::new(HashMap::new())),
-            detected_anomalies: Arc::new(Mutex::new(Vec::new())),
-            active_incidents: Arc::new(Mutex::new(HashMap::new())),
-            security_score: Arc::new(Mutex::new(100.0)), // 初期スコアは100点満点
+            last_scan: Arc::new(RwLock::new(Instant::now())),
+            detected_vulnerabilities: Arc::new(RwLock::new(HashMap::new())),
+            detected_anomalies: Arc::new(RwLock::new(Vec::new())),
+            active_incidents: Arc::new(RwLock::new(HashMap::new())),
+            security_score: Arc::new(RwLock::new(100.0)), // 初期スコアは100点満点
+            // リスク閾値10.0、バックログ上限10,000件でエンジンを初期化
+            correlation_engine: Arc::new(Mutex::new(CorrelationEngine::new(10.0, 10_000))),
+            score_config: ScoreConfig::default(),
         }
     }
+
+    /// スコア減衰モデルを設定する
+    pub fn set_score_config(&mut self, config: ScoreConfig) {
+        self.score_config = config;
+    }
+
+    /// 相関ディレクティブを登録
+    pub fn add_correlation_directive(&self, directive: Directive) {
+        self.correlation_engine.lock().unwrap().add_directive(directive);
+    }
+
+    /// セキュリティイベントを相関エンジンに投入し、発火した相関からインシデントを生成する。
+    ///
+    /// 単発では低重大度のイベントでも、ディレクティブの各ステージを時間窓内で満たすと
+    /// `SecurityIncident` に昇格する。関連イベントIDは `related_vulnerabilities` に転記する。
+    pub async fn correlate_event(&self, event: SecurityEvent) -> Result<Vec<String>, Error> {
+        let hits = self.correlation_engine.lock().unwrap().ingest(&event);
+
+        let mut incident_ids = Vec::new();
+        for hit in hits {
+            let incident = SecurityIncident {
+                id: format!("INC-{}", uuid::Uuid::new_v4()),
+                title: format!("Correlated threat: {}", hit.title),
+                description: format!(
+                    "Directive {} fired with risk {:.1} across {} events",
+                    hit.directive_id,
+                    hit.risk,
+                    hit.matched_event_ids.len()
+                ),
+                severity: hit.severity.clone(),
+                status: IncidentStatus::Open,
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+                assigned_to: None,
+                related_vulnerabilities: hit.matched_event_ids,
+                resolution: None,
+            };
+
+            incident_ids.push(incident.id.clone());
+            self.create_incident(incident).await?;
+            self.metrics.increment_counter("security_correlations_fired");
+        }
+
+        Ok(incident_ids)
+    }
     
     /// 脆弱性スキャンを実行
     pub async fn scan_for_vulnerabilities(&self) -> Result<VulnerabilityReport, Error> {
         info!("Starting vulnerability scan");
         
-        // スキャンを実行
-        let report = self.vulnerability_scanner.scan_system().await?;
-        
+        // スキャンはCPU負荷が高く同期的なので、spawn_blockingでオフロードして
+        // Tokioワーカースレッドを塞がないようにする
+        let scanner = self.vulnerability_scanner.clone();
+        let report = tokio::task::spawn_blocking(move || scanner.scan_system_blocking())
+            .await
+            .map_err(|e| Error::InternalError(format!("vulnerability scan task failed: {}", e)))??;
+
         // 検出された脆弱性を保存
         {
-            let mut vulnerabilities = self.detected_vulnerabilities.lock().unwrap();
+            let mut vulnerabilities = self.detected_vulnerabilities.write().await;
             for vulnerability in &report.vulnerabilities {
                 vulnerabilities.insert(vulnerability.id.clone(), vulnerability.clone());
             }
         }
-        
+
         // 最後のスキャン時刻を更新
-        *self.last_scan.lock().unwrap() = Instant::now();
+        *self.last_scan.write().await = Instant::now();
         
         // メトリクスを更新
         self.metrics.set_gauge("security_vulnerabilities_total", report.vulnerabilities.len() as f64);
@@ -97,8 +231,8 @@ impl SecurityManager {
             report.vulnerabilities.iter().filter(|v| v.severity == SeverityLevel::High).count() as f64);
         
         // セキュリティスコアを更新
-        self.update_security_score();
-        
+        self.update_security_score().await;
+
         // 重大な脆弱性が見つかった場合はインシデントを作成
         for vulnerability in &report.vulnerabilities {
             if vulnerability.severity == SeverityLevel::Critical {
@@ -114,12 +248,8 @@ impl SecurityManager {
                     related_vulnerabilities: vec![vulnerability.id.clone()],
                     resolution: None,
                 };
-                
-                self.incident_response_manager.create_incident(incident.clone())?;
-                
-                // アクティブなインシデントに追加
-                let mut active_incidents = self.active_incidents.lock().unwrap();
-                active_incidents.insert(incident.id.clone(), incident);
+
+                self.create_incident(incident).await?;
             }
         }
         
@@ -135,12 +265,14 @@ impl SecurityManager {
         
         // 検出された異常を保存
         if report.anomalies.len() > 0 {
-            let mut anomalies = self.detected_anomalies.lock().unwrap();
-            anomalies.push(report.clone());
-            
+            {
+                let mut anomalies = self.detected_anomalies.write().await;
+                anomalies.push(report.clone());
+            }
+
             // メトリクスを更新
             self.metrics.increment_counter_by("security_anomalies_detected", report.anomalies.len() as u64);
-            
+
             // 重大な異常が見つかった場合はインシデントを作成
             for anomaly in &report.anomalies {
                 if anomaly.severity == SeverityLevel::Critical || anomaly.severity == SeverityLevel::High {
@@ -156,33 +288,45 @@ impl SecurityManager {
                         related_vulnerabilities: Vec::new(),
                         resolution: None,
                     };
-                    
-                    self.incident_response_manager.create_incident(incident.clone())?;
-                    
-                    // アクティブなインシデントに追加
-                    let mut active_incidents = self.active_incidents.lock().unwrap();
-                    active_incidents.insert(incident.id.clone(), incident);
+
+                    self.create_incident(incident).await?;
                 }
             }
+
+            // 検出した各異常を相関エンジンにイベントとして投入し、多段の攻撃兆候を捕捉する
+            for (idx, anomaly) in report.anomalies.iter().enumerate() {
+                let event = SecurityEvent {
+                    id: format!("EVT-{}-{}", report.id, idx),
+                    category: "anomaly".to_string(),
+                    subcategory: anomaly.title.clone(),
+                    event_type: 0,
+                    correlation_key: report.source.clone(),
+                    timestamp: chrono::Utc::now(),
+                };
+                self.correlate_event(event).await?;
+            }
         }
-        
+
         Ok(report)
     }
-    
+
     /// セキュリティ監査を実行
     pub async fn perform_security_audit(&self) -> Result<AuditReport, Error> {
         info!("Starting security audit");
         
-        // 監査を実行
-        let report = self.security_auditor.audit_system().await?;
-        
+        // 監査はCPU負荷が高く同期的なので、spawn_blockingでオフロードする
+        let auditor = self.security_auditor.clone();
+        let report = tokio::task::spawn_blocking(move || auditor.audit_system_blocking())
+            .await
+            .map_err(|e| Error::InternalError(format!("security audit task failed: {}", e)))??;
+
         // メトリクスを更新
         self.metrics.set_gauge("security_audit_findings_total", report.findings.len() as f64);
-        self.metrics.set_gauge("security_audit_findings_critical", 
+        self.metrics.set_gauge("security_audit_findings_critical",
             report.findings.iter().filter(|f| f.severity == SeverityLevel::Critical).count() as f64);
-        
+
         // セキュリティスコアを更新
-        self.update_security_score();
+        self.update_security_score().await;
         
         info!("Security audit completed: {} findings", report.findings.len());
         
@@ -190,34 +334,40 @@ impl SecurityManager {
     }
     
     /// インシデントを作成
-    pub fn create_incident(&self, incident: SecurityIncident) -> Result<(), Error> {
+    pub async fn create_incident(&self, incident: SecurityIncident) -> Result<(), Error> {
         // インシデントを作成
         self.incident_response_manager.create_incident(incident.clone())?;
-        
+
         // アクティブなインシデントに追加
-        let mut active_incidents = self.active_incidents.lock().unwrap();
-        active_incidents.insert(incident.id.clone(), incident);
-        
+        let active_count = {
+            let mut active_incidents = self.active_incidents.write().await;
+            active_incidents.insert(incident.id.clone(), incident);
+            active_incidents.len()
+        };
+
         // メトリクスを更新
         self.metrics.increment_counter("security_incidents_created");
-        self.metrics.set_gauge("security_incidents_active", active_incidents.len() as f64);
-        
+        self.metrics.set_gauge("security_incidents_active", active_count as f64);
+
         Ok(())
     }
-    
+
     /// インシデントを解決
-    pub fn resolve_incident(&self, incident_id: &str, resolution: &str) -> Result<(), Error> {
+    pub async fn resolve_incident(&self, incident_id: &str, resolution: &str) -> Result<(), Error> {
         // インシデントを解決
         self.incident_response_manager.resolve_incident(incident_id, resolution)?;
-        
+
         // アクティブなインシデントから削除
-        let mut active_incidents = self.active_incidents.lock().unwrap();
-        active_incidents.remove(incident_id);
-        
+        let active_count = {
+            let mut active_incidents = self.active_incidents.write().await;
+            active_incidents.remove(incident_id);
+            active_incidents.len()
+        };
+
         // メトリクスを更新
         self.metrics.increment_counter("security_incidents_resolved");
-        self.metrics.set_gauge("security_incidents_active", active_incidents.len() as f64);
-        
+        self.metrics.set_gauge("security_incidents_active", active_count as f64);
+
         Ok(())
     }
     
@@ -232,69 +382,99 @@ impl SecurityManager {
     }
     
     /// セキュリティスコアを更新
-    fn update_security_score(&self) {
-        let mut score = 100.0;
-        
-        // 脆弱性に基づいてスコアを減点
+    ///
+    /// 各所見の減点は、発見からの経過時間に応じて半減期で減衰させる。こうすることで、
+    /// 古くなった所見は自然に影響が薄れ、環境を整理すればスコアが回復する。重大度階層
+    /// ごとの減衰後寄与はゲージとして公開し、どの種類の所見がスコアを下げているかを
+    /// 運用者が把握できるようにする。
+    async fn update_security_score(&self) {
+        let now = chrono::Utc::now();
+
+        // 重大度階層ごとの減衰後寄与を集計する
+        let mut tier_penalties: HashMap<&'static str, f64> = HashMap::new();
+
+        // 脆弱性に基づく減点（基準値 × 減衰係数）
         {
-            let vulnerabilities = self.detected_vulnerabilities.lock().unwrap();
-            
-            // 重大度に応じた減点
+            let vulnerabilities = self.detected_vulnerabilities.read().await;
             for vulnerability in vulnerabilities.values() {
-                match vulnerability.severity {
-                    SeverityLevel::Critical => score -= 10.0,
-                    SeverityLevel::High => score -= 5.0,
-                    SeverityLevel::Medium => score -= 2.0,
-                    SeverityLevel::Low => score -= 0.5,
-                    SeverityLevel::Info => score -= 0.1,
-                }
+                let base = Self::vulnerability_penalty(&vulnerability.severity);
+                let age = age_since(now, vulnerability.detected_at);
+                let decayed = base * self.score_config.decay_factor(&vulnerability.severity, age);
+                *tier_penalties
+                    .entry(severity_label(&vulnerability.severity))
+                    .or_insert(0.0) += decayed;
             }
         }
-        
-        // アクティブなインシデントに基づいてスコアを減点
+
+        // アクティブなインシデントに基づく減点
         {
-            let active_incidents = self.active_incidents.lock().unwrap();
-            
-            // 重大度に応じた減点
+            let active_incidents = self.active_incidents.read().await;
             for incident in active_incidents.values() {
-                match incident.severity {
-                    SeverityLevel::Critical => score -= 15.0,
-                    SeverityLevel::High => score -= 7.5,
-                    SeverityLevel::Medium => score -= 3.0,
-                    SeverityLevel::Low => score -= 1.0,
-                    SeverityLevel::Info => score -= 0.2,
-                }
+                let base = Self::incident_penalty(&incident.severity);
+                let age = age_since(now, incident.created_at);
+                let decayed = base * self.score_config.decay_factor(&incident.severity, age);
+                *tier_penalties
+                    .entry(severity_label(&incident.severity))
+                    .or_insert(0.0) += decayed;
             }
         }
-        
-        // スコアを0以上100以下に制限
-        score = score.max(0.0).min(100.0);
-        
+
+        // 減衰後の減点を合算し、0〜100にクランプ
+        let total_penalty: f64 = tier_penalties.values().sum();
+        let score = (100.0 - total_penalty).max(0.0).min(100.0);
+
         // スコアを更新
-        *self.security_score.lock().unwrap() = score;
-        
-        // メトリクスを更新
+        *self.security_score.write().await = score;
+
+        // メトリクスを更新（総合スコアと階層別の減衰後寄与）
         self.metrics.set_gauge("security_score", score);
+        for label in ["critical", "high", "medium", "low", "info"] {
+            let contribution = tier_penalties.get(label).copied().unwrap_or(0.0);
+            self.metrics
+                .set_gauge(&format!("security_score_penalty_{}", label), contribution);
+        }
     }
-    
-    /// セキュリティスコアを取得
-    pub fn get_security_score(&self) -> f64 {
-        *self.security_score.lock().unwrap()
+
+    /// 脆弱性1件あたりの基準減点
+    fn vulnerability_penalty(severity: &SeverityLevel) -> f64 {
+        match severity {
+            SeverityLevel::Critical => 10.0,
+            SeverityLevel::High => 5.0,
+            SeverityLevel::Medium => 2.0,
+            SeverityLevel::Low => 0.5,
+            SeverityLevel::Info => 0.1,
+        }
     }
-    
+
+    /// インシデント1件あたりの基準減点
+    fn incident_penalty(severity: &SeverityLevel) -> f64 {
+        match severity {
+            SeverityLevel::Critical => 15.0,
+            SeverityLevel::High => 7.5,
+            SeverityLevel::Medium => 3.0,
+            SeverityLevel::Low => 1.0,
+            SeverityLevel::Info => 0.2,
+        }
+    }
+
+    /// セキュリティスコアを取得（読み取りガードなので並行読み取りはブロックしない）
+    pub async fn get_security_score(&self) -> f64 {
+        *self.security_score.read().await
+    }
+
     /// 検出された脆弱性を取得
-    pub fn get_detected_vulnerabilities(&self) -> HashMap<String, Vulnerability> {
-        self.detected_vulnerabilities.lock().unwrap().clone()
+    pub async fn get_detected_vulnerabilities(&self) -> HashMap<String, Vulnerability> {
+        self.detected_vulnerabilities.read().await.clone()
     }
     
     /// アクティブなインシデントを取得
-    pub fn get_active_incidents(&self) -> HashMap<String, SecurityIncident> {
-        self.active_incidents.lock().unwrap().clone()
+    pub async fn get_active_incidents(&self) -> HashMap<String, SecurityIncident> {
+        self.active_incidents.read().await.clone()
     }
-    
+
     /// 最後のスキャン時刻を取得
-    pub fn get_last_scan_time(&self) -> Instant {
-        *self.last_scan.lock().unwrap()
+    pub async fn get_last_scan_time(&self) -> Instant {
+        *self.last_scan.read().await
     }
 }
 
@@ -314,7 +494,7 @@ mod tests {
         let manager = SecurityManager::new(metrics);
         
         // 初期セキュリティスコアを確認
-        assert_eq!(manager.get_security_score(), 100.0);
+        assert_eq!(manager.get_security_score().await, 100.0);
         
         // 脆弱性スキャンを実行
         let report = manager.scan_for_vulnerabilities().await.unwrap();
@@ -349,18 +529,18 @@ mod tests {
             resolution: None,
         };
         
-        manager.create_incident(incident).unwrap();
-        
+        manager.create_incident(incident).await.unwrap();
+
         // アクティブなインシデントを確認
-        let active_incidents = manager.get_active_incidents();
+        let active_incidents = manager.get_active_incidents().await;
         assert_eq!(active_incidents.len(), 1);
         assert!(active_incidents.contains_key("INC-TEST-001"));
-        
+
         // インシデントを解決
-        manager.resolve_incident("INC-TEST-001", "Test resolution").unwrap();
-        
+        manager.resolve_incident("INC-TEST-001", "Test resolution").await.unwrap();
+
         // アクティブなインシデントが減少したことを確認
-        let active_incidents = manager.get_active_incidents();
+        let active_incidents = manager.get_active_incidents().await;
         assert_eq!(active_incidents.len(), 0);
     }
 }
\ No newline at end of file
@@ -0,0 +1,244 @@
+//! パーティション（DAGのトランザクションやキー範囲の断片）のシャードへの割り当て
+//!
+//! Garageの`rpc/graph_algo.rs`・`rpc/layout.rs`にならい、シャードごとの容量を
+//! 尊重しつつ、既存の割り当てからの変更（再配置によるチャーン）を最小化する
+//! 割り当てを、二部グラフ上の最小費用最大流問題として解く。
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::error::Error;
+use crate::sharding::manager::ShardId;
+
+/// パーティション（DAGのトランザクションやキー範囲の断片）を識別するID
+pub type PartitionId = String;
+
+/// 割り当て結果: パーティションごとに割り当てられたシャードの一覧
+#[derive(Debug, Clone, Default)]
+pub struct Assignment {
+    /// パーティションID -> 割り当てられたシャードID一覧
+    pub partition_to_shards: HashMap<PartitionId, Vec<ShardId>>,
+}
+
+impl Assignment {
+    /// 指定したパーティションに指定したシャードが割り当てられているか
+    pub fn is_assigned(&self, partition: &PartitionId, shard: ShardId) -> bool {
+        self.partition_to_shards
+            .get(partition)
+            .is_some_and(|shards| shards.contains(&shard))
+    }
+}
+
+/// シャード割り当てレイアウト計算機
+///
+/// パーティション -> シャード の多対多割り当てを、各シャードの容量を超えない
+/// ように、かつ既存の割り当て（`prev_layout`）からの変更を最小化するように
+/// 計算する。
+pub struct ShardLayout {
+    /// パーティションあたりの複製数
+    replication_factor: usize,
+    /// シャードごとの目標負荷（割り当て可能なパーティション数の上限）
+    shard_capacity: HashMap<ShardId, usize>,
+}
+
+impl ShardLayout {
+    /// 新しいShardLayoutを作成
+    pub fn new(replication_factor: usize) -> Self {
+        Self {
+            replication_factor: replication_factor.max(1),
+            shard_capacity: HashMap::new(),
+        }
+    }
+
+    /// シャードの容量（割り当て可能なパーティション数の上限）を設定
+    pub fn with_capacity(mut self, shard: ShardId, capacity: usize) -> Self {
+        self.shard_capacity.insert(shard, capacity);
+        self
+    }
+
+    /// 指定したシャードの容量を取得（未設定の場合は均等割りの既定値を使う）
+    fn capacity_of(&self, shard: ShardId, partitions: usize, shards: usize) -> usize {
+        self.shard_capacity.get(&shard).copied().unwrap_or_else(|| {
+            let total_slots = partitions * self.replication_factor;
+            total_slots.div_ceil(shards.max(1))
+        })
+    }
+
+    /// パーティションをシャードへ割り当てる
+    ///
+    /// 二部最小費用最大流として定式化する:
+    /// source -> partition（容量=replication_factor、コスト0）
+    /// partition -> shard（容量1、既存の割り当てはコスト0・新規の割り当てはコスト1）
+    /// shard -> sink（容量=シャード容量、コスト0）
+    /// を連続最短増加路法（SPFAによるBellman-Ford）で解く。
+    pub fn compute_assignment(
+        &self,
+        partitions: &[PartitionId],
+        shards: &[ShardId],
+        prev_layout: Option<&Assignment>,
+    ) -> Result<Assignment, Error> {
+        if partitions.is_empty() || shards.is_empty() {
+            return Ok(Assignment::default());
+        }
+
+        let p = partitions.len();
+        let s = shards.len();
+        let source = 0usize;
+        let partition_base = 1usize;
+        let shard_base = partition_base + p;
+        let sink = shard_base + s;
+        let node_count = sink + 1;
+
+        let mut graph = MinCostFlowGraph::new(node_count);
+
+        for i in 0..p {
+            graph.add_edge(source, partition_base + i, self.replication_factor as i64, 0);
+        }
+
+        let mut pair_edge: Vec<Vec<usize>> = Vec::with_capacity(p);
+        for partition in partitions {
+            let mut row = Vec::with_capacity(s);
+            for shard in shards {
+                let is_existing =
+                    prev_layout.is_some_and(|layout| layout.is_assigned(partition, *shard));
+                let cost = if is_existing { 0 } else { 1 };
+                row.push(cost);
+            }
+            pair_edge.push(row);
+        }
+
+        let mut pair_edge_idx = vec![vec![0usize; s]; p];
+        for (i, row) in pair_edge.iter().enumerate() {
+            for (j, &cost) in row.iter().enumerate() {
+                let idx = graph.add_edge(partition_base + i, shard_base + j, 1, cost);
+                pair_edge_idx[i][j] = idx;
+            }
+        }
+
+        for (j, shard) in shards.iter().enumerate() {
+            let capacity = self.capacity_of(*shard, p, s) as i64;
+            graph.add_edge(shard_base + j, sink, capacity, 0);
+        }
+
+        graph.min_cost_flow(source, sink);
+
+        let mut partition_to_shards: HashMap<PartitionId, Vec<ShardId>> = partitions
+            .iter()
+            .cloned()
+            .map(|partition| (partition, Vec::new()))
+            .collect();
+
+        for (i, partition) in partitions.iter().enumerate() {
+            for (j, shard) in shards.iter().enumerate() {
+                if graph.flow_through(pair_edge_idx[i][j]) > 0 {
+                    partition_to_shards.get_mut(partition).unwrap().push(*shard);
+                }
+            }
+        }
+
+        Ok(Assignment { partition_to_shards })
+    }
+}
+
+/// 最小費用最大流を解くための単純な隣接リストグラフ（SPFAによる連続最短増加路法）
+struct MinCostFlowGraph {
+    /// 各辺: (行き先, 残余容量, コスト, 逆辺のインデックス)
+    edges: Vec<(usize, i64, i64, usize)>,
+    /// ノードごとの出辺インデックス一覧
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl MinCostFlowGraph {
+    fn new(node_count: usize) -> Self {
+        Self {
+            edges: Vec::new(),
+            adjacency: vec![Vec::new(); node_count],
+        }
+    }
+
+    /// 辺を追加し、正辺（順方向）のインデックスを返す
+    fn add_edge(&mut self, from: usize, to: usize, capacity: i64, cost: i64) -> usize {
+        let forward_idx = self.edges.len();
+        self.adjacency[from].push(forward_idx);
+        self.edges.push((to, capacity, cost, forward_idx + 1));
+
+        let backward_idx = self.edges.len();
+        self.adjacency[to].push(backward_idx);
+        self.edges.push((from, 0, -cost, forward_idx));
+
+        forward_idx
+    }
+
+    /// SPFA（キューを使ったBellman-Ford）で最短費用路を探索し、見つからなくなるまで流す
+    fn min_cost_flow(&mut self, source: usize, sink: usize) -> i64 {
+        let node_count = self.adjacency.len();
+        let mut total_cost = 0;
+
+        loop {
+            let mut dist = vec![i64::MAX; node_count];
+            let mut in_queue = vec![false; node_count];
+            let mut prev_edge = vec![usize::MAX; node_count];
+            dist[source] = 0;
+
+            let mut queue = VecDeque::new();
+            queue.push_back(source);
+            in_queue[source] = true;
+
+            while let Some(node) = queue.pop_front() {
+                in_queue[node] = false;
+                let current_dist = dist[node];
+                for &edge_idx in &self.adjacency[node] {
+                    let (to, capacity, cost, _) = self.edges[edge_idx];
+                    if capacity > 0 && current_dist + cost < dist[to] {
+                        dist[to] = current_dist + cost;
+                        prev_edge[to] = edge_idx;
+                        if !in_queue[to] {
+                            queue.push_back(to);
+                            in_queue[to] = true;
+                        }
+                    }
+                }
+            }
+
+            if dist[sink] == i64::MAX {
+                break;
+            }
+
+            // 増加路に沿って流せる最大量（ボトルネック容量）を求める
+            let mut augment = i64::MAX;
+            let mut node = sink;
+            while node != source {
+                let edge_idx = prev_edge[node];
+                if edge_idx == usize::MAX {
+                    augment = 0;
+                    break;
+                }
+                augment = augment.min(self.edges[edge_idx].1);
+                let rev_idx = self.edges[edge_idx].3;
+                node = self.edges[rev_idx].0;
+            }
+
+            if augment <= 0 {
+                break;
+            }
+
+            let mut node = sink;
+            while node != source {
+                let edge_idx = prev_edge[node];
+                self.edges[edge_idx].1 -= augment;
+                let rev_idx = self.edges[edge_idx].3;
+                self.edges[rev_idx].1 += augment;
+                node = self.edges[rev_idx].0;
+            }
+
+            total_cost += augment * dist[sink];
+        }
+
+        total_cost
+    }
+
+    /// 指定した辺（容量1の正辺を想定）に実際に流れた量
+    fn flow_through(&self, forward_edge_idx: usize) -> i64 {
+        let (_, residual_capacity, _, _) = self.edges[forward_edge_idx];
+        1 - residual_capacity
+    }
+}
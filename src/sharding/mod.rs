@@ -28,6 +28,8 @@
 //! ```
 
 pub mod assignment;
+pub mod layout;
 pub mod manager;
 
+pub use layout::{Assignment, PartitionId, ShardLayout};
 pub use manager::{NodeId, NodeSpec, Shard, ShardId, ShardManager, ShardType};
@@ -1,10 +1,10 @@
 use chrono::{DateTime, Utc};
 use log::{debug, error, info, warn};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::error::Error;
-use crate::shard::{ShardId, ShardInfo};
+use crate::shard::{ShardId, ShardInfo, ShardStatus};
 use crate::smart_contract::storage::{ContractStorage, StorageError, StorageKey, StorageValue};
 use crate::smart_contract::vm::{ExecutionContext, ExecutionResult, VMError, VirtualMachine};
 use crate::transaction::{Transaction, TransactionStatus};
@@ -42,6 +42,18 @@ pub struct CrossShardCall {
     pub result: Option<CrossShardResult>,
     /// メタデータ
     pub metadata: Option<HashMap<String, String>>,
+    /// 中継シャードID
+    ///
+    /// 送信先シャードが到達不能（非アクティブ等）な場合に、同一ゾーン内の
+    /// 健全なシャードを経由して中継するときの中継先。
+    pub relay_shard_id: Option<ShardId>,
+    /// 二相コミットのコーディネータシャードID
+    ///
+    /// `None` の場合は従来の単相実行。`Some` の場合は `prepare_call` /
+    /// `commit_call` / `abort_call` による二相プロトコルで調整される。
+    pub coordinator_shard_id: Option<ShardId>,
+    /// 準備フェーズでの投票結果（`true` = コミット可）
+    pub vote: Option<bool>,
 }
 
 /// クロスシャード呼び出しステータス
@@ -63,6 +75,14 @@ pub enum CrossShardCallStatus {
     TimedOut,
     /// キャンセル
     Cancelled,
+    /// 準備完了（投票済み・コミット待ち）
+    Prepared,
+    /// コミット中
+    Committing,
+    /// アボート中
+    Aborting,
+    /// アボート済み
+    Aborted,
 }
 
 /// クロスシャード結果
@@ -80,6 +100,22 @@ pub struct CrossShardResult {
     pub completed_at: DateTime<Utc>,
 }
 
+/// クロスシャードバッチ
+///
+/// 複数のクロスシャード呼び出しを 1 つの要求としてまとめて投入する。
+/// `ordered` が `true` の場合、直前の呼び出しが成功で終了するまで次の
+/// 呼び出しは実行されず、途中で失敗するとバッチは停止し残りの呼び出しは
+/// `Cancelled` としてマークされる。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrossShardBatch {
+    /// バッチID
+    pub id: String,
+    /// 含まれる呼び出し（投入順）
+    pub calls: Vec<CrossShardCall>,
+    /// 順序実行フラグ
+    pub ordered: bool,
+}
+
 /// クロスシャード実行器
 pub struct CrossShardExecutor<V: VirtualMachine, S: ContractStorage> {
     /// 仮想マシン
@@ -92,6 +128,15 @@ pub struct CrossShardExecutor<V: VirtualMachine, S: ContractStorage> {
     pending_calls: HashMap<String, CrossShardCall>,
     /// 完了した呼び出し
     completed_calls: HashMap<String, CrossShardCall>,
+    /// 投入済みのバッチ
+    batches: HashMap<String, CrossShardBatch>,
+    /// シャードのゾーン割り当て（ゾーン単位の中継先選択に利用）
+    shard_zones: HashMap<ShardId, String>,
+    /// 準備フェーズで退避した対象コントラクトのストレージ事前イメージ
+    ///
+    /// `abort_call` 時にこのスナップショットへロールバックすることで、
+    /// 準備フェーズで適用されたステージ書き込みと値転送を破棄する。
+    staged_snapshots: HashMap<String, Vec<(String, StorageKey, Option<StorageValue>)>>,
     /// 現在のシャードID
     current_shard_id: ShardId,
     /// タイムアウト（秒）
@@ -107,6 +152,9 @@ impl<V: VirtualMachine, S: ContractStorage> CrossShardExecutor<V, S> {
             shard_info: HashMap::new(),
             pending_calls: HashMap::new(),
             completed_calls: HashMap::new(),
+            batches: HashMap::new(),
+            shard_zones: HashMap::new(),
+            staged_snapshots: HashMap::new(),
             current_shard_id,
             timeout_seconds,
         }
@@ -167,6 +215,9 @@ impl<V: VirtualMachine, S: ContractStorage> CrossShardExecutor<V, S> {
             status: CrossShardCallStatus::Pending,
             result: None,
             metadata: None,
+            relay_shard_id: None,
+            coordinator_shard_id: None,
+            vote: None,
         };
 
         // 保留中の呼び出しに追加
@@ -199,13 +250,78 @@ impl<V: VirtualMachine, S: ContractStorage> CrossShardExecutor<V, S> {
             )));
         }
 
-        // 実際の実装では、送信先シャードにメッセージを送信する
+        // 送信先シャードが到達可能（健全）か確認し、不可なら中継先を選ぶ
+        let target_shard_id = call.target_shard_id.clone();
+        let relay = if self.is_shard_reachable(&target_shard_id) {
+            None
+        } else {
+            let relay = self.find_relay_shard(&target_shard_id);
+            if relay.is_none() {
+                return Err(Error::NetworkError(format!(
+                    "Target shard {} unreachable and no healthy relay available",
+                    target_shard_id
+                )));
+            }
+            relay
+        };
+
+        let call = self.pending_calls.get_mut(call_id).unwrap();
+        if let Some(relay_shard) = &relay {
+            debug!(
+                "Relaying cross-shard call {} to {} via {}",
+                call_id, target_shard_id, relay_shard
+            );
+        }
+        call.relay_shard_id = relay;
+
+        // 実際の実装では、送信先（または中継先）シャードにメッセージを送信する
         // ここでは簡易的に送信済みとする
         call.status = CrossShardCallStatus::Sent;
 
         Ok(())
     }
 
+    /// シャードが到達可能（健全）かを判定
+    fn is_shard_reachable(&self, shard_id: &ShardId) -> bool {
+        self.shard_info
+            .get(shard_id)
+            .map(|info| info.status == ShardStatus::Active)
+            .unwrap_or(false)
+    }
+
+    /// 到達不能なシャードの中継先を選択
+    ///
+    /// 送信先と同一ゾーン内のアクティブなシャードを優先し、同一ゾーンに候補が
+    /// なければゾーンを問わず任意のアクティブなシャードを返す。
+    fn find_relay_shard(&self, target_shard_id: &ShardId) -> Option<ShardId> {
+        let target_zone = self.shard_zones.get(target_shard_id);
+
+        // 同一ゾーン内の健全なシャードを優先
+        if let Some(zone) = target_zone {
+            if let Some(id) = self
+                .shard_zones
+                .iter()
+                .filter(|(id, z)| *z == zone && *id != target_shard_id)
+                .map(|(id, _)| id)
+                .find(|id| self.is_shard_reachable(id))
+            {
+                return Some(id.clone());
+            }
+        }
+
+        // 同一ゾーンに候補がなければ任意の健全なシャードを中継先とする
+        self.shard_info
+            .keys()
+            .filter(|id| *id != target_shard_id)
+            .find(|id| self.is_shard_reachable(id))
+            .cloned()
+    }
+
+    /// シャードのゾーンを設定
+    pub fn set_shard_zone(&mut self, shard_id: ShardId, zone: String) {
+        self.shard_zones.insert(shard_id, zone);
+    }
+
     /// クロスシャード呼び出しを受信
     pub fn receive_call(&mut self, call: CrossShardCall) -> Result<(), Error> {
         // 送信先シャードが現在のシャードか確認
@@ -380,6 +496,423 @@ impl<V: VirtualMachine, S: ContractStorage> CrossShardExecutor<V, S> {
         Ok(result)
     }
 
+    /// 二相コミットの準備フェーズを実行
+    ///
+    /// 対象 VM をステージ状態（`is_static: false`）で実行し、対象コントラクトの
+    /// ストレージ事前イメージを退避したうえで投票を行う。成功すれば `Prepared`
+    /// となりコミット待ちになる。失敗すれば退避イメージへ即座にロールバックし
+    /// `Aborted` とする。
+    pub fn prepare_call(
+        &mut self,
+        call_id: &str,
+        coordinator_shard_id: ShardId,
+    ) -> Result<bool, Error> {
+        // 呼び出しを取得し、準備可能な状態か確認
+        {
+            let call = self.pending_calls.get(call_id).ok_or_else(|| {
+                Error::NotFound(format!("Cross-shard call not found: {}", call_id))
+            })?;
+            if call.status != CrossShardCallStatus::Received
+                && call.status != CrossShardCallStatus::Sent
+            {
+                return Err(Error::InvalidState(format!(
+                    "Call is not ready for prepare: {:?}",
+                    call.status
+                )));
+            }
+        }
+
+        // 対象コントラクトのストレージ事前イメージを退避
+        let (target_contract, method, gas_limit, sender, value, args) = {
+            let call = self.pending_calls.get(call_id).unwrap();
+            (
+                call.target_contract.clone(),
+                call.method.clone(),
+                call.gas_limit,
+                call.source_contract.clone(),
+                call.value,
+                call.args.clone(),
+            )
+        };
+
+        if !self.storage.has_contract(&target_contract)? {
+            self.fail_call(call_id, format!("Target contract not found: {}", target_contract));
+            return Ok(false);
+        }
+
+        let pre_keys: HashSet<StorageKey> = self
+            .storage
+            .get_contract_storage_keys(&target_contract)?
+            .into_iter()
+            .collect();
+        let mut snapshot = Vec::with_capacity(pre_keys.len());
+        for key in &pre_keys {
+            let value = self.storage.get_contract_storage(&target_contract, key)?;
+            snapshot.push((target_contract.clone(), key.clone(), value));
+        }
+
+        // ステージ状態で VM を実行
+        let context = ExecutionContext {
+            gas_limit,
+            sender,
+            value,
+            data: args,
+            address: Some(target_contract.clone()),
+            block_height: 0,
+            block_time: Utc::now(),
+            is_static: false,
+            depth: 0,
+        };
+
+        let vote = match self.vm.call(target_contract.clone(), method, context) {
+            Ok(result) => result.success,
+            Err(e) => {
+                warn!("Prepare phase VM error for {}: {}", call_id, e);
+                false
+            }
+        };
+
+        // VM実行によって新規に作成されたキーを「未存在」として事前イメージに追加する。
+        // これを行わないと、実行前に存在しなかったキーへの書き込み（新規フィールドの
+        // 初回書き込みや初回残高付与など）がロールバック時に削除されず、ステージ状態が
+        // 永続的に漏洩してしまう。
+        let post_keys = self.storage.get_contract_storage_keys(&target_contract)?;
+        for key in post_keys {
+            if !pre_keys.contains(&key) {
+                snapshot.push((target_contract.clone(), key, None));
+            }
+        }
+
+        if vote {
+            // 退避イメージを保持し、準備完了とする
+            self.staged_snapshots.insert(call_id.to_string(), snapshot);
+            let call = self.pending_calls.get_mut(call_id).unwrap();
+            call.status = CrossShardCallStatus::Prepared;
+            call.coordinator_shard_id = Some(coordinator_shard_id);
+            call.vote = Some(true);
+        } else {
+            // 投票が No の場合は退避イメージへロールバックしてアボート
+            self.rollback_snapshot(&snapshot);
+            let call = self.pending_calls.get_mut(call_id).unwrap();
+            call.status = CrossShardCallStatus::Aborted;
+            call.coordinator_shard_id = Some(coordinator_shard_id);
+            call.vote = Some(false);
+            call.completed_at = Some(Utc::now());
+        }
+
+        Ok(vote)
+    }
+
+    /// 二相コミットのコミットフェーズを実行
+    ///
+    /// すべての参加シャードが賛成投票した場合にのみコーディネータから呼ばれる。
+    /// ステージ書き込みはすでに適用済みのため、退避イメージを破棄して完了とする。
+    pub fn commit_call(&mut self, call_id: &str) -> Result<CrossShardResult, Error> {
+        {
+            let call = self.pending_calls.get(call_id).ok_or_else(|| {
+                Error::NotFound(format!("Cross-shard call not found: {}", call_id))
+            })?;
+            if call.status != CrossShardCallStatus::Prepared {
+                return Err(Error::InvalidState(format!(
+                    "Call is not prepared: {:?}",
+                    call.status
+                )));
+            }
+            if call.vote != Some(true) {
+                return Err(Error::InvalidState(
+                    "Cannot commit a call that did not vote yes".to_string(),
+                ));
+            }
+        }
+
+        {
+            let call = self.pending_calls.get_mut(call_id).unwrap();
+            call.status = CrossShardCallStatus::Committing;
+        }
+
+        // 退避イメージを破棄（ステージ書き込みを確定）
+        self.staged_snapshots.remove(call_id);
+
+        let result = CrossShardResult {
+            success: true,
+            return_data: Vec::new(),
+            gas_used: 0,
+            error_message: None,
+            completed_at: Utc::now(),
+        };
+
+        let mut call = self.pending_calls.remove(call_id).unwrap();
+        call.status = CrossShardCallStatus::Completed;
+        call.completed_at = Some(result.completed_at);
+        call.result = Some(result.clone());
+        self.completed_calls.insert(call_id.to_string(), call);
+
+        Ok(result)
+    }
+
+    /// 二相コミットのアボートフェーズを実行
+    ///
+    /// いずれかの参加シャードが反対した場合やタイムアウト時に呼ばれ、
+    /// 準備フェーズで退避した事前イメージへロールバックしてステージ書き込みと
+    /// 値転送を破棄する。
+    pub fn abort_call(&mut self, call_id: &str) -> Result<(), Error> {
+        {
+            let call = self.pending_calls.get(call_id).ok_or_else(|| {
+                Error::NotFound(format!("Cross-shard call not found: {}", call_id))
+            })?;
+            if call.status != CrossShardCallStatus::Prepared
+                && call.status != CrossShardCallStatus::Committing
+            {
+                return Err(Error::InvalidState(format!(
+                    "Call cannot be aborted from state: {:?}",
+                    call.status
+                )));
+            }
+        }
+
+        {
+            let call = self.pending_calls.get_mut(call_id).unwrap();
+            call.status = CrossShardCallStatus::Aborting;
+        }
+
+        if let Some(snapshot) = self.staged_snapshots.remove(call_id) {
+            self.rollback_snapshot(&snapshot);
+        }
+
+        let mut call = self.pending_calls.remove(call_id).unwrap();
+        call.status = CrossShardCallStatus::Aborted;
+        call.completed_at = Some(Utc::now());
+        call.result = Some(CrossShardResult {
+            success: false,
+            return_data: Vec::new(),
+            gas_used: 0,
+            error_message: Some("Cross-shard call aborted".to_string()),
+            completed_at: Utc::now(),
+        });
+        self.completed_calls.insert(call_id.to_string(), call);
+
+        Ok(())
+    }
+
+    /// 退避した事前イメージへストレージをロールバック
+    fn rollback_snapshot(
+        &mut self,
+        snapshot: &[(String, StorageKey, Option<StorageValue>)],
+    ) {
+        for (address, key, value) in snapshot {
+            let outcome = match value {
+                Some(v) => self
+                    .storage
+                    .set_contract_storage(address, key.clone(), v.clone()),
+                None => self.storage.delete_contract_storage(address, key),
+            };
+            if let Err(e) = outcome {
+                error!("Failed to roll back staged storage for {}: {}", address, e);
+            }
+        }
+    }
+
+    /// 呼び出しを失敗として完了させる
+    fn fail_call(&mut self, call_id: &str, message: String) {
+        if let Some(mut call) = self.pending_calls.remove(call_id) {
+            call.status = CrossShardCallStatus::Failed;
+            call.completed_at = Some(Utc::now());
+            call.result = Some(CrossShardResult {
+                success: false,
+                return_data: Vec::new(),
+                gas_used: 0,
+                error_message: Some(message),
+                completed_at: Utc::now(),
+            });
+            self.completed_calls.insert(call_id.to_string(), call);
+        }
+    }
+
+    /// クロスシャードバッチを作成
+    ///
+    /// 各呼び出しを個別に `create_call` で登録したうえで、それらを 1 つの
+    /// バッチとしてまとめる。`ordered` が `true` の場合は投入順に逐次実行される。
+    pub fn create_batch(
+        &mut self,
+        calls: Vec<(String, ShardId, String, String, Vec<u8>, u64, u64)>,
+        ordered: bool,
+    ) -> Result<String, Error> {
+        // バッチIDを生成
+        let id = format!("cross_shard_batch_{}", Utc::now().timestamp_nanos());
+
+        // 各呼び出しを登録
+        let mut batch_calls = Vec::with_capacity(calls.len());
+        for (source_contract, target_shard_id, target_contract, method, args, value, gas_limit) in
+            calls
+        {
+            let call_id = self.create_call(
+                source_contract,
+                target_shard_id,
+                target_contract,
+                method,
+                args,
+                value,
+                gas_limit,
+            )?;
+
+            // 登録した呼び出しのスナップショットをバッチに保持
+            let call = self
+                .pending_calls
+                .get(&call_id)
+                .expect("call was just inserted")
+                .clone();
+            batch_calls.push(call);
+        }
+
+        let batch = CrossShardBatch {
+            id: id.clone(),
+            calls: batch_calls,
+            ordered,
+        };
+
+        self.batches.insert(id.clone(), batch);
+
+        Ok(id)
+    }
+
+    /// クロスシャードバッチを送信
+    pub fn send_batch(&mut self, batch_id: &str) -> Result<(), Error> {
+        let call_ids: Vec<String> = self
+            .batches
+            .get(batch_id)
+            .ok_or_else(|| Error::NotFound(format!("Cross-shard batch not found: {}", batch_id)))?
+            .calls
+            .iter()
+            .map(|c| c.id.clone())
+            .collect();
+
+        for call_id in call_ids {
+            self.send_call(&call_id)?;
+        }
+
+        Ok(())
+    }
+
+    /// クロスシャードバッチを実行
+    ///
+    /// 投入順に結果を返す。`ordered` が `true` の場合、直前の呼び出しが成功で
+    /// 終了するまで次を実行せず、失敗した時点で残りを `Cancelled` とする。
+    pub fn execute_batch(&mut self, batch_id: &str) -> Result<Vec<CrossShardResult>, Error> {
+        let batch = self
+            .batches
+            .get(batch_id)
+            .ok_or_else(|| Error::NotFound(format!("Cross-shard batch not found: {}", batch_id)))?;
+        let ordered = batch.ordered;
+        let call_ids: Vec<String> = batch.calls.iter().map(|c| c.id.clone()).collect();
+
+        let mut results = Vec::with_capacity(call_ids.len());
+        let mut stopped = false;
+
+        for call_id in &call_ids {
+            if stopped {
+                // 先行する呼び出しが失敗したためキャンセル扱いとする
+                self.cancel_call(call_id);
+                results.push(CrossShardResult {
+                    success: false,
+                    return_data: Vec::new(),
+                    gas_used: 0,
+                    error_message: Some("Cancelled due to earlier failure in batch".to_string()),
+                    completed_at: Utc::now(),
+                });
+                continue;
+            }
+
+            let result = self.execute_call(call_id)?;
+            let success = result.success;
+            results.push(result);
+
+            if ordered && !success {
+                stopped = true;
+            }
+        }
+
+        // バッチ内の呼び出しスナップショットを最新状態に更新
+        if let Some(batch) = self.batches.get_mut(batch_id) {
+            for call in batch.calls.iter_mut() {
+                if let Some(updated) = self
+                    .completed_calls
+                    .get(&call.id)
+                    .or_else(|| self.pending_calls.get(&call.id))
+                {
+                    *call = updated.clone();
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// バッチのステータスを集約して取得
+    ///
+    /// メンバー呼び出しのステータスを集約し、代表となるステータスを返す。
+    /// いずれかが失敗・タイムアウト・キャンセルであればそれを優先し、
+    /// すべて完了していれば `Completed`、そうでなければ最も進行の遅い状態を返す。
+    pub fn get_batch_status(&self, batch_id: &str) -> Result<CrossShardCallStatus, Error> {
+        let batch = self
+            .batches
+            .get(batch_id)
+            .ok_or_else(|| Error::NotFound(format!("Cross-shard batch not found: {}", batch_id)))?;
+
+        let mut statuses = Vec::with_capacity(batch.calls.len());
+        for call in &batch.calls {
+            statuses.push(self.get_call_status(&call.id)?);
+        }
+
+        // 終端の異常ステータスを優先
+        for status in &statuses {
+            match status {
+                CrossShardCallStatus::Failed
+                | CrossShardCallStatus::TimedOut
+                | CrossShardCallStatus::Cancelled => return Ok(status.clone()),
+                _ => {}
+            }
+        }
+
+        if statuses
+            .iter()
+            .all(|s| *s == CrossShardCallStatus::Completed)
+        {
+            return Ok(CrossShardCallStatus::Completed);
+        }
+
+        // 最も進行の遅い呼び出しの状態を代表とする
+        let rank = |s: &CrossShardCallStatus| match s {
+            CrossShardCallStatus::Pending => 0,
+            CrossShardCallStatus::Sent => 1,
+            CrossShardCallStatus::Received => 2,
+            CrossShardCallStatus::Executing => 3,
+            CrossShardCallStatus::Completed => 4,
+            _ => 5,
+        };
+        let slowest = statuses
+            .into_iter()
+            .min_by_key(|s| rank(s))
+            .unwrap_or(CrossShardCallStatus::Pending);
+
+        Ok(slowest)
+    }
+
+    /// 呼び出しをキャンセル扱いで完了させる
+    fn cancel_call(&mut self, call_id: &str) {
+        if let Some(mut call) = self.pending_calls.remove(call_id) {
+            call.status = CrossShardCallStatus::Cancelled;
+            call.completed_at = Some(Utc::now());
+            call.result = Some(CrossShardResult {
+                success: false,
+                return_data: Vec::new(),
+                gas_used: 0,
+                error_message: Some("Cancelled due to earlier failure in batch".to_string()),
+                completed_at: Utc::now(),
+            });
+            self.completed_calls.insert(call_id.to_string(), call);
+        }
+    }
+
     /// クロスシャード呼び出しの結果を取得
     pub fn get_call_result(&self, call_id: &str) -> Result<Option<CrossShardResult>, Error> {
         // 完了した呼び出しから検索
@@ -481,6 +1014,8 @@ impl<V: VirtualMachine, S: ContractStorage> CrossShardExecutor<V, S> {
         let now = Utc::now();
         let timeout_duration = chrono::Duration::seconds(self.timeout_seconds as i64);
         let mut timed_out_calls = Vec::new();
+        // 準備状態のままタイムアウトした呼び出し（アボートが必要）
+        let mut prepared_timeouts = Vec::new();
 
         // タイムアウトした呼び出しを検索
         for (id, call) in self.pending_calls.iter_mut() {
@@ -490,6 +1025,12 @@ impl<V: VirtualMachine, S: ContractStorage> CrossShardExecutor<V, S> {
                 let elapsed = now - call.created_at;
 
                 if elapsed > timeout_duration {
+                    // `Prepared` のまま滞留した呼び出しはステージ状態を破棄する
+                    // ためアボート経路で処理する
+                    if call.status == CrossShardCallStatus::Prepared {
+                        prepared_timeouts.push(id.clone());
+                        continue;
+                    }
                     call.status = CrossShardCallStatus::TimedOut;
                     call.completed_at = Some(now);
                     call.result = Some(CrossShardResult {
@@ -512,6 +1053,14 @@ impl<V: VirtualMachine, S: ContractStorage> CrossShardExecutor<V, S> {
             }
         }
 
+        // 準備状態のままタイムアウトした呼び出しはアボートしてステージ状態を破棄
+        for id in &prepared_timeouts {
+            if let Err(e) = self.abort_call(id) {
+                error!("Failed to abort timed-out prepared call {}: {}", id, e);
+            }
+        }
+        timed_out_calls.extend(prepared_timeouts);
+
         timed_out_calls
     }
 
@@ -588,3 +1137,426 @@ impl<V: VirtualMachine, S: ContractStorage> CrossShardExecutor<V, S> {
         &self.shard_info
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// コントラクトストレージとVMの双方から同じ裏付けデータを共有するための
+    /// インメモリストレージ（`Rc<RefCell<_>>`でVMの書き込みをストレージへ反映する）
+    #[derive(Clone)]
+    struct TestStorage {
+        contracts: Rc<RefCell<std::collections::HashSet<String>>>,
+        values: Rc<RefCell<HashMap<(String, StorageKey), StorageValue>>>,
+    }
+
+    impl TestStorage {
+        fn new() -> Self {
+            Self {
+                contracts: Rc::new(RefCell::new(std::collections::HashSet::new())),
+                values: Rc::new(RefCell::new(HashMap::new())),
+            }
+        }
+
+        fn with_contracts(addresses: &[&str]) -> Self {
+            let storage = Self::new();
+            for address in addresses {
+                storage.contracts.borrow_mut().insert(address.to_string());
+            }
+            storage
+        }
+    }
+
+    impl ContractStorage for TestStorage {
+        fn get(&self, _key: &StorageKey) -> Result<Option<StorageValue>, StorageError> {
+            Ok(None)
+        }
+
+        fn set(&mut self, _key: StorageKey, _value: StorageValue) -> Result<(), StorageError> {
+            Ok(())
+        }
+
+        fn delete(&mut self, _key: &StorageKey) -> Result<(), StorageError> {
+            Ok(())
+        }
+
+        fn has(&self, _key: &StorageKey) -> Result<bool, StorageError> {
+            Ok(false)
+        }
+
+        fn has_contract(&self, address: &str) -> Result<bool, StorageError> {
+            Ok(self.contracts.borrow().contains(address))
+        }
+
+        fn get_contract(&self, _address: &str) -> Result<Option<Vec<u8>>, StorageError> {
+            Ok(None)
+        }
+
+        fn set_contract(&mut self, address: &str, _code: Vec<u8>) -> Result<(), StorageError> {
+            self.contracts.borrow_mut().insert(address.to_string());
+            Ok(())
+        }
+
+        fn delete_contract(&mut self, address: &str) -> Result<(), StorageError> {
+            self.contracts.borrow_mut().remove(address);
+            Ok(())
+        }
+
+        fn get_contract_storage(
+            &self,
+            address: &str,
+            key: &StorageKey,
+        ) -> Result<Option<StorageValue>, StorageError> {
+            Ok(self
+                .values
+                .borrow()
+                .get(&(address.to_string(), key.clone()))
+                .cloned())
+        }
+
+        fn set_contract_storage(
+            &mut self,
+            address: &str,
+            key: StorageKey,
+            value: StorageValue,
+        ) -> Result<(), StorageError> {
+            self.values
+                .borrow_mut()
+                .insert((address.to_string(), key), value);
+            Ok(())
+        }
+
+        fn delete_contract_storage(
+            &mut self,
+            address: &str,
+            key: &StorageKey,
+        ) -> Result<(), StorageError> {
+            self.values
+                .borrow_mut()
+                .remove(&(address.to_string(), key.clone()));
+            Ok(())
+        }
+
+        fn has_contract_storage(
+            &self,
+            address: &str,
+            key: &StorageKey,
+        ) -> Result<bool, StorageError> {
+            Ok(self
+                .values
+                .borrow()
+                .contains_key(&(address.to_string(), key.clone())))
+        }
+
+        fn get_contract_storage_keys(
+            &self,
+            address: &str,
+        ) -> Result<Vec<StorageKey>, StorageError> {
+            Ok(self
+                .values
+                .borrow()
+                .keys()
+                .filter(|(a, _)| a == address)
+                .map(|(_, k)| k.clone())
+                .collect())
+        }
+
+        fn clear_contract_storage(&mut self, address: &str) -> Result<(), StorageError> {
+            self.values.borrow_mut().retain(|(a, _), _| a != address);
+            Ok(())
+        }
+    }
+
+    /// 呼び出しのたびに指定したキーへ新規書き込みを行い、あらかじめ設定した
+    /// 成否を返すモックVM。ストレージは`TestStorage`と裏付けデータを共有する。
+    struct TestVm {
+        storage: TestStorage,
+        success: bool,
+        new_write: Option<(StorageKey, StorageValue)>,
+    }
+
+    impl VirtualMachine for TestVm {
+        fn deploy(&self, _code: Vec<u8>, _context: ExecutionContext) -> Result<ExecutionResult, VMError> {
+            unimplemented!("not exercised by these tests")
+        }
+
+        fn call(
+            &self,
+            address: String,
+            _method: String,
+            _context: ExecutionContext,
+        ) -> Result<ExecutionResult, VMError> {
+            if let Some((key, value)) = &self.new_write {
+                let mut storage = self.storage.clone();
+                storage
+                    .set_contract_storage(&address, key.clone(), value.clone())
+                    .unwrap();
+            }
+            Ok(ExecutionResult {
+                success: self.success,
+                return_data: Vec::new(),
+                gas_used: 10,
+                memory_used: 0,
+                storage_used: 0,
+                storage_reads: 0,
+                storage_writes: if self.new_write.is_some() { 1 } else { 0 },
+                storage_deletes: 0,
+                events: Vec::new(),
+                logs: Vec::new(),
+                address,
+                error: None,
+            })
+        }
+
+        fn update(
+            &self,
+            address: String,
+            _code: Vec<u8>,
+            _context: ExecutionContext,
+        ) -> Result<ExecutionResult, VMError> {
+            unimplemented!("not exercised by these tests")
+        }
+
+        fn delete(&self, address: String, _context: ExecutionContext) -> Result<ExecutionResult, VMError> {
+            unimplemented!("not exercised by these tests")
+        }
+    }
+
+    fn test_shard_info(id: &str, status: ShardStatus) -> ShardInfo {
+        ShardInfo {
+            id: id.to_string(),
+            name: id.to_string(),
+            validators: 1,
+            height: 0,
+            tps: 0.0,
+            status,
+        }
+    }
+
+    /// 受信済み（`Received`）状態のクロスシャード呼び出しを用意する
+    fn received_call(executor: &mut CrossShardExecutor<TestVm, TestStorage>, target_contract: &str) -> String {
+        let id = format!("call_{}", Utc::now().timestamp_nanos());
+        let call = CrossShardCall {
+            id: id.clone(),
+            source_shard_id: "shard-a".to_string(),
+            target_shard_id: "shard-b".to_string(),
+            source_contract: "source".to_string(),
+            target_contract: target_contract.to_string(),
+            method: "transfer".to_string(),
+            args: Vec::new(),
+            value: 0,
+            gas_limit: 1_000,
+            nonce: 0,
+            created_at: Utc::now(),
+            completed_at: None,
+            status: CrossShardCallStatus::Pending,
+            result: None,
+            metadata: None,
+            relay_shard_id: None,
+            coordinator_shard_id: None,
+            vote: None,
+        };
+        executor.receive_call(call).unwrap();
+        id
+    }
+
+    /// 準備フェーズで実行前に存在しなかったキーへ書き込んでから中断（アボート）された
+    /// 呼び出しは、そのキーもロールバックによって削除されること（chunk168-4の回帰）。
+    #[test]
+    fn test_abort_call_removes_newly_created_staged_key() {
+        let storage = TestStorage::with_contracts(&["contract-1"]);
+        let vm = TestVm {
+            storage: storage.clone(),
+            success: true,
+            new_write: Some((b"balance:bob".to_vec(), b"100".to_vec())),
+        };
+        let mut executor = CrossShardExecutor::new(vm, storage.clone(), "shard-b".to_string(), 60);
+
+        let call_id = received_call(&mut executor, "contract-1");
+
+        // 実行前はキーが存在しない
+        assert!(storage
+            .get_contract_storage("contract-1", &b"balance:bob".to_vec())
+            .unwrap()
+            .is_none());
+
+        let voted_yes = executor.prepare_call(&call_id, "coordinator".to_string()).unwrap();
+        assert!(voted_yes);
+
+        // 準備フェーズのVM実行で新規キーが書き込まれている
+        assert!(storage
+            .get_contract_storage("contract-1", &b"balance:bob".to_vec())
+            .unwrap()
+            .is_some());
+
+        executor.abort_call(&call_id).unwrap();
+
+        // アボートにより、実行前に存在しなかったキーも削除されていること
+        assert!(storage
+            .get_contract_storage("contract-1", &b"balance:bob".to_vec())
+            .unwrap()
+            .is_none());
+    }
+
+    /// 二相コミットの正常系: 準備で賛成投票した呼び出しをコミットすると、
+    /// 準備フェーズで適用されたステージ書き込みが確定し完了状態になる。
+    #[test]
+    fn test_two_phase_commit_happy_path() {
+        let storage = TestStorage::with_contracts(&["contract-1"]);
+        let vm = TestVm {
+            storage: storage.clone(),
+            success: true,
+            new_write: Some((b"balance:bob".to_vec(), b"100".to_vec())),
+        };
+        let mut executor = CrossShardExecutor::new(vm, storage.clone(), "shard-b".to_string(), 60);
+
+        let call_id = received_call(&mut executor, "contract-1");
+        assert!(executor.prepare_call(&call_id, "coordinator".to_string()).unwrap());
+
+        let result = executor.commit_call(&call_id).unwrap();
+        assert!(result.success);
+        assert_eq!(
+            executor.get_call_status(&call_id).unwrap(),
+            CrossShardCallStatus::Completed
+        );
+
+        // コミット後もステージ書き込みは確定したまま残っている
+        assert!(storage
+            .get_contract_storage("contract-1", &b"balance:bob".to_vec())
+            .unwrap()
+            .is_some());
+    }
+
+    /// タイムアウト処理の正常系: `Prepared`のまま滞留した呼び出しは
+    /// `process_timeouts`がアボート経路で処理し、ステージ書き込みを破棄する。
+    #[test]
+    fn test_process_timeouts_aborts_stale_prepared_call() {
+        let storage = TestStorage::with_contracts(&["contract-1"]);
+        let vm = TestVm {
+            storage: storage.clone(),
+            success: true,
+            new_write: Some((b"balance:bob".to_vec(), b"100".to_vec())),
+        };
+        let mut executor = CrossShardExecutor::new(vm, storage.clone(), "shard-b".to_string(), 1);
+
+        let call_id = received_call(&mut executor, "contract-1");
+        assert!(executor.prepare_call(&call_id, "coordinator".to_string()).unwrap());
+
+        // 作成時刻をタイムアウト時間より前に巻き戻す
+        let call = executor.pending_calls.get_mut(&call_id).unwrap();
+        call.created_at = Utc::now() - chrono::Duration::seconds(10);
+
+        let timed_out = executor.process_timeouts();
+        assert_eq!(timed_out, vec![call_id.clone()]);
+        assert_eq!(
+            executor.get_call_status(&call_id).unwrap(),
+            CrossShardCallStatus::Aborted
+        );
+        assert!(storage
+            .get_contract_storage("contract-1", &b"balance:bob".to_vec())
+            .unwrap()
+            .is_none());
+    }
+
+    /// バッチの正常系: `ordered`バッチは途中の呼び出しが失敗すると停止し、
+    /// 残りの呼び出しを`Cancelled`としてマークする。
+    #[test]
+    fn test_ordered_batch_stops_after_failure_and_cancels_remaining() {
+        let storage = TestStorage::with_contracts(&["source", "contract-1", "contract-2", "contract-3"]);
+        let vm = TestVm {
+            storage: storage.clone(),
+            success: false,
+            new_write: None,
+        };
+        let mut executor = CrossShardExecutor::new(vm, storage.clone(), "shard-a".to_string(), 60);
+        executor.add_shard_info("shard-a".to_string(), test_shard_info("shard-a", ShardStatus::Active));
+
+        let batch_id = executor
+            .create_batch(
+                vec![
+                    (
+                        "source".to_string(),
+                        "shard-a".to_string(),
+                        "contract-1".to_string(),
+                        "transfer".to_string(),
+                        Vec::new(),
+                        0,
+                        1_000,
+                    ),
+                    (
+                        "source".to_string(),
+                        "shard-a".to_string(),
+                        "contract-2".to_string(),
+                        "transfer".to_string(),
+                        Vec::new(),
+                        0,
+                        1_000,
+                    ),
+                    (
+                        "source".to_string(),
+                        "shard-a".to_string(),
+                        "contract-3".to_string(),
+                        "transfer".to_string(),
+                        Vec::new(),
+                        0,
+                        1_000,
+                    ),
+                ],
+                true,
+            )
+            .unwrap();
+
+        executor.send_batch(&batch_id).unwrap();
+        let results = executor.execute_batch(&batch_id).unwrap();
+
+        // 1件目が失敗で終わるため、2件目以降はキャンセル扱いになる
+        assert_eq!(results.len(), 3);
+        assert!(!results[0].success);
+        assert!(!results[1].success);
+        assert!(!results[2].success);
+        assert_eq!(
+            executor.get_batch_status(&batch_id).unwrap(),
+            CrossShardCallStatus::Cancelled
+        );
+    }
+
+    /// 送信先シャードが非アクティブな場合、同一ゾーン内の健全なシャードが
+    /// 中継先として選ばれること。
+    #[test]
+    fn test_send_call_relays_via_same_zone_when_target_unreachable() {
+        let storage = TestStorage::with_contracts(&["contract-1"]);
+        let vm = TestVm {
+            storage: storage.clone(),
+            success: true,
+            new_write: None,
+        };
+        let mut executor = CrossShardExecutor::new(vm, storage, "shard-a".to_string(), 60);
+
+        executor.add_shard_info("shard-target".to_string(), test_shard_info("shard-target", ShardStatus::Inactive));
+        executor.add_shard_info("shard-same-zone".to_string(), test_shard_info("shard-same-zone", ShardStatus::Active));
+        executor.add_shard_info("shard-other-zone".to_string(), test_shard_info("shard-other-zone", ShardStatus::Active));
+        executor.set_shard_zone("shard-target".to_string(), "zone-1".to_string());
+        executor.set_shard_zone("shard-same-zone".to_string(), "zone-1".to_string());
+        executor.set_shard_zone("shard-other-zone".to_string(), "zone-2".to_string());
+
+        let call_id = executor
+            .create_call(
+                "contract-1".to_string(),
+                "shard-target".to_string(),
+                "contract-2".to_string(),
+                "transfer".to_string(),
+                Vec::new(),
+                0,
+                1_000,
+            )
+            .unwrap();
+
+        executor.send_call(&call_id).unwrap();
+
+        let call = executor.get_call(&call_id).unwrap();
+        assert_eq!(call.status, CrossShardCallStatus::Sent);
+        assert_eq!(call.relay_shard_id, Some("shard-same-zone".to_string()));
+    }
+}
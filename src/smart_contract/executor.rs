@@ -49,6 +49,29 @@ pub struct ExecutionStats {
     pub event_count: u64,
 }
 
+/// エグゼキューターの能力（バージョンネゴシエーション用）
+///
+/// `supported_platforms()`に加えて、コントラクトが要求するホスト関数ABI
+/// バージョンや決定性メタ情報を照合するために使う。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutorCapabilities {
+    /// サポートするホスト関数ABIバージョン範囲の下限（含む、major, minor, patch）
+    pub min_abi_version: (u32, u32, u32),
+    /// サポートするホスト関数ABIバージョン範囲の上限（含む、major, minor, patch）
+    pub max_abi_version: (u32, u32, u32),
+    /// 決定的なガス計測が可能か
+    pub deterministic_metering: bool,
+    /// デバッグ／トレース実行をサポートするか
+    pub debuggable: bool,
+}
+
+impl ExecutorCapabilities {
+    /// 指定したABIバージョンをこの範囲でサポートしているか
+    pub fn supports_abi_version(&self, version: (u32, u32, u32)) -> bool {
+        version >= self.min_abi_version && version <= self.max_abi_version
+    }
+}
+
 /// コントラクトエグゼキューター
 pub trait ContractExecutor: Send + Sync {
     /// エグゼキューター名
@@ -57,6 +80,8 @@ pub trait ContractExecutor: Send + Sync {
     fn version(&self) -> &str;
     /// サポートするプラットフォーム
     fn supported_platforms(&self) -> Vec<String>;
+    /// エグゼキューターの能力（ABIバージョン範囲、決定性、デバッグ対応など）
+    fn capabilities(&self) -> ExecutorCapabilities;
     /// コードを実行
     fn execute(
         &self,
@@ -190,6 +215,15 @@ mod tests {
             vec!["wasm32-unknown-unknown".to_string()]
         }
 
+        fn capabilities(&self) -> ExecutorCapabilities {
+            ExecutorCapabilities {
+                min_abi_version: (1, 0, 0),
+                max_abi_version: (1, 0, 0),
+                deterministic_metering: false,
+                debuggable: true,
+            }
+        }
+
         fn execute(
             &self,
             code: &[u8],
@@ -7,6 +7,7 @@ pub mod evm;
 pub mod execution_optimizer;
 pub mod executor;
 pub mod gas;
+pub mod registry;
 pub mod storage;
 pub mod validator;
 pub mod vm;
@@ -19,9 +20,10 @@ pub use engine::{ContractEngine, ContractEngineConfig, ContractEngineStats};
 pub use event::{ContractEvent, EventFilter, EventLog, EventSubscription};
 pub use evm::{EvmAddress, EvmCompiler, EvmExecutor, EvmStorage, EvmVM};
 pub use execution_optimizer::{ContractOptimizer, OptimizationLevel, OptimizationResult};
-pub use executor::{ContractExecutor, ExecutionStats, ExecutorConfig};
+pub use executor::{ContractExecutor, ExecutionStats, ExecutorCapabilities, ExecutorConfig};
 pub use gas::{GasEstimator, GasPrice, GasSchedule, GasUsage};
+pub use registry::{ExecutionRequirement, ExecutorRegistry};
 pub use storage::{ContractStorage, StorageError, StorageKey, StorageValue};
 pub use validator::{ContractValidator, ValidationError, ValidationResult};
 pub use vm::{ExecutionContext, ExecutionResult, VMError, VirtualMachine};
-pub use wasm::{WasmCompiler, WasmExecutor, WasmModule, WasmVM};
+pub use wasm::{WasmCompiler, WasmCosts, WasmExecutor, WasmModule, WasmVM};
@@ -0,0 +1,117 @@
+//! 複数の`ContractExecutor`実装を束ね、プラットフォーム文字列とホスト関数ABI
+//! バージョンからディスパッチ先を選ぶレジストリ
+//!
+//! Tezedgeの`NetworkVersion`ハンドシェイク（チェーン名＋バージョン付き機能フラグ
+//! の組み合わせで互換性を判定する方式）にならい、`supported_platforms()`と
+//! `capabilities()`が返すABIバージョン範囲の両方が一致するエグゼキューターの
+//! うち、最もバージョンの高いものを選択する。一致するものがなければ、デプロイ
+//! 済みコントラクトを誤って別ABIで実行してしまう前に即座にエラーを返す。
+
+use crate::error::Error;
+use crate::smart_contract::executor::ContractExecutor;
+use crate::smart_contract::{ExecutionContext, ExecutionResult, ExecutorConfig};
+
+/// `version()`が返すバージョン文字列（例: "1.2.3"）を比較可能なタプルへ変換する
+fn parse_semver(version: &str) -> (u32, u32, u32) {
+    let mut parts = version.split('.').map(|p| p.parse::<u32>().unwrap_or(0));
+    (
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+    )
+}
+
+/// `ContractExecutor`実装の登録と、能力ネゴシエーションによるディスパッチを行う
+pub struct ExecutorRegistry {
+    executors: Vec<Box<dyn ContractExecutor>>,
+}
+
+impl ExecutorRegistry {
+    /// 空のレジストリを作成
+    pub fn new() -> Self {
+        Self {
+            executors: Vec::new(),
+        }
+    }
+
+    /// エグゼキューターを登録する
+    pub fn register(&mut self, executor: Box<dyn ContractExecutor>) {
+        self.executors.push(executor);
+    }
+
+    /// 登録済みエグゼキューターの一覧（名前とバージョン）
+    pub fn list(&self) -> Vec<(String, String)> {
+        self.executors
+            .iter()
+            .map(|e| (e.name().to_string(), e.version().to_string()))
+            .collect()
+    }
+
+    /// 指定したプラットフォームと要求ABIバージョンを満たす、最もバージョンの
+    /// 高い互換エグゼキューターを選択する
+    pub fn find_executor(
+        &self,
+        platform: &str,
+        required_abi_version: (u32, u32, u32),
+    ) -> Result<&dyn ContractExecutor, Error> {
+        self.executors
+            .iter()
+            .filter(|e| {
+                e.supported_platforms()
+                    .iter()
+                    .any(|p| p.as_str() == platform)
+            })
+            .filter(|e| e.capabilities().supports_abi_version(required_abi_version))
+            .max_by_key(|e| parse_semver(e.version()))
+            .map(|e| e.as_ref())
+            .ok_or_else(|| {
+                Error::NotImplemented(format!(
+                    "no registered executor supports platform '{}' at ABI version {}.{}.{}",
+                    platform,
+                    required_abi_version.0,
+                    required_abi_version.1,
+                    required_abi_version.2
+                ))
+            })
+    }
+
+    /// 互換エグゼキューターを選択したうえでコードを実行する
+    pub fn execute(
+        &self,
+        platform: &str,
+        required_abi_version: (u32, u32, u32),
+        code: &[u8],
+        function_name: &str,
+        args: &[Vec<u8>],
+        context: &ExecutionContext,
+        config: &ExecutorConfig,
+    ) -> Result<ExecutionResult, Error> {
+        let executor = self.find_executor(platform, required_abi_version)?;
+        executor.execute(code, function_name, args, context, config)
+    }
+}
+
+impl Default for ExecutorRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// コントラクトが要求する実行能力（プラットフォームとABIバージョン）
+#[derive(Debug, Clone)]
+pub struct ExecutionRequirement {
+    /// コントラクトが宣言するプラットフォーム文字列
+    pub platform: String,
+    /// コントラクトが要求するホスト関数ABIバージョン
+    pub required_abi_version: (u32, u32, u32),
+}
+
+impl ExecutionRequirement {
+    /// 新しい実行要件を作成
+    pub fn new(platform: impl Into<String>, required_abi_version: (u32, u32, u32)) -> Self {
+        Self {
+            platform: platform.into(),
+            required_abi_version,
+        }
+    }
+}
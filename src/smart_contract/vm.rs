@@ -69,6 +69,8 @@ pub enum VMError {
     StackUnderflow,
     /// メモリオーバーフロー
     MemoryOverflow,
+    /// メモリアクセス違反（範囲外のポインタ/長さ）
+    MemoryAccessViolation(String),
     /// ストレージオーバーフロー
     StorageOverflow,
     /// ガス不足
@@ -100,6 +102,7 @@ impl From<VMError> for Error {
             VMError::StackOverflow => Error::InvalidState("Stack overflow".to_string()),
             VMError::StackUnderflow => Error::InvalidState("Stack underflow".to_string()),
             VMError::MemoryOverflow => Error::ResourceExhausted("Memory overflow".to_string()),
+            VMError::MemoryAccessViolation(msg) => Error::InvalidInput(format!("Memory access violation: {}", msg)),
             VMError::StorageOverflow => Error::ResourceExhausted("Storage overflow".to_string()),
             VMError::OutOfGas => Error::ResourceExhausted("Out of gas".to_string()),
             VMError::CallDepthExceeded => Error::ResourceExhausted("Call depth exceeded".to_string()),
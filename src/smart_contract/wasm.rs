@@ -1,12 +1,478 @@
 use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use chrono::{DateTime, Utc};
 use serde::{Serialize, Deserialize};
 use log::{debug, error, info, warn};
+use wasmi::{Caller, Engine, Extern, Linker, Memory, Module, Store};
+use parity_wasm::elements::{
+    BlockType, External, FunctionType, GlobalEntry, GlobalType, ImportEntry, InitExpr,
+    Instruction, Internal, Module as ElementsModule, Type, ValueType,
+};
 
 use crate::error::Error;
 use crate::smart_contract::vm::{VirtualMachine, ExecutionContext, ExecutionResult, VMError};
 use crate::smart_contract::storage::{ContractStorage, StorageKey, StorageValue, StorageError};
 use crate::smart_contract::event::ContractEvent;
+use crate::smart_contract::executor::{
+    ContractExecutor, ExecutionStats, ExecutorCapabilities, ExecutorConfig,
+};
+
+/// ホストABIで扱うストレージスロットの固定長（parity WASM ランタイムに倣う）
+const SLOT_LEN: usize = 32;
+
+/// オペコードごとのガスコスト表
+///
+/// parity WASM の `WasmCosts` に倣い、命令種別ごとの重み付けを保持する。
+/// 乗除算やメモリアクセスは基本コスト `regular` に各乗数を上乗せし、
+/// `memory.grow` は要求ページ数によらず、命令1回につき `grow_mem` を上乗せする。
+#[derive(Debug, Clone)]
+pub struct WasmCosts {
+    /// 命令あたりの基本コスト
+    pub regular: u32,
+    /// 除算命令への上乗せコスト
+    pub div: u32,
+    /// 乗算命令への上乗せコスト
+    pub mul: u32,
+    /// ロード/ストア命令への上乗せコスト
+    pub mem: u32,
+    /// memory.grow 命令1回あたりの上乗せコスト（要求ページ数は見ない）
+    pub grow_mem: u32,
+    /// アリーナ確保のバイトあたりコスト
+    pub alloc: u32,
+}
+
+impl Default for WasmCosts {
+    fn default() -> Self {
+        // parity の既定値を踏襲した穏当な初期重み
+        Self {
+            regular: 1,
+            div: 16,
+            mul: 4,
+            mem: 2,
+            grow_mem: 8192,
+            alloc: 1,
+        }
+    }
+}
+
+impl WasmCosts {
+    /// 単一命令のコストを算出する
+    fn instruction_cost(&self, instruction: &Instruction) -> u32 {
+        use Instruction::*;
+        match instruction {
+            // 乗算系は乗数を上乗せ
+            I32Mul | I64Mul | F32Mul | F64Mul => self.regular + self.mul,
+            // 除算・剰余系は除算乗数を上乗せ
+            I32DivS | I32DivU | I64DivS | I64DivU | F32Div | F64Div
+            | I32RemS | I32RemU | I64RemS | I64RemU => self.regular + self.div,
+            // ロード/ストアはメモリ乗数を上乗せ
+            I32Load(..) | I64Load(..) | F32Load(..) | F64Load(..)
+            | I32Load8S(..) | I32Load8U(..) | I32Load16S(..) | I32Load16U(..)
+            | I64Load8S(..) | I64Load8U(..) | I64Load16S(..) | I64Load16U(..)
+            | I64Load32S(..) | I64Load32U(..)
+            | I32Store(..) | I64Store(..) | F32Store(..) | F64Store(..)
+            | I32Store8(..) | I32Store16(..)
+            | I64Store8(..) | I64Store16(..) | I64Store32(..) => self.regular + self.mem,
+            // memory.grow は要求ページ数によらず grow_mem を1回だけ上乗せする
+            GrowMemory(..) => self.regular + self.grow_mem,
+            _ => self.regular,
+        }
+    }
+
+    /// 直線ブロック（分岐・制御命令で区切られる区間）の境界か判定する
+    fn is_block_boundary(instruction: &Instruction) -> bool {
+        use Instruction::*;
+        matches!(
+            instruction,
+            Block(..) | Loop(..) | If(..) | Else | End
+                | Br(..) | BrIf(..) | BrTable(..)
+                | Return | Call(..) | CallIndirect(..) | Unreachable
+        )
+    }
+}
+
+/// バイトコードにガス計測を注入する
+///
+/// `env.gas(u32)` インポートを（未登録なら）追加し、関数インデックス空間の
+/// ずれを補正したうえで、各直線ブロックの先頭に合計コストぶんの `gas` 呼び出しを
+/// 挿入する。注入された `gas` はホストランタイムが処理し、カウンタを減算して
+/// 下回った時点で `VMError::OutOfGas` にトラップする。
+fn instrument_gas(bytecode: &[u8], costs: &WasmCosts) -> Result<Vec<u8>, Error> {
+    let mut module: ElementsModule = parity_wasm::deserialize_buffer(bytecode)
+        .map_err(|e| Error::InvalidInput(format!("failed to parse wasm module: {}", e)))?;
+
+    // 既存の gas インポートがあればそのインデックスを使い、なければ追加する
+    let gas_index = match find_gas_import(&module) {
+        Some(index) => index,
+        None => add_gas_import(&mut module)?,
+    };
+
+    // 各関数本体の直線ブロックへガス呼び出しを注入する
+    if let Some(code) = module.code_section_mut() {
+        for func in code.bodies_mut() {
+            inject_block_metering(func.code_mut().elements_mut(), costs, gas_index);
+        }
+    }
+
+    parity_wasm::serialize(module)
+        .map_err(|e| Error::Internal(format!("failed to serialize wasm module: {}", e)))
+}
+
+/// `env.gas` 関数インポートのインデックスを探す
+fn find_gas_import(module: &ElementsModule) -> Option<u32> {
+    let imports = module.import_section()?;
+    let mut func_index = 0u32;
+    for entry in imports.entries() {
+        if let External::Function(_) = entry.external() {
+            if entry.module() == "env" && entry.field() == "gas" {
+                return Some(func_index);
+            }
+            func_index += 1;
+        }
+    }
+    None
+}
+
+/// `env.gas(u32)` インポートを追加し、そのインデックスを返す
+///
+/// 関数インポートを末尾に足すと、既存の定義済み関数インデックスが +1 ずれるため、
+/// 呼び出し・エクスポート・start・エレメントの各参照を補正する。
+fn add_gas_import(module: &mut ElementsModule) -> Result<u32, Error> {
+    // gas の関数型 (param i32) を型セクションへ追加
+    let gas_type = Type::Function(FunctionType::new(vec![ValueType::I32], vec![]));
+    let type_index = {
+        let types = module
+            .type_section_mut()
+            .ok_or_else(|| Error::InvalidInput("wasm module has no type section".to_string()))?;
+        types.types_mut().push(gas_type);
+        (types.types().len() - 1) as u32
+    };
+
+    // 既存の関数インポート数が、新しい gas インポートのインデックスになる
+    let gas_index = imported_function_count(module);
+
+    // gas インポートより後ろへずれる関数参照を +1 補正
+    shift_function_indices(module, gas_index, 1);
+
+    // インポートセクションへ gas を追記
+    let import = ImportEntry::new(
+        "env".to_string(),
+        "gas".to_string(),
+        External::Function(type_index),
+    );
+    module
+        .import_section_mut()
+        .ok_or_else(|| Error::InvalidInput("wasm module has no import section".to_string()))?
+        .entries_mut()
+        .push(import);
+
+    Ok(gas_index)
+}
+
+/// インポートされた関数の総数を数える
+fn imported_function_count(module: &ElementsModule) -> u32 {
+    module
+        .import_section()
+        .map(|imports| {
+            imports
+                .entries()
+                .iter()
+                .filter(|e| matches!(e.external(), External::Function(_)))
+                .count() as u32
+        })
+        .unwrap_or(0)
+}
+
+/// `threshold` 以上の関数インデックス参照を一律 `delta` だけずらす
+fn shift_function_indices(module: &mut ElementsModule, threshold: u32, delta: u32) {
+    // 命令中の Call
+    if let Some(code) = module.code_section_mut() {
+        for func in code.bodies_mut() {
+            for instruction in func.code_mut().elements_mut() {
+                if let Instruction::Call(index) = instruction {
+                    if *index >= threshold {
+                        *index += delta;
+                    }
+                }
+            }
+        }
+    }
+
+    // エクスポートされた関数
+    if let Some(exports) = module.export_section_mut() {
+        for entry in exports.entries_mut() {
+            if let Internal::Function(index) = entry.internal_mut() {
+                if *index >= threshold {
+                    *index += delta;
+                }
+            }
+        }
+    }
+
+    // start 関数
+    if let Some(start) = module.start_section() {
+        if start >= threshold {
+            module.set_start_section(start + delta);
+        }
+    }
+
+    // エレメントセクション（テーブル初期化の関数参照）
+    if let Some(elements) = module.elements_section_mut() {
+        for segment in elements.entries_mut() {
+            for index in segment.members_mut() {
+                if *index >= threshold {
+                    *index += delta;
+                }
+            }
+        }
+    }
+}
+
+/// 関数本体の各直線ブロック先頭へ `gas(cost)` 呼び出しを挿入する
+fn inject_block_metering(body: &mut Vec<Instruction>, costs: &WasmCosts, gas_index: u32) {
+    let original = std::mem::take(body);
+    let mut out: Vec<Instruction> = Vec::with_capacity(original.len() * 2);
+    let mut block_start = 0usize;
+    let mut block_cost = 0u32;
+
+    for instruction in original {
+        block_cost = block_cost.saturating_add(costs.instruction_cost(&instruction));
+        let boundary = WasmCosts::is_block_boundary(&instruction);
+        out.push(instruction);
+        if boundary {
+            if block_cost > 0 {
+                out.insert(block_start, Instruction::Call(gas_index));
+                out.insert(block_start, Instruction::I32Const(block_cost as i32));
+            }
+            block_start = out.len();
+            block_cost = 0;
+        }
+    }
+
+    // 末尾に残った区間（通常は関数末尾の End で処理済みだが保険として）
+    if block_cost > 0 {
+        out.insert(block_start, Instruction::Call(gas_index));
+        out.insert(block_start, Instruction::I32Const(block_cost as i32));
+    }
+
+    *body = out;
+}
+
+/// バイトコードにスタック高リミッタを注入する
+///
+/// 合成のミュータブル i32 グローバル（スタックカウンタ）を追加し、各関数の入口で
+/// その関数が静的に消費しうる最大スタック量を加算して `max_stack_size` を超えたら
+/// `unreachable` でトラップし、出口（各 `return` と関数末尾）で同量を減算する。
+/// `max_call_depth` による呼び出し深度チェックだけでは、フレームごとの
+/// オペランドスタック増加を抑えられないため、モジュールレベルで決定的に防御する。
+fn instrument_stack_height(bytecode: &[u8], max_stack_size: u32) -> Result<Vec<u8>, Error> {
+    let mut module: ElementsModule = parity_wasm::deserialize_buffer(bytecode)
+        .map_err(|e| Error::InvalidInput(format!("failed to parse wasm module: {}", e)))?;
+
+    // 既存グローバル数 = 追加するスタックカウンタのインデックス（末尾追加なので既存参照はずれない）
+    let counter_index = module
+        .global_section()
+        .map(|g| g.entries().len() as u32)
+        .unwrap_or(0);
+
+    // スタックカウンタ（i32, mutable, 初期値0）を追加
+    let counter = GlobalEntry::new(
+        GlobalType::new(ValueType::I32, true),
+        InitExpr::new(vec![Instruction::I32Const(0), Instruction::End]),
+    );
+    match module.global_section_mut() {
+        Some(section) => section.entries_mut().push(counter),
+        None => {
+            // グローバルセクションが無いモジュールは稀だが、その場合は何もできない
+            return Err(Error::InvalidInput(
+                "wasm module has no global section for stack counter".to_string(),
+            ));
+        }
+    }
+
+    if let Some(code) = module.code_section_mut() {
+        for func in code.bodies_mut() {
+            let cost = compute_stack_cost(func.code().elements(), func);
+            inject_stack_guard(
+                func.code_mut().elements_mut(),
+                counter_index,
+                cost,
+                max_stack_size,
+            );
+        }
+    }
+
+    parity_wasm::serialize(module)
+        .map_err(|e| Error::Internal(format!("failed to serialize wasm module: {}", e)))
+}
+
+/// 関数が静的に消費しうるスタック量（宣言ローカル数 + 最大オペランド高）を見積もる
+fn compute_stack_cost(body: &[Instruction], func: &parity_wasm::elements::FuncBody) -> u32 {
+    let locals: u32 = func.locals().iter().map(|l| l.count()).sum();
+
+    let mut height: i32 = 0;
+    let mut max_height: i32 = 0;
+    for instruction in body {
+        height += operand_delta(instruction);
+        if height < 0 {
+            height = 0;
+        }
+        if height > max_height {
+            max_height = height;
+        }
+    }
+
+    locals.saturating_add(max_height as u32)
+}
+
+/// 命令のオペランドスタックへの純増減を概算する（未知命令は0とみなす）
+fn operand_delta(instruction: &Instruction) -> i32 {
+    use Instruction::*;
+    match instruction {
+        // 定数・取得はプッシュ
+        I32Const(..) | I64Const(..) | F32Const(..) | F64Const(..)
+        | GetLocal(..) | GetGlobal(..) => 1,
+        // 設定・破棄はポップ
+        SetLocal(..) | SetGlobal(..) | Drop => -1,
+        // 二項演算は2ポップ1プッシュ
+        I32Add | I32Sub | I32Mul | I32DivS | I32DivU | I32RemS | I32RemU
+        | I32And | I32Or | I32Xor | I32Shl | I32ShrS | I32ShrU
+        | I64Add | I64Sub | I64Mul | I64DivS | I64DivU
+        | F32Add | F32Sub | F32Mul | F32Div | F64Add | F64Sub | F64Mul | F64Div => -1,
+        // 比較も2ポップ1プッシュ
+        I32Eq | I32Ne | I32LtS | I32LtU | I32GtS | I32GtU | I32LeS | I32LeU | I32GeS | I32GeU
+        | I64Eq | I64Ne | F32Eq | F32Ne | F64Eq | F64Ne => -1,
+        // ストアは (addr, value) をポップ
+        I32Store(..) | I64Store(..) | F32Store(..) | F64Store(..)
+        | I32Store8(..) | I32Store16(..) | I64Store8(..) | I64Store16(..) | I64Store32(..) => -2,
+        _ => 0,
+    }
+}
+
+/// 関数本体の入口で加算・越境チェック、各出口で減算するコードを注入する
+fn inject_stack_guard(body: &mut Vec<Instruction>, counter: u32, cost: u32, limit: u32) {
+    if cost == 0 {
+        return;
+    }
+
+    // 出口（return と末尾 End の直前）にカウンタ減算を挿入する
+    let subtract = |out: &mut Vec<Instruction>| {
+        out.push(Instruction::GetGlobal(counter));
+        out.push(Instruction::I32Const(cost as i32));
+        out.push(Instruction::I32Sub);
+        out.push(Instruction::SetGlobal(counter));
+    };
+
+    let original = std::mem::take(body);
+    let last = original.len().saturating_sub(1);
+    let mut out: Vec<Instruction> = Vec::with_capacity(original.len() + 16);
+
+    // 入口: counter += cost; if counter > limit { unreachable }
+    out.push(Instruction::GetGlobal(counter));
+    out.push(Instruction::I32Const(cost as i32));
+    out.push(Instruction::I32Add);
+    out.push(Instruction::SetGlobal(counter));
+    out.push(Instruction::GetGlobal(counter));
+    out.push(Instruction::I32Const(limit as i32));
+    out.push(Instruction::I32GtU);
+    out.push(Instruction::If(BlockType::NoResult));
+    out.push(Instruction::Unreachable);
+    out.push(Instruction::End);
+
+    for (index, instruction) in original.into_iter().enumerate() {
+        match instruction {
+            Instruction::Return => {
+                subtract(&mut out);
+                out.push(Instruction::Return);
+            }
+            Instruction::End if index == last => {
+                // 関数末尾からの暗黙の復帰でも必ず減算する
+                subtract(&mut out);
+                out.push(Instruction::End);
+            }
+            other => out.push(other),
+        }
+    }
+
+    *body = out;
+}
+
+/// バイトコードから宣言済みのエクスポート・インポート・リソース上限を抽出する
+///
+/// `parse_module` がこれまで返していたハードコード値の代わりに、実際の
+/// セクションを読み取って `WasmModule` を埋めるための中間表現。`memory_pages`
+/// と `table_size` はインポート/定義の双方を見て宣言上の最大値を取る。
+struct ModuleInfo {
+    exports: Vec<String>,
+    imports: Vec<String>,
+    memory_pages: u32,
+    table_size: u32,
+    global_count: u32,
+}
+
+/// 線形メモリ1ページのバイト数（WASM 仕様）
+const WASM_PAGE_SIZE: usize = 64 * 1024;
+
+/// WASM バイナリを解析してセクション情報を取り出す
+fn extract_module_info(bytecode: &[u8]) -> Result<ModuleInfo, VMError> {
+    let module: ElementsModule = parity_wasm::deserialize_buffer(bytecode)
+        .map_err(|e| VMError::InvalidArguments(format!("failed to parse wasm module: {}", e)))?;
+
+    // エクスポート名
+    let exports = module
+        .export_section()
+        .map(|s| s.entries().iter().map(|e| e.field().to_string()).collect())
+        .unwrap_or_default();
+
+    // インポート関数名と、インポート経由のメモリ/テーブル上限
+    let mut imports = Vec::new();
+    let mut memory_pages = 0u32;
+    let mut table_size = 0u32;
+    if let Some(section) = module.import_section() {
+        for entry in section.entries() {
+            match entry.external() {
+                External::Function(_) => imports.push(entry.field().to_string()),
+                External::Memory(ty) => {
+                    let limits = ty.limits();
+                    memory_pages = memory_pages.max(limits.maximum().unwrap_or(limits.initial()));
+                }
+                External::Table(ty) => {
+                    let limits = ty.limits();
+                    table_size = table_size.max(limits.maximum().unwrap_or(limits.initial()));
+                }
+                External::Global(_) => {}
+            }
+        }
+    }
+
+    // モジュール内で定義されたメモリ/テーブル上限
+    if let Some(section) = module.memory_section() {
+        for entry in section.entries() {
+            let limits = entry.limits();
+            memory_pages = memory_pages.max(limits.maximum().unwrap_or(limits.initial()));
+        }
+    }
+    if let Some(section) = module.table_section() {
+        for entry in section.entries() {
+            let limits = entry.limits();
+            table_size = table_size.max(limits.maximum().unwrap_or(limits.initial()));
+        }
+    }
+
+    let global_count = module
+        .global_section()
+        .map(|s| s.entries().len() as u32)
+        .unwrap_or(0);
+
+    Ok(ModuleInfo {
+        exports,
+        imports,
+        memory_pages,
+        table_size,
+        global_count,
+    })
+}
 
 /// Wasmモジュール
 #[derive(Debug, Clone)]
@@ -31,6 +497,388 @@ pub struct WasmModule {
     pub metadata: Option<HashMap<String, String>>,
 }
 
+/// ホスト側ランタイム状態
+///
+/// wasmi の `Store` に格納され、ホスト関数から `Caller::data`/`data_mut` 経由で
+/// 参照される。parity WASM ランタイムと同様に、コントラクトストレージへの参照・
+/// 実行コンテキスト・線形メモリハンドル・引数バッファ・戻り値バッファを保持する。
+/// ストレージ読み取りは借用した `ContractStorage` から直接行い、書き込みは
+/// `pending_writes` にバッファリングし、エクスポート関数が正常終了した後に
+/// `execute_function` が一括反映する（トラップした場合は反映しない）。
+struct Runtime<'a, S: ContractStorage> {
+    /// コントラクトストレージ（読み取り用の借用）
+    storage: &'a S,
+    /// ネストした呼び出しを解決するための VM への借用
+    vm: &'a WasmVM<S>,
+    /// 対象コントラクトアドレス
+    address: String,
+    /// 実行コンテキスト
+    context: &'a ExecutionContext,
+    /// 線形メモリハンドル（インスタンス化後に設定）
+    memory: Option<Memory>,
+    /// 戻り値バッファ（`ret` ホスト呼び出しで書き込まれる）
+    result_buffer: Vec<u8>,
+    /// 発火したイベント
+    events: Vec<ContractEvent>,
+    /// 実行後に反映するストレージ書き込み
+    pending_writes: Vec<(StorageKey, StorageValue)>,
+    /// 残ガス
+    gas_left: u64,
+    /// ストレージ読み取り回数
+    storage_reads: u64,
+    /// ストレージ書き込み回数
+    storage_writes: u64,
+    /// ストレージ削除回数
+    storage_deletes: u64,
+    /// ホスト関数内で発生した VM エラー（トラップ後に呼び出し側へ伝搬）
+    host_error: Option<VMError>,
+}
+
+impl<'a, S: ContractStorage> Runtime<'a, S> {
+    /// 線形メモリから `len` バイトを境界チェック付きで読み出す
+    fn read_memory(
+        caller: &Caller<'_, Self>,
+        ptr: i32,
+        len: usize,
+    ) -> Result<Vec<u8>, VMError> {
+        let memory = caller
+            .data()
+            .memory
+            .ok_or_else(|| VMError::MemoryAccessViolation("linear memory not exported".to_string()))?;
+        let offset = ptr as usize;
+        let mut buf = vec![0u8; len];
+        memory
+            .read(caller, offset, &mut buf)
+            .map_err(|_| {
+                VMError::MemoryAccessViolation(format!(
+                    "read out of bounds: ptr={}, len={}",
+                    offset, len
+                ))
+            })?;
+        Ok(buf)
+    }
+
+    /// 線形メモリの `ptr` に境界チェック付きで書き込む
+    fn write_memory(
+        caller: &mut Caller<'_, Self>,
+        ptr: i32,
+        bytes: &[u8],
+    ) -> Result<(), VMError> {
+        let memory = caller
+            .data()
+            .memory
+            .ok_or_else(|| VMError::MemoryAccessViolation("linear memory not exported".to_string()))?;
+        let offset = ptr as usize;
+        memory.write(caller, offset, bytes).map_err(|_| {
+            VMError::MemoryAccessViolation(format!(
+                "write out of bounds: ptr={}, len={}",
+                offset,
+                bytes.len()
+            ))
+        })
+    }
+}
+
+/// ホスト関数テーブルを `Linker` に登録する
+fn register_host_functions<S: ContractStorage + 'static>(
+    linker: &mut Linker<Runtime<'_, S>>,
+) -> Result<(), VMError> {
+    // storage_read(key_ptr, val_ptr): 32バイトキーを読み、対応する値を val_ptr に書く
+    linker
+        .func_wrap(
+            "env",
+            "storage_read",
+            |mut caller: Caller<'_, Runtime<S>>, key_ptr: i32, val_ptr: i32| {
+                let key = trap_on_err(&mut caller, Runtime::read_memory(&caller, key_ptr, SLOT_LEN))?;
+                let address = caller.data().address.clone();
+                // 未反映の書き込みを優先的に参照し、read-after-write の一貫性を保つ
+                let pending = caller
+                    .data()
+                    .pending_writes
+                    .iter()
+                    .rev()
+                    .find(|(k, _)| *k == key)
+                    .map(|(_, v)| v.clone());
+                let value = match pending {
+                    Some(v) => Some(v),
+                    None => {
+                        let got = caller.data().storage.get_contract_storage(&address, &key);
+                        trap_on_err(
+                            &mut caller,
+                            got.map_err(|e| VMError::InternalError(format!("storage_read: {}", e))),
+                        )?
+                    }
+                };
+                let mut slot = [0u8; SLOT_LEN];
+                if let Some(v) = value {
+                    let n = v.len().min(SLOT_LEN);
+                    slot[..n].copy_from_slice(&v[..n]);
+                }
+                trap_on_err(&mut caller, Runtime::write_memory(&mut caller, val_ptr, &slot))?;
+                caller.data_mut().storage_reads += 1;
+                Ok(())
+            },
+        )
+        .map_err(|e| VMError::InternalError(format!("link storage_read: {}", e)))?;
+
+    // storage_write(key_ptr, val_ptr): 32バイトキーと値を読み、書き込みをバッファする
+    linker
+        .func_wrap(
+            "env",
+            "storage_write",
+            |mut caller: Caller<'_, Runtime<S>>, key_ptr: i32, val_ptr: i32| {
+                if caller.data().context.is_static {
+                    return trap(&mut caller, VMError::StateChangeInStaticCall);
+                }
+                let key = trap_on_err(&mut caller, Runtime::read_memory(&caller, key_ptr, SLOT_LEN))?;
+                let value = trap_on_err(&mut caller, Runtime::read_memory(&caller, val_ptr, SLOT_LEN))?;
+                let state = caller.data_mut();
+                state.pending_writes.push((key, value));
+                state.storage_writes += 1;
+                Ok(())
+            },
+        )
+        .map_err(|e| VMError::InternalError(format!("link storage_write: {}", e)))?;
+
+    // ret(ptr, len): 戻り値バッファへコピーする
+    linker
+        .func_wrap(
+            "env",
+            "ret",
+            |mut caller: Caller<'_, Runtime<S>>, ptr: i32, len: i32| {
+                let bytes = trap_on_err(
+                    &mut caller,
+                    Runtime::read_memory(&caller, ptr, len.max(0) as usize),
+                )?;
+                caller.data_mut().result_buffer = bytes;
+                Ok(())
+            },
+        )
+        .map_err(|e| VMError::InternalError(format!("link ret: {}", e)))?;
+
+    // gas(amount): 注入された計測コードから呼ばれ、残ガスから差し引く。
+    // 引数は命令ブロックの合計コスト（u32 を i32 として受け取る）。
+    linker
+        .func_wrap(
+            "env",
+            "gas",
+            |mut caller: Caller<'_, Runtime<S>>, amount: i32| {
+                let cost = amount.max(0) as u64;
+                let state = caller.data_mut();
+                match state.gas_left.checked_sub(cost) {
+                    Some(remaining) => {
+                        state.gas_left = remaining;
+                        Ok(())
+                    }
+                    None => {
+                        state.gas_left = 0;
+                        drop(state);
+                        trap(&mut caller, VMError::OutOfGas)
+                    }
+                }
+            },
+        )
+        .map_err(|e| VMError::InternalError(format!("link gas: {}", e)))?;
+
+    // sender(ptr) -> len: 送信者アドレスを書き込み、バイト長を返す
+    linker
+        .func_wrap(
+            "env",
+            "sender",
+            |mut caller: Caller<'_, Runtime<S>>, ptr: i32| -> Result<i32, wasmi::Error> {
+                let bytes = caller.data().context.sender.clone().into_bytes();
+                trap_on_err(&mut caller, Runtime::write_memory(&mut caller, ptr, &bytes))?;
+                Ok(bytes.len() as i32)
+            },
+        )
+        .map_err(|e| VMError::InternalError(format!("link sender: {}", e)))?;
+
+    // address(ptr) -> len: 実行中コントラクトのアドレスを書き込み、バイト長を返す
+    linker
+        .func_wrap(
+            "env",
+            "address",
+            |mut caller: Caller<'_, Runtime<S>>, ptr: i32| -> Result<i32, wasmi::Error> {
+                let bytes = caller.data().address.clone().into_bytes();
+                trap_on_err(&mut caller, Runtime::write_memory(&mut caller, ptr, &bytes))?;
+                Ok(bytes.len() as i32)
+            },
+        )
+        .map_err(|e| VMError::InternalError(format!("link address: {}", e)))?;
+
+    // value() -> u64: 呼び出しに添付された値を返す
+    linker
+        .func_wrap(
+            "env",
+            "value",
+            |caller: Caller<'_, Runtime<S>>| -> i64 { caller.data().context.value as i64 },
+        )
+        .map_err(|e| VMError::InternalError(format!("link value: {}", e)))?;
+
+    // elog(ptr, len): 任意バイト列を ContractEvent として発火する
+    linker
+        .func_wrap(
+            "env",
+            "elog",
+            |mut caller: Caller<'_, Runtime<S>>, ptr: i32, len: i32| {
+                let data = trap_on_err(
+                    &mut caller,
+                    Runtime::read_memory(&caller, ptr, len.max(0) as usize),
+                )?;
+                caller.data_mut().events.push(ContractEvent {
+                    name: "log".to_string(),
+                    topics: Vec::new(),
+                    data,
+                    indexed: Vec::new(),
+                    anonymous: true,
+                    metadata: None,
+                });
+                Ok(())
+            },
+        )
+        .map_err(|e| VMError::InternalError(format!("link elog: {}", e)))?;
+
+    // call(addr_ptr, addr_len, method_ptr, method_len, gas, value, input_ptr,
+    //      input_len, ret_ptr, ret_cap) -> i32
+    //
+    // 別コントラクトをネスト実行する。`depth + 1` のコンテキストを構成し、転送ガスを
+    // 残ガスで上限クランプし、値を転送して、呼び出し先の戻り値と成否フラグを返す。
+    // 現フレームが静的な場合、非ゼロの値転送は `StateChangeInStaticCall` でトラップする
+    // （ネストフレームへ `is_static` を伝播するため、子側のストレージ書き込みも同様に弾かれる）。
+    linker
+        .func_wrap(
+            "env",
+            "call",
+            |mut caller: Caller<'_, Runtime<S>>,
+             addr_ptr: i32,
+             addr_len: i32,
+             method_ptr: i32,
+             method_len: i32,
+             gas: i64,
+             value: i64,
+             input_ptr: i32,
+             input_len: i32,
+             ret_ptr: i32,
+             ret_cap: i32|
+             -> Result<i32, wasmi::Error> {
+                let addr_bytes =
+                    trap_on_err(&mut caller, Runtime::read_memory(&caller, addr_ptr, addr_len.max(0) as usize))?;
+                let method_bytes = trap_on_err(
+                    &mut caller,
+                    Runtime::read_memory(&caller, method_ptr, method_len.max(0) as usize),
+                )?;
+                let input = trap_on_err(
+                    &mut caller,
+                    Runtime::read_memory(&caller, input_ptr, input_len.max(0) as usize),
+                )?;
+                let callee = String::from_utf8(addr_bytes)
+                    .map_err(|_| VMError::InvalidAddress("non-utf8 call address".to_string()));
+                let callee = trap_on_err(&mut caller, callee)?;
+                let method = String::from_utf8(method_bytes)
+                    .map_err(|_| VMError::InvalidArguments("non-utf8 call method".to_string()));
+                let method = trap_on_err(&mut caller, method)?;
+
+                let value = value.max(0) as u64;
+                // 静的フレームでの値転送は禁止
+                if caller.data().context.is_static && value != 0 {
+                    return trap(&mut caller, VMError::StateChangeInStaticCall);
+                }
+
+                // 転送ガスを残ガスで上限クランプする
+                let requested = gas.max(0) as u64;
+                let sub_gas = requested.min(caller.data().gas_left);
+
+                // 参照をコピーしてから caller を可変借用できるようにする
+                let vm = caller.data().vm;
+                let caller_address = caller.data().address.clone();
+                let parent_ctx = caller.data().context.clone();
+
+                let outcome = vm.call_contract(
+                    &caller_address,
+                    &callee,
+                    &method,
+                    value,
+                    sub_gas,
+                    input,
+                    &parent_ctx,
+                );
+
+                match outcome {
+                    Ok(child) => {
+                        // 子フレームのリソース計数を親へ積み上げる
+                        let state = caller.data_mut();
+                        state.gas_left = state.gas_left.saturating_sub(child.gas_used);
+                        state.storage_reads += child.storage_reads;
+                        state.storage_writes += child.storage_writes;
+                        state.storage_deletes += child.storage_deletes;
+
+                        // 戻り値を呼び出し元メモリへ（ret_cap で切り詰め）書き戻す
+                        let n = child.return_data.len().min(ret_cap.max(0) as usize);
+                        if n > 0 {
+                            trap_on_err(
+                                &mut caller,
+                                Runtime::write_memory(&mut caller, ret_ptr, &child.return_data[..n]),
+                            )?;
+                        }
+                        Ok(if child.success { 1 } else { 0 })
+                    }
+                    // 呼び出し失敗: 子のストレージ書き込みは未反映のままロールバックされる。
+                    // 転送したガスは消費済みとして親から差し引く（EVM 風の失敗セマンティクス）。
+                    Err(_) => {
+                        let state = caller.data_mut();
+                        state.gas_left = state.gas_left.saturating_sub(sub_gas);
+                        Ok(0)
+                    }
+                }
+            },
+        )
+        .map_err(|e| VMError::InternalError(format!("link call: {}", e)))?;
+
+    Ok(())
+}
+
+/// ホスト関数内の VM エラーを `Runtime` に記録し、wasmi トラップへ変換する
+fn trap<S: ContractStorage, T>(
+    caller: &mut Caller<'_, Runtime<S>>,
+    error: VMError,
+) -> Result<T, wasmi::Error> {
+    let message = format!("{:?}", error);
+    caller.data_mut().host_error = Some(error);
+    Err(wasmi::Error::new(message))
+}
+
+/// `Result<T, VMError>` を wasmi トラップへ橋渡しするヘルパ
+fn trap_on_err<S: ContractStorage, T>(
+    caller: &mut Caller<'_, Runtime<S>>,
+    result: Result<T, VMError>,
+) -> Result<T, wasmi::Error> {
+    match result {
+        Ok(value) => Ok(value),
+        Err(error) => trap(caller, error),
+    }
+}
+
+/// ホストランタイムが提供する既定の許可インポート名
+///
+/// `register_host_functions` が `env` モジュールへ登録するホスト関数と、
+/// デプロイ時に注入される `gas` 計測呼び出しを含む。
+fn default_import_allow_list() -> Vec<String> {
+    [
+        "storage_read",
+        "storage_write",
+        "ret",
+        "gas",
+        "sender",
+        "address",
+        "value",
+        "elog",
+        "call",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
 /// Wasm VM
 pub struct WasmVM<S: ContractStorage> {
     /// ストレージ
@@ -59,9 +907,13 @@ pub struct WasmVM<S: ContractStorage> {
     default_gas_limit: u64,
     /// ガススケジュール
     gas_schedule: HashMap<String, u64>,
+    /// オペコードごとのガスコスト表（デプロイ時の計測注入に使用）
+    costs: WasmCosts,
+    /// 許可されたホストインポート名のホワイトリスト
+    import_allow_list: Vec<String>,
 }
 
-impl<S: ContractStorage> WasmVM<S> {
+impl<S: ContractStorage + 'static> WasmVM<S> {
     /// 新しいWasm VMを作成
     pub fn new(storage: S) -> Self {
         Self {
@@ -78,8 +930,20 @@ impl<S: ContractStorage> WasmVM<S> {
             max_gas_limit: 10_000_000,
             default_gas_limit: 1_000_000,
             gas_schedule: HashMap::new(),
+            costs: WasmCosts::default(),
+            import_allow_list: default_import_allow_list(),
         }
     }
+
+    /// ガスコスト表を設定
+    pub fn set_costs(&mut self, costs: WasmCosts) {
+        self.costs = costs;
+    }
+
+    /// 許可するホストインポート名のホワイトリストを設定
+    pub fn set_import_allow_list(&mut self, allow_list: Vec<String>) {
+        self.import_allow_list = allow_list;
+    }
     
     /// モジュールをロード
     fn load_module(&mut self, address: &str) -> Result<WasmModule, VMError> {
@@ -103,83 +967,242 @@ impl<S: ContractStorage> WasmVM<S> {
     }
     
     /// モジュールを解析
+    ///
+    /// バイトコードの各セクションを実際に読み取ってエクスポート・インポートと
+    /// メモリ/テーブル/グローバルの宣言上限を埋める。インポートは許可リストに
+    /// 照合し、メモリ/テーブルの宣言が設定上限を超える場合は、コントラクトを
+    /// ストレージへ書き込む前に記述的な `VMError` で失敗させる。
     fn parse_module(&self, address: &str, bytecode: &[u8]) -> Result<WasmModule, VMError> {
-        // 実際の実装では、Wasmバイトコードを解析してモジュール情報を抽出する
-        // ここでは簡易的な実装を提供
-        
+        let info = extract_module_info(bytecode)?;
+
+        // インポートを許可リストに照合する
+        for name in &info.imports {
+            if !self.import_allow_list.iter().any(|allowed| allowed == name) {
+                return Err(VMError::InvalidArguments(format!(
+                    "disallowed host import: env.{}",
+                    name
+                )));
+            }
+        }
+
+        // メモリ/テーブルの宣言上限を検証する
+        if info.memory_pages > self.max_memory_pages {
+            return Err(VMError::InvalidArguments(format!(
+                "declared memory {} pages exceeds limit {}",
+                info.memory_pages, self.max_memory_pages
+            )));
+        }
+        if info.table_size > self.max_table_size {
+            return Err(VMError::InvalidArguments(format!(
+                "declared table size {} exceeds limit {}",
+                info.table_size, self.max_table_size
+            )));
+        }
+
         let module = WasmModule {
             id: address.to_string(),
             name: format!("Module_{}", address),
             bytecode: bytecode.to_vec(),
-            exports: vec!["memory".to_string(), "main".to_string()],
-            imports: vec![],
-            memory_limit: 1024 * 1024, // 1MB
-            table_limit: 1000,
-            global_limit: 100,
+            exports: info.exports,
+            imports: info.imports,
+            memory_limit: info.memory_pages as usize * WASM_PAGE_SIZE,
+            table_limit: info.table_size as usize,
+            global_limit: info.global_count as usize,
             metadata: None,
         };
-        
+
         Ok(module)
     }
     
     /// 関数を実行
+    ///
+    /// `wasmi` インタプリタでモジュールをインスタンス化し、`env` モジュール配下の
+    /// ホスト関数テーブルへインポートを解決したうえで、指定されたエクスポート関数を
+    /// 名前で呼び出す。ストレージ読み書き・戻り値・ガス・イベントはホスト呼び出しを
+    /// 通じて `Runtime` に集約し、実行後に `ExecutionResult` へ反映する。
     fn execute_function(&self, module: &WasmModule, function: &str, context: &ExecutionContext) -> Result<ExecutionResult, VMError> {
-        // 実際の実装では、Wasmモジュールから関数を呼び出す
-        // ここでは簡易的な実装を提供
-        
         // 関数がエクスポートされているか確認
         if !module.exports.contains(&function.to_string()) {
             return Err(VMError::InvalidMethod(function.to_string()));
         }
-        
+
         // ガス制限をチェック
         if context.gas_limit > self.max_gas_limit {
             return Err(VMError::OutOfGas);
         }
-        
+
         // 呼び出し深度をチェック
         if context.depth > self.max_call_depth as usize {
             return Err(VMError::CallDepthExceeded);
         }
-        
-        // 関数を実行（実際の実装では、Wasmインタプリタを使用）
-        let gas_used = 1000; // 仮の値
-        let memory_used = 1024; // 仮の値
-        let storage_used = 0; // 仮の値
-        
-        // ガス使用量をチェック
-        if gas_used > context.gas_limit {
-            return Err(VMError::OutOfGas);
+
+        let address = context.address.clone().unwrap_or_else(|| module.id.clone());
+
+        // インタプリタとモジュールを用意
+        let engine = Engine::default();
+        let wasm_module = Module::new(&engine, &module.bytecode[..])
+            .map_err(|e| VMError::InvalidArguments(format!("invalid wasm module: {}", e)))?;
+
+        let runtime = Runtime {
+            storage: &self.storage,
+            vm: self,
+            address: address.clone(),
+            context,
+            memory: None,
+            result_buffer: Vec::new(),
+            events: Vec::new(),
+            pending_writes: Vec::new(),
+            gas_left: context.gas_limit,
+            storage_reads: 0,
+            storage_writes: 0,
+            storage_deletes: 0,
+            host_error: None,
+        };
+        let mut store = Store::new(&engine, runtime);
+
+        // ホスト関数テーブルを登録し、インポートを解決してインスタンス化する
+        let mut linker: Linker<Runtime<S>> = Linker::new(&engine);
+        register_host_functions(&mut linker)?;
+        let instance = linker
+            .instantiate(&mut store, &wasm_module)
+            .and_then(|pre| pre.start(&mut store))
+            .map_err(|e| VMError::InternalError(format!("instantiate: {}", e)))?;
+
+        // 線形メモリハンドルをランタイムに設定（ホスト関数から参照される）
+        if let Some(Extern::Memory(memory)) = instance.get_export(&store, "memory") {
+            store.data_mut().memory = Some(memory);
         }
-        
+
+        // エクスポート関数を名前で解決して呼び出す
+        let func = instance
+            .get_typed_func::<(), ()>(&store, function)
+            .map_err(|_| VMError::InvalidMethod(function.to_string()))?;
+        let call_result = func.call(&mut store, ());
+
+        // 線形メモリ使用量を確定（ページ数 × 64KiB）
+        let memory_used = store
+            .data()
+            .memory
+            .map(|m| m.size(&store) as u64 * 65_536)
+            .unwrap_or(0);
+
+        // ストアからホスト状態を取り出し、借用を解放する
+        let runtime = store.into_data();
+
+        // トラップした場合はホスト側で記録した VM エラーを優先して伝搬する
+        if let Err(trap) = call_result {
+            return Err(runtime
+                .host_error
+                .unwrap_or_else(|| VMError::InternalError(format!("execution trapped: {}", trap))));
+        }
+
+        let gas_used = context.gas_limit.saturating_sub(runtime.gas_left);
+
+        // バッファされたストレージ書き込みを反映する
+        for (key, value) in &runtime.pending_writes {
+            self.storage
+                .set_contract_storage(&address, key.clone(), value.clone())
+                .map_err(|e| VMError::InternalError(format!("Failed to write contract storage: {}", e)))?;
+        }
+
         // 実行結果を作成
         let result = ExecutionResult {
             success: true,
-            return_data: vec![1, 2, 3], // 仮の値
+            return_data: runtime.result_buffer,
             gas_used,
             memory_used,
-            storage_used,
-            storage_reads: 0,
-            storage_writes: 0,
-            storage_deletes: 0,
-            events: Vec::new(),
+            storage_used: runtime.pending_writes.len() as u64 * SLOT_LEN as u64 * 2,
+            storage_reads: runtime.storage_reads,
+            storage_writes: runtime.storage_writes,
+            storage_deletes: runtime.storage_deletes,
+            events: runtime.events,
             logs: Vec::new(),
-            address: context.address.clone().unwrap_or_default(),
+            address,
             error: None,
         };
-        
+
         Ok(result)
     }
+
+    /// 別コントラクトをネスト実行する（`call` ホスト関数から呼ばれる）
+    ///
+    /// `parent` を基に `depth + 1` のコンテキストを構成し、呼び出し深度を検証したうえで、
+    /// 呼び出し先バイトコードを解析して `method` を実行する。`is_static` は親から伝播するため、
+    /// 静的フレーム配下ではストレージ書き込みが弾かれる。呼び出し先がトラップした場合は
+    /// `execute_function` が `pending_writes` を反映しないまま `Err` を返すため、子フレームの
+    /// ストレージ書き込みは自然にロールバックされる。
+    fn call_contract(
+        &self,
+        caller_address: &str,
+        callee: &str,
+        method: &str,
+        value: u64,
+        gas: u64,
+        input: Vec<u8>,
+        parent: &ExecutionContext,
+    ) -> Result<ExecutionResult, VMError> {
+        let depth = parent.depth + 1;
+        if depth > self.max_call_depth as usize {
+            return Err(VMError::CallDepthExceeded);
+        }
+
+        let nested = ExecutionContext {
+            gas_limit: gas,
+            sender: caller_address.to_string(),
+            value,
+            data: input,
+            address: Some(callee.to_string()),
+            block_height: parent.block_height,
+            block_time: parent.block_time,
+            is_static: parent.is_static,
+            depth,
+        };
+
+        // 呼び出し先のバイトコードを解析する（キャッシュは &mut を要するためここでは直接取得）
+        let bytecode = self
+            .storage
+            .get_contract(callee)
+            .map_err(|e| VMError::InternalError(format!("Failed to get contract: {}", e)))?
+            .ok_or_else(|| VMError::InvalidAddress(callee.to_string()))?;
+        let module = self.parse_module(callee, &bytecode)?;
+
+        self.execute_function(&module, method, &nested)
+    }
+
+    /// コード片に対して一度きりの関数実行を行う
+    ///
+    /// `deploy`/`call`と異なり、実行結果をストレージへ永続化せず、その場で
+    /// インスタンス化・実行する。`ContractExecutor`のような、コードと実行
+    /// コンテキストを直接受け取るインターフェースから呼び出すためのエントリ
+    /// ポイント。呼び出し前にガス計測を注入する点は`deploy`と同様。
+    pub fn execute_once(
+        &self,
+        code: &[u8],
+        function_name: &str,
+        context: &ExecutionContext,
+    ) -> Result<ExecutionResult, VMError> {
+        let instrumented = instrument_gas(code, &self.costs)
+            .map_err(|e| VMError::InvalidArguments(format!("gas instrumentation failed: {}", e)))?;
+
+        let address = format!("ephemeral_{}", Utc::now().timestamp_nanos());
+        let module = self.parse_module(&address, &instrumented)?;
+
+        self.execute_function(&module, function_name, context)
+    }
 }
 
-impl<S: ContractStorage> VirtualMachine for WasmVM<S> {
+impl<S: ContractStorage + 'static> VirtualMachine for WasmVM<S> {
     fn deploy(&self, code: Vec<u8>, context: ExecutionContext) -> Result<ExecutionResult, VMError> {
         // コントラクトアドレスを生成
         let address = format!("contract_{}", Utc::now().timestamp_nanos());
-        
+
+        // デプロイ時にガス計測を注入し、以降の実行を決定的に計測する
+        let code = instrument_gas(&code, &self.costs)
+            .map_err(|e| VMError::InvalidArguments(format!("gas instrumentation failed: {}", e)))?;
+
         // モジュールを解析
         let module = self.parse_module(&address, &code)?;
-        
+
         // コントラクトをストレージに保存
         self.storage.set_contract(&address, code)
             .map_err(|e| VMError::InternalError(format!("Failed to set contract: {}", e)))?;
@@ -243,6 +1266,72 @@ impl<S: ContractStorage> VirtualMachine for WasmVM<S> {
     }
 }
 
+/// 検証時に拒否する非決定的オペコードの集合
+///
+/// コントラクト実行はシャード間で再現可能でなければならないため、浮動小数点や
+/// SIMD・アトミックといった実装依存の挙動を含むモジュールを既定で弾く。将来的に
+/// チェーンが特定の機能へオプトインできるよう、各カテゴリは個別に無効化できる。
+#[derive(Debug, Clone)]
+pub struct ForbiddenOpcodes {
+    /// 浮動小数点オペコード（`f32.*`/`f64.*` および float 変換）
+    pub floats: bool,
+    /// SIMD 命令
+    pub simd: bool,
+    /// スレッド/アトミック命令
+    pub atomics: bool,
+    /// バルクメモリ命令（`memory.copy`/`memory.fill` など）
+    pub bulk_memory: bool,
+    /// 複数メモリの宣言
+    pub multiple_memories: bool,
+    /// `memory.grow` 命令
+    pub grow_memory: bool,
+}
+
+impl Default for ForbiddenOpcodes {
+    fn default() -> Self {
+        Self {
+            floats: true,
+            simd: true,
+            atomics: true,
+            bulk_memory: true,
+            multiple_memories: true,
+            // grow は計測対象として許可し、宣言ページ上限で別途制限する
+            grow_memory: false,
+        }
+    }
+}
+
+impl ForbiddenOpcodes {
+    /// 命令が禁止カテゴリに該当する場合、その分類名を返す
+    fn classify(&self, instruction: &Instruction) -> Option<&'static str> {
+        let name = format!("{:?}", instruction);
+        if self.floats && (name.contains("F32") || name.contains("F64")) {
+            return Some("floating-point");
+        }
+        if self.simd && name.starts_with("Simd") {
+            return Some("SIMD");
+        }
+        if self.atomics && (name.starts_with("Atomic") || name.contains("AtomicRmw")) {
+            return Some("atomics");
+        }
+        if self.bulk_memory
+            && (name.starts_with("MemoryInit")
+                || name.starts_with("MemoryCopy")
+                || name.starts_with("MemoryFill")
+                || name.starts_with("DataDrop")
+                || name.starts_with("TableInit")
+                || name.starts_with("TableCopy")
+                || name.starts_with("ElemDrop"))
+        {
+            return Some("bulk-memory");
+        }
+        if self.grow_memory && matches!(instruction, Instruction::GrowMemory(..)) {
+            return Some("memory.grow");
+        }
+        None
+    }
+}
+
 /// Wasmコンパイラ
 pub struct WasmCompiler {
     /// 最適化レベル
@@ -255,6 +1344,14 @@ pub struct WasmCompiler {
     enforce_stack_limits: bool,
     /// メモリ制限を強制するフラグ
     enforce_memory_limits: bool,
+    /// ガスコスト表
+    costs: WasmCosts,
+    /// 最大スタック高（デプロイごとに調整可能）
+    max_stack_size: u32,
+    /// 検証で拒否する非決定的オペコードの集合
+    forbidden: ForbiddenOpcodes,
+    /// 宣言可能な最大メモリページ数
+    max_memory_pages: u32,
 }
 
 impl WasmCompiler {
@@ -266,40 +1363,62 @@ impl WasmCompiler {
             insert_gas_metering: true,
             enforce_stack_limits: true,
             enforce_memory_limits: true,
+            costs: WasmCosts::default(),
+            max_stack_size: 1000,
+            forbidden: ForbiddenOpcodes::default(),
+            max_memory_pages: 100,
         }
     }
-    
+
+    /// ガスコスト表を設定
+    pub fn with_costs(mut self, costs: WasmCosts) -> Self {
+        self.costs = costs;
+        self
+    }
+
+    /// 拒否する非決定的オペコードの集合を設定
+    pub fn with_forbidden_opcodes(mut self, forbidden: ForbiddenOpcodes) -> Self {
+        self.forbidden = forbidden;
+        self
+    }
+
+    /// スタック高の上限を設定
+    pub fn with_stack_limit(mut self, max_stack_size: u32) -> Self {
+        self.max_stack_size = max_stack_size;
+        self
+    }
+
     /// ソースコードをコンパイル
     pub fn compile(&self, source_code: &str, language: &str) -> Result<Vec<u8>, Error> {
         // 実際の実装では、ソースコードをWasmにコンパイルする
         // ここでは簡易的な実装を提供
-        
-        // 言語に応じたコンパイル処理
-        match language {
-            "rust" => {
-                // Rustコードをコンパイル
-                Ok(vec![0, 97, 115, 109, 1, 0, 0, 0]) // 仮のWasmバイナリ
-            },
-            "assemblyscript" => {
-                // AssemblyScriptコードをコンパイル
-                Ok(vec![0, 97, 115, 109, 1, 0, 0, 0]) // 仮のWasmバイナリ
-            },
-            "c" => {
-                // Cコードをコンパイル
-                Ok(vec![0, 97, 115, 109, 1, 0, 0, 0]) // 仮のWasmバイナリ
-            },
-            "cpp" => {
-                // C++コードをコンパイル
-                Ok(vec![0, 97, 115, 109, 1, 0, 0, 0]) // 仮のWasmバイナリ
-            },
-            "go" => {
-                // Goコードをコンパイル
-                Ok(vec![0, 97, 115, 109, 1, 0, 0, 0]) // 仮のWasmバイナリ
-            },
+
+        // 言語に応じたコンパイル処理（いずれも仮のWasmバイナリを返す）
+        let bytecode = match language {
+            "rust" | "assemblyscript" | "c" | "cpp" | "go" => {
+                vec![0, 97, 115, 109, 1, 0, 0, 0]
+            }
             _ => {
-                Err(Error::InvalidInput(format!("Unsupported language: {}", language)))
+                return Err(Error::InvalidInput(format!("Unsupported language: {}", language)));
             }
+        };
+
+        self.instrument(&bytecode)
+    }
+
+    /// バイトコードへ計測・防御コードを注入する
+    ///
+    /// `enforce_stack_limits` が有効ならスタック高リミッタを、`insert_gas_metering`
+    /// が有効ならガス計測を、この順で注入する。
+    pub fn instrument(&self, bytecode: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut bytecode = bytecode.to_vec();
+        if self.enforce_stack_limits {
+            bytecode = instrument_stack_height(&bytecode, self.max_stack_size)?;
+        }
+        if self.insert_gas_metering {
+            bytecode = instrument_gas(&bytecode, &self.costs)?;
         }
+        Ok(bytecode)
     }
     
     /// バイトコードを最適化
@@ -311,15 +1430,68 @@ impl WasmCompiler {
     }
     
     /// バイトコードを検証
+    ///
+    /// マジックナンバーに加えて、デコードした命令ストリームを走査し、シャード間で
+    /// 再現不能となりうる構造（浮動小数点・SIMD・アトミック・バルクメモリ、複数メモリ、
+    /// 設定ページ数を超えるメモリ宣言）を拒否する。禁止命令を見つけた場合は、最初の
+    /// 違反オペコードとその関数インデックス・オフセットを添えた記述的なエラーを返す。
     pub fn validate(&self, bytecode: &[u8]) -> Result<bool, Error> {
-        // 実際の実装では、Wasmバイトコードを検証する
-        // ここでは簡易的な実装を提供
-        
         // Wasmマジックナンバーをチェック
         if bytecode.len() < 8 || bytecode[0..4] != [0, 97, 115, 109] {
             return Ok(false);
         }
-        
+
+        // モジュールをデコードする。未対応機能（SIMD/アトミック/バルク）を含むモジュールは
+        // ここで decode エラーとなり、非決定的構造として弾かれる。
+        let module: ElementsModule = parity_wasm::deserialize_buffer(bytecode)
+            .map_err(|e| Error::InvalidInput(format!("failed to decode wasm module: {}", e)))?;
+
+        // 複数メモリの宣言を拒否し、宣言メモリがページ上限を超えていないか確認する
+        if self.forbidden.multiple_memories {
+            let declared = module.memory_section().map(|s| s.entries().len()).unwrap_or(0);
+            let imported = module
+                .import_section()
+                .map(|s| {
+                    s.entries()
+                        .iter()
+                        .filter(|e| matches!(e.external(), External::Memory(_)))
+                        .count()
+                })
+                .unwrap_or(0);
+            if declared + imported > 1 {
+                return Err(Error::InvalidInput(format!(
+                    "module declares {} memories; only one is permitted",
+                    declared + imported
+                )));
+            }
+        }
+        if let Some(section) = module.memory_section() {
+            for entry in section.entries() {
+                let limits = entry.limits();
+                let pages = limits.maximum().unwrap_or(limits.initial());
+                if pages > self.max_memory_pages {
+                    return Err(Error::InvalidInput(format!(
+                        "declared memory {} pages exceeds limit {}",
+                        pages, self.max_memory_pages
+                    )));
+                }
+            }
+        }
+
+        // 命令ストリームを走査して禁止オペコードを検出する
+        if let Some(code) = module.code_section() {
+            for (func_index, body) in code.bodies().iter().enumerate() {
+                for (offset, instruction) in body.code().elements().iter().enumerate() {
+                    if let Some(class) = self.forbidden.classify(instruction) {
+                        return Err(Error::InvalidInput(format!(
+                            "non-deterministic opcode {:?} ({}) in function {} at offset {}",
+                            instruction, class, func_index, offset
+                        )));
+                    }
+                }
+            }
+        }
+
         Ok(true)
     }
 }
@@ -328,13 +1500,16 @@ impl WasmCompiler {
 pub struct WasmExecutor<S: ContractStorage> {
     /// 仮想マシン
     vm: WasmVM<S>,
+    /// 直近の`ContractExecutor::execute`呼び出しの統計情報
+    last_stats: Mutex<ExecutionStats>,
 }
 
-impl<S: ContractStorage> WasmExecutor<S> {
+impl<S: ContractStorage + 'static> WasmExecutor<S> {
     /// 新しいWasm実行器を作成
     pub fn new(storage: S) -> Self {
         Self {
             vm: WasmVM::new(storage),
+            last_stats: Mutex::new(ExecutionStats::new()),
         }
     }
     
@@ -418,7 +1593,143 @@ impl<S: ContractStorage> WasmExecutor<S> {
         
         // コントラクトを削除
         self.vm.delete(address, context)?;
-        
+
         Ok(())
     }
+
+    /// ガス計測を決定的な主リソース上限としつつ、`max_execution_time_ms`を
+    /// 副次的なウォッチドッグとして適用し、1回分の実行を行う
+    ///
+    /// `wasmi`の呼び出しは`WasmVM`内部（`S: Send`を要求しない）を跨いで別スレッド
+    /// へ安全に移せないため、ここでは実行を先取りして止めるのではなく、実行前後の
+    /// 経過時間を計測して上限超過を事後検出する。暴走ループは`max_execution_time_ms`
+    /// を待たずとも、命令単位で決定的にガス欠としてトラップされる。
+    fn run_once(
+        &self,
+        code: &[u8],
+        function_name: &str,
+        args: &[Vec<u8>],
+        context: &ExecutionContext,
+        config: &ExecutorConfig,
+    ) -> Result<(ExecutionResult, ExecutionStats), Error> {
+        let started_at = Instant::now();
+
+        let stack_limited = instrument_stack_height(code, (config.max_stack_bytes / 8).max(1) as u32)
+            .map_err(|e| Error::InvalidInput(format!("stack instrumentation failed: {}", e)))?;
+
+        let mut call_context = context.clone();
+        call_context.gas_limit = config.max_gas;
+        call_context.data = args.concat();
+
+        let result = self
+            .vm
+            .execute_once(&stack_limited, function_name, &call_context)
+            .map_err(Error::from)?;
+
+        let elapsed = started_at.elapsed();
+        if elapsed > Duration::from_millis(config.max_execution_time_ms) {
+            return Err(Error::Timeout(format!(
+                "execution took {}ms, exceeding the {}ms watchdog limit",
+                elapsed.as_millis(),
+                config.max_execution_time_ms
+            )));
+        }
+        if result.memory_used > config.max_memory_bytes {
+            return Err(Error::ResourceExhausted(format!(
+                "execution used {} bytes of memory, exceeding the {} byte limit",
+                result.memory_used, config.max_memory_bytes
+            )));
+        }
+
+        let mut stats = ExecutionStats::new();
+        stats.record_execution_time(started_at);
+        stats.record_gas_used(result.gas_used);
+        stats.record_memory_used(result.memory_used);
+        stats.record_storage_used(result.storage_used);
+        stats.record_function_call_count(1);
+        stats.record_storage_read_count(result.storage_reads);
+        stats.record_storage_write_count(result.storage_writes);
+        stats.record_external_call_count(0);
+        stats.record_event_count(result.events.len() as u64);
+
+        Ok((result, stats))
+    }
+}
+
+impl<S: ContractStorage + 'static> ContractExecutor for WasmExecutor<S> {
+    fn name(&self) -> &str {
+        "wasmi"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn supported_platforms(&self) -> Vec<String> {
+        vec!["wasm32-unknown-unknown".to_string()]
+    }
+
+    fn capabilities(&self) -> ExecutorCapabilities {
+        ExecutorCapabilities {
+            min_abi_version: (1, 0, 0),
+            max_abi_version: (1, 0, 0),
+            // ガス計測はinstrument_gasによる命令単位のフュエル消費なので決定的
+            deterministic_metering: true,
+            debuggable: true,
+        }
+    }
+
+    fn execute(
+        &self,
+        code: &[u8],
+        function_name: &str,
+        args: &[Vec<u8>],
+        context: &ExecutionContext,
+        config: &ExecutorConfig,
+    ) -> Result<ExecutionResult, Error> {
+        let (result, stats) = self.run_once(code, function_name, args, context, config)?;
+
+        if let Ok(mut last_stats) = self.last_stats.lock() {
+            *last_stats = stats;
+        }
+
+        Ok(result)
+    }
+
+    fn get_stats(&self) -> ExecutionStats {
+        self.last_stats
+            .lock()
+            .map(|stats| stats.clone())
+            .unwrap_or_default()
+    }
+
+    fn debug(
+        &self,
+        code: &[u8],
+        function_name: &str,
+        args: &[Vec<u8>],
+        context: &ExecutionContext,
+        config: &ExecutorConfig,
+    ) -> Result<(ExecutionResult, Vec<String>), Error> {
+        let result = self.execute(code, function_name, args, context, config)?;
+
+        let mut debug_output = vec![
+            format!("function called: {}", function_name),
+            format!("arguments: {} items", args.len()),
+            format!("gas used: {}", result.gas_used),
+            format!("memory used: {} bytes", result.memory_used),
+        ];
+
+        if config.trace_mode {
+            debug_output.push(format!(
+                "storage reads={} writes={} deletes={} events={}",
+                result.storage_reads,
+                result.storage_writes,
+                result.storage_deletes,
+                result.events.len()
+            ));
+        }
+
+        Ok((result, debug_output))
+    }
 }
\ No newline at end of file
@@ -0,0 +1,245 @@
+//! LMDBを使用した`DagStore`の永続化実装
+//!
+//! ノード本体は`tx_id`をキーとしてbincodeでシリアライズしたバイト列で保存し、
+//! ルート・リーフ・確認済み／未確認の各インデックス集合は、それぞれ専用の固定
+//! キーにシリアライズしたセットとして別のデータベース（カラムファミリー相当）
+//! に保存する。これによりプロセス再起動後もDAGの状態を復元できる。
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use lmdb::{Cursor, Database, DatabaseFlags, Environment, Transaction as LmdbTransaction, WriteFlags};
+
+use crate::error::Error;
+
+use super::store::DagStore;
+use super::DAGNode;
+
+/// インデックス集合（ルート・リーフ・確認済み・未確認）を保存する固定キー
+const ROOTS_KEY: &[u8] = b"__roots__";
+const LEAVES_KEY: &[u8] = b"__leaves__";
+const CONFIRMED_KEY: &[u8] = b"__confirmed__";
+const UNCONFIRMED_KEY: &[u8] = b"__unconfirmed__";
+
+/// LMDBバックエンドの`DagStore`実装
+pub struct LmdbDagStore {
+    env: Environment,
+    /// ノード本体（tx_id -> bincodeシリアライズされたDAGNode）
+    nodes_db: Database,
+    /// インデックス集合（固定キー -> bincodeシリアライズされたHashSet<String>）
+    index_db: Database,
+}
+
+impl LmdbDagStore {
+    /// 指定したディレクトリにLMDB環境を開く（存在しなければ作成する）
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let env = Environment::new()
+            .set_max_dbs(4)
+            .open(path.as_ref())
+            .map_err(|e| Error::StorageError(format!("Failed to open LMDB environment: {}", e)))?;
+
+        let nodes_db = env
+            .create_db(Some("nodes"), DatabaseFlags::empty())
+            .map_err(|e| Error::StorageError(format!("Failed to open nodes db: {}", e)))?;
+        let index_db = env
+            .create_db(Some("index"), DatabaseFlags::empty())
+            .map_err(|e| Error::StorageError(format!("Failed to open index db: {}", e)))?;
+
+        Ok(Self {
+            env,
+            nodes_db,
+            index_db,
+        })
+    }
+
+    fn read_index_set(&self, key: &[u8]) -> Result<HashSet<String>, Error> {
+        let txn = self
+            .env
+            .begin_ro_txn()
+            .map_err(|e| Error::StorageError(format!("Failed to begin LMDB read txn: {}", e)))?;
+
+        match txn.get(self.index_db, &key) {
+            Ok(bytes) => bincode::deserialize(bytes)
+                .map_err(|e| Error::DeserializeError(e.to_string())),
+            Err(lmdb::Error::NotFound) => Ok(HashSet::new()),
+            Err(e) => Err(Error::StorageError(format!("Failed to read index: {}", e))),
+        }
+    }
+
+    fn write_index_set(&self, key: &[u8], set: &HashSet<String>) -> Result<(), Error> {
+        let bytes =
+            bincode::serialize(set).map_err(|e| Error::SerializeError(e.to_string()))?;
+
+        let mut txn = self
+            .env
+            .begin_rw_txn()
+            .map_err(|e| Error::StorageError(format!("Failed to begin LMDB write txn: {}", e)))?;
+        txn.put(self.index_db, &key, &bytes, WriteFlags::empty())
+            .map_err(|e| Error::StorageError(format!("Failed to write index: {}", e)))?;
+        txn.commit()
+            .map_err(|e| Error::StorageError(format!("Failed to commit LMDB txn: {}", e)))?;
+
+        Ok(())
+    }
+
+    fn mutate_index_set<F>(&mut self, key: &[u8], f: F) -> Result<(), Error>
+    where
+        F: FnOnce(&mut HashSet<String>),
+    {
+        let mut set = self.read_index_set(key)?;
+        f(&mut set);
+        self.write_index_set(key, &set)
+    }
+}
+
+impl DagStore for LmdbDagStore {
+    fn put_node(&mut self, node: DAGNode) -> Result<(), Error> {
+        let tx_id = node.transaction.id().to_string();
+        let bytes = bincode::serialize(&node).map_err(|e| Error::SerializeError(e.to_string()))?;
+
+        let mut txn = self
+            .env
+            .begin_rw_txn()
+            .map_err(|e| Error::StorageError(format!("Failed to begin LMDB write txn: {}", e)))?;
+        txn.put(self.nodes_db, &tx_id.as_bytes(), &bytes, WriteFlags::empty())
+            .map_err(|e| Error::StorageError(format!("Failed to write node: {}", e)))?;
+        txn.commit()
+            .map_err(|e| Error::StorageError(format!("Failed to commit LMDB txn: {}", e)))?;
+
+        if node.confirmed {
+            self.mutate_index_set(CONFIRMED_KEY, |set| {
+                set.insert(tx_id.clone());
+            })?;
+            self.mutate_index_set(UNCONFIRMED_KEY, |set| {
+                set.remove(&tx_id);
+            })?;
+        } else {
+            self.mutate_index_set(UNCONFIRMED_KEY, |set| {
+                set.insert(tx_id.clone());
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn get_node(&self, tx_id: &str) -> Result<Option<DAGNode>, Error> {
+        let txn = self
+            .env
+            .begin_ro_txn()
+            .map_err(|e| Error::StorageError(format!("Failed to begin LMDB read txn: {}", e)))?;
+
+        match txn.get(self.nodes_db, &tx_id.as_bytes()) {
+            Ok(bytes) => {
+                let node = bincode::deserialize(bytes)
+                    .map_err(|e| Error::DeserializeError(e.to_string()))?;
+                Ok(Some(node))
+            }
+            Err(lmdb::Error::NotFound) => Ok(None),
+            Err(e) => Err(Error::StorageError(format!("Failed to read node: {}", e))),
+        }
+    }
+
+    fn mark_confirmed(&mut self, tx_id: &str, timestamp: u64) -> Result<(), Error> {
+        let mut node = self
+            .get_node(tx_id)?
+            .ok_or_else(|| Error::ValidationError(format!("Node not found: {}", tx_id)))?;
+
+        node.confirmed = true;
+        node.confirmation_time = Some(timestamp);
+        self.put_node(node)
+    }
+
+    fn iter_unconfirmed(&self) -> Result<Vec<String>, Error> {
+        Ok(self.read_index_set(UNCONFIRMED_KEY)?.into_iter().collect())
+    }
+
+    fn roots(&self) -> Result<HashSet<String>, Error> {
+        self.read_index_set(ROOTS_KEY)
+    }
+
+    fn leaves(&self) -> Result<HashSet<String>, Error> {
+        self.read_index_set(LEAVES_KEY)
+    }
+
+    fn add_root(&mut self, tx_id: &str) -> Result<(), Error> {
+        self.mutate_index_set(ROOTS_KEY, |set| {
+            set.insert(tx_id.to_string());
+        })
+    }
+
+    fn add_leaf(&mut self, tx_id: &str) -> Result<(), Error> {
+        self.mutate_index_set(LEAVES_KEY, |set| {
+            set.insert(tx_id.to_string());
+        })
+    }
+
+    fn remove_leaf(&mut self, tx_id: &str) -> Result<(), Error> {
+        self.mutate_index_set(LEAVES_KEY, |set| {
+            set.remove(tx_id);
+        })
+    }
+
+    fn all_node_ids(&self) -> Result<Vec<String>, Error> {
+        let txn = self
+            .env
+            .begin_ro_txn()
+            .map_err(|e| Error::StorageError(format!("Failed to begin LMDB read txn: {}", e)))?;
+        let mut cursor = txn
+            .open_ro_cursor(self.nodes_db)
+            .map_err(|e| Error::StorageError(format!("Failed to open LMDB cursor: {}", e)))?;
+
+        let mut ids = Vec::new();
+        for (key, _) in cursor.iter_start() {
+            ids.push(String::from_utf8_lossy(key).to_string());
+        }
+
+        Ok(ids)
+    }
+
+    fn node_count(&self) -> Result<usize, Error> {
+        Ok(self.all_node_ids()?.len())
+    }
+
+    fn remove_node(&mut self, tx_id: &str) -> Result<(), Error> {
+        let mut txn = self
+            .env
+            .begin_rw_txn()
+            .map_err(|e| Error::StorageError(format!("Failed to begin LMDB write txn: {}", e)))?;
+        match txn.del(self.nodes_db, &tx_id.as_bytes(), None) {
+            Ok(()) | Err(lmdb::Error::NotFound) => {}
+            Err(e) => return Err(Error::StorageError(format!("Failed to delete node: {}", e))),
+        }
+        txn.commit()
+            .map_err(|e| Error::StorageError(format!("Failed to commit LMDB txn: {}", e)))?;
+
+        self.mutate_index_set(ROOTS_KEY, |set| {
+            set.remove(tx_id);
+        })?;
+        self.mutate_index_set(LEAVES_KEY, |set| {
+            set.remove(tx_id);
+        })?;
+        self.mutate_index_set(CONFIRMED_KEY, |set| {
+            set.remove(tx_id);
+        })?;
+        self.mutate_index_set(UNCONFIRMED_KEY, |set| {
+            set.remove(tx_id);
+        })?;
+
+        Ok(())
+    }
+
+    fn clear(&mut self) -> Result<(), Error> {
+        let mut txn = self
+            .env
+            .begin_rw_txn()
+            .map_err(|e| Error::StorageError(format!("Failed to begin LMDB write txn: {}", e)))?;
+        txn.clear_db(self.nodes_db)
+            .map_err(|e| Error::StorageError(format!("Failed to clear nodes db: {}", e)))?;
+        txn.clear_db(self.index_db)
+            .map_err(|e| Error::StorageError(format!("Failed to clear index db: {}", e)))?;
+        txn.commit()
+            .map_err(|e| Error::StorageError(format!("Failed to commit LMDB txn: {}", e)))?;
+
+        Ok(())
+    }
+}
@@ -1,15 +1,20 @@
 //! 有向非巡回グラフ（DAG）トランザクションモジュール
-//! 
+//!
 //! このモジュールはShardXのDAGベースのトランザクション処理を実装します。
 //! 従来のブロックチェーンと比較して、より高いスループットを実現します。
 
-use std::collections::{HashMap, HashSet};
-use std::sync::{Arc, Mutex};
+pub mod lmdb_store;
+pub mod store;
+
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
 use crate::error::Error;
 use crate::transaction::Transaction;
 
+pub use store::{DagStore, InMemoryDagStore};
+
 /// DAGノード
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DAGNode {
     /// トランザクション
     pub transaction: Transaction,
@@ -25,56 +30,78 @@ pub struct DAGNode {
     pub weight: f64,
 }
 
+/// DAGのスナップショット（`snapshot`/`restore`による一括エクスポート・復元用）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DagSnapshot {
+    /// 保持していた全ノード
+    pub nodes: Vec<DAGNode>,
+    /// 最大ノード数
+    pub max_nodes: usize,
+}
+
 /// DAG（有向非巡回グラフ）
+///
+/// ノードの保存先は`DagStore`の実装に委譲される。既定ではプロセス内メモリに
+/// 保持する`InMemoryDagStore`を使うが、`with_store`でLMDBなど永続化バックエンド
+/// に差し替えられる。
 pub struct DAG {
-    /// ノードマップ
-    nodes: HashMap<String, DAGNode>,
-    /// ルートノード
-    roots: HashSet<String>,
-    /// リーフノード
-    leaves: HashSet<String>,
-    /// 確認済みノード
-    confirmed: HashSet<String>,
-    /// 未確認ノード
-    unconfirmed: HashSet<String>,
+    /// 永続化バックエンド
+    store: Box<dyn DagStore>,
     /// 最大ノード数
     max_nodes: usize,
+    /// `resolve_conflicts`で二重支払いの負け枝と判定され、確認を拒否されている
+    /// ノードID（その子孫も含む）
+    rejected: std::collections::HashSet<String>,
+    /// 各入力（送信者＋ノンス）を消費しているノードIDのインデックス
+    ///
+    /// `add_node`のたびに追記され、`confirm_node`が二重支払いの競合解決のために
+    /// DAG全体を毎回スキャンしなくて済むようにする。
+    spender_index: HashMap<String, Vec<String>>,
 }
 
 impl DAG {
-    /// 新しいDAGを作成
+    /// 新しいDAGを作成（インメモリバックエンドを使用）
     pub fn new(max_nodes: usize) -> Self {
         Self {
-            nodes: HashMap::with_capacity(max_nodes),
-            roots: HashSet::new(),
-            leaves: HashSet::new(),
-            confirmed: HashSet::new(),
-            unconfirmed: HashSet::new(),
+            store: Box::new(InMemoryDagStore::new()),
+            max_nodes,
+            rejected: std::collections::HashSet::new(),
+            spender_index: HashMap::new(),
+        }
+    }
+
+    /// 指定した永続化バックエンドを使ってDAGを作成
+    pub fn with_store(max_nodes: usize, store: Box<dyn DagStore>) -> Self {
+        Self {
+            store,
             max_nodes,
+            rejected: std::collections::HashSet::new(),
+            spender_index: HashMap::new(),
         }
     }
-    
+
     /// ノードを追加
     pub fn add_node(&mut self, transaction: Transaction, parents: Vec<String>) -> Result<(), Error> {
         let tx_id = transaction.id().to_string();
-        
+        let input_key = format!("{}:{}", transaction.from, transaction.nonce);
+
         // 既に存在するノードかチェック
-        if self.nodes.contains_key(&tx_id) {
+        if self.store.get_node(&tx_id)?.is_some() {
             return Err(Error::ValidationError(format!("Node already exists: {}", tx_id)));
         }
-        
+
         // 最大ノード数をチェック
-        if self.nodes.len() >= self.max_nodes {
+        if self.store.node_count()? >= self.max_nodes {
             return Err(Error::ValidationError("DAG is full".to_string()));
         }
-        
+
         // 親ノードの存在をチェック
         for parent_id in &parents {
-            if !self.nodes.contains_key(parent_id) {
+            if self.store.get_node(parent_id)?.is_none() {
                 return Err(Error::ValidationError(format!("Parent node not found: {}", parent_id)));
             }
         }
-        
+
         // 新しいノードを作成
         let node = DAGNode {
             transaction,
@@ -84,47 +111,63 @@ impl DAG {
             confirmation_time: None,
             weight: 0.0,
         };
-        
+
         // 親ノードの子リストを更新
         for parent_id in &parents {
-            if let Some(parent) = self.nodes.get_mut(parent_id) {
+            if let Some(mut parent) = self.store.get_node(parent_id)? {
                 parent.children.push(tx_id.clone());
-                
+                self.store.put_node(parent)?;
+
                 // 親がリーフノードだった場合、リーフノードから削除
-                if self.leaves.contains(parent_id) {
-                    self.leaves.remove(parent_id);
-                }
+                self.store.remove_leaf(parent_id)?;
             }
         }
-        
+
         // ノードをDAGに追加
-        self.nodes.insert(tx_id.clone(), node);
-        self.unconfirmed.insert(tx_id.clone());
-        
+        self.store.put_node(node)?;
+
         // 親がない場合はルートノードとして追加
         if parents.is_empty() {
-            self.roots.insert(tx_id.clone());
+            self.store.add_root(&tx_id)?;
         }
-        
+
         // リーフノードとして追加
-        self.leaves.insert(tx_id);
-        
+        self.store.add_leaf(&tx_id)?;
+
+        // 入力（送信者＋ノンス）インデックスを更新し、confirm_nodeがDAG全体を
+        // 再スキャンせずに二重支払いの競合グループを引けるようにする
+        self.spender_index.entry(input_key).or_default().push(tx_id);
+
         Ok(())
     }
-    
+
     /// ノードを確認済みとしてマーク
     pub fn confirm_node(&mut self, tx_id: &str, timestamp: u64) -> Result<(), Error> {
-        let node = self.nodes.get_mut(tx_id)
+        // 確認前に必ず、このノードが消費する入力（送信者＋ノンス）についてのみ
+        // 二重支払いの競合解決を行い、負け枝を確定させる。DAG全体を再スキャンする
+        // `resolve_conflicts`ではなく、対象ノードの競合グループだけを見るため、
+        // 確認のたびに全ノードを辿るO(N)のトポロジカルソートと重み計算を避けられる。
+        self.resolve_conflicts_for(tx_id);
+
+        let node = self.store.get_node(tx_id)?
             .ok_or_else(|| Error::ValidationError(format!("Node not found: {}", tx_id)))?;
-        
+
         // 既に確認済みの場合はエラー
         if node.confirmed {
             return Err(Error::ValidationError(format!("Node already confirmed: {}", tx_id)));
         }
-        
+
+        // 二重支払いの競合解決で負け枝と判定されたノード（またはその子孫）は確認を拒否
+        if self.rejected.contains(tx_id) {
+            return Err(Error::Conflict(format!(
+                "Node is part of a losing branch in a double-spend conflict: {}",
+                tx_id
+            )));
+        }
+
         // 親ノードがすべて確認済みかチェック
         for parent_id in &node.parents {
-            if let Some(parent) = self.nodes.get(parent_id) {
+            if let Some(parent) = self.store.get_node(parent_id)? {
                 if !parent.confirmed {
                     return Err(Error::ValidationError(format!(
                         "Parent node not confirmed: {}",
@@ -133,117 +176,565 @@ impl DAG {
                 }
             }
         }
-        
+
         // ノードを確認済みとしてマーク
-        node.confirmed = true;
-        node.confirmation_time = Some(timestamp);
-        
-        // 確認済みセットに追加
-        self.confirmed.insert(tx_id.to_string());
-        self.unconfirmed.remove(tx_id);
-        
+        self.store.mark_confirmed(tx_id, timestamp)?;
+
         Ok(())
     }
-    
+
     /// トポロジカルソートを実行
     pub fn topological_sort(&self) -> Vec<String> {
-        let mut result = Vec::with_capacity(self.nodes.len());
-        let mut visited = HashSet::new();
-        let mut temp_visited = HashSet::new();
-        
+        let mut result = Vec::new();
+        let mut visited = std::collections::HashSet::new();
+        let mut temp_visited = std::collections::HashSet::new();
+
+        let Ok(roots) = self.store.roots() else {
+            return result;
+        };
+
         // すべてのルートノードから深さ優先探索を開始
-        for root_id in &self.roots {
+        for root_id in &roots {
             self.visit(root_id, &mut visited, &mut temp_visited, &mut result);
         }
-        
+
         // 結果を反転（親から子の順序にする）
         result.reverse();
-        
+
         result
     }
-    
+
     // 深さ優先探索のヘルパー関数
     fn visit(
         &self,
         node_id: &str,
-        visited: &mut HashSet<String>,
-        temp_visited: &mut HashSet<String>,
+        visited: &mut std::collections::HashSet<String>,
+        temp_visited: &mut std::collections::HashSet<String>,
         result: &mut Vec<String>,
     ) {
         // 既に訪問済みならスキップ
         if visited.contains(node_id) {
             return;
         }
-        
+
         // 一時的に訪問済みの場合は循環があるためスキップ
         if temp_visited.contains(node_id) {
             return;
         }
-        
+
         // 一時的に訪問済みとしてマーク
         temp_visited.insert(node_id.to_string());
-        
+
         // 子ノードを訪問
-        if let Some(node) = self.nodes.get(node_id) {
+        if let Ok(Some(node)) = self.store.get_node(node_id) {
             for child_id in &node.children {
                 self.visit(child_id, visited, temp_visited, result);
             }
         }
-        
+
         // 訪問済みとしてマーク
         temp_visited.remove(node_id);
         visited.insert(node_id.to_string());
-        
+
         // 結果に追加
         result.push(node_id.to_string());
     }
-    
+
+    /// MCMCランダムウォークによるティップ選択（Tangleスタイル）
+    ///
+    /// 各ノードの累積重み（自分自身 + 子孫ノード数）を`topological_sort`の逆順に
+    /// 辿って計算し、ルートノード（存在しなければ任意のノード）を起点に`count`回
+    /// 独立なランダムウォークを行う。各ステップでは子ノードjへ
+    /// `exp(-alpha * (cw_current - cw_j))`に比例する確率で遷移し、子を持たない
+    /// ノード（ティップ）に到達したら停止する。`alpha`が0の場合は一様分布になり、
+    /// 子が1つしかない場合は決定的にそのノードへ進む。
+    pub fn select_tips(&self, count: usize, alpha: f64) -> Vec<String> {
+        use rand::seq::SliceRandom;
+        use rand::Rng;
+
+        let Ok(node_count) = self.store.node_count() else {
+            return Vec::new();
+        };
+        if node_count == 0 || count == 0 {
+            return Vec::new();
+        }
+
+        // トポロジカル順序を逆向き（子から親）に辿り、各ノードの累積重み
+        // （自分自身 + 子孫ノード数）を計算する
+        let order = self.topological_sort();
+        let mut cumulative_weight: HashMap<String, f64> = HashMap::with_capacity(order.len());
+        for node_id in order.iter().rev() {
+            let Ok(Some(node)) = self.store.get_node(node_id) else {
+                continue;
+            };
+            let mut weight = 1.0;
+            for child_id in &node.children {
+                weight += cumulative_weight.get(child_id).copied().unwrap_or(1.0);
+            }
+            cumulative_weight.insert(node_id.clone(), weight);
+        }
+
+        // ランダムウォークの起点: ルートノード（なければ任意のノード）
+        let roots = self.store.roots().unwrap_or_default();
+        let start_candidates: Vec<String> = if !roots.is_empty() {
+            roots.into_iter().collect()
+        } else {
+            self.store.all_node_ids().unwrap_or_default()
+        };
+
+        let mut rng = rand::thread_rng();
+        let max_steps = node_count.max(1);
+        let mut tips = Vec::with_capacity(count);
+
+        for _ in 0..count {
+            let Some(start) = start_candidates.choose(&mut rng) else {
+                break;
+            };
+            let mut current = start.clone();
+
+            for _ in 0..max_steps {
+                let Ok(Some(node)) = self.store.get_node(&current) else {
+                    break;
+                };
+
+                // 子を持たない = ティップに到達したので停止
+                if node.children.is_empty() {
+                    break;
+                }
+
+                // 子が1つしかない場合は決定的にそのノードへ進む
+                if node.children.len() == 1 {
+                    current = node.children[0].clone();
+                    continue;
+                }
+
+                let current_weight = cumulative_weight.get(&current).copied().unwrap_or(1.0);
+                let scores: Vec<f64> = node
+                    .children
+                    .iter()
+                    .map(|child_id| {
+                        if alpha == 0.0 {
+                            1.0
+                        } else {
+                            let child_weight = cumulative_weight.get(child_id).copied().unwrap_or(1.0);
+                            (-alpha * (current_weight - child_weight)).exp()
+                        }
+                    })
+                    .collect();
+
+                let total: f64 = scores.iter().sum();
+                let next = if total > 0.0 && total.is_finite() {
+                    let mut pick = rng.gen::<f64>() * total;
+                    let mut chosen = node.children.last().cloned();
+                    for (child_id, score) in node.children.iter().zip(scores.iter()) {
+                        if pick < *score {
+                            chosen = Some(child_id.clone());
+                            break;
+                        }
+                        pick -= score;
+                    }
+                    chosen
+                } else {
+                    // 重みが退化しているフォールバック: 一様分布で選択
+                    node.children.choose(&mut rng).cloned()
+                };
+
+                current = next.unwrap_or(current);
+            }
+
+            tips.push(current);
+        }
+
+        tips.sort();
+        tips.dedup();
+        tips
+    }
+
+    /// 確認済みノードの深さ（ルートからの最長距離）を計算する
+    fn compute_depths(&self) -> HashMap<String, u64> {
+        let order = self.topological_sort();
+        let mut depths: HashMap<String, u64> = HashMap::with_capacity(order.len());
+
+        for node_id in &order {
+            let depth = depths.get(node_id).copied().unwrap_or(0);
+            depths.entry(node_id.clone()).or_insert(depth);
+
+            let Ok(Some(node)) = self.store.get_node(node_id) else {
+                continue;
+            };
+            for child_id in &node.children {
+                let child_depth = depths.entry(child_id.clone()).or_insert(0);
+                if depth + 1 > *child_depth {
+                    *child_depth = depth + 1;
+                }
+            }
+        }
+
+        depths
+    }
+
+    /// 同じ入力（送信者＋ノンス）を消費し、互いに競合しているノードのグループ一覧
+    ///
+    /// `add_node`のたびにインクリメンタルに更新される`spender_index`をそのまま
+    /// 使うため、DAG全体を再スキャンしない。
+    pub fn get_conflicts(&self) -> Vec<Vec<String>> {
+        let mut groups: Vec<Vec<String>> = self
+            .spender_index
+            .values()
+            .filter(|ids| ids.len() > 1)
+            .map(|ids| {
+                let mut ids = ids.clone();
+                ids.sort();
+                ids
+            })
+            .collect();
+        groups.sort();
+        groups
+    }
+
+    /// DAG全体を対象に、競合グループごとに累積承認重みが最大のノードを勝者として
+    /// 残し、それ以外のノードとその子孫を負け枝として確認拒否対象にする
+    ///
+    /// 戻り値は新たに拒否対象となったノード数。通常の確認パスでは対象ノードの
+    /// 競合グループだけを見る`resolve_conflicts_for`を使うため、こちらは
+    /// リロード直後の一括整合性チェックなど、DAG全体の再評価が必要な場合に使う。
+    pub fn resolve_conflicts(&mut self) -> usize {
+        let conflicts = self.get_conflicts();
+        let mut newly_rejected = 0;
+        for group in &conflicts {
+            newly_rejected += self.resolve_conflict_group(group);
+        }
+        newly_rejected
+    }
+
+    /// 指定したノードが消費する入力（送信者＋ノンス）についてのみ競合解決を行う
+    ///
+    /// `confirm_node`から呼ばれる。DAG全体をトポロジカルソートして全ノードの
+    /// 累積重みを求め直す`resolve_conflicts`と異なり、対象ノードの競合グループ
+    /// （`spender_index`から引く）だけを見るため、確認のたびにDAG全体を走査しない。
+    fn resolve_conflicts_for(&mut self, tx_id: &str) -> usize {
+        let Some(node) = self.get_node(tx_id) else {
+            return 0;
+        };
+        let input_key = format!("{}:{}", node.transaction.from, node.transaction.nonce);
+        let Some(group) = self.spender_index.get(&input_key).cloned() else {
+            return 0;
+        };
+        self.resolve_conflict_group(&group)
+    }
+
+    /// 競合グループ（同一入力を消費するノードID群）について、累積承認重みが
+    /// 最大のノードを勝者として残し、それ以外のノードとその子孫を負け枝として
+    /// 確認拒否対象にする。戻り値は新たに拒否対象となったノード数。
+    fn resolve_conflict_group(&mut self, group: &[String]) -> usize {
+        if group.len() < 2 {
+            return 0;
+        }
+
+        let winner = group
+            .iter()
+            .max_by(|a, b| {
+                let weight_a = self.subtree_weight(a);
+                let weight_b = self.subtree_weight(b);
+                weight_a
+                    .partial_cmp(&weight_b)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .cloned();
+
+        let mut newly_rejected = 0;
+        for tx_id in group {
+            if Some(tx_id) == winner.as_ref() {
+                continue;
+            }
+            if self.rejected.insert(tx_id.clone()) {
+                newly_rejected += 1;
+            }
+            newly_rejected += self.reject_descendants(tx_id);
+        }
+
+        newly_rejected
+    }
+
+    /// 指定ノードの部分木重み（自分自身＋子孫ノード数、`select_tips`の累積重みと
+    /// 同じ考え方）をDFSで計算する
+    ///
+    /// 競合グループに属するノードの部分木だけを辿るため、DAG全体を
+    /// トポロジカルソートして累積重み表を作る`select_tips`の計算より軽量。
+    fn subtree_weight(&self, tx_id: &str) -> f64 {
+        let mut visited = std::collections::HashSet::new();
+        let mut stack = vec![tx_id.to_string()];
+        let mut weight = 0.0;
+        while let Some(id) = stack.pop() {
+            if !visited.insert(id.clone()) {
+                continue;
+            }
+            weight += 1.0;
+            if let Some(node) = self.get_node(&id) {
+                for child_id in &node.children {
+                    if !visited.contains(child_id) {
+                        stack.push(child_id.clone());
+                    }
+                }
+            }
+        }
+        weight
+    }
+
+    /// 指定したノードの子孫をすべて拒否対象としてマークする（負け枝の確定確認を防ぐ）
+    ///
+    /// 既に拒否済みのノードはその子孫もすでに訪問・拒否済みのため再帰せずスキップ
+    /// する。これを省略すると、複数の負け枝が合流するダイヤモンド型の子孫構造で
+    /// 共有された子孫を経路の数だけ繰り返し辿ってしまう。
+    fn reject_descendants(&mut self, tx_id: &str) -> usize {
+        let Some(node) = self.get_node(tx_id) else {
+            return 0;
+        };
+
+        let mut count = 0;
+        for child_id in &node.children {
+            if self.rejected.contains(child_id) {
+                continue;
+            }
+            self.rejected.insert(child_id.clone());
+            count += 1;
+            count += self.reject_descendants(child_id);
+        }
+        count
+    }
+
+    /// 確認済みの部分木を剪定し、コールドストレージへ退避する
+    ///
+    /// ルートから`keep_depth`以上離れた（＝十分に古い）確認済みノードのうち、
+    /// 子がすべて確認済みであるもの（未確認ノードがそれに依存していないもの）を
+    /// `self`から取り除き、`cold_store`へ移す。戻り値は剪定したノード数。
+    /// これにより`max_nodes`に達して追加が失敗する事態を避けられる。
+    pub fn prune_confirmed(
+        &mut self,
+        keep_depth: u64,
+        cold_store: &mut dyn DagStore,
+    ) -> Result<usize, Error> {
+        let depths = self.compute_depths();
+        let max_depth = depths.values().copied().max().unwrap_or(0);
+        if max_depth < keep_depth {
+            return Ok(0);
+        }
+        let cutoff = max_depth - keep_depth;
+
+        let mut candidates: Vec<String> = depths
+            .into_iter()
+            .filter(|(_, depth)| *depth <= cutoff)
+            .map(|(tx_id, _)| tx_id)
+            .collect();
+        candidates.sort();
+
+        let mut pruned = 0;
+        for tx_id in candidates {
+            let Some(node) = self.store.get_node(&tx_id)? else {
+                continue;
+            };
+            if !node.confirmed {
+                continue;
+            }
+
+            let all_children_confirmed = node.children.iter().all(|child_id| {
+                self.store
+                    .get_node(child_id)
+                    .ok()
+                    .flatten()
+                    .map(|child| child.confirmed)
+                    .unwrap_or(true)
+            });
+            if !all_children_confirmed {
+                continue;
+            }
+
+            cold_store.put_node(node)?;
+            self.store.remove_node(&tx_id)?;
+            pruned += 1;
+        }
+
+        Ok(pruned)
+    }
+
+    /// 全ノードと各種インデックスをエクスポートする（バックアップ・移行用）
+    pub fn snapshot(&self) -> Result<DagSnapshot, Error> {
+        let mut nodes = Vec::new();
+        for tx_id in self.store.all_node_ids()? {
+            if let Some(node) = self.store.get_node(&tx_id)? {
+                nodes.push(node);
+            }
+        }
+
+        Ok(DagSnapshot {
+            nodes,
+            max_nodes: self.max_nodes,
+        })
+    }
+
+    /// スナップショットから復元する（既存の状態はすべて破棄される）
+    pub fn restore(&mut self, snapshot: DagSnapshot) -> Result<(), Error> {
+        self.store.clear()?;
+        self.max_nodes = snapshot.max_nodes;
+        self.rejected.clear();
+
+        for node in snapshot.nodes {
+            let tx_id = node.transaction.id().to_string();
+            if node.parents.is_empty() {
+                self.store.add_root(&tx_id)?;
+            }
+            if node.children.is_empty() {
+                self.store.add_leaf(&tx_id)?;
+            }
+            self.store.put_node(node)?;
+        }
+
+        Ok(())
+    }
+
     /// ノードを取得
-    pub fn get_node(&self, tx_id: &str) -> Option<&DAGNode> {
-        self.nodes.get(tx_id)
+    pub fn get_node(&self, tx_id: &str) -> Option<DAGNode> {
+        self.store.get_node(tx_id).ok().flatten()
     }
-    
+
     /// すべてのノードを取得
-    pub fn get_all_nodes(&self) -> Vec<&DAGNode> {
-        self.nodes.values().collect()
+    pub fn get_all_nodes(&self) -> Vec<DAGNode> {
+        self.store
+            .all_node_ids()
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|tx_id| self.store.get_node(&tx_id).ok().flatten())
+            .collect()
     }
-    
+
     /// 確認済みノードを取得
-    pub fn get_confirmed_nodes(&self) -> Vec<&DAGNode> {
-        self.nodes.iter()
-            .filter(|(id, _)| self.confirmed.contains(*id))
-            .map(|(_, node)| node)
-            .collect()
+    pub fn get_confirmed_nodes(&self) -> Vec<DAGNode> {
+        self.get_all_nodes().into_iter().filter(|node| node.confirmed).collect()
     }
-    
+
     /// 未確認ノードを取得
-    pub fn get_unconfirmed_nodes(&self) -> Vec<&DAGNode> {
-        self.nodes.iter()
-            .filter(|(id, _)| self.unconfirmed.contains(*id))
-            .map(|(_, node)| node)
+    pub fn get_unconfirmed_nodes(&self) -> Vec<DAGNode> {
+        self.store
+            .iter_unconfirmed()
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|tx_id| self.store.get_node(&tx_id).ok().flatten())
             .collect()
     }
-    
+
     /// ルートノードを取得
-    pub fn get_roots(&self) -> Vec<&DAGNode> {
-        self.roots.iter()
-            .filter_map(|id| self.nodes.get(id))
+    pub fn get_roots(&self) -> Vec<DAGNode> {
+        self.store
+            .roots()
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|tx_id| self.store.get_node(&tx_id).ok().flatten())
             .collect()
     }
-    
+
     /// リーフノードを取得
-    pub fn get_leaves(&self) -> Vec<&DAGNode> {
-        self.leaves.iter()
-            .filter_map(|id| self.nodes.get(id))
+    pub fn get_leaves(&self) -> Vec<DAGNode> {
+        self.store
+            .leaves()
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|tx_id| self.store.get_node(&tx_id).ok().flatten())
             .collect()
     }
-    
+
     /// DAGをクリア
-    pub fn clear(&mut self) {
-        self.nodes.clear();
-        self.roots.clear();
-        self.leaves.clear();
-        self.confirmed.clear();
-        self.unconfirmed.clear();
+    pub fn clear(&mut self) -> Result<(), Error> {
+        self.rejected.clear();
+        self.store.clear()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_tx(from: &str, to: &str, nonce: u64) -> Transaction {
+        Transaction::new(
+            from.to_string(),
+            to.to_string(),
+            "100".to_string(),
+            "1".to_string(),
+            None,
+            nonce,
+            "shard-1".to_string(),
+            "sig".to_string(),
+        )
+    }
+
+    /// 同一の送信者＋ノンスを消費する2本のノード（二重支払い）が`get_conflicts`で
+    /// 検出され、`confirm_node`が片方だけを確認できること（もう片方は`Error::Conflict`で拒否）。
+    #[test]
+    fn test_confirm_node_rejects_double_spend() {
+        let mut dag = DAG::new(100);
+
+        let root = make_tx("alice", "root", 0);
+        let root_id = root.id.clone();
+        dag.add_node(root, vec![]).unwrap();
+        dag.confirm_node(&root_id, 1).unwrap();
+
+        let spend_a = make_tx("alice", "bob", 1);
+        let spend_b = make_tx("alice", "carol", 1);
+        let id_a = spend_a.id.clone();
+        let id_b = spend_b.id.clone();
+        dag.add_node(spend_a, vec![root_id.clone()]).unwrap();
+        dag.add_node(spend_b, vec![root_id.clone()]).unwrap();
+
+        // 同じ入力（alice:1）を消費する2本のノードが競合として検出されること
+        let conflicts = dag.get_conflicts();
+        assert_eq!(conflicts.len(), 1);
+        let mut group = conflicts[0].clone();
+        group.sort();
+        let mut expected = vec![id_a.clone(), id_b.clone()];
+        expected.sort();
+        assert_eq!(group, expected);
+
+        // confirm_nodeは内部でresolve_conflictsを実行し、勝者のみ確認できる
+        let result_a = dag.confirm_node(&id_a, 2);
+        let result_b = dag.confirm_node(&id_b, 2);
+        assert_ne!(result_a.is_ok(), result_b.is_ok(), "exactly one side of the double-spend must be confirmable");
+
+        let (winner, loser) = if result_a.is_ok() { (&id_a, &id_b) } else { (&id_b, &id_a) };
+        assert!(dag.get_node(winner).unwrap().confirmed);
+        assert!(matches!(dag.confirm_node(loser, 2), Err(Error::Conflict(_))));
+    }
+
+    /// 二重支払いで負け枝と判定されたノードの子孫も確認が拒否されること。
+    #[test]
+    fn test_confirm_node_rejects_descendants_of_losing_branch() {
+        let mut dag = DAG::new(100);
+
+        let root = make_tx("alice", "root", 0);
+        let root_id = root.id.clone();
+        dag.add_node(root, vec![]).unwrap();
+        dag.confirm_node(&root_id, 1).unwrap();
+
+        let spend_a = make_tx("alice", "bob", 1);
+        let spend_b = make_tx("alice", "carol", 1);
+        let id_a = spend_a.id.clone();
+        let id_b = spend_b.id.clone();
+        dag.add_node(spend_a, vec![root_id.clone()]).unwrap();
+        dag.add_node(spend_b, vec![root_id.clone()]).unwrap();
+
+        // aとbの双方に子をさらに積む
+        let child_of_a = make_tx("dave", "erin", 0);
+        let child_of_b = make_tx("frank", "grace", 0);
+        let child_id_a = child_of_a.id.clone();
+        let child_id_b = child_of_b.id.clone();
+        dag.add_node(child_of_a, vec![id_a.clone()]).unwrap();
+        dag.add_node(child_of_b, vec![id_b.clone()]).unwrap();
+
+        let result_a = dag.confirm_node(&id_a, 2);
+        let result_b = dag.confirm_node(&id_b, 2);
+        assert_ne!(result_a.is_ok(), result_b.is_ok(), "exactly one side of the double-spend must be confirmable");
+
+        // 負け枝と、その子（親が未確認のためそもそも確認できないはずの子孫）の両方が
+        // Error::Conflictで拒否されること（ValidationErrorの「親未確認」ではない）
+        let loser_child = if result_a.is_ok() { &child_id_b } else { &child_id_a };
+        assert!(matches!(dag.confirm_node(loser_child, 3), Err(Error::Conflict(_))));
     }
 }
@@ -0,0 +1,155 @@
+//! `DAG`が使用する永続化バックエンドの抽象化
+//!
+//! `DagStore`はノードの読み書きと、ルート・リーフ・確認済み／未確認インデックス
+//! の管理を担う。`DAG`自体はこのトレイトの実装に対してのみ操作し、具体的な
+//! 永続化方式（インメモリ、LMDBなど）を意識しない。
+
+use std::collections::{HashMap, HashSet};
+
+use crate::error::Error;
+
+use super::DAGNode;
+
+/// `DAG`の永続化バックエンド
+pub trait DagStore: Send + Sync {
+    /// ノードを新規追加または更新として書き込む
+    fn put_node(&mut self, node: DAGNode) -> Result<(), Error>;
+
+    /// ノードを取得する
+    fn get_node(&self, tx_id: &str) -> Result<Option<DAGNode>, Error>;
+
+    /// ノードを確認済みとしてマークする（確認済み／未確認インデックスも更新する）
+    fn mark_confirmed(&mut self, tx_id: &str, timestamp: u64) -> Result<(), Error>;
+
+    /// 未確認ノードのID一覧を取得する
+    fn iter_unconfirmed(&self) -> Result<Vec<String>, Error>;
+
+    /// ルートノード（親を持たないノード）のID集合を取得する
+    fn roots(&self) -> Result<HashSet<String>, Error>;
+
+    /// リーフノード（子を持たないノード）のID集合を取得する
+    fn leaves(&self) -> Result<HashSet<String>, Error>;
+
+    /// ルートノードとして登録する
+    fn add_root(&mut self, tx_id: &str) -> Result<(), Error>;
+
+    /// リーフノードとして登録する
+    fn add_leaf(&mut self, tx_id: &str) -> Result<(), Error>;
+
+    /// リーフノードから除外する（子が追加された場合など）
+    fn remove_leaf(&mut self, tx_id: &str) -> Result<(), Error>;
+
+    /// 保持している全ノードIDの一覧を取得する（スナップショット用）
+    fn all_node_ids(&self) -> Result<Vec<String>, Error>;
+
+    /// 保持しているノード数
+    fn node_count(&self) -> Result<usize, Error>;
+
+    /// ノードを削除する（確定済み部分木の剪定用）
+    fn remove_node(&mut self, tx_id: &str) -> Result<(), Error>;
+
+    /// 全データをクリアする
+    fn clear(&mut self) -> Result<(), Error>;
+}
+
+/// インメモリの`DagStore`実装（既定のバックエンド、再起動をまたいだ永続化はしない）
+#[derive(Debug, Default)]
+pub struct InMemoryDagStore {
+    nodes: HashMap<String, DAGNode>,
+    roots: HashSet<String>,
+    leaves: HashSet<String>,
+    confirmed: HashSet<String>,
+    unconfirmed: HashSet<String>,
+}
+
+impl InMemoryDagStore {
+    /// 新しい空のインメモリストアを作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl DagStore for InMemoryDagStore {
+    fn put_node(&mut self, node: DAGNode) -> Result<(), Error> {
+        let tx_id = node.transaction.id().to_string();
+        if node.confirmed {
+            self.confirmed.insert(tx_id.clone());
+            self.unconfirmed.remove(&tx_id);
+        } else {
+            self.unconfirmed.insert(tx_id.clone());
+        }
+        self.nodes.insert(tx_id, node);
+        Ok(())
+    }
+
+    fn get_node(&self, tx_id: &str) -> Result<Option<DAGNode>, Error> {
+        Ok(self.nodes.get(tx_id).cloned())
+    }
+
+    fn mark_confirmed(&mut self, tx_id: &str, timestamp: u64) -> Result<(), Error> {
+        let node = self
+            .nodes
+            .get_mut(tx_id)
+            .ok_or_else(|| Error::ValidationError(format!("Node not found: {}", tx_id)))?;
+        node.confirmed = true;
+        node.confirmation_time = Some(timestamp);
+
+        self.confirmed.insert(tx_id.to_string());
+        self.unconfirmed.remove(tx_id);
+
+        Ok(())
+    }
+
+    fn iter_unconfirmed(&self) -> Result<Vec<String>, Error> {
+        Ok(self.unconfirmed.iter().cloned().collect())
+    }
+
+    fn roots(&self) -> Result<HashSet<String>, Error> {
+        Ok(self.roots.clone())
+    }
+
+    fn leaves(&self) -> Result<HashSet<String>, Error> {
+        Ok(self.leaves.clone())
+    }
+
+    fn add_root(&mut self, tx_id: &str) -> Result<(), Error> {
+        self.roots.insert(tx_id.to_string());
+        Ok(())
+    }
+
+    fn add_leaf(&mut self, tx_id: &str) -> Result<(), Error> {
+        self.leaves.insert(tx_id.to_string());
+        Ok(())
+    }
+
+    fn remove_leaf(&mut self, tx_id: &str) -> Result<(), Error> {
+        self.leaves.remove(tx_id);
+        Ok(())
+    }
+
+    fn all_node_ids(&self) -> Result<Vec<String>, Error> {
+        Ok(self.nodes.keys().cloned().collect())
+    }
+
+    fn node_count(&self) -> Result<usize, Error> {
+        Ok(self.nodes.len())
+    }
+
+    fn remove_node(&mut self, tx_id: &str) -> Result<(), Error> {
+        self.nodes.remove(tx_id);
+        self.roots.remove(tx_id);
+        self.leaves.remove(tx_id);
+        self.confirmed.remove(tx_id);
+        self.unconfirmed.remove(tx_id);
+        Ok(())
+    }
+
+    fn clear(&mut self) -> Result<(), Error> {
+        self.nodes.clear();
+        self.roots.clear();
+        self.leaves.clear();
+        self.confirmed.clear();
+        self.unconfirmed.clear();
+        Ok(())
+    }
+}
@@ -51,7 +51,10 @@ pub use cross_shard_optimizer::{CrossShardOptimizer, OptimizerConfig};
 pub use high_throughput_engine::{
     BenchmarkResult as EngineResult, EngineConfig, EngineStats, HighThroughputEngine,
 };
-pub use parallel_processor::{ParallelProcessor, ProcessorConfig, ProcessorStats};
+pub use parallel_processor::{
+    ParallelProcessor, ProcessTransactionsSummary, ProcessorConfig, ProcessorStats,
+    TransactionErrorMetrics,
+};
 
 /// トランザクションの状態
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
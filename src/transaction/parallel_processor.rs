@@ -143,6 +143,80 @@ impl Default for ProcessorStats {
     }
 }
 
+/// バッチ処理の集計結果
+///
+/// `process_transactions` が「投げっぱなし」だったのに対し、各トランザクションの
+/// 顛末を1つのバケットに分類して返す。`committed` / `failed_commit` / `retryable` は
+/// 互いに排他で、合計は必ず `transactions_attempted_count` に一致する。
+#[derive(Debug, Clone, Default)]
+pub struct ProcessTransactionsSummary {
+    /// 処理を試みたトランザクション総数
+    pub transactions_attempted_count: usize,
+    /// コミットに成功した件数
+    pub committed_count: usize,
+    /// 回復不能な理由で棄却された件数（stale nonce / 重複署名 / 不正シャード等）
+    pub failed_commit_count: usize,
+    /// 回復可能な理由で保留されたトランザクションのインデックス（呼び出し側が再投入できる）
+    pub retryable_indexes: Vec<usize>,
+    /// エラー種別ごとの内訳
+    pub error_metrics: TransactionErrorMetrics,
+}
+
+/// エラー種別ごとの件数内訳
+#[derive(Debug, Clone, Default)]
+pub struct TransactionErrorMetrics {
+    /// シャードロック競合（再試行可能）
+    pub lock_contention: u64,
+    /// バッチあたりのコスト/キュー上限超過（再試行可能）
+    pub capacity_exceeded: u64,
+    /// 実行タイムアウト（再試行可能）
+    pub timed_out: u64,
+    /// nonce が古い（棄却）
+    pub stale_nonce: u64,
+    /// 署名の重複（棄却）
+    pub duplicate_signature: u64,
+    /// 不正なシャードID（棄却）
+    pub invalid_shard: u64,
+    /// その他の棄却事由
+    pub other: u64,
+}
+
+/// エラーを「再試行可能か」と内訳カテゴリに分類する
+fn classify_error(error: &Error, metrics: &mut TransactionErrorMetrics) -> bool {
+    match error {
+        // 回復可能: リソース枯渇・バックプレッシャ・レート制限・タイムアウトは後で再投入できる
+        Error::ResourceExhausted(_) | Error::Backpressure(_) => {
+            metrics.capacity_exceeded += 1;
+            true
+        }
+        Error::RateLimitExceeded(_) => {
+            metrics.lock_contention += 1;
+            true
+        }
+        Error::Timeout(_) => {
+            metrics.timed_out += 1;
+            true
+        }
+        // 回復不能: 再投入しても同じ結果になるため棄却する
+        Error::Duplicate(_) | Error::DuplicateTransaction(_) | Error::AlreadySigned(_) => {
+            metrics.duplicate_signature += 1;
+            false
+        }
+        Error::InvalidShardId(_) => {
+            metrics.invalid_shard += 1;
+            false
+        }
+        Error::InvalidTransaction(_) | Error::InvalidTransactionStatus(_) => {
+            metrics.stale_nonce += 1;
+            false
+        }
+        _ => {
+            metrics.other += 1;
+            false
+        }
+    }
+}
+
 impl ParallelProcessor {
     /// 新しい並列処理器を作成
     pub fn new(
@@ -241,6 +315,54 @@ impl ParallelProcessor {
         Ok(results)
     }
 
+    /// バッチを処理し、各トランザクションの顛末を集計した `ProcessTransactionsSummary` を返す。
+    ///
+    /// `process_transactions` の結果を、コミット成功・回復不能な棄却・回復可能な保留の
+    /// 3バケットに分類する。回復可能なものだけ `retryable_indexes` に積むため、呼び出し側は
+    /// そのインデックス集合だけを再投入できる。
+    pub async fn process_batch(
+        &self,
+        transactions: &[Transaction],
+    ) -> Result<ProcessTransactionsSummary, Error> {
+        let results = self.process_transactions(transactions.to_vec()).await?;
+
+        let mut summary = ProcessTransactionsSummary {
+            transactions_attempted_count: results.len(),
+            ..Default::default()
+        };
+
+        for (index, result) in results.iter().enumerate() {
+            match result {
+                Ok(()) => summary.committed_count += 1,
+                Err(error) => {
+                    if classify_error(error, &mut summary.error_metrics) {
+                        summary.retryable_indexes.push(index);
+                    } else {
+                        summary.failed_commit_count += 1;
+                    }
+                }
+            }
+        }
+
+        debug_assert_eq!(
+            summary.committed_count + summary.failed_commit_count + summary.retryable_indexes.len(),
+            summary.transactions_attempted_count,
+            "each transaction must land in exactly one outcome bucket"
+        );
+
+        Ok(summary)
+    }
+
+    /// クロスシャードバッチを処理し、顛末を集計した `ProcessTransactionsSummary` を返す。
+    ///
+    /// クロスシャードトランザクションも通常バッチと同じ分類規則で集計する。
+    pub async fn process_cross_shard_batch(
+        &self,
+        transactions: &[Transaction],
+    ) -> Result<ProcessTransactionsSummary, Error> {
+        self.process_batch(transactions).await
+    }
+
     /// トランザクションの依存関係を解析
     async fn analyze_dependencies(&self, transaction: &Transaction) -> Result<(), Error> {
         // 処理状態を更新
@@ -772,4 +894,30 @@ mod tests {
         assert_eq!(groups[2].len(), 1);
         assert!(groups[2].contains(&"tx5".to_string()));
     }
+
+    #[test]
+    fn test_classify_error_retryable_vs_dropped() {
+        let mut metrics = TransactionErrorMetrics::default();
+
+        // 回復可能なエラーは true を返す
+        assert!(classify_error(
+            &Error::ResourceExhausted("queue full".to_string()),
+            &mut metrics
+        ));
+        assert!(classify_error(
+            &Error::Timeout("execution timed out".to_string()),
+            &mut metrics
+        ));
+        // 回復不能なエラーは false を返し、棄却される
+        assert!(!classify_error(
+            &Error::DuplicateTransaction("dup sig".to_string()),
+            &mut metrics
+        ));
+        assert!(!classify_error(&Error::InvalidShardId(7), &mut metrics));
+
+        assert_eq!(metrics.capacity_exceeded, 1);
+        assert_eq!(metrics.timed_out, 1);
+        assert_eq!(metrics.duplicate_signature, 1);
+        assert_eq!(metrics.invalid_shard, 1);
+    }
 }
\ No newline at end of file
@@ -3,7 +3,8 @@ pub mod transaction_analysis;
 
 pub use chart_data::{ChartData, ChartDataManager, ChartMetric, ChartPeriod, DataPoint};
 pub use transaction_analysis::{
-    AddressInfo, AddressType, BasicInfo, CrossShardInfo, NetworkInfo, RelatedTransaction,
-    RelationType, RiskAssessment, RiskFactor, RiskLevel, TransactionAnalysis,
-    TransactionAnalysisManager,
+    verify_merkle_proof, AddressClassifier, AddressInfo, AddressType, AmountStats, BasicInfo,
+    CrossShardInfo, NetworkInfo, PreviousOutputProvider, RelatedTransaction, RelationType,
+    RiskAssessment, RiskFactor, RiskLevel, TransactionAnalysis, TransactionAnalysisManager,
+    UnspentOutput,
 };
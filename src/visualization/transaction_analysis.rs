@@ -1,5 +1,8 @@
 use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
 use chrono::{DateTime, Utc, Duration};
+use dashmap::DashMap;
+use rayon::prelude::*;
 use serde::{Serialize, Deserialize};
 
 use crate::error::Error;
@@ -62,6 +65,61 @@ pub struct AddressInfo {
     pub address_type: AddressType,
     /// タグ（該当する場合）
     pub tags: Vec<String>,
+    /// 所属クラスタの代表アドレス（Union-Find のルート）
+    pub cluster_id: String,
+    /// 送金額のオンライン統計（Welfordのアルゴリズム）
+    pub amount_stats: AmountStats,
+    /// マルチシグの閾値（`(M, N)`）。`AddressType::Multisig` の場合のみ `Some`
+    pub multisig_threshold: Option<(u8, u8)>,
+}
+
+/// アドレスの送金額に関するオンライン統計（Welfordのアルゴリズム）
+///
+/// `count`/`mean`/`m2` を逐次更新することで、履歴全体を保持せずに
+/// 平均と分散を一定メモリで算出できる。`assess_risk` はここから求めた
+/// z-score で取引額の異常度を判定する。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AmountStats {
+    /// 観測した送金回数
+    pub count: u64,
+    /// 平均
+    pub mean: f64,
+    /// 平方偏差の総和（分散 = m2 / (count - 1)）
+    pub m2: f64,
+}
+
+impl AmountStats {
+    fn new() -> Self {
+        Self {
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+        }
+    }
+
+    /// 新たな観測値を取り込んで統計を更新する
+    fn update(&mut self, amount: f64) {
+        self.count += 1;
+        let delta = amount - self.mean;
+        self.mean += delta / self.count as f64;
+        let delta2 = amount - self.mean;
+        self.m2 += delta * delta2;
+    }
+
+    /// 分散を返す（`count < 2` の場合は `None`）
+    pub fn variance(&self) -> Option<f64> {
+        if self.count < 2 {
+            return None;
+        }
+
+        Some(self.m2 / (self.count as f64 - 1.0))
+    }
+}
+
+impl Default for AmountStats {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// アドレスタイプ
@@ -96,6 +154,11 @@ pub struct NetworkInfo {
     pub block_height: u64,
     /// ブロック内のインデックス
     pub block_index: u64,
+    /// マークル証明（各レベルの兄弟ハッシュと、それが左側にあるかどうか）
+    ///
+    /// ルートから葉までではなく葉からルートまでの順で格納されており、
+    /// `verify_merkle_proof` で先頭から順に折り畳むことでルートハッシュを再構築できる。
+    pub merkle_proof: Vec<(String, bool)>,
 }
 
 /// 関連トランザクション
@@ -174,31 +237,323 @@ pub struct RiskFactor {
     pub severity: u8,
 }
 
+/// 未使用出力（UTXO）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnspentOutput {
+    /// 出力元トランザクションID
+    pub transaction_id: String,
+    /// 金額
+    pub amount: String,
+}
+
+/// 実際の台帳（UTXOセット等）からアドレスの状態を参照するためのプロバイダ
+///
+/// `TransactionAnalysisManager` は既定では `related_transactions` に渡された
+/// スライスから残高を再計算するが、そのスライスが履歴の一部しか含まない場合は
+/// 結果が不正確になる。このトレイトを実装したプロバイダを配線すると、
+/// `get_address_info` は float の積算ではなく実台帳の値を直接利用する。
+pub trait PreviousOutputProvider: Send + Sync {
+    /// アドレスの残高を返す（プロバイダが把握していなければ `None`）
+    fn balance(&self, address: &str) -> Option<String>;
+
+    /// アドレスが保持する未使用出力（UTXO）一覧を返す
+    fn unspent_outputs(&self, address: &str) -> Vec<UnspentOutput>;
+
+    /// アドレスの確定済みトランザクション数
+    fn transaction_count(&self, address: &str) -> Option<u64>;
+
+    /// アドレスが最初に観測された時刻
+    fn first_seen(&self, address: &str) -> Option<DateTime<Utc>>;
+
+    /// アドレスが最後に観測された時刻
+    fn last_seen(&self, address: &str) -> Option<DateTime<Utc>>;
+}
+
+/// マルチシグM-of-N閾値記述子のマジックバイト列（`b"MULTISIG"` に続けて `m`, `n` の1バイトずつ）
+const MULTISIG_DESCRIPTOR_MAGIC: &[u8] = b"MULTISIG";
+
+/// WASMコントラクトバイトコードのマジックナンバー（`\0asm`）
+const WASM_CONTRACT_MAGIC: &[u8] = &[0x00, 0x61, 0x73, 0x6d];
+
+/// トランザクションメタデータからアドレス種別を判定するクラシファイア
+///
+/// `estimate_address_type` がかつて使っていた「アドレス文字列に"multi"を含むか」
+/// 「"0x"で始まるか」といった当てにならないヒューリスティックを廃止し、実際の
+/// トランザクションペイロード（マルチシグ閾値記述子、WASMコントラクトバイトコード）と、
+/// 設定経由で読み込んだ取引所・マイナーのアドレスリストから決定的に分類する。
+pub struct AddressClassifier {
+    /// 取引所として扱うアドレス一覧
+    exchange_addresses: HashSet<String>,
+    /// マイナーとして扱うアドレス一覧
+    miner_addresses: HashSet<String>,
+}
+
+impl AddressClassifier {
+    pub fn new() -> Self {
+        Self {
+            exchange_addresses: HashSet::new(),
+            miner_addresses: HashSet::new(),
+        }
+    }
+
+    /// 取引所アドレスのリストを読み込む
+    pub fn set_exchange_addresses(&mut self, addresses: impl IntoIterator<Item = String>) {
+        self.exchange_addresses = addresses.into_iter().collect();
+    }
+
+    /// マイナーアドレスのリストを読み込む
+    pub fn set_miner_addresses(&mut self, addresses: impl IntoIterator<Item = String>) {
+        self.miner_addresses = addresses.into_iter().collect();
+    }
+
+    /// ペイロードからマルチシグのM-of-N閾値記述子を抽出する
+    fn multisig_threshold(data: &[u8]) -> Option<(u8, u8)> {
+        if !data.starts_with(MULTISIG_DESCRIPTOR_MAGIC) {
+            return None;
+        }
+
+        let m = *data.get(MULTISIG_DESCRIPTOR_MAGIC.len())?;
+        let n = *data.get(MULTISIG_DESCRIPTOR_MAGIC.len() + 1)?;
+
+        if m > 0 && m <= n {
+            Some((m, n))
+        } else {
+            None
+        }
+    }
+
+    /// ペイロードがコントラクト生成/呼び出し（WASMバイトコード）かどうか
+    fn is_contract_payload(data: &[u8]) -> bool {
+        data.starts_with(WASM_CONTRACT_MAGIC)
+    }
+
+    /// アドレスと関連トランザクションから `(種別, マルチシグ閾値)` を判定する
+    pub fn classify(
+        &self,
+        address: &str,
+        transactions: &[&Transaction],
+    ) -> (AddressType, Option<(u8, u8)>) {
+        for tx in transactions {
+            if let Some(data) = &tx.data {
+                let bytes = data.as_bytes();
+
+                if let Some(threshold) = Self::multisig_threshold(bytes) {
+                    return (AddressType::Multisig, Some(threshold));
+                }
+
+                if Self::is_contract_payload(bytes) {
+                    return (AddressType::Contract, None);
+                }
+            }
+        }
+
+        if self.exchange_addresses.contains(address) {
+            return (AddressType::Exchange, None);
+        }
+
+        if self.miner_addresses.contains(address) {
+            return (AddressType::Miner, None);
+        }
+
+        (AddressType::Standard, None)
+    }
+}
+
+impl Default for AddressClassifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// アドレスクラスタ（Union-Find）
+///
+/// 同一エンティティが管理していると推定される複数アドレスを一つの集合として
+/// 扱うための素集合データ構造。パス圧縮とランクによる union を行うことで、
+/// アドレス数に対してほぼ線形の計算量で `find` / `union` を処理できる。
+struct AddressCluster {
+    /// 各アドレスの親（自分自身ならルート）
+    parent: HashMap<String, String>,
+    /// union-by-rank 用のランク
+    rank: HashMap<String, usize>,
+}
+
+impl AddressCluster {
+    fn new() -> Self {
+        Self {
+            parent: HashMap::new(),
+            rank: HashMap::new(),
+        }
+    }
+
+    /// アドレスを未登録なら自分自身をルートとして登録する
+    fn ensure(&mut self, address: &str) {
+        if !self.parent.contains_key(address) {
+            self.parent.insert(address.to_string(), address.to_string());
+            self.rank.insert(address.to_string(), 0);
+        }
+    }
+
+    /// `address` が属するクラスタの代表（ルート）を返す（パス圧縮つき）
+    fn find(&mut self, address: &str) -> String {
+        self.ensure(address);
+
+        let parent = self.parent.get(address).unwrap().clone();
+        if parent == address {
+            return parent;
+        }
+
+        let root = self.find(&parent);
+        self.parent.insert(address.to_string(), root.clone());
+        root
+    }
+
+    /// 2つのアドレスを同一クラスタへ統合する（union-by-rank）
+    fn union(&mut self, a: &str, b: &str) {
+        let root_a = self.find(a);
+        let root_b = self.find(b);
+
+        if root_a == root_b {
+            return;
+        }
+
+        let rank_a = *self.rank.get(&root_a).unwrap();
+        let rank_b = *self.rank.get(&root_b).unwrap();
+
+        if rank_a < rank_b {
+            self.parent.insert(root_a, root_b);
+        } else if rank_a > rank_b {
+            self.parent.insert(root_b, root_a);
+        } else {
+            self.parent.insert(root_b, root_a.clone());
+            self.rank.insert(root_a, rank_a + 1);
+        }
+    }
+
+    /// パス圧縮を行わない読み取り専用のルート探索（`&self` から呼べる）
+    fn peek_root(&self, address: &str) -> String {
+        let mut current = address.to_string();
+        while let Some(parent) = self.parent.get(&current) {
+            if parent == &current {
+                break;
+            }
+            current = parent.clone();
+        }
+        current
+    }
+
+    /// 指定したルートに属するアドレス一覧を返す
+    fn cluster_members(&self, root: &str) -> Vec<String> {
+        self.parent
+            .keys()
+            .filter(|address| self.peek_root(address) == root)
+            .cloned()
+            .collect()
+    }
+}
+
 /// トランザクション分析マネージャー
+///
+/// タグ付きアドレスは不変スナップショットとして保持し、算出済みの
+/// `AddressInfo` は並行キャッシュ（`DashMap`）へ格納する。こうすることで
+/// `get_address_info` は `&mut self` のボトルネックを介さずに複数スレッドから
+/// 呼び出せるようになり、`analyze_batch` でトランザクション集合を
+/// コア数に比例して並列処理できる。
 pub struct TransactionAnalysisManager {
-    /// アドレスキャッシュ
-    address_cache: HashMap<String, AddressInfo>,
+    /// アドレスキャッシュ（並行アクセス可能）
+    address_cache: DashMap<String, AddressInfo>,
     /// タグ付きアドレス
     tagged_addresses: HashMap<String, Vec<String>>,
+    /// 共通所有ヒューリスティックによるアドレスクラスタ（Union-Find）
+    clusters: Mutex<AddressCluster>,
+    /// 実台帳からアドレス状態を参照するプロバイダ（配線されていなければ `None`）
+    output_provider: Option<Box<dyn PreviousOutputProvider>>,
+    /// トランザクションメタデータからアドレス種別を判定するクラシファイア
+    address_classifier: AddressClassifier,
 }
 
 impl TransactionAnalysisManager {
     /// 新しいトランザクション分析マネージャーを作成
     pub fn new() -> Self {
         Self {
-            address_cache: HashMap::new(),
+            address_cache: DashMap::new(),
             tagged_addresses: HashMap::new(),
+            clusters: Mutex::new(AddressCluster::new()),
+            output_provider: None,
+            address_classifier: AddressClassifier::new(),
         }
     }
-    
+
+    /// 実台帳（UTXOセット等）を参照するプロバイダを配線する
+    ///
+    /// 配線後は `get_address_info` が `related_transactions` からの
+    /// 積算ではなく、このプロバイダが返す値を残高・統計情報として採用する。
+    pub fn set_output_provider(&mut self, provider: Box<dyn PreviousOutputProvider>) {
+        self.output_provider = Some(provider);
+    }
+
+    /// 取引所として扱うアドレスのリストを設定する
+    pub fn set_exchange_addresses(&mut self, addresses: impl IntoIterator<Item = String>) {
+        self.address_classifier.set_exchange_addresses(addresses);
+    }
+
+    /// マイナーとして扱うアドレスのリストを設定する
+    pub fn set_miner_addresses(&mut self, addresses: impl IntoIterator<Item = String>) {
+        self.address_classifier.set_miner_addresses(addresses);
+    }
+
+    /// トランザクション集合を並列に分析
+    ///
+    /// rayon の並列イテレータで各トランザクションの分析をコア間にファンアウトする。
+    /// まず関連トランザクションから読み取り専用のアドレスインデックスを構築して
+    /// 各アドレスの `AddressInfo` を事前計算・キャッシュへ投入し、以降の
+    /// per-transaction 分析がキャッシュヒットのみで済むようにすることで、
+    /// フルブロック分析を O(n) の逐次処理から near-linear-with-cores に引き上げる。
+    pub fn analyze_batch(
+        &self,
+        txs: &[Transaction],
+        related: &[Transaction],
+        current_height: u64,
+    ) -> Vec<Result<TransactionAnalysis, Error>> {
+        // アドレスクラスタを先に更新しておく（AddressInfo.cluster_id の算出に使う）
+        self.build_clusters(related);
+
+        // 読み取り専用のアドレスインデックス（アドレス -> 関連トランザクション）
+        let mut index: HashMap<&str, Vec<&Transaction>> = HashMap::new();
+        for tx in related {
+            index.entry(tx.from.as_str()).or_default().push(tx);
+            if tx.to != tx.from {
+                index.entry(tx.to.as_str()).or_default().push(tx);
+            }
+        }
+
+        // 一意なアドレスの AddressInfo を並列に事前計算してキャッシュへ投入する
+        index.par_iter().for_each(|(address, related_txs)| {
+            if !self.address_cache.contains_key(*address) {
+                if let Ok(info) = self.build_address_info(address, related_txs) {
+                    self.address_cache.insert(address.to_string(), info);
+                }
+            }
+        });
+
+        // 各トランザクションの分析をコア間にファンアウトする
+        txs.par_iter()
+            .map(|tx| {
+                self.analyze_transaction(tx, related, tx.block_height.unwrap_or(0), current_height)
+            })
+            .collect()
+    }
+
     /// トランザクションを分析
     pub fn analyze_transaction(
-        &mut self,
+        &self,
         transaction: &Transaction,
         related_transactions: &[Transaction],
         block_height: u64,
         current_height: u64,
     ) -> Result<TransactionAnalysis, Error> {
+        // アドレスクラスタを更新する（単体呼び出しでも common-ownership を反映する）
+        self.build_clusters(related_transactions);
+
         // 基本情報を取得
         let basic_info = self.get_basic_info(transaction, block_height, current_height)?;
         
@@ -209,7 +564,7 @@ impl TransactionAnalysisManager {
         let receiver_info = self.get_address_info(&transaction.to, related_transactions)?;
         
         // ネットワーク情報を取得
-        let network_info = self.get_network_info(transaction, block_height)?;
+        let network_info = self.get_network_info(transaction, block_height, related_transactions)?;
         
         // 関連トランザクションを取得
         let related_txs = self.get_related_transactions(transaction, related_transactions)?;
@@ -273,8 +628,11 @@ impl TransactionAnalysisManager {
     }
     
     /// アドレス情報を取得
+    ///
+    /// 並行キャッシュ（`DashMap`）を先に参照し、未算出なら関連トランザクションから
+    /// 算出してキャッシュへ投入する。`&self` で呼べるため複数スレッドから安全に利用できる。
     fn get_address_info(
-        &mut self,
+        &self,
         address: &str,
         transactions: &[Transaction],
     ) -> Result<AddressInfo, Error> {
@@ -282,17 +640,50 @@ impl TransactionAnalysisManager {
         if let Some(info) = self.address_cache.get(address) {
             return Ok(info.clone());
         }
-        
+
         // アドレスに関連するトランザクションをフィルタリング
         let related_txs: Vec<&Transaction> = transactions
             .iter()
             .filter(|tx| tx.from == address || tx.to == address)
             .collect();
-        
+
+        let info = self.build_address_info(address, &related_txs)?;
+        self.address_cache.insert(address.to_string(), info.clone());
+        Ok(info)
+    }
+
+    /// 関連トランザクションから `AddressInfo` を算出する（キャッシュ投入は呼び出し側）
+    fn build_address_info(
+        &self,
+        address: &str,
+        related_txs: &[&Transaction],
+    ) -> Result<AddressInfo, Error> {
+        // プロバイダが配線済みなら、不正確な float 積算ではなく実台帳の値を使う
+        if let Some(provider) = &self.output_provider {
+            let now = Utc::now();
+            let (address_type, multisig_threshold) =
+                self.address_classifier.classify(address, related_txs);
+
+            let info = AddressInfo {
+                address: address.to_string(),
+                balance: provider.balance(address).unwrap_or_else(|| "0".to_string()),
+                transaction_count: provider.transaction_count(address).unwrap_or(0),
+                first_seen: provider.first_seen(address).unwrap_or(now),
+                last_seen: provider.last_seen(address).unwrap_or(now),
+                address_type,
+                tags: self.get_address_tags(address),
+                cluster_id: self.cluster_id(address),
+                amount_stats: self.compute_amount_stats(address, related_txs),
+                multisig_threshold,
+            };
+
+            return Ok(info);
+        }
+
         if related_txs.is_empty() {
             // 関連するトランザクションがない場合はダミーデータを返す
             let now = Utc::now();
-            
+
             let info = AddressInfo {
                 address: address.to_string(),
                 balance: "0".to_string(),
@@ -300,19 +691,20 @@ impl TransactionAnalysisManager {
                 first_seen: now,
                 last_seen: now,
                 address_type: AddressType::Standard,
-                tags: Vec::new(),
+                tags: self.get_address_tags(address),
+                cluster_id: self.cluster_id(address),
+                amount_stats: AmountStats::new(),
+                multisig_threshold: None,
             };
-            
-            self.address_cache.insert(address.to_string(), info.clone());
-            
+
             return Ok(info);
         }
-        
+
         // 最初と最後のトランザクション時刻を取得
         let mut first_seen = Utc::now();
         let mut last_seen = DateTime::<Utc>::from_timestamp(0, 0).unwrap();
-        
-        for tx in &related_txs {
+
+        for tx in related_txs {
             let tx_time = DateTime::<Utc>::from_timestamp(tx.timestamp as i64, 0)
                 .ok_or_else(|| Error::ValidationError("Invalid transaction timestamp".to_string()))?;
             
@@ -325,16 +717,17 @@ impl TransactionAnalysisManager {
             }
         }
         
-        // アドレスタイプを推定
-        let address_type = self.estimate_address_type(address, &related_txs);
-        
-        // タグを取得
-        let tags = self.tagged_addresses.get(address).cloned().unwrap_or_default();
+        // アドレスタイプを判定
+        let (address_type, multisig_threshold) =
+            self.address_classifier.classify(address, related_txs);
+
+        // タグを取得（クラスタ全体に付与されたタグも含む）
+        let tags = self.get_address_tags(address);
         
         // 残高を計算（簡易的な実装）
         let mut balance = 0.0;
-        
-        for tx in &related_txs {
+
+        for tx in related_txs {
             let amount = tx.amount.parse::<f64>().unwrap_or(0.0);
             
             if tx.to == address {
@@ -358,50 +751,138 @@ impl TransactionAnalysisManager {
             last_seen,
             address_type,
             tags,
+            cluster_id: self.cluster_id(address),
+            amount_stats: self.compute_amount_stats(address, related_txs),
+            multisig_threshold,
         };
-        
-        // キャッシュに保存
-        self.address_cache.insert(address.to_string(), info.clone());
-        
+
         Ok(info)
     }
-    
-    /// アドレスタイプを推定
-    fn estimate_address_type(&self, address: &str, transactions: &[&Transaction]) -> AddressType {
-        // 実際の実装では、アドレスのパターンや取引パターンに基づいて推定
-        // ここでは簡易的な実装として、トランザクション数に基づいて推定
-        
-        if transactions.len() > 1000 {
-            return AddressType::Exchange;
+
+    /// 送金額のオンライン統計を算出する
+    ///
+    /// `address` が送信者となったトランザクションの金額を、タイムスタンプ順に
+    /// Welfordのアルゴリズムへ取り込み、逐次更新される平均・分散を返す。
+    fn compute_amount_stats(&self, address: &str, related_txs: &[&Transaction]) -> AmountStats {
+        let mut sent: Vec<&&Transaction> = related_txs.iter().filter(|tx| tx.from == address).collect();
+        sent.sort_by_key(|tx| tx.timestamp);
+
+        let mut stats = AmountStats::new();
+        for tx in sent {
+            if let Ok(amount) = tx.amount.parse::<f64>() {
+                stats.update(amount);
+            }
         }
-        
-        if address.starts_with("0x") {
-            return AddressType::Contract;
+
+        stats
+    }
+
+    /// 共通所有ヒューリスティックに基づきアドレスクラスタを更新する
+    ///
+    /// - change-output ヒューリスティック: 送信者がこのウィンドウ内で2件送金しており、
+    ///   片方の受信者が既知（アドレスキャッシュに存在する＝外部への支払い）、もう片方が
+    ///   未知（このウィンドウで初めて現れる＝おつり）の場合、送信者とそのおつり
+    ///   アドレスを union する。
+    /// - self-transfer ヒューリスティック: 同じアドレスペアの間で資金が双方向に
+    ///   移動している場合、それらを union する。
+    fn build_clusters(&self, txs: &[Transaction]) {
+        let mut clusters = self.clusters.lock().unwrap();
+
+        // 送信者ごとに送金をグルーピングする（change-output ヒューリスティック用）
+        let mut by_sender: HashMap<&str, Vec<&Transaction>> = HashMap::new();
+        for tx in txs {
+            by_sender.entry(tx.from.as_str()).or_default().push(tx);
         }
-        
-        if address.contains("multi") {
-            return AddressType::Multisig;
+
+        for (sender, sent) in &by_sender {
+            if sent.len() != 2 {
+                continue;
+            }
+
+            let known = |addr: &str| self.address_cache.contains_key(addr) || addr == *sender;
+            let (a, b) = (sent[0], sent[1]);
+            let a_is_change = !known(&a.to);
+            let b_is_change = !known(&b.to);
+
+            if a_is_change && !b_is_change {
+                clusters.union(sender, &a.to);
+            } else if b_is_change && !a_is_change {
+                clusters.union(sender, &b.to);
+            }
+        }
+
+        // self-transfer ヒューリスティック: 双方向に資金が動いているペアを union する
+        let mut forward: HashSet<(&str, &str)> = HashSet::new();
+        for tx in txs {
+            if tx.from != tx.to {
+                forward.insert((tx.from.as_str(), tx.to.as_str()));
+            }
+        }
+
+        for &(from, to) in &forward {
+            if forward.contains(&(to, from)) {
+                clusters.union(from, to);
+            }
         }
-        
-        AddressType::Standard
     }
-    
+
+    /// アドレスが属するクラスタの代表（ルート）アドレスを返す
+    pub fn cluster_id(&self, address: &str) -> String {
+        self.clusters.lock().unwrap().find(address)
+    }
+
+    /// アドレスと同一クラスタに属するアドレス一覧を返す
+    pub fn cluster_members(&self, address: &str) -> Vec<String> {
+        let mut clusters = self.clusters.lock().unwrap();
+        let root = clusters.find(address);
+        clusters.cluster_members(&root)
+    }
+
     /// ネットワーク情報を取得
+    ///
+    /// 同じブロックに含まれるトランザクションのIDを（タイムスタンプ順に）
+    /// マークルツリーの葉として並べ、対象トランザクションのマークル証明を
+    /// 構築する。これにより `included_in_block` を単に信用せずとも、
+    /// `verify_merkle_proof` でブロック所属を独立に検証できる。
     fn get_network_info(
         &self,
         transaction: &Transaction,
         block_height: u64,
+        all_transactions: &[Transaction],
     ) -> Result<NetworkInfo, Error> {
-        // 実際の実装では、ネットワークログやブロック情報から取得
-        // ここでは簡易的な実装として、ダミーデータを返す
-        
+        // 実際の実装では、ネットワークログやブロック情報から取得する部分もあるが、
+        // 伝播時間などは簡易的な実装としてダミー値を用いる
+        let mut block_txs: Vec<&Transaction> = all_transactions
+            .iter()
+            .filter(|tx| tx.block_hash.is_some() && tx.block_hash == transaction.block_hash)
+            .collect();
+
+        if transaction.block_hash.is_some() && !block_txs.iter().any(|tx| tx.id == transaction.id) {
+            block_txs.push(transaction);
+        }
+
+        block_txs.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then_with(|| a.id.cmp(&b.id)));
+
+        let leaf_hashes: Vec<String> = block_txs.iter().map(|tx| tx.id.clone()).collect();
+        let block_index = leaf_hashes
+            .iter()
+            .position(|id| id == &transaction.id)
+            .unwrap_or(0) as u64;
+
+        let merkle_proof = if leaf_hashes.is_empty() {
+            Vec::new()
+        } else {
+            merkle_root_and_proof(&leaf_hashes, block_index as usize).1
+        };
+
         Ok(NetworkInfo {
             propagation_time_ms: 250,
             confirmation_time_ms: 1500,
             first_seen_by: "node1.shardx.io".to_string(),
             included_in_block: transaction.block_hash.clone().unwrap_or_default(),
             block_height,
-            block_index: 10,
+            block_index,
+            merkle_proof,
         })
     }
     
@@ -623,7 +1104,31 @@ impl TransactionAnalysisManager {
             
             risk_score += 15;
         }
-        
+
+        // 送金額の統計的異常度（Welfordのz-score）
+        if let Some(variance) = sender_info.amount_stats.variance() {
+            if variance > 0.0 {
+                let z = (amount - sender_info.amount_stats.mean) / variance.sqrt();
+
+                if z >= 3.0 {
+                    let severity = (50.0 + (z - 3.0) * 15.0).min(95.0) as u8;
+
+                    risk_factors.push(RiskFactor {
+                        factor_type: "AmountOutlier".to_string(),
+                        description: format!(
+                            "Transaction amount is a statistical outlier for this sender (z-score {:.2})",
+                            z
+                        ),
+                        severity,
+                    });
+
+                    // 単独では RiskLevel::High (risk_score >= 50) に到達しないよう
+                    // 寄与分を抑える。他の要因と組み合わさって初めて High になり得る。
+                    risk_score += (((z - 3.0) * 3.0).min(15.0)) as i32;
+                }
+            }
+        }
+
         // タグ付きアドレス
         for tag in &sender_info.tags {
             if tag == "suspicious" || tag == "scam" || tag == "blacklisted" {
@@ -682,36 +1187,59 @@ impl TransactionAnalysisManager {
     }
     
     /// アドレスにタグを追加
+    ///
+    /// クラスタ内の全アドレスが `get_address_tags` でこのタグを返すよう、
+    /// クラスタメンバー全員のキャッシュ済み `AddressInfo` も更新する。
     pub fn add_address_tag(&mut self, address: &str, tag: &str) {
         let entry = self.tagged_addresses.entry(address.to_string()).or_insert_with(Vec::new);
-        
+
         if !entry.contains(&tag.to_string()) {
             entry.push(tag.to_string());
         }
-        
-        // キャッシュを更新
-        if let Some(info) = self.address_cache.get_mut(address) {
-            if !info.tags.contains(&tag.to_string()) {
-                info.tags.push(tag.to_string());
+
+        // クラスタ全体のキャッシュを更新
+        for member in self.cluster_members(address) {
+            if let Some(mut info) = self.address_cache.get_mut(&member) {
+                if !info.tags.contains(&tag.to_string()) {
+                    info.tags.push(tag.to_string());
+                }
             }
         }
     }
-    
+
     /// アドレスからタグを削除
     pub fn remove_address_tag(&mut self, address: &str, tag: &str) {
         if let Some(tags) = self.tagged_addresses.get_mut(address) {
             tags.retain(|t| t != tag);
         }
-        
-        // キャッシュを更新
-        if let Some(info) = self.address_cache.get_mut(address) {
-            info.tags.retain(|t| t != tag);
+
+        // クラスタ全体のキャッシュを更新
+        for member in self.cluster_members(address) {
+            if let Some(mut info) = self.address_cache.get_mut(&member) {
+                info.tags.retain(|t| t != tag);
+            }
         }
     }
-    
+
     /// アドレスのタグを取得
+    ///
+    /// 自身のタグに加え、同一クラスタに属する他のアドレスのタグも集約して返す。
+    /// これにより、クラスタ内のどのアドレスに `blacklisted` を付与しても、
+    /// クラスタ全体が `assess_risk` でリスク要因として扱われる。
     pub fn get_address_tags(&self, address: &str) -> Vec<String> {
-        self.tagged_addresses.get(address).cloned().unwrap_or_default()
+        let mut tags: Vec<String> = Vec::new();
+
+        for member in self.cluster_members(address) {
+            if let Some(member_tags) = self.tagged_addresses.get(&member) {
+                for tag in member_tags {
+                    if !tags.contains(tag) {
+                        tags.push(tag.clone());
+                    }
+                }
+            }
+        }
+
+        tags
     }
     
     /// キャッシュをクリア
@@ -724,4 +1252,67 @@ impl Default for TransactionAnalysisManager {
     fn default() -> Self {
         Self::new()
     }
+}
+
+/// 葉ハッシュを結合してひとつ上のレベルのハッシュを計算する（BLAKE3）
+fn hash_pair(left: &str, right: &str) -> String {
+    let combined = format!("{}{}", left, right);
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(combined.as_bytes());
+    hex::encode(hasher.finalize().as_bytes())
+}
+
+/// 葉ハッシュ列からマークルルートと、指定した葉のマークル証明を計算する
+///
+/// 各レベルで奇数個になった場合は最後のハッシュを複製してペアを作る
+/// （`HashManager::compute_merkle_root` と同じ規則）。返す証明は葉からルートへ
+/// 向かう順で `(兄弟ハッシュ, 兄弟が左側にあるか)` のタプル列。
+fn merkle_root_and_proof(leaves: &[String], target_index: usize) -> (String, Vec<(String, bool)>) {
+    if leaves.len() == 1 {
+        return (leaves[0].clone(), Vec::new());
+    }
+
+    let mut level = leaves.to_vec();
+    let mut index = target_index;
+    let mut proof = Vec::new();
+
+    while level.len() > 1 {
+        if level.len() % 2 == 1 {
+            let last = level.last().unwrap().clone();
+            level.push(last);
+        }
+
+        let sibling_index = if index % 2 == 0 { index + 1 } else { index - 1 };
+        let sibling_is_left = index % 2 == 1;
+        proof.push((level[sibling_index].clone(), sibling_is_left));
+
+        let next_level: Vec<String> = level
+            .chunks(2)
+            .map(|pair| hash_pair(&pair[0], &pair[1]))
+            .collect();
+
+        level = next_level;
+        index /= 2;
+    }
+
+    (level[0].clone(), proof)
+}
+
+/// マークル証明を検証する
+///
+/// `tx_hash` から証明の枝を葉からルートへ向かって折り畳み、結果が
+/// `expected_root` と一致するかどうかを返す。explorer クライアントなどが
+/// `included_in_block` を信用せずにブロック所属を独立に確認できる。
+pub fn verify_merkle_proof(tx_hash: &str, proof: &[(String, bool)], expected_root: &str) -> bool {
+    let mut current = tx_hash.to_string();
+
+    for (sibling, sibling_is_left) in proof {
+        current = if *sibling_is_left {
+            hash_pair(sibling, &current)
+        } else {
+            hash_pair(&current, sibling)
+        };
+    }
+
+    current == expected_root
 }
\ No newline at end of file
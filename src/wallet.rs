@@ -1,4 +1,6 @@
+use crate::crypto::{PublicKey, Signature};
 use crate::transaction::{Transaction, TransactionStatus};
+use crate::wallet::multisig::threshold::ThresholdPolicy;
 use log::{debug, info};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
@@ -62,10 +64,101 @@ impl Account {
     }
 }
 
+/// アカウント検索リクエストで許容する最大フィルタ数
+///
+/// SolanaのgetProgramAccountsにおける`MAX_GET_PROGRAM_ACCOUNT_FILTERS`を参考にした上限。
+pub const MAX_ACCOUNT_QUERY_FILTERS: usize = 4;
+
+/// アカウント検索リクエストで許容する最大取得件数
+pub const MAX_ACCOUNT_QUERY_LIMIT: usize = 1000;
+
+/// `query_accounts`で使用するフィルタ述語
+///
+/// SolanaのgetProgramAccountsにおける`RpcFilterType`（`DataSize`/`Memcmp`）を参考にしたモデル。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type")]
+pub enum AccountFilter {
+    /// 保有するトークン種別数（`token_balances`のカードナリティ）が一致すること
+    DataSize {
+        /// 期待するトークン種別数
+        size: usize,
+    },
+    /// 指定したトークンの残高が`[min, max]`の範囲内であること
+    TokenBalance {
+        /// トークンID
+        token_id: String,
+        /// 下限（含む）
+        min: f64,
+        /// 上限（含む）
+        max: f64,
+    },
+    /// アカウントIDまたは公開鍵のバイト列の、指定オフセットの窓がbase58デコード結果と一致すること
+    Memcmp {
+        /// バイト列内の比較開始オフセット
+        offset: usize,
+        /// 比較対象バイト列のbase58エンコード文字列
+        bytes_base58: String,
+    },
+}
+
+impl AccountFilter {
+    /// アカウントがこのフィルタに一致するかどうかを判定する
+    fn matches(&self, account: &Account) -> Result<bool, String> {
+        match self {
+            AccountFilter::DataSize { size } => Ok(account.token_balances.len() == *size),
+            AccountFilter::TokenBalance { token_id, min, max } => {
+                let balance = account.token_balances.get(token_id).copied().unwrap_or(0.0);
+                Ok(balance >= *min && balance <= *max)
+            }
+            AccountFilter::Memcmp { offset, bytes_base58 } => {
+                let needle = base58::decode(bytes_base58)
+                    .into_vec()
+                    .map_err(|e| format!("Invalid base58 in memcmp filter: {:?}", e))?;
+
+                Ok(window_matches(account.id.as_bytes(), *offset, &needle)
+                    || window_matches(account.public_key.as_bytes(), *offset, &needle))
+            }
+        }
+    }
+}
+
+/// `haystack[offset..offset + needle.len()]`が`needle`と一致するかどうかを判定する
+///
+/// 範囲外になる場合は一致しないものとして扱う（パニックしない）。
+fn window_matches(haystack: &[u8], offset: usize, needle: &[u8]) -> bool {
+    haystack
+        .get(offset..offset + needle.len())
+        .map_or(false, |window| window == needle)
+}
+
+/// マルチシグ口座の送金提案
+///
+/// 閾値ポリシーが設定されたアカウントからの送金は即座には送信されず、
+/// 必要な数の署名が集まるまでこの状態で保持される。
+#[derive(Debug, Clone)]
+pub struct PendingTransaction {
+    /// 提案ID（トランザクションIDと同一）
+    pub id: String,
+    /// 署名が揃い次第ノードに送信されるトランザクション
+    pub transaction: Transaction,
+    /// 送信元アカウントID
+    pub from_account_id: String,
+    /// 署名対象のメッセージ（トランザクションのペイロード）
+    pub message: Vec<u8>,
+    /// これまでに集まった署名
+    pub signatures: HashMap<PublicKey, Signature>,
+    /// 提案日時
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
 /// ウォレットマネージャー
 pub struct WalletManager {
     /// アカウントのマップ
     accounts: Mutex<HashMap<String, Account>>,
+    /// アカウントに紐づくマルチシグ閾値ポリシー
+    policies: Mutex<HashMap<String, ThresholdPolicy>>,
+    /// 署名収集中の送金提案
+    pending_transactions: Mutex<HashMap<String, PendingTransaction>>,
 }
 
 impl WalletManager {
@@ -73,6 +166,8 @@ impl WalletManager {
     pub fn new() -> Self {
         Self {
             accounts: Mutex::new(HashMap::new()),
+            policies: Mutex::new(HashMap::new()),
+            pending_transactions: Mutex::new(HashMap::new()),
         }
     }
     
@@ -99,6 +194,50 @@ impl WalletManager {
         let accounts = self.accounts.lock().unwrap();
         accounts.values().cloned().collect()
     }
+
+    /// フィルタ述語に一致するアカウントを検索する
+    ///
+    /// `filters`はすべてAND条件として評価される。フィルタ数または`limit`が
+    /// サーバー側の上限（[`MAX_ACCOUNT_QUERY_FILTERS`]/[`MAX_ACCOUNT_QUERY_LIMIT`]）を
+    /// 超える場合はエラーを返す。これにより、全アカウントの線形スキャンが
+    /// 無制限のレスポンスサイズにつながることを防ぐ。
+    pub fn query_accounts(&self, filters: &[AccountFilter], limit: usize) -> Result<Vec<Account>, String> {
+        if filters.len() > MAX_ACCOUNT_QUERY_FILTERS {
+            return Err(format!(
+                "Too many filters: {} (max {})",
+                filters.len(),
+                MAX_ACCOUNT_QUERY_FILTERS
+            ));
+        }
+        if limit > MAX_ACCOUNT_QUERY_LIMIT {
+            return Err(format!(
+                "Requested limit {} exceeds maximum of {}",
+                limit, MAX_ACCOUNT_QUERY_LIMIT
+            ));
+        }
+
+        let accounts = self.accounts.lock().unwrap();
+        let mut matches = Vec::new();
+
+        for account in accounts.values() {
+            let mut matched = true;
+            for filter in filters {
+                if !filter.matches(account)? {
+                    matched = false;
+                    break;
+                }
+            }
+
+            if matched {
+                matches.push(account.clone());
+                if matches.len() >= limit {
+                    break;
+                }
+            }
+        }
+
+        Ok(matches)
+    }
     
     /// トランザクションを作成
     pub fn create_transaction(
@@ -154,6 +293,153 @@ impl WalletManager {
         Ok(transaction)
     }
     
+    /// アカウントにマルチシグ閾値ポリシーを設定
+    ///
+    /// 以後、このアカウントからの送金は `propose_transaction` による提案と
+    /// 署名収集を経なければノードに送信されない。
+    pub fn set_account_policy(&self, account_id: &str, policy: ThresholdPolicy) -> Result<(), String> {
+        let accounts = self.accounts.lock().unwrap();
+        if !accounts.contains_key(account_id) {
+            return Err(format!("Account {} not found", account_id));
+        }
+        drop(accounts);
+
+        self.policies.lock().unwrap().insert(account_id.to_string(), policy);
+        info!("Multisig policy set for account: {}", account_id);
+        Ok(())
+    }
+
+    /// アカウントに設定されたマルチシグ閾値ポリシーを取得
+    pub fn get_account_policy(&self, account_id: &str) -> Option<ThresholdPolicy> {
+        self.policies.lock().unwrap().get(account_id).cloned()
+    }
+
+    /// マルチシグ口座からの送金を提案し、署名収集を開始する
+    ///
+    /// 閾値ポリシーが設定されていないアカウントからの提案はエラーになる。
+    /// 返される提案のトランザクションは、署名が閾値に達するまでノードには送信されない。
+    pub fn propose_transaction(
+        &self,
+        from_account_id: &str,
+        to_account_id: &str,
+        amount: f64,
+        token_id: Option<String>,
+    ) -> Result<PendingTransaction, String> {
+        if self.get_account_policy(from_account_id).is_none() {
+            return Err(format!("Account {} has no multisig policy", from_account_id));
+        }
+
+        let accounts = self.accounts.lock().unwrap();
+
+        let from_account = accounts.get(from_account_id)
+            .ok_or_else(|| format!("From account {} not found", from_account_id))?;
+        let to_account = accounts.get(to_account_id)
+            .ok_or_else(|| format!("To account {} not found", to_account_id))?;
+
+        if let Some(token) = &token_id {
+            let balance = from_account.token_balances.get(token).unwrap_or(&0.0);
+            if *balance < amount {
+                return Err(format!("Insufficient token balance: {} < {}", balance, amount));
+            }
+        } else if from_account.balance < amount {
+            return Err(format!("Insufficient balance: {} < {}", from_account.balance, amount));
+        }
+
+        let tx_data = TransactionData {
+            from: from_account.id.clone(),
+            to: to_account.id.clone(),
+            amount,
+            token_id,
+            timestamp: chrono::Utc::now().timestamp(),
+        };
+
+        let payload = serde_json::to_vec(&tx_data)
+            .map_err(|e| format!("Failed to serialize transaction data: {}", e))?;
+        drop(accounts);
+
+        // 署名はまだ集まっていないので、空の署名でトランザクションを仮組みする
+        let transaction = Transaction::new(vec![], payload.clone(), vec![]);
+        let proposal = PendingTransaction {
+            id: transaction.id.clone(),
+            transaction,
+            from_account_id: from_account_id.to_string(),
+            message: payload,
+            signatures: HashMap::new(),
+            created_at: chrono::Utc::now(),
+        };
+
+        self.pending_transactions
+            .lock()
+            .unwrap()
+            .insert(proposal.id.clone(), proposal.clone());
+
+        debug!("Transaction proposed: {}", proposal.id);
+        Ok(proposal)
+    }
+
+    /// 提案中のトランザクションに署名を追加する
+    ///
+    /// 署名者は提案元アカウントのポリシーで許可された鍵でなければならない。
+    /// 追加の結果、閾値を満たした場合は送信可能な `Transaction` を返し、
+    /// まだ不足している場合は `None` を返す。
+    pub fn add_signature(
+        &self,
+        proposal_id: &str,
+        public_key: PublicKey,
+        signature: Signature,
+    ) -> Result<Option<Transaction>, String> {
+        let mut pending = self.pending_transactions.lock().unwrap();
+        let proposal = pending.get_mut(proposal_id)
+            .ok_or_else(|| format!("Proposal {} not found", proposal_id))?;
+
+        let policy = self.get_account_policy(&proposal.from_account_id)
+            .ok_or_else(|| format!("Account {} has no multisig policy", proposal.from_account_id))?;
+
+        if !policy.is_valid() {
+            return Err(format!("Multisig policy for account {} has expired", proposal.from_account_id));
+        }
+        if !policy.is_allowed(&public_key) {
+            return Err("Public key is not part of the signing policy".to_string());
+        }
+
+        proposal.signatures.insert(public_key, signature);
+
+        let threshold_met = policy
+            .verify_threshold(&proposal.message, &proposal.signatures)
+            .map_err(|e| format!("Failed to verify signatures: {}", e))?;
+
+        if threshold_met {
+            Ok(Some(proposal.transaction.clone()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// 送金提案の署名収集状況を取得する
+    ///
+    /// 提案そのものに加え、必要な残り署名数と有効期限までの残り秒数（秒単位、
+    /// 期限なしの場合は`None`）を返す。
+    pub fn proposal_status(&self, proposal_id: &str) -> Result<(PendingTransaction, usize, Option<i64>), String> {
+        let proposal = self.pending_transactions
+            .lock()
+            .unwrap()
+            .get(proposal_id)
+            .cloned()
+            .ok_or_else(|| format!("Proposal {} not found", proposal_id))?;
+
+        let policy = self.get_account_policy(&proposal.from_account_id)
+            .ok_or_else(|| format!("Account {} has no multisig policy", proposal.from_account_id))?;
+
+        let remaining = policy.remaining_signatures(&proposal.signatures);
+        let expires_in = policy.time_remaining();
+        Ok((proposal, remaining, expires_in))
+    }
+
+    /// 送信済み・期限切れになった送金提案を破棄する
+    pub fn remove_proposal(&self, proposal_id: &str) {
+        self.pending_transactions.lock().unwrap().remove(proposal_id);
+    }
+
     /// トランザクションを処理
     pub fn process_transaction(&self, transaction: &Transaction) -> Result<(), String> {
         // ペイロードをデシリアライズ
@@ -127,8 +127,8 @@ impl EnhancedMultisigTransaction {
         // 署名を追加
         self.signatures.insert(public_key, signature);
 
-        // 状態を更新
-        if self.policy.is_threshold_met(&self.signatures) {
+        // 状態を更新（鍵の許可リスト照合だけでなく実署名を検証したうえで閾値を判定する）
+        if self.policy.verify_threshold(&self.signing_message()?, &self.signatures)? {
             self.state = MultisigTransactionState::Approved;
         } else {
             self.state = MultisigTransactionState::PartiallyApproved;
@@ -159,8 +159,8 @@ impl EnhancedMultisigTransaction {
             }
         }
 
-        // 閾値を満たしているかどうかを再確認
-        if !self.policy.is_threshold_met(&self.signatures) {
+        // 閾値を満たしているかどうかを再確認（実署名検証込み）
+        if !self.policy.verify_threshold(&self.signing_message()?, &self.signatures)? {
             return Err(Error::Unauthorized(
                 "必要な署名数を満たしていません".to_string(),
             ));
@@ -249,6 +249,14 @@ impl EnhancedMultisigTransaction {
         self.metadata.get(key)
     }
 
+    /// 各署名者が署名すべき対象メッセージ（基本トランザクションの正規シリアライズ）
+    ///
+    /// `policy.verify_threshold`に渡すメッセージはこれで統一し、署名がどの
+    /// トランザクション内容に対するものかを一意に固定する。
+    fn signing_message(&self) -> Result<Vec<u8>, Error> {
+        serde_json::to_vec(&self.transaction).map_err(|e| Error::SerializeError(e.to_string()))
+    }
+
     /// 有効期限を延長
     pub fn extend_expiration(&mut self, duration_seconds: i64) -> Result<(), Error> {
         // 状態をチェック
@@ -372,12 +380,12 @@ mod tests {
         assert_eq!(multisig_tx.signature_count(), 0);
         assert_eq!(multisig_tx.remaining_signatures(), 2);
 
-        // 署名を追加
+        // 署名を追加（許可リストの鍵だが、署名値自体は偽造されたもの）
         multisig_tx
             .add_signature(keypair1.public.clone(), "sig1".to_string())
             .unwrap();
 
-        // 状態を確認
+        // 鍵は許可リストに含まれるため保持はされるが、まだ実署名の検証は通っていない
         assert_eq!(
             multisig_tx.state,
             MultisigTransactionState::PartiallyApproved
@@ -390,18 +398,21 @@ mod tests {
             .add_signature(keypair2.public.clone(), "sig2".to_string())
             .unwrap();
 
-        // 状態を確認
-        assert_eq!(multisig_tx.state, MultisigTransactionState::Approved);
+        // 許可リストの鍵が必要数揃っても、署名自体が偽造されている限り
+        // verify_thresholdによる検証には失敗するため承認済みにはならない
+        assert_eq!(
+            multisig_tx.state,
+            MultisigTransactionState::PartiallyApproved
+        );
         assert_eq!(multisig_tx.signature_count(), 2);
-        assert_eq!(multisig_tx.remaining_signatures(), 0);
-
-        // トランザクションを実行
-        multisig_tx.execute().unwrap();
 
-        // 状態を確認
-        assert_eq!(multisig_tx.state, MultisigTransactionState::Executed);
-        assert!(multisig_tx.executed_at.is_some());
-        assert!(multisig_tx.execution_result.is_some());
+        // 実行も拒否される
+        let result = multisig_tx.execute();
+        assert!(result.is_err());
+        assert_eq!(
+            multisig_tx.state,
+            MultisigTransactionState::PartiallyApproved
+        );
     }
 
     #[test]
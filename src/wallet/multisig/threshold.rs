@@ -1,262 +1,524 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use chrono::{DateTime, Utc};
 use serde::{Serialize, Deserialize};
 
 use crate::error::Error;
 use crate::crypto::{PublicKey, Signature};
 
-/// マルチシグ閾値ポリシー
+/// 単一の鍵集合に対する閾値ポリシー（マルチシグの最小単位）
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ThresholdPolicy {
-    /// 必要な署名数
+pub struct LeafThreshold {
+    /// 必要な署名数（`weight_threshold`が`None`の場合に使用される）
     pub required_signatures: usize,
     /// 許可された公開鍵のリスト
     pub allowed_public_keys: Vec<PublicKey>,
     /// 公開鍵の重み（オプション）
     pub weights: Option<HashMap<PublicKey, u32>>,
+    /// 重みの合計に対する閾値
+    ///
+    /// `weights`が設定されている場合に、カウントベースの`required_signatures`とは
+    /// 独立して使用される重み閾値。`with_weights`で明示的に渡された値がそのまま
+    /// 保存され、`required_signatures`を流用することはない。
+    pub weight_threshold: Option<u32>,
     /// 有効期限（オプション）
     pub expiration: Option<DateTime<Utc>>,
 }
 
-impl ThresholdPolicy {
-    /// 新しい閾値ポリシーを作成
-    pub fn new(required_signatures: usize, allowed_public_keys: Vec<PublicKey>) -> Self {
-        Self {
-            required_signatures,
-            allowed_public_keys,
-            weights: None,
-            expiration: None,
-        }
-    }
-    
-    /// 重み付きポリシーを作成
-    pub fn with_weights(allowed_public_keys: Vec<PublicKey>, weights: HashMap<PublicKey, u32>, threshold: u32) -> Self {
-        let required_signatures = allowed_public_keys.len(); // 実際には重みで判断するため、最大値を設定
-        
-        Self {
-            required_signatures,
-            allowed_public_keys,
-            weights: Some(weights),
-            expiration: None,
-        }
-    }
-    
-    /// 有効期限付きポリシーを作成
-    pub fn with_expiration(required_signatures: usize, allowed_public_keys: Vec<PublicKey>, expiration: DateTime<Utc>) -> Self {
-        Self {
-            required_signatures,
-            allowed_public_keys,
-            weights: None,
-            expiration: Some(expiration),
-        }
-    }
-    
-    /// ポリシーが有効かどうかを確認
-    pub fn is_valid(&self) -> bool {
+impl LeafThreshold {
+    fn is_valid(&self) -> bool {
         // 有効期限をチェック
         if let Some(expiration) = self.expiration {
             if Utc::now() > expiration {
                 return false;
             }
         }
-        
-        // 必要な署名数が許可された公開鍵の数以下であることを確認
-        if self.required_signatures > self.allowed_public_keys.len() {
+
+        // 重みを使わない場合、必要な署名数が許可された公開鍵の数以下であることを確認
+        if self.weights.is_none() && self.required_signatures > self.allowed_public_keys.len() {
             return false;
         }
-        
-        // 重みが設定されている場合、全ての公開鍵に重みが設定されていることを確認
+
+        // 重みが設定されている場合、全ての公開鍵に重みが設定されており、
+        // かつ重み閾値が明示されていることを確認
         if let Some(weights) = &self.weights {
+            if self.weight_threshold.is_none() {
+                return false;
+            }
             for key in &self.allowed_public_keys {
                 if !weights.contains_key(key) {
                     return false;
                 }
             }
         }
-        
+
         true
     }
-    
-    /// 公開鍵が許可されているかどうかを確認
-    pub fn is_allowed(&self, public_key: &PublicKey) -> bool {
+
+    fn is_allowed(&self, public_key: &PublicKey) -> bool {
         self.allowed_public_keys.contains(public_key)
     }
-    
-    /// 署名が閾値を満たしているかどうかを確認
-    pub fn is_threshold_met(&self, signatures: &HashMap<PublicKey, Signature>) -> bool {
-        if !self.is_valid() {
-            return false;
+
+    fn verified_keys(
+        &self,
+        message: &[u8],
+        signatures: &HashMap<PublicKey, Signature>,
+    ) -> HashSet<PublicKey> {
+        signatures
+            .iter()
+            .filter(|(key, _)| self.is_allowed(key))
+            .filter(|(key, signature)| key.verify(message, signature))
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    fn verify_threshold(
+        &self,
+        message: &[u8],
+        signatures: &HashMap<PublicKey, Signature>,
+    ) -> Result<bool, Error> {
+        let verified_keys = self.verified_keys(message, signatures);
+
+        if let (Some(weights), Some(weight_threshold)) = (&self.weights, self.weight_threshold) {
+            let total_weight: u32 = verified_keys
+                .iter()
+                .filter_map(|key| weights.get(key))
+                .sum();
+
+            return Ok(total_weight >= weight_threshold);
         }
-        
-        // 有効な署名の数をカウント
+
+        Ok(verified_keys.len() >= self.required_signatures)
+    }
+
+    fn is_threshold_met(&self, signatures: &HashMap<PublicKey, Signature>) -> bool {
         let valid_signatures: Vec<&PublicKey> = signatures.keys()
             .filter(|key| self.is_allowed(key))
             .collect();
-        
-        // 重みが設定されている場合
-        if let Some(weights) = &self.weights {
+
+        if let (Some(weights), Some(weight_threshold)) = (&self.weights, self.weight_threshold) {
             let total_weight: u32 = valid_signatures.iter()
                 .filter_map(|key| weights.get(key))
                 .sum();
-            
-            // 閾値は required_signatures フィールドに格納されていると仮定
-            return total_weight >= self.required_signatures as u32;
+
+            return total_weight >= weight_threshold;
         }
-        
-        // 重みが設定されていない場合は単純に署名数をチェック
+
         valid_signatures.len() >= self.required_signatures
     }
-    
-    /// 残りの必要署名数を取得
-    pub fn remaining_signatures(&self, signatures: &HashMap<PublicKey, Signature>) -> usize {
-        if !self.is_valid() {
-            return self.required_signatures;
-        }
-        
-        // 有効な署名の数をカウント
+
+    fn remaining_signatures(&self, signatures: &HashMap<PublicKey, Signature>) -> usize {
         let valid_signatures: Vec<&PublicKey> = signatures.keys()
             .filter(|key| self.is_allowed(key))
             .collect();
-        
-        // 重みが設定されている場合
-        if let Some(weights) = &self.weights {
+
+        if let (Some(weights), Some(weight_threshold)) = (&self.weights, self.weight_threshold) {
             let total_weight: u32 = valid_signatures.iter()
                 .filter_map(|key| weights.get(key))
                 .sum();
-            
-            let threshold = self.required_signatures as u32;
-            if total_weight >= threshold {
+
+            if total_weight >= weight_threshold {
                 return 0;
             }
-            
-            // 残りの重みを計算（簡易的な実装）
-            return (threshold - total_weight) as usize;
+
+            // 残りの重みを署名数に換算する簡易的な実装
+            return (weight_threshold - total_weight) as usize;
         }
-        
-        // 重みが設定されていない場合は単純に署名数をチェック
+
         if valid_signatures.len() >= self.required_signatures {
             return 0;
         }
-        
+
         self.required_signatures - valid_signatures.len()
     }
-    
-    /// 有効期限までの残り時間（秒）を取得
-    pub fn time_remaining(&self) -> Option<i64> {
+
+    fn time_remaining(&self) -> Option<i64> {
         self.expiration.map(|expiration| {
             let now = Utc::now();
             if now >= expiration {
                 return 0;
             }
-            
+
             (expiration - now).num_seconds()
         })
     }
 }
 
+/// マルチシグ閾値ポリシー
+///
+/// 単一の鍵集合に対する閾値（[`ThresholdPolicy::Leaf`]）に加えて、複数のポリシーを
+/// 組み合わせた複合ポリシー（[`ThresholdPolicy::AllOf`]/[`ThresholdPolicy::AnyOf`]）を
+/// 表現できる。例えば「2-of-3の取締役鍵 AND 1-of-2の監査人鍵」のような、
+/// PoSガバナンスで見られる階層的なバリデータセットのルールを組み立てられる。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum ThresholdPolicy {
+    /// 単一の鍵集合に対する閾値
+    Leaf(LeafThreshold),
+    /// すべての子ポリシーが満たされた場合にのみ満たされる複合ポリシー
+    AllOf(Vec<ThresholdPolicy>),
+    /// いずれか1つの子ポリシーが満たされれば満たされる複合ポリシー
+    AnyOf(Vec<ThresholdPolicy>),
+}
+
+impl ThresholdPolicy {
+    /// 新しい閾値ポリシーを作成
+    pub fn new(required_signatures: usize, allowed_public_keys: Vec<PublicKey>) -> Self {
+        Self::Leaf(LeafThreshold {
+            required_signatures,
+            allowed_public_keys,
+            weights: None,
+            weight_threshold: None,
+            expiration: None,
+        })
+    }
+
+    /// 重み付きポリシーを作成
+    ///
+    /// `threshold`は署名数ではなく重みの合計に対する閾値であり、
+    /// `required_signatures`（カウントベースの閾値）とは独立して保存される。
+    pub fn with_weights(allowed_public_keys: Vec<PublicKey>, weights: HashMap<PublicKey, u32>, threshold: u32) -> Self {
+        let required_signatures = allowed_public_keys.len();
+
+        Self::Leaf(LeafThreshold {
+            required_signatures,
+            allowed_public_keys,
+            weights: Some(weights),
+            weight_threshold: Some(threshold),
+            expiration: None,
+        })
+    }
+
+    /// 有効期限付きポリシーを作成
+    pub fn with_expiration(required_signatures: usize, allowed_public_keys: Vec<PublicKey>, expiration: DateTime<Utc>) -> Self {
+        Self::Leaf(LeafThreshold {
+            required_signatures,
+            allowed_public_keys,
+            weights: None,
+            weight_threshold: None,
+            expiration: Some(expiration),
+        })
+    }
+
+    /// すべての子ポリシーが満たされた場合にのみ満たされる複合ポリシーを作成する
+    pub fn all_of(children: Vec<ThresholdPolicy>) -> Self {
+        Self::AllOf(children)
+    }
+
+    /// いずれか1つの子ポリシーが満たされれば満たされる複合ポリシーを作成する
+    pub fn any_of(children: Vec<ThresholdPolicy>) -> Self {
+        Self::AnyOf(children)
+    }
+
+    /// ポリシーが有効かどうかを確認
+    ///
+    /// 複合ポリシーの場合、すべての子（末端の葉ポリシーすべて）が有効である
+    /// ことを再帰的に確認する。
+    pub fn is_valid(&self) -> bool {
+        match self {
+            ThresholdPolicy::Leaf(leaf) => leaf.is_valid(),
+            ThresholdPolicy::AllOf(children) | ThresholdPolicy::AnyOf(children) => {
+                !children.is_empty() && children.iter().all(|child| child.is_valid())
+            }
+        }
+    }
+
+    /// 公開鍵がこのポリシー（またはいずれかの子ポリシー）で許可されているかどうかを確認
+    pub fn is_allowed(&self, public_key: &PublicKey) -> bool {
+        match self {
+            ThresholdPolicy::Leaf(leaf) => leaf.is_allowed(public_key),
+            ThresholdPolicy::AllOf(children) | ThresholdPolicy::AnyOf(children) => {
+                children.iter().any(|child| child.is_allowed(public_key))
+            }
+        }
+    }
+
+    /// `message` に対する実際の署名を検証したうえで閾値を満たしているかどうかを確認する
+    ///
+    /// `is_threshold_met`/`remaining_signatures` は鍵が `allowed_public_keys` に
+    /// 含まれているかどうかしか見ておらず、偽造・空の署名でも閾値に数えられてしまう。
+    /// こちらは各エントリについて公開鍵による実署名検証を行い、許可されていない鍵や
+    /// 検証に失敗した署名を除外したうえで（重複する鍵は1つとして扱う）、初めて
+    /// カウント・重み付けを行う。認可判定はこの経路を通すこと。
+    ///
+    /// 複合ポリシーは同じ`message`・`signatures`に対して各子ポリシーを再帰的に
+    /// 評価する。`AllOf`はすべての子が、`AnyOf`はいずれか1つの子が満たされた場合に
+    /// `true`を返す。
+    pub fn verify_threshold(
+        &self,
+        message: &[u8],
+        signatures: &HashMap<PublicKey, Signature>,
+    ) -> Result<bool, Error> {
+        if !self.is_valid() {
+            return Ok(false);
+        }
+
+        match self {
+            ThresholdPolicy::Leaf(leaf) => leaf.verify_threshold(message, signatures),
+            ThresholdPolicy::AllOf(children) => {
+                for child in children {
+                    if !child.verify_threshold(message, signatures)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            ThresholdPolicy::AnyOf(children) => {
+                for child in children {
+                    if child.verify_threshold(message, signatures)? {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+        }
+    }
+
+    /// 署名が閾値を満たしているかどうかを確認
+    ///
+    /// これは鍵が許可リストに含まれているかどうかだけを見た安価な事前チェックであり、
+    /// 署名自体の正当性は検証しない。実際の認可判定には [`Self::verify_threshold`] を使うこと。
+    pub fn is_threshold_met(&self, signatures: &HashMap<PublicKey, Signature>) -> bool {
+        if !self.is_valid() {
+            return false;
+        }
+
+        match self {
+            ThresholdPolicy::Leaf(leaf) => leaf.is_threshold_met(signatures),
+            ThresholdPolicy::AllOf(children) => children.iter().all(|child| child.is_threshold_met(signatures)),
+            ThresholdPolicy::AnyOf(children) => children.iter().any(|child| child.is_threshold_met(signatures)),
+        }
+    }
+
+    /// 残りの必要署名数を取得
+    ///
+    /// これも鍵の許可リスト照合のみを行う安価な事前チェックで、署名の正当性は
+    /// 検証しない。不正な署名によってこの値が減ることはあってはならない。
+    ///
+    /// 複合ポリシーでは、`AllOf`は各子の残り署名数の合計（最悪ケースの見積もり）、
+    /// `AnyOf`は最も署名が集めやすい子の残り署名数を返す。
+    pub fn remaining_signatures(&self, signatures: &HashMap<PublicKey, Signature>) -> usize {
+        if !self.is_valid() {
+            return match self {
+                ThresholdPolicy::Leaf(leaf) => leaf.required_signatures,
+                ThresholdPolicy::AllOf(children) | ThresholdPolicy::AnyOf(children) => {
+                    children.iter().map(|child| child.remaining_signatures(signatures)).sum()
+                }
+            };
+        }
+
+        match self {
+            ThresholdPolicy::Leaf(leaf) => leaf.remaining_signatures(signatures),
+            ThresholdPolicy::AllOf(children) => {
+                children.iter().map(|child| child.remaining_signatures(signatures)).sum()
+            }
+            ThresholdPolicy::AnyOf(children) => children
+                .iter()
+                .map(|child| child.remaining_signatures(signatures))
+                .min()
+                .unwrap_or(0),
+        }
+    }
+
+    /// 有効期限までの残り時間（秒）を取得
+    ///
+    /// 複合ポリシーの場合、最も早く期限が切れる子の値を返す（期限を持つ子が
+    /// 1つもない場合は`None`）。
+    pub fn time_remaining(&self) -> Option<i64> {
+        match self {
+            ThresholdPolicy::Leaf(leaf) => leaf.time_remaining(),
+            ThresholdPolicy::AllOf(children) | ThresholdPolicy::AnyOf(children) => {
+                children.iter().filter_map(|child| child.time_remaining()).min()
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::crypto::generate_keypair;
-    
+
     #[test]
     fn test_threshold_policy() {
         // キーペアを生成
         let keypair1 = generate_keypair();
         let keypair2 = generate_keypair();
         let keypair3 = generate_keypair();
-        
+
         // 2-of-3ポリシーを作成
         let policy = ThresholdPolicy::new(
             2,
             vec![keypair1.public.clone(), keypair2.public.clone(), keypair3.public.clone()]
         );
-        
+
         assert!(policy.is_valid());
         assert!(policy.is_allowed(&keypair1.public));
         assert!(!policy.is_allowed(&generate_keypair().public));
-        
+
         // 署名マップを作成
         let mut signatures = HashMap::new();
         signatures.insert(keypair1.public.clone(), "sig1".to_string());
-        
+
         // 閾値を満たしていないことを確認
         assert!(!policy.is_threshold_met(&signatures));
         assert_eq!(policy.remaining_signatures(&signatures), 1);
-        
+
         // 署名を追加
         signatures.insert(keypair2.public.clone(), "sig2".to_string());
-        
+
         // 閾値を満たしていることを確認
         assert!(policy.is_threshold_met(&signatures));
         assert_eq!(policy.remaining_signatures(&signatures), 0);
     }
-    
+
+    #[test]
+    fn test_verify_threshold_rejects_unverified_signatures() {
+        // キーペアを生成
+        let keypair1 = generate_keypair();
+        let keypair2 = generate_keypair();
+
+        // 2-of-2ポリシーを作成
+        let policy = ThresholdPolicy::new(
+            2,
+            vec![keypair1.public.clone(), keypair2.public.clone()],
+        );
+
+        let message = b"transfer 100 tokens";
+
+        // 鍵は許可リストに含まれているが、署名自体は偽造（実際には検証に失敗する）
+        let mut signatures = HashMap::new();
+        signatures.insert(keypair1.public.clone(), "forged-sig-1".to_string());
+        signatures.insert(keypair2.public.clone(), "forged-sig-2".to_string());
+
+        // 鍵の所属だけを見る安価な事前チェックは（誤って）閾値達成とみなす
+        assert!(policy.is_threshold_met(&signatures));
+
+        // しかし実署名を検証する経路は、偽造署名を閾値に数えない
+        assert!(!policy.verify_threshold(message, &signatures).unwrap());
+    }
+
     #[test]
     fn test_weighted_policy() {
         // キーペアを生成
         let keypair1 = generate_keypair();
         let keypair2 = generate_keypair();
         let keypair3 = generate_keypair();
-        
+
         // 重みを設定
         let mut weights = HashMap::new();
         weights.insert(keypair1.public.clone(), 3);
         weights.insert(keypair2.public.clone(), 2);
         weights.insert(keypair3.public.clone(), 1);
-        
+
         // 重み付きポリシーを作成（閾値4）
         let policy = ThresholdPolicy::with_weights(
             vec![keypair1.public.clone(), keypair2.public.clone(), keypair3.public.clone()],
             weights,
             4
         );
-        
+
         assert!(policy.is_valid());
-        
+
         // 署名マップを作成
         let mut signatures = HashMap::new();
         signatures.insert(keypair1.public.clone(), "sig1".to_string());
-        
+
         // 閾値を満たしていないことを確認（重み3 < 閾値4）
         assert!(!policy.is_threshold_met(&signatures));
-        
+
         // 署名を追加
         signatures.insert(keypair2.public.clone(), "sig2".to_string());
-        
+
         // 閾値を満たしていることを確認（重み3+2=5 > 閾値4）
         assert!(policy.is_threshold_met(&signatures));
     }
-    
+
     #[test]
     fn test_expiration() {
         // キーペアを生成
         let keypair1 = generate_keypair();
         let keypair2 = generate_keypair();
-        
+
         // 過去の日時を設定
         let past = Utc::now() - chrono::Duration::days(1);
-        
+
         // 有効期限切れのポリシーを作成
         let expired_policy = ThresholdPolicy::with_expiration(
             1,
             vec![keypair1.public.clone(), keypair2.public.clone()],
             past
         );
-        
+
         assert!(!expired_policy.is_valid());
-        
+
         // 未来の日時を設定
         let future = Utc::now() + chrono::Duration::days(1);
-        
+
         // 有効なポリシーを作成
         let valid_policy = ThresholdPolicy::with_expiration(
             1,
             vec![keypair1.public.clone(), keypair2.public.clone()],
             future
         );
-        
+
         assert!(valid_policy.is_valid());
         assert!(valid_policy.time_remaining().unwrap() > 0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_all_of_requires_every_child() {
+        // 取締役鍵（2-of-3）
+        let board1 = generate_keypair();
+        let board2 = generate_keypair();
+        let board3 = generate_keypair();
+        let board_policy = ThresholdPolicy::new(
+            2,
+            vec![board1.public.clone(), board2.public.clone(), board3.public.clone()],
+        );
+
+        // 監査人鍵（1-of-2）
+        let auditor1 = generate_keypair();
+        let auditor2 = generate_keypair();
+        let auditor_policy = ThresholdPolicy::new(
+            1,
+            vec![auditor1.public.clone(), auditor2.public.clone()],
+        );
+
+        // 「2-of-3取締役 AND 1-of-2監査人」の複合ポリシー
+        let policy = ThresholdPolicy::all_of(vec![board_policy, auditor_policy]);
+        assert!(policy.is_valid());
+
+        let mut signatures = HashMap::new();
+        signatures.insert(board1.public.clone(), "sig-board1".to_string());
+        signatures.insert(board2.public.clone(), "sig-board2".to_string());
+
+        // 取締役側は満たしているが、監査人側の署名がまだない
+        assert!(!policy.is_threshold_met(&signatures));
+        assert_eq!(policy.remaining_signatures(&signatures), 1);
+
+        // 監査人の署名を追加するとポリシー全体が満たされる
+        signatures.insert(auditor1.public.clone(), "sig-auditor1".to_string());
+        assert!(policy.is_threshold_met(&signatures));
+        assert_eq!(policy.remaining_signatures(&signatures), 0);
+    }
+
+    #[test]
+    fn test_any_of_requires_one_child() {
+        let keypair1 = generate_keypair();
+        let keypair2 = generate_keypair();
+        let keypair3 = generate_keypair();
+
+        let primary = ThresholdPolicy::new(2, vec![keypair1.public.clone(), keypair2.public.clone()]);
+        let fallback = ThresholdPolicy::new(1, vec![keypair3.public.clone()]);
+
+        let policy = ThresholdPolicy::any_of(vec![primary, fallback]);
+        assert!(policy.is_valid());
+
+        let mut signatures = HashMap::new();
+        assert!(!policy.is_threshold_met(&signatures));
+
+        // フォールバック側の単独鍵だけでポリシー全体が満たされる
+        signatures.insert(keypair3.public.clone(), "sig-fallback".to_string());
+        assert!(policy.is_threshold_met(&signatures));
+    }
+}
@@ -10,6 +10,158 @@ use std::fs::{self, File};
 use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
 
+/// 複数ランをマージした結果
+#[derive(Debug, Clone)]
+pub struct MergedCoverage {
+    /// マージ後のレポート（既存のレポータにそのまま渡せる）
+    pub report: CoverageReport,
+    /// (正規化パス → 行番号 → 合算実行回数)
+    pub per_line_counts: HashMap<PathBuf, HashMap<usize, u64>>,
+}
+
+/// XML の特殊文字をエスケープする
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// カバー行数 / 総行数 をパーセントで返す（0除算は 0.0）
+fn percentage(covered: usize, total: usize) -> f64 {
+    if total > 0 {
+        (covered as f64 / total as f64) * 100.0
+    } else {
+        0.0
+    }
+}
+
+/// LCOV `.info` を解析し、ファイルパス → 行番号 → 実行回数のマップを返す。
+///
+/// `SF:` でファイルを切り替え、`DA:<line>,<count>` を実行回数として取り込む。
+fn parse_lcov(content: &str) -> HashMap<PathBuf, HashMap<usize, u64>> {
+    let mut profile: HashMap<PathBuf, HashMap<usize, u64>> = HashMap::new();
+    let mut current: Option<PathBuf> = None;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if let Some(path) = line.strip_prefix("SF:") {
+            current = Some(PathBuf::from(path));
+        } else if let Some(da) = line.strip_prefix("DA:") {
+            if let Some(file) = &current {
+                let mut parts = da.splitn(2, ',');
+                if let (Some(line_no), Some(count)) = (parts.next(), parts.next()) {
+                    if let (Ok(line_no), Ok(count)) =
+                        (line_no.parse::<usize>(), count.parse::<u64>())
+                    {
+                        let entry = profile.entry(file.clone()).or_default();
+                        // 同一行が複数回現れたら実行回数を合算する
+                        *entry.entry(line_no).or_insert(0) += count;
+                    }
+                }
+            }
+        } else if line == "end_of_record" {
+            current = None;
+        }
+    }
+
+    profile
+}
+
+/// `cargo llvm-cov --json` 形式を解析する。
+///
+/// エクスポート JSON の `data[].files[].filename` と `segments`（`[line, col, count, ...]`）
+/// から行ごとの実行回数を組み立てる。
+fn parse_llvm_cov_json(content: &str) -> io::Result<HashMap<PathBuf, HashMap<usize, u64>>> {
+    let value: serde_json::Value = serde_json::from_str(content)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    let mut profile: HashMap<PathBuf, HashMap<usize, u64>> = HashMap::new();
+
+    let Some(data) = value.get("data").and_then(|d| d.as_array()) else {
+        return Ok(profile);
+    };
+
+    for datum in data {
+        let Some(files) = datum.get("files").and_then(|f| f.as_array()) else {
+            continue;
+        };
+        for file in files {
+            let Some(filename) = file.get("filename").and_then(|f| f.as_str()) else {
+                continue;
+            };
+            let entry = profile.entry(PathBuf::from(filename)).or_default();
+
+            if let Some(segments) = file.get("segments").and_then(|s| s.as_array()) {
+                for seg in segments {
+                    let Some(arr) = seg.as_array() else { continue };
+                    // segment = [line, col, count, has_count, is_region_entry, ...]
+                    let line = arr.first().and_then(|v| v.as_u64());
+                    let count = arr.get(2).and_then(|v| v.as_u64());
+                    let has_count = arr.get(3).and_then(|v| v.as_bool()).unwrap_or(true);
+                    if let (Some(line), Some(count)) = (line, count) {
+                        if has_count {
+                            let slot = entry.entry(line as usize).or_insert(0);
+                            *slot = (*slot).max(count);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(profile)
+}
+
+/// 文字列を指定長に切り詰める（超過分は末尾を `…` に置換）
+fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        s.to_string()
+    } else {
+        let keep = max.saturating_sub(1);
+        format!("{}…", s.chars().take(keep).collect::<String>())
+    }
+}
+
+/// 昇順のソート済み行番号列を連続範囲 `(start, end)` のリストに圧縮する
+fn contiguous_ranges(sorted: &[usize]) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut iter = sorted.iter().copied();
+    let Some(mut start) = iter.next() else {
+        return ranges;
+    };
+    let mut prev = start;
+    for line in iter {
+        if line == prev + 1 {
+            prev = line;
+        } else {
+            ranges.push((start, prev));
+            start = line;
+            prev = line;
+        }
+    }
+    ranges.push((start, prev));
+    ranges
+}
+
+/// レポート出力形式の選択子
+///
+/// 成熟したカバレッジツールに倣い、1回の呼び出しで毎回すべての成果物を書くのではなく、
+/// 必要な形式だけを選んで出力できるようにする。`Summary`/`Pretty` は端末向けで CI ログに
+/// そのまま表示でき、`Html`/`Lcov` はファイルに書き出す。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoverageType {
+    /// ファイルごとの1行サマリと合計行を降順（カバレッジ昇順）で端末に表示
+    Summary,
+    /// 各ファイルのソースを未カバー行を強調して表示
+    Pretty,
+    /// HTMLレポートを `coverage.html` に書き出す
+    Html,
+    /// LCOVレポートを `coverage.lcov` に書き出す
+    Lcov,
+}
+
 /// カバレッジレポート
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CoverageReport {
@@ -63,6 +215,23 @@ pub struct CoverageManager {
     source_dir: PathBuf,
     /// 除外パターン
     exclude_patterns: Vec<String>,
+    /// 外部プロファイルから読み込んだ実行回数（ファイルパス → 行番号 → 実行回数）
+    profile: Option<HashMap<PathBuf, HashMap<usize, u64>>>,
+    /// 全体の最低カバレッジ閾値（%）
+    global_threshold: Option<f64>,
+    /// モジュールごとの最低カバレッジ閾値（%）
+    module_thresholds: HashMap<String, f64>,
+}
+
+/// カバレッジ閾値違反
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThresholdViolation {
+    /// 対象（モジュール名、全体の場合は "<global>"）
+    pub module: String,
+    /// 実際のカバレッジ率（%）
+    pub actual: f64,
+    /// 要求される最低カバレッジ率（%）
+    pub required: f64,
 }
 
 impl CoverageManager {
@@ -77,6 +246,54 @@ impl CoverageManager {
                 String::from("**/*.md"),
                 String::from("**/.git/**"),
             ],
+            profile: None,
+            global_threshold: None,
+            module_thresholds: HashMap::new(),
+        }
+    }
+
+    /// カバレッジ閾値を設定する（全体とモジュール別）
+    pub fn set_thresholds(&mut self, global: f64, per_module: HashMap<String, f64>) {
+        self.global_threshold = Some(global);
+        self.module_thresholds = per_module;
+    }
+
+    /// レポートを設定済みの閾値と照合し、違反のリストを返す。
+    ///
+    /// 違反が空なら `Ok(())`、1件以上なら `Err` を返す。CI では非空の違反リストを
+    /// 非ゼロ終了コードに対応付けることで、品質ゲートとして機能させられる。
+    pub fn check_thresholds(
+        &self,
+        report: &CoverageReport,
+    ) -> Result<(), Vec<ThresholdViolation>> {
+        let mut violations = Vec::new();
+
+        if let Some(required) = self.global_threshold {
+            if report.coverage_percentage < required {
+                violations.push(ThresholdViolation {
+                    module: "<global>".to_string(),
+                    actual: report.coverage_percentage,
+                    required,
+                });
+            }
+        }
+
+        for (module, &required) in &self.module_thresholds {
+            if let Some(coverage) = report.module_coverage.get(module) {
+                if coverage.coverage_percentage < required {
+                    violations.push(ThresholdViolation {
+                        module: module.clone(),
+                        actual: coverage.coverage_percentage,
+                        required,
+                    });
+                }
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
         }
     }
 
@@ -85,6 +302,40 @@ impl CoverageManager {
         self.exclude_patterns.push(pattern.to_string());
     }
 
+    /// 外部プロファイル（LCOV `.info` または `cargo llvm-cov --json`）を読み込み、
+    /// ファイル・行番号ごとの実行回数をロードする。
+    ///
+    /// これ以降 `get_file_coverage` は、ダミーの `is_covered = true` ではなく、
+    /// プロファイルに `DA:` として現れた行だけを「実行可能行」とみなし、実行回数が
+    /// 1以上なら「カバー済み」として扱う。
+    pub fn load_profile(&mut self, lcov_or_json: &Path) -> io::Result<()> {
+        let content = fs::read_to_string(lcov_or_json)?;
+        let profile = if lcov_or_json.extension().map_or(false, |e| e == "json") {
+            parse_llvm_cov_json(&content)?
+        } else {
+            parse_lcov(&content)
+        };
+        self.profile = Some(profile);
+        Ok(())
+    }
+
+    /// 指定ソースファイルに対応するプロファイルの行マップを探す。
+    ///
+    /// LCOV/llvm-cov の `SF:` は絶対パスのことが多いため、完全一致のほか
+    /// 末尾一致（サフィックス）でも照合する。
+    fn profile_for<'a>(
+        profile: &'a HashMap<PathBuf, HashMap<usize, u64>>,
+        file_path: &Path,
+    ) -> Option<&'a HashMap<usize, u64>> {
+        if let Some(found) = profile.get(file_path) {
+            return Some(found);
+        }
+        profile
+            .iter()
+            .find(|(path, _)| path.ends_with(file_path) || file_path.ends_with(path.as_path()))
+            .map(|(_, lines)| lines)
+    }
+
     /// カバレッジレポートを生成
     pub fn generate_report(&self) -> io::Result<CoverageReport> {
         info!("Generating coverage report...");
@@ -246,32 +497,53 @@ impl CoverageManager {
 
         // 行ごとにカバレッジを分析
         let lines: Vec<&str> = content.lines().collect();
-        let total_lines = lines.len();
 
-        // 実際のカバレッジデータはテスト実行時に収集されるため、
-        // ここではダミーデータを生成
         let mut line_coverage = HashMap::new();
         let mut covered_lines = 0;
 
-        for (i, line) in lines.iter().enumerate() {
-            let line_number = i + 1;
-            let is_code_line = !line.trim().is_empty()
-                && !line.trim().starts_with("//")
-                && !line.trim().starts_with("/*")
-                && !line.trim().starts_with("*/")
-                && !line.trim().starts_with("*");
-
-            if is_code_line {
-                // テスト用のダミーデータ: 実際のカバレッジデータはテスト実行時に収集される
-                let is_covered = true; // ダミーデータ
-                line_coverage.insert(line_number, is_covered);
+        // プロファイルが読み込まれている場合は、実行回数から実際のカバレッジを組み立てる。
+        // `DA:` として現れた行だけを実行可能行とみなす。
+        let profile_lines = self
+            .profile
+            .as_ref()
+            .and_then(|p| Self::profile_for(p, file_path));
 
+        if let Some(counts) = profile_lines {
+            for (&line_number, &count) in counts {
+                let is_covered = count > 0;
+                line_coverage.insert(line_number, is_covered);
                 if is_covered {
                     covered_lines += 1;
                 }
             }
+        } else {
+            // プロファイル未読み込み時のみ、従来のヒューリスティックにフォールバックする
+            for (i, line) in lines.iter().enumerate() {
+                let line_number = i + 1;
+                let is_code_line = !line.trim().is_empty()
+                    && !line.trim().starts_with("//")
+                    && !line.trim().starts_with("/*")
+                    && !line.trim().starts_with("*/")
+                    && !line.trim().starts_with("*");
+
+                if is_code_line {
+                    let is_covered = true; // ダミーデータ
+                    line_coverage.insert(line_number, is_covered);
+
+                    if is_covered {
+                        covered_lines += 1;
+                    }
+                }
+            }
         }
 
+        // プロファイルがあれば実行可能行数は DA エントリ数、無ければ物理行数を使う
+        let total_lines = if profile_lines.is_some() {
+            line_coverage.len()
+        } else {
+            lines.len()
+        };
+
         let coverage_percentage = if total_lines > 0 {
             (covered_lines as f64 / total_lines as f64) * 100.0
         } else {
@@ -287,6 +559,294 @@ impl CoverageManager {
         })
     }
 
+    /// 複数のカバレッジ入力（各シャード/ノードの `coverage.lcov` 等）をマージして
+    /// 1つの `CoverageReport` にまとめる。
+    ///
+    /// 各 (ファイル, 行) について全ランの実行回数を合算し、いずれかのランでヒットして
+    /// いれば「カバー済み」とみなす。ファイルは正規化パスで重複排除し、行マップを和集合
+    /// して `LF`/`LH` を一貫して積み上げるので、マージ後の `coverage_percentage` は
+    /// 単一ランではなく全体像を反映する。
+    pub fn merge_profiles(&self, inputs: &[PathBuf]) -> io::Result<MergedCoverage> {
+        // (正規化パス → 行番号 → 合算実行回数)
+        let mut combined: HashMap<PathBuf, HashMap<usize, u64>> = HashMap::new();
+
+        for input in inputs {
+            let content = fs::read_to_string(input)?;
+            let profile = if input.extension().map_or(false, |e| e == "json") {
+                parse_llvm_cov_json(&content)?
+            } else {
+                parse_lcov(&content)
+            };
+
+            for (path, lines) in profile {
+                // 正規化に失敗しても生パスで重複排除する
+                let key = fs::canonicalize(&path).unwrap_or(path);
+                let entry = combined.entry(key).or_default();
+                for (line, count) in lines {
+                    *entry.entry(line).or_insert(0) += count;
+                }
+            }
+        }
+
+        // マージ済みプロファイルから CoverageReport を組み立てる
+        let mut module_coverage: HashMap<String, ModuleCoverage> = HashMap::new();
+        let mut total_lines = 0usize;
+        let mut covered_lines = 0usize;
+
+        for (path, lines) in &combined {
+            let lf = lines.len();
+            let lh = lines.values().filter(|&&c| c > 0).count();
+            total_lines += lf;
+            covered_lines += lh;
+
+            let display = path.to_string_lossy().to_string();
+            let file_name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| display.clone());
+            let module_name = self.get_module_name(&display);
+
+            let line_coverage: HashMap<usize, bool> =
+                lines.iter().map(|(&l, &c)| (l, c > 0)).collect();
+
+            let file_cov = FileCoverage {
+                name: file_name,
+                total_lines: lf,
+                covered_lines: lh,
+                coverage_percentage: percentage(lh, lf),
+                line_coverage,
+            };
+
+            let module = module_coverage
+                .entry(module_name.clone())
+                .or_insert_with(|| ModuleCoverage {
+                    name: module_name,
+                    total_lines: 0,
+                    covered_lines: 0,
+                    coverage_percentage: 0.0,
+                    file_coverage: HashMap::new(),
+                });
+            module.total_lines += lf;
+            module.covered_lines += lh;
+            module.file_coverage.insert(display, file_cov);
+        }
+
+        for module in module_coverage.values_mut() {
+            module.coverage_percentage = percentage(module.covered_lines, module.total_lines);
+        }
+
+        let report = CoverageReport {
+            total_lines,
+            covered_lines,
+            coverage_percentage: percentage(covered_lines, total_lines),
+            module_coverage,
+            generated_at: chrono::Utc::now(),
+        };
+
+        Ok(MergedCoverage {
+            report,
+            per_line_counts: combined,
+        })
+    }
+
+    /// JUnit XML 形式のレポートを書き出す。
+    ///
+    /// `report.module_coverage` の各モジュールを1つの `<testsuite>` とし、ファイルごとに
+    /// カバレッジ率をプロパティに持つ `<testcase>` を出力する。設定された閾値を下回る
+    /// ファイルには `<failure>` を付与する。これにより Forgejo/GitHub Actions の
+    /// テストレポート UI に結果が表示される。
+    pub fn write_junit(&self, report: &CoverageReport, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() && !parent.exists() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+
+        let mut xml = String::new();
+        xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+
+        let total_files: usize = report
+            .module_coverage
+            .values()
+            .map(|m| m.file_coverage.len())
+            .sum();
+        xml.push_str(&format!(
+            "<testsuites name=\"coverage\" tests=\"{}\">\n",
+            total_files
+        ));
+
+        for module in report.module_coverage.values() {
+            // モジュール別閾値、無ければ全体閾値を失敗判定に使う
+            let threshold = self
+                .module_thresholds
+                .get(&module.name)
+                .copied()
+                .or(self.global_threshold);
+
+            let failures = threshold.map_or(0, |t| {
+                module
+                    .file_coverage
+                    .values()
+                    .filter(|f| f.coverage_percentage < t)
+                    .count()
+            });
+
+            xml.push_str(&format!(
+                "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\">\n",
+                xml_escape(&module.name),
+                module.file_coverage.len(),
+                failures
+            ));
+
+            for (path_name, file) in &module.file_coverage {
+                xml.push_str(&format!(
+                    "    <testcase name=\"{}\" classname=\"{}\">\n",
+                    xml_escape(path_name),
+                    xml_escape(&module.name)
+                ));
+                xml.push_str(&format!(
+                    "      <properties>\n        <property name=\"coverage\" value=\"{:.2}\"/>\n      </properties>\n",
+                    file.coverage_percentage
+                ));
+                if let Some(t) = threshold {
+                    if file.coverage_percentage < t {
+                        xml.push_str(&format!(
+                            "      <failure message=\"coverage {:.2}% below threshold {:.2}%\"/>\n",
+                            file.coverage_percentage, t
+                        ));
+                    }
+                }
+                xml.push_str("    </testcase>\n");
+            }
+
+            xml.push_str("  </testsuite>\n");
+        }
+
+        xml.push_str("</testsuites>\n");
+
+        let mut file = File::create(path)?;
+        file.write_all(xml.as_bytes())
+    }
+
+    /// 指定形式でレポートを出力する。
+    ///
+    /// `Summary`/`Pretty` は端末へ表示し、`Html`/`Lcov` はファイルに書き出す。
+    pub fn report(&self, report: &CoverageReport, kind: CoverageType) -> io::Result<()> {
+        match kind {
+            CoverageType::Summary => {
+                print!("{}", self.generate_summary_report(report));
+                Ok(())
+            }
+            CoverageType::Pretty => {
+                print!("{}", self.generate_pretty_report(report)?);
+                Ok(())
+            }
+            CoverageType::Html => {
+                if !self.output_dir.exists() {
+                    fs::create_dir_all(&self.output_dir)?;
+                }
+                let html_path = self.output_dir.join("coverage.html");
+                let html_content = self.generate_html_report(report);
+                let mut html_file = File::create(html_path)?;
+                html_file.write_all(html_content.as_bytes())
+            }
+            CoverageType::Lcov => self.save_lcov_report(report, None),
+        }
+    }
+
+    /// 端末向けのサマリ表を生成する（カバレッジ昇順でソート、末尾に合計行）
+    fn generate_summary_report(&self, report: &CoverageReport) -> String {
+        // 全ファイルを (表示名, FileCoverage) で集める
+        let mut rows: Vec<(&str, &FileCoverage)> = report
+            .module_coverage
+            .values()
+            .flat_map(|m| m.file_coverage.iter().map(|(path, fc)| (path.as_str(), fc)))
+            .collect();
+        // カバレッジの低い順（問題の大きいファイルを上に）
+        rows.sort_by(|a, b| {
+            a.1.coverage_percentage
+                .partial_cmp(&b.1.coverage_percentage)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let mut out = String::new();
+        out.push_str(&format!(
+            "{:<50} {:>8} {:>14} {:>10}\n",
+            "File", "Line %", "Covered/Total", "Uncovered"
+        ));
+        out.push_str(&format!("{}\n", "-".repeat(84)));
+        for (path, fc) in &rows {
+            let uncovered = fc.total_lines.saturating_sub(fc.covered_lines);
+            out.push_str(&format!(
+                "{:<50} {:>7.1}% {:>14} {:>10}\n",
+                truncate(path, 50),
+                fc.coverage_percentage,
+                format!("{}/{}", fc.covered_lines, fc.total_lines),
+                uncovered
+            ));
+        }
+        out.push_str(&format!("{}\n", "-".repeat(84)));
+        out.push_str(&format!(
+            "{:<50} {:>7.1}% {:>14} {:>10}\n",
+            "TOTAL",
+            report.coverage_percentage,
+            format!("{}/{}", report.covered_lines, report.total_lines),
+            report.total_lines.saturating_sub(report.covered_lines)
+        ));
+        out
+    }
+
+    /// 各ファイルのソースを未カバー行を強調して表示する。
+    ///
+    /// 連続してカバーされた区間は折り畳み、未カバー行は連続範囲
+    /// （例: `lines 42-57 uncovered`）としてまとめて示す。
+    fn generate_pretty_report(&self, report: &CoverageReport) -> io::Result<String> {
+        let mut out = String::new();
+
+        for module in report.module_coverage.values() {
+            for (path, fc) in &module.file_coverage {
+                out.push_str(&format!(
+                    "\n=== {} ({:.1}% covered) ===\n",
+                    path, fc.coverage_percentage
+                ));
+
+                // ソースを読み込めれば行内容を添えて表示する
+                let source_path = self.source_dir.join(path);
+                let source = fs::read_to_string(&source_path).ok();
+
+                // 未カバー行を昇順に集める
+                let mut uncovered: Vec<usize> = fc
+                    .line_coverage
+                    .iter()
+                    .filter_map(|(line, covered)| if *covered { None } else { Some(*line) })
+                    .collect();
+                uncovered.sort_unstable();
+
+                if uncovered.is_empty() {
+                    out.push_str("  all executable lines covered\n");
+                    continue;
+                }
+
+                // 連続範囲に圧縮して表示
+                for (start, end) in contiguous_ranges(&uncovered) {
+                    if start == end {
+                        out.push_str(&format!("  line {} uncovered", start));
+                    } else {
+                        out.push_str(&format!("  lines {}-{} uncovered", start, end));
+                    }
+                    if let Some(src) = &source {
+                        if let Some(text) = src.lines().nth(start - 1) {
+                            out.push_str(&format!(" | {}", text.trim()));
+                        }
+                    }
+                    out.push('\n');
+                }
+            }
+        }
+
+        Ok(out)
+    }
+
     /// レポートを保存
     fn save_report(&self, report: &CoverageReport) -> io::Result<()> {
         // 出力ディレクトリが存在しない場合は作成
@@ -306,9 +866,75 @@ impl CoverageManager {
         let mut html_file = File::create(html_path)?;
         html_file.write_all(html_content.as_bytes())?;
 
+        // LCOVレポートを保存（Codecov/Coveralls/genhtml/IDE 連携用）
+        self.save_lcov_report(report, None)?;
+
+        Ok(())
+    }
+
+    /// LCOV形式（`coverage.lcov`）のレポートを単独で保存する。
+    ///
+    /// JSON/HTML とは独立に呼び出せるので、CI では LCOV だけを生成して
+    /// Codecov や Coveralls にアップロードできる。`test_name` は `TN:` 行の
+    /// テスト名タグとして使われる（`None` なら空タグ）。
+    pub fn save_lcov_report(
+        &self,
+        report: &CoverageReport,
+        test_name: Option<&str>,
+    ) -> io::Result<()> {
+        if !self.output_dir.exists() {
+            fs::create_dir_all(&self.output_dir)?;
+        }
+
+        let lcov_path = self.output_dir.join("coverage.lcov");
+        let lcov_content = self.generate_lcov_report(report, test_name);
+        let mut lcov_file = File::create(lcov_path)?;
+        lcov_file.write_all(lcov_content.as_bytes())?;
+
         Ok(())
     }
 
+    /// LCOV `.info` 形式の文字列を生成する。
+    ///
+    /// 各ファイルごとに1レコードを出力する:
+    /// `TN:<tag>` / `SF:<絶対パス>` / 行ごとの `DA:<行番号>,<実行回数>` /
+    /// `LF:<実行可能行数>` / `LH:<カバー行数>` / `end_of_record`。
+    fn generate_lcov_report(&self, report: &CoverageReport, test_name: Option<&str>) -> String {
+        let tag = test_name.unwrap_or("");
+        let mut lcov = String::new();
+
+        for module in report.module_coverage.values() {
+            for file in module.file_coverage.values() {
+                lcov.push_str(&format!("TN:{}\n", tag));
+
+                // ソースディレクトリからの絶対パスに解決する
+                let source_path = self.source_dir.join(&file.name);
+                lcov.push_str(&format!("SF:{}\n", source_path.display()));
+
+                // 行番号順に DA 行を出力（決定的な順序にするためソートする）
+                let mut lines: Vec<(&usize, &bool)> = file.line_coverage.iter().collect();
+                lines.sort_by_key(|(line_number, _)| **line_number);
+
+                let mut covered = 0usize;
+                for (line_number, is_covered) in &lines {
+                    let count = if **is_covered {
+                        covered += 1;
+                        1
+                    } else {
+                        0
+                    };
+                    lcov.push_str(&format!("DA:{},{}\n", line_number, count));
+                }
+
+                lcov.push_str(&format!("LF:{}\n", lines.len()));
+                lcov.push_str(&format!("LH:{}\n", covered));
+                lcov.push_str("end_of_record\n");
+            }
+        }
+
+        lcov
+    }
+
     /// HTMLレポートを生成
     fn generate_html_report(&self, report: &CoverageReport) -> String {
         let mut html = String::new();
@@ -489,4 +1115,127 @@ mod tests {
         assert_eq!(manager.get_module_name("src/module/file.rs"), "src");
         assert_eq!(manager.get_module_name("file.rs"), "root");
     }
+
+    #[test]
+    fn test_generate_lcov_report() {
+        let temp_dir = tempdir().unwrap();
+        let manager = CoverageManager::new(temp_dir.path(), temp_dir.path());
+
+        let mut line_coverage = HashMap::new();
+        line_coverage.insert(1, true);
+        line_coverage.insert(2, false);
+        line_coverage.insert(3, true);
+
+        let mut file_coverage = HashMap::new();
+        file_coverage.insert(
+            "src/lib.rs".to_string(),
+            FileCoverage {
+                name: "src/lib.rs".to_string(),
+                total_lines: 3,
+                covered_lines: 2,
+                coverage_percentage: 66.6,
+                line_coverage,
+            },
+        );
+
+        let mut module_coverage = HashMap::new();
+        module_coverage.insert(
+            "src".to_string(),
+            ModuleCoverage {
+                name: "src".to_string(),
+                total_lines: 3,
+                covered_lines: 2,
+                coverage_percentage: 66.6,
+                file_coverage,
+            },
+        );
+
+        let report = CoverageReport {
+            total_lines: 3,
+            covered_lines: 2,
+            coverage_percentage: 66.6,
+            module_coverage,
+            generated_at: chrono::Utc::now(),
+        };
+
+        let lcov = manager.generate_lcov_report(&report, Some("unit"));
+
+        assert!(lcov.contains("TN:unit\n"));
+        assert!(lcov.contains("SF:"));
+        // 行番号順に出力される
+        assert!(lcov.contains("DA:1,1\nDA:2,0\nDA:3,1\n"));
+        assert!(lcov.contains("LF:3\n"));
+        assert!(lcov.contains("LH:2\n"));
+        assert!(lcov.trim_end().ends_with("end_of_record"));
+    }
+
+    #[test]
+    fn test_parse_lcov() {
+        let content = "\
+TN:unit
+SF:/repo/src/lib.rs
+DA:1,5
+DA:2,0
+DA:1,3
+LF:2
+LH:1
+end_of_record
+";
+        let profile = parse_lcov(content);
+        let lines = profile.get(Path::new("/repo/src/lib.rs")).unwrap();
+        // 同一行の実行回数は合算される (5 + 3)
+        assert_eq!(lines.get(&1), Some(&8));
+        assert_eq!(lines.get(&2), Some(&0));
+    }
+
+    #[test]
+    fn test_xml_escape() {
+        assert_eq!(xml_escape("a<b>&\"c\""), "a&lt;b&gt;&amp;&quot;c&quot;");
+    }
+
+    #[test]
+    fn test_check_thresholds() {
+        let temp_dir = tempdir().unwrap();
+        let mut manager = CoverageManager::new(temp_dir.path(), temp_dir.path());
+
+        let mut per_module = HashMap::new();
+        per_module.insert("zk".to_string(), 90.0);
+        manager.set_thresholds(80.0, per_module);
+
+        let mut module_coverage = HashMap::new();
+        module_coverage.insert(
+            "zk".to_string(),
+            ModuleCoverage {
+                name: "zk".to_string(),
+                total_lines: 100,
+                covered_lines: 70,
+                coverage_percentage: 70.0,
+                file_coverage: HashMap::new(),
+            },
+        );
+
+        let report = CoverageReport {
+            total_lines: 100,
+            covered_lines: 75,
+            coverage_percentage: 75.0,
+            module_coverage,
+            generated_at: chrono::Utc::now(),
+        };
+
+        let violations = manager.check_thresholds(&report).unwrap_err();
+        // 全体(75<80) と zk(70<90) の2件
+        assert_eq!(violations.len(), 2);
+        assert!(violations.iter().any(|v| v.module == "<global>"));
+        assert!(violations.iter().any(|v| v.module == "zk"));
+    }
+
+    #[test]
+    fn test_contiguous_ranges() {
+        assert_eq!(contiguous_ranges(&[]), vec![]);
+        assert_eq!(contiguous_ranges(&[5]), vec![(5, 5)]);
+        assert_eq!(
+            contiguous_ranges(&[1, 2, 3, 7, 8, 10]),
+            vec![(1, 3), (7, 8), (10, 10)]
+        );
+    }
 }